@@ -1,11 +1,20 @@
 use clap::{Args, Subcommand, ValueEnum};
 use std::collections::HashMap;
-use std::io::{self, Write};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 use crate::config::Config;
 use crate::models::{Dish, Ingredient, Nutrient};
 use crate::sync::SyncDishRepository;
+use todu_fit_core::formats::{
+    bundle, cooklang, jsonld, mealie, paprika, split_duplicates, Collection, Fetcher, HttpFetcher,
+};
+use todu_fit_core::ingredient_parser::parse_ingredient_lines;
+use todu_fit_core::nutrition_db::UnmatchedReason;
+use todu_fit_core::scaling::format_kitchen_quantity;
+use todu_fit_core::FoodDatabase;
 
 #[derive(Clone, ValueEnum, Default)]
 pub enum OutputFormat {
@@ -14,6 +23,48 @@ pub enum OutputFormat {
     Json,
 }
 
+/// Recipe formats accepted by `dish import`
+#[derive(Clone, ValueEnum, Default, PartialEq)]
+pub enum ImportFormat {
+    /// Pick by file extension or content
+    #[default]
+    Auto,
+    /// schema.org Recipe JSON-LD in an HTML page or JSON file
+    Jsonld,
+    /// Cooklang (.cook)
+    Cooklang,
+    /// Paprika archive (.paprikarecipes) or single .paprikarecipe
+    Paprika,
+    /// Mealie recipe JSON (one recipe or an array)
+    Mealie,
+    /// todu-fit dish bundle (from `dish export --format json`)
+    Json,
+}
+
+impl ImportFormat {
+    /// Formats that hold a whole collection of recipes
+    fn is_collection(&self) -> bool {
+        matches!(
+            self,
+            ImportFormat::Paprika | ImportFormat::Mealie | ImportFormat::Json
+        )
+    }
+}
+
+/// Recipe formats written by `dish export`
+#[derive(Clone, ValueEnum, Default)]
+pub enum ExportFormat {
+    /// Cooklang (.cook); with --all, one file per dish in the --output directory
+    #[default]
+    Cooklang,
+    /// Paprika archive (.paprikarecipes); requires --output
+    Paprika,
+    /// Mealie recipe JSON
+    Mealie,
+    /// Lossless todu-fit dish bundle
+    Json,
+}
+
 #[derive(Args)]
 pub struct DishCommand {
     #[command(subcommand)]
@@ -80,6 +131,99 @@ pub enum DishSubcommand {
         /// Output format
         #[arg(long, short, value_enum, default_value = "text")]
         format: OutputFormat,
+
+        /// Show the dish as of an earlier revision (from `dish history`)
+        #[arg(long, value_name = "REV")]
+        at: Option<String>,
+    },
+
+    /// List the changes made to a dish
+    History {
+        /// Dish ID (UUID) or name; use the ID for deleted dishes
+        identifier: String,
+
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Restore a dish to an earlier revision
+    Revert {
+        /// Dish ID (UUID) or name; use the ID for deleted dishes
+        identifier: String,
+
+        /// Revision to restore (from `dish history`)
+        revision: String,
+    },
+
+    /// Import dishes from a recipe web page, Cooklang file or recipe collection
+    ///
+    /// Collections (Paprika, Mealie, todu-fit JSON bundles) import every
+    /// recipe, skipping dishes that already exist by name or source URL.
+    Import {
+        /// Path to an HTML/JSON/.cook/.paprikarecipes file, or an http(s) URL
+        source: String,
+
+        /// Recipe format
+        #[arg(long, short, value_enum, default_value = "auto")]
+        format: ImportFormat,
+
+        /// Treat the source as a collection and import every recipe in it
+        #[arg(long)]
+        all: bool,
+
+        /// Use this name instead of the recipe's name
+        #[arg(long, conflicts_with = "all")]
+        name: Option<String>,
+    },
+
+    /// Export dishes to a recipe format
+    Export {
+        /// Dish ID (UUID) or name
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        identifier: Option<String>,
+
+        /// Export every dish
+        #[arg(long)]
+        all: bool,
+
+        /// Recipe format
+        #[arg(long, short, value_enum, default_value = "cooklang")]
+        format: ExportFormat,
+
+        /// Write to this file (or directory, for --all Cooklang) instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show a dish scaled to a number of servings
+    Scale {
+        /// Dish ID (UUID) or name
+        identifier: String,
+
+        /// Target number of servings
+        #[arg(long)]
+        servings: i32,
+
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Calculate a dish's nutrients from the local food database.
+    /// Dishes without hand-entered nutrients are recalculated on every edit.
+    Nutrition {
+        /// Dish ID (UUID) or name
+        identifier: String,
+
+        /// Store the calculated per-serving nutrients on the dish, replacing
+        /// hand-entered ones
+        #[arg(long)]
+        save: bool,
+
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
     },
 
     /// Update an existing dish
@@ -157,6 +301,19 @@ pub enum DishSubcommand {
         unit: String,
     },
 
+    /// Add many ingredients from free-text lines (stdin or a file)
+    ///
+    /// Each line is parsed like "1 1/2 cups flour, sifted" or "salt to taste".
+    /// Blank lines and lines starting with '#' are skipped.
+    AddIngredients {
+        /// Dish ID (UUID) or name
+        identifier: String,
+
+        /// Read lines from this file instead of stdin
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
     /// Remove an ingredient from a dish
     RemoveIngredient {
         /// Dish ID (UUID) or name
@@ -166,6 +323,31 @@ pub enum DishSubcommand {
         #[arg(long)]
         name: String,
     },
+
+    /// Use another dish as a component (sub-recipe) of a dish
+    ///
+    /// Components are expanded when scaling, in nutrition totals, shopping
+    /// lists and meal logs. Adding an existing component changes its scale.
+    AddComponent {
+        /// Dish ID (UUID) or name
+        identifier: String,
+
+        /// Component dish ID (UUID) or name
+        component: String,
+
+        /// Batches of the component recipe used per batch of the dish
+        #[arg(long, default_value = "1")]
+        scale: f64,
+    },
+
+    /// Remove a component from a dish
+    RemoveComponent {
+        /// Dish ID (UUID) or name
+        identifier: String,
+
+        /// Component dish ID (UUID) or name
+        component: String,
+    },
 }
 
 /// Parse nutrients from JSON string like '{"calories": 650, "protein": 25}'
@@ -189,6 +371,188 @@ fn parse_nutrients(json: &str) -> Result<Vec<Nutrient>, Box<dyn std::error::Erro
     Ok(nutrients)
 }
 
+/// Dish ID for a UUID or the name of a current dish. UUIDs are taken as-is
+/// so deleted dishes can still be looked up in history.
+fn resolve_dish_id(
+    repo: &SyncDishRepository,
+    identifier: &str,
+) -> Result<Uuid, Box<dyn std::error::Error>> {
+    if let Ok(uuid) = Uuid::parse_str(identifier) {
+        return Ok(uuid);
+    }
+    repo.get_by_name(identifier)?
+        .map(|d| d.id)
+        .ok_or_else(|| format!("Dish not found: {}", identifier).into())
+}
+
+/// Look up a current dish by UUID or name.
+fn find_dish(
+    repo: &SyncDishRepository,
+    identifier: &str,
+) -> Result<Dish, Box<dyn std::error::Error>> {
+    let dish = if let Ok(uuid) = Uuid::parse_str(identifier) {
+        repo.get_by_id(uuid)?
+    } else {
+        repo.get_by_name(identifier)?
+    };
+    dish.ok_or_else(|| format!("Dish not found: {}", identifier).into())
+}
+
+/// List a dish's components by name and its nutrition with them included.
+fn print_components(
+    repo: &SyncDishRepository,
+    dish: &Dish,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dishes = repo.list()?;
+
+    println!("Components:");
+    for component in &dish.components {
+        let name = dishes
+            .iter()
+            .find(|d| d.id == component.dish_id)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| format!("missing dish {}", component.dish_id));
+        println!(
+            "  - {} x {}",
+            format_kitchen_quantity(component.scale),
+            name
+        );
+    }
+
+    let flat = dish.flatten(&dishes)?;
+    if let Some(nutrients) = flat.nutrients.filter(|n| !n.is_empty()) {
+        println!("\nNutrition with components (per serving):");
+        for nutrient in &nutrients {
+            println!("  - {}", nutrient);
+        }
+    }
+    Ok(())
+}
+
+/// The local food database, if one has been imported.
+fn load_food_db(config: &Config) -> Option<FoodDatabase> {
+    FoodDatabase::load(&FoodDatabase::path(&config.data_dir.value))
+        .ok()
+        .filter(|db| !db.foods.is_empty())
+}
+
+/// Recalculate a dish's nutrients from the food database unless they were
+/// entered by hand, and say which ingredients were left out. Returns true
+/// if the dish was recalculated.
+fn recalculate_nutrients(db: Option<&FoodDatabase>, dish: &mut Dish) -> bool {
+    let Some(calc) = db.and_then(|db| db.update_nutrients(dish)) else {
+        return false;
+    };
+
+    let unmatched: Vec<&str> = calc
+        .unmatched
+        .iter()
+        .filter(|u| u.reason != UnmatchedReason::Unmeasured)
+        .map(|u| u.ingredient.name.as_str())
+        .collect();
+    if !unmatched.is_empty() {
+        println!(
+            "Nutrients for '{}' leave out unmatched ingredient(s): {} (see 'fit dish nutrition \"{}\"')",
+            dish.name,
+            unmatched.join(", "),
+            dish.name
+        );
+    }
+    true
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+fn read_source(source: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if is_url(source) {
+        Ok(HttpFetcher::new().fetch(source)?.into_bytes())
+    } else {
+        Ok(fs::read(source).map_err(|e| format!("Failed to read {}: {}", source, e))?)
+    }
+}
+
+/// Pick a collection format from the file extension, then the content.
+fn detect_collection_format(source: &str, content: &[u8]) -> ImportFormat {
+    let lower = source.to_lowercase();
+    if lower.ends_with(".paprikarecipes")
+        || lower.ends_with(".paprikarecipe")
+        || content.starts_with(b"PK")
+        || content.starts_with(&[0x1f, 0x8b])
+    {
+        ImportFormat::Paprika
+    } else if bundle::is_bundle(&String::from_utf8_lossy(content)) {
+        ImportFormat::Json
+    } else {
+        ImportFormat::Mealie
+    }
+}
+
+/// Import every dish in a collection, skipping duplicates, and print a summary.
+fn import_collection(
+    repo: &SyncDishRepository,
+    config: &Config,
+    collection: Collection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let existing = repo.list()?;
+    let mut check = split_duplicates(collection.dishes, &existing);
+    let relinked = check.relink_components(&existing);
+
+    let food_db = load_food_db(config);
+    for dish in &mut check.unique {
+        recalculate_nutrients(food_db.as_ref(), dish);
+    }
+
+    repo.create_many(&check.unique)?;
+
+    for dish in &check.unique {
+        println!("  + {}", dish.name);
+    }
+    for duplicate in &check.duplicates {
+        println!(
+            "  = {} (duplicate of '{}', {})",
+            duplicate.dish.name, duplicate.existing, duplicate.reason
+        );
+    }
+    for (entry, error) in &collection.failed {
+        println!("  ! {}: {}", entry, error);
+    }
+    for name in &relinked {
+        println!("  ~ {}: dropped components that weren't imported", name);
+    }
+
+    println!(
+        "Imported {} dish(es), skipped {} duplicate(s), {} failed",
+        check.unique.len(),
+        check.duplicates.len(),
+        collection.failed.len()
+    );
+    Ok(())
+}
+
+/// A file name for a dish that is safe on common filesystems.
+///
+/// Names already in `used` (compared case-insensitively, as some
+/// filesystems do) get a numeric suffix, so dishes sharing a name don't
+/// overwrite each other's files. The chosen name is added to `used`.
+fn dish_file_name(name: &str, extension: &str, used: &mut Vec<String>) -> String {
+    let base: String = name
+        .chars()
+        .map(|c| if "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let base = base.trim();
+
+    let mut file_name = format!("{}.{}", base, extension);
+    let mut n = 2;
+    while used.contains(&file_name.to_lowercase()) {
+        file_name = format!("{} ({}).{}", base, n, extension);
+        n += 1;
+    }
+    used.push(file_name.to_lowercase());
+    file_name
+}
+
 impl DishCommand {
     pub fn run(
         &self,
@@ -238,6 +602,7 @@ impl DishCommand {
                     let parsed = parse_nutrients(nutrients_json)?;
                     dish = dish.with_nutrients(parsed);
                 }
+                recalculate_nutrients(load_food_db(config).as_ref(), &mut dish);
 
                 let created = repo.create(&dish)?;
                 println!("Created dish:");
@@ -286,9 +651,19 @@ impl DishCommand {
                 Ok(())
             }
 
-            DishSubcommand::Show { identifier, format } => {
-                // Try to parse as UUID first, then fall back to name lookup
-                let dish = if let Ok(uuid) = Uuid::parse_str(identifier) {
+            DishSubcommand::Show {
+                identifier,
+                format,
+                at,
+            } => {
+                let dish = if let Some(rev) = at {
+                    let id = resolve_dish_id(repo, identifier)?;
+                    let old = repo
+                        .get_at_revision(id, rev)?
+                        .ok_or_else(|| format!("Dish {} did not exist at revision {}", id, rev))?;
+                    Some(old)
+                } else if let Ok(uuid) = Uuid::parse_str(identifier) {
+                    // Try to parse as UUID first, then fall back to name lookup
                     repo.get_by_id(uuid)?
                 } else {
                     repo.get_by_name(identifier)?
@@ -302,6 +677,9 @@ impl DishCommand {
                             }
                             OutputFormat::Text => {
                                 println!("{}", dish);
+                                if !dish.components.is_empty() {
+                                    print_components(repo, &dish)?;
+                                }
                             }
                         }
                         Ok(())
@@ -310,6 +688,400 @@ impl DishCommand {
                 }
             }
 
+            DishSubcommand::History { identifier, format } => {
+                let id = resolve_dish_id(repo, identifier)?;
+                let history = repo.history(id)?;
+                if history.is_empty() {
+                    return Err(format!("No history found for dish: {}", identifier).into());
+                }
+
+                match format {
+                    OutputFormat::Json => {
+                        let revisions: Vec<serde_json::Value> = history
+                            .iter()
+                            .map(|r| {
+                                serde_json::json!({
+                                    "revision": r.hash.to_string(),
+                                    "actor": r.actor,
+                                    "timestamp": r.timestamp,
+                                    "message": r.message,
+                                })
+                            })
+                            .collect();
+                        println!("{}", serde_json::to_string_pretty(&revisions)?);
+                    }
+                    OutputFormat::Text => {
+                        println!("History of dish {} (oldest first):", id);
+                        println!();
+                        println!("{:<10} {:<17} {:<10} MESSAGE", "REVISION", "TIME", "ACTOR");
+                        println!("{}", "-".repeat(70));
+                        for rev in &history {
+                            let time = rev
+                                .timestamp
+                                .map(|t| {
+                                    t.with_timezone(&chrono::Local)
+                                        .format("%Y-%m-%d %H:%M")
+                                        .to_string()
+                                })
+                                .unwrap_or_else(|| "-".to_string());
+                            println!(
+                                "{:<10} {:<17} {:<10} {}",
+                                rev.short_hash(),
+                                time,
+                                rev.short_actor(),
+                                rev.message.as_deref().unwrap_or("")
+                            );
+                        }
+                        println!();
+                        println!("Use 'fit dish show <DISH> --at <REVISION>' to view a revision.");
+                    }
+                }
+                Ok(())
+            }
+
+            DishSubcommand::Revert {
+                identifier,
+                revision,
+            } => {
+                let id = resolve_dish_id(repo, identifier)?;
+                let dish = repo.revert(id, revision)?;
+                println!("Reverted dish '{}' to revision {}", dish.name, revision);
+                Ok(())
+            }
+
+            DishSubcommand::Import {
+                source,
+                format,
+                all,
+                name,
+            } => {
+                let created_by = &config.created_by.value;
+                let lower = source.to_lowercase();
+
+                if *all
+                    || format.is_collection()
+                    || (*format == ImportFormat::Auto && lower.contains(".paprikarecipe"))
+                {
+                    let content = read_source(source)?;
+                    let format = match format {
+                        ImportFormat::Auto => detect_collection_format(source, &content),
+                        other => other.clone(),
+                    };
+
+                    let collection = match format {
+                        ImportFormat::Paprika => paprika::read_archive(&content, created_by)?,
+                        ImportFormat::Mealie => {
+                            mealie::read_recipes(&String::from_utf8_lossy(&content), created_by)?
+                        }
+                        ImportFormat::Json => Collection {
+                            dishes: bundle::read_bundle(&String::from_utf8_lossy(&content))?,
+                            failed: Vec::new(),
+                        },
+                        _ => {
+                            return Err(
+                                "--all requires a collection format (paprika, mealie or json)"
+                                    .into(),
+                            )
+                        }
+                    };
+                    return import_collection(repo, config, collection);
+                }
+
+                let format = match format {
+                    ImportFormat::Auto if lower.ends_with(".cook") => ImportFormat::Cooklang,
+                    ImportFormat::Auto => ImportFormat::Jsonld,
+                    other => other.clone(),
+                };
+
+                let mut dish = if format == ImportFormat::Cooklang {
+                    let content = if is_url(source) {
+                        HttpFetcher::new().fetch(source)?
+                    } else {
+                        fs::read_to_string(source)
+                            .map_err(|e| format!("Failed to read {}: {}", source, e))?
+                    };
+                    let file_name = Path::new(source).file_stem().and_then(|s| s.to_str());
+                    cooklang::parse_cooklang(&content, file_name, created_by)?
+                } else if is_url(source) {
+                    jsonld::import_recipe_from_url(source, &HttpFetcher::new(), created_by)?
+                } else {
+                    let content = fs::read_to_string(source)
+                        .map_err(|e| format!("Failed to read {}: {}", source, e))?;
+                    jsonld::import_recipe(&content, created_by, None)?
+                };
+
+                if let Some(name) = name {
+                    dish.name = name.trim().to_string();
+                }
+
+                if repo.get_by_name(&dish.name)?.is_some() {
+                    return Err(format!(
+                        "Dish '{}' already exists. Use --name to import it under another name.",
+                        dish.name
+                    )
+                    .into());
+                }
+
+                recalculate_nutrients(load_food_db(config).as_ref(), &mut dish);
+                let created = repo.create(&dish)?;
+                println!("Imported dish:");
+                println!("{}", created);
+                Ok(())
+            }
+
+            DishSubcommand::Export {
+                identifier,
+                all,
+                format,
+                output,
+            } => {
+                let dishes = match identifier {
+                    Some(identifier) => {
+                        let dish = if let Ok(uuid) = Uuid::parse_str(identifier) {
+                            repo.get_by_id(uuid)?
+                        } else {
+                            repo.get_by_name(identifier)?
+                        };
+                        match dish {
+                            Some(d) => vec![d],
+                            None => return Err(format!("Dish not found: {}", identifier).into()),
+                        }
+                    }
+                    None => repo.list()?,
+                };
+
+                let bytes = match format {
+                    ExportFormat::Cooklang if *all => {
+                        let dir = output
+                            .as_ref()
+                            .ok_or("--all with Cooklang needs --output <DIR>")?;
+                        fs::create_dir_all(dir)
+                            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                        let mut used = Vec::new();
+                        let mut written = 0;
+                        let mut failed = 0;
+                        for dish in &dishes {
+                            let path = dir.join(dish_file_name(&dish.name, "cook", &mut used));
+                            match fs::write(&path, cooklang::to_cooklang(dish)) {
+                                Ok(()) => written += 1,
+                                Err(e) => {
+                                    println!("  ! {}: {}", path.display(), e);
+                                    failed += 1;
+                                }
+                            }
+                        }
+                        println!(
+                            "Exported {} dish(es) to {}, {} failed",
+                            written,
+                            dir.display(),
+                            failed
+                        );
+                        if failed > 0 {
+                            return Err(format!("{} dish(es) could not be written", failed).into());
+                        }
+                        return Ok(());
+                    }
+                    ExportFormat::Cooklang => cooklang::to_cooklang(&dishes[0]).into_bytes(),
+                    ExportFormat::Paprika => {
+                        if output.is_none() {
+                            return Err("Paprika archives are binary; use --output <FILE>".into());
+                        }
+                        paprika::write_archive(&dishes)?
+                    }
+                    ExportFormat::Mealie => mealie::write_recipes(&dishes)?.into_bytes(),
+                    ExportFormat::Json => bundle::write_bundle(&dishes)?.into_bytes(),
+                };
+
+                match output {
+                    Some(path) => {
+                        fs::write(path, bytes)
+                            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                        match dishes.as_slice() {
+                            [dish] => println!("Exported '{}' to {}", dish.name, path.display()),
+                            _ => {
+                                println!("Exported {} dish(es) to {}", dishes.len(), path.display())
+                            }
+                        }
+                    }
+                    None => {
+                        io::stdout().write_all(&bytes)?;
+                        if !bytes.ends_with(b"\n") {
+                            println!();
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            DishSubcommand::Nutrition {
+                identifier,
+                save,
+                format,
+            } => {
+                let dish = if let Ok(uuid) = Uuid::parse_str(identifier) {
+                    repo.get_by_id(uuid)?
+                } else {
+                    repo.get_by_name(identifier)?
+                };
+
+                let mut dish = match dish {
+                    Some(d) => d,
+                    None => return Err(format!("Dish not found: {}", identifier).into()),
+                };
+
+                let db = FoodDatabase::load(&FoodDatabase::path(&config.data_dir.value))?;
+                if db.foods.is_empty() {
+                    return Err("Food database is empty. Run 'fit food import <DIR>' first.".into());
+                }
+
+                let calc = db.calculate(&dish);
+
+                match format {
+                    OutputFormat::Json => {
+                        let matched: Vec<_> = calc
+                            .matched
+                            .iter()
+                            .map(|m| {
+                                serde_json::json!({
+                                    "ingredient": m.ingredient.name,
+                                    "fdc_id": m.fdc_id,
+                                    "food": m.food_name,
+                                    "grams": (m.grams * 10.0).round() / 10.0,
+                                })
+                            })
+                            .collect();
+                        let unmatched: Vec<_> = calc
+                            .unmatched
+                            .iter()
+                            .map(|u| {
+                                serde_json::json!({
+                                    "ingredient": u.ingredient.name,
+                                    "food": u.food_name,
+                                    "reason": u.reason.to_string(),
+                                })
+                            })
+                            .collect();
+                        let output = serde_json::json!({
+                            "dish": dish.name,
+                            "servings": calc.servings,
+                            "per_serving": calc.per_serving,
+                            "total": calc.total,
+                            "matched": matched,
+                            "unmatched": unmatched,
+                        });
+                        println!("{}", serde_json::to_string_pretty(&output)?);
+                    }
+                    OutputFormat::Text => {
+                        println!("{}", dish.name);
+                        println!("{}", "=".repeat(dish.name.len()));
+
+                        if !calc.matched.is_empty() {
+                            println!("\nMatched:");
+                            for m in &calc.matched {
+                                println!(
+                                    "  - {} -> {} ({:.0} g)",
+                                    m.ingredient, m.food_name, m.grams
+                                );
+                            }
+                        }
+
+                        if !calc.unmatched.is_empty() {
+                            println!("\nNot included:");
+                            for u in &calc.unmatched {
+                                match &u.food_name {
+                                    Some(food) => println!(
+                                        "  - {}: {} (matched {})",
+                                        u.ingredient, u.reason, food
+                                    ),
+                                    None => println!("  - {}: {}", u.ingredient, u.reason),
+                                }
+                            }
+                        }
+
+                        println!("\nPer serving ({} servings):", calc.servings);
+                        for nutrient in &calc.per_serving {
+                            println!("  - {}", nutrient);
+                        }
+
+                        if !calc.is_complete() {
+                            println!(
+                                "\nUse 'fit food search <name>' and 'fit food alias <ingredient> <fdc-id>' to fix unmatched ingredients."
+                            );
+                        }
+                        if !dish.components.is_empty() {
+                            println!(
+                                "\nComponents aren't included; their own nutrients are added when the dish is used."
+                            );
+                        }
+                    }
+                }
+
+                if *save {
+                    dish.nutrients = Some(calc.per_serving.clone());
+                    dish.nutrients_calculated = true;
+                    repo.update(&dish)?;
+                    if matches!(format, OutputFormat::Text) {
+                        println!("\nSaved nutrients to '{}'.", dish.name);
+                    }
+                }
+                Ok(())
+            }
+
+            DishSubcommand::Scale {
+                identifier,
+                servings,
+                format,
+            } => {
+                let dish = if let Ok(uuid) = Uuid::parse_str(identifier) {
+                    repo.get_by_id(uuid)?
+                } else {
+                    repo.get_by_name(identifier)?
+                };
+
+                let dish = match dish {
+                    Some(d) => d,
+                    None => return Err(format!("Dish not found: {}", identifier).into()),
+                };
+
+                let scaled = repo.flatten(&dish)?.scale_to_servings(*servings)?;
+
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&scaled)?);
+                    }
+                    OutputFormat::Text => {
+                        println!("{}", scaled.name);
+                        println!("{}", "=".repeat(scaled.name.len()));
+                        println!(
+                            "Servings: {} (scaled from {})",
+                            servings,
+                            dish.servings.unwrap_or_default()
+                        );
+
+                        if !scaled.ingredients.is_empty() {
+                            println!("\nIngredients:");
+                            for ing in &scaled.ingredients {
+                                let qty = format_kitchen_quantity(ing.quantity);
+                                if ing.unit.is_empty() {
+                                    println!("  - {} {}", qty, ing.name);
+                                } else {
+                                    println!("  - {} {} {}", qty, ing.unit, ing.name);
+                                }
+                            }
+                        }
+
+                        let totals = scaled.nutrients_for_servings(*servings as f64);
+                        if !totals.is_empty() {
+                            println!("\nNutrition (total for {} servings):", servings);
+                            for nutrient in &totals {
+                                println!("  - {}", nutrient);
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+
             DishSubcommand::Update {
                 identifier,
                 name,
@@ -391,11 +1163,13 @@ impl DishCommand {
                     dish.tags.retain(|t| t.to_lowercase() != tag_lower);
                 }
 
-                // Handle nutrients (replaces existing)
+                // Handle nutrients (replaces existing, and stops recalculation)
                 if let Some(nutrients_json) = nutrients {
                     let parsed = parse_nutrients(nutrients_json)?;
                     dish.nutrients = Some(parsed);
+                    dish.nutrients_calculated = false;
                 }
+                recalculate_nutrients(load_food_db(config).as_ref(), &mut dish);
 
                 let updated = repo.update(&dish)?;
                 println!("Updated dish:");
@@ -416,6 +1190,20 @@ impl DishCommand {
                     None => return Err(format!("Dish not found: {}", identifier).into()),
                 };
 
+                let dishes = repo.list()?;
+                let users: Vec<&str> = dishes
+                    .iter()
+                    .filter(|d| d.components.iter().any(|c| c.dish_id == dish.id))
+                    .map(|d| d.name.as_str())
+                    .collect();
+                if !users.is_empty() {
+                    println!(
+                        "Warning: '{}' is a component of: {}",
+                        dish.name,
+                        users.join(", ")
+                    );
+                }
+
                 // Confirm deletion unless --force is used
                 if !force {
                     print!("Delete dish '{}'? [y/N] ", dish.name);
@@ -453,7 +1241,7 @@ impl DishCommand {
                     repo.get_by_name(identifier)?
                 };
 
-                let dish = match dish {
+                let mut dish = match dish {
                     Some(d) => d,
                     None => return Err(format!("Dish not found: {}", identifier).into()),
                 };
@@ -461,7 +1249,63 @@ impl DishCommand {
                 let ingredient = Ingredient::new(name, *quantity, unit);
                 println!("Added ingredient to '{}':", dish.name);
                 println!("  {}", ingredient);
-                repo.add_ingredient(dish.id, ingredient)?;
+                dish.ingredients.push(ingredient);
+                recalculate_nutrients(load_food_db(config).as_ref(), &mut dish);
+                repo.update(&dish)?;
+                Ok(())
+            }
+
+            DishSubcommand::AddIngredients { identifier, file } => {
+                // Find the dish
+                let dish = if let Ok(uuid) = Uuid::parse_str(identifier) {
+                    repo.get_by_id(uuid)?
+                } else {
+                    repo.get_by_name(identifier)?
+                };
+
+                let mut dish = match dish {
+                    Some(d) => d,
+                    None => return Err(format!("Dish not found: {}", identifier).into()),
+                };
+
+                let text = match file {
+                    Some(path) => fs::read_to_string(path)
+                        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+                    None => {
+                        let mut buf = String::new();
+                        io::stdin().read_to_string(&mut buf)?;
+                        buf
+                    }
+                };
+
+                let mut parsed = Vec::new();
+                let mut errors = Vec::new();
+                for (line_no, result) in parse_ingredient_lines(&text) {
+                    match result {
+                        Ok(ingredient) => parsed.push(ingredient),
+                        Err(e) => errors.push(format!("  line {}: {}", line_no, e)),
+                    }
+                }
+
+                if !errors.is_empty() {
+                    return Err(format!(
+                        "Could not parse ingredients (nothing was added):\n{}",
+                        errors.join("\n")
+                    )
+                    .into());
+                }
+
+                if parsed.is_empty() {
+                    return Err("No ingredient lines found".into());
+                }
+
+                println!("Added {} ingredient(s) to '{}':", parsed.len(), dish.name);
+                for ingredient in &parsed {
+                    println!("  {}", ingredient);
+                }
+                dish.ingredients.extend(parsed);
+                recalculate_nutrients(load_food_db(config).as_ref(), &mut dish);
+                repo.update(&dish)?;
                 Ok(())
             }
 
@@ -473,25 +1317,90 @@ impl DishCommand {
                     repo.get_by_name(identifier)?
                 };
 
-                let dish = match dish {
+                let mut dish = match dish {
                     Some(d) => d,
                     None => return Err(format!("Dish not found: {}", identifier).into()),
                 };
 
                 // Check if ingredient exists
+                let name_lower = name.to_lowercase();
                 let has_ingredient = dish
                     .ingredients
                     .iter()
-                    .any(|i| i.name.to_lowercase() == name.to_lowercase());
+                    .any(|i| i.name.to_lowercase() == name_lower);
 
                 if !has_ingredient {
                     return Err(format!("Ingredient not found: {}", name).into());
                 }
 
-                repo.remove_ingredient(dish.id, name)?;
+                dish.ingredients
+                    .retain(|i| i.name.to_lowercase() != name_lower);
+                recalculate_nutrients(load_food_db(config).as_ref(), &mut dish);
+                repo.update(&dish)?;
                 println!("Removed ingredient '{}' from '{}'", name, dish.name);
                 Ok(())
             }
+
+            DishSubcommand::AddComponent {
+                identifier,
+                component,
+                scale,
+            } => {
+                if *scale <= 0.0 {
+                    return Err("Scale must be a positive number".into());
+                }
+
+                let dish_id = resolve_dish_id(repo, identifier)?;
+                let component = find_dish(repo, component)?;
+                let dish = repo.add_component(dish_id, component.id, *scale)?;
+
+                println!(
+                    "Added {} x '{}' to '{}'",
+                    format_kitchen_quantity(*scale),
+                    component.name,
+                    dish.name
+                );
+                Ok(())
+            }
+
+            DishSubcommand::RemoveComponent {
+                identifier,
+                component,
+            } => {
+                let dish = find_dish(repo, identifier)?;
+                let component_id = resolve_dish_id(repo, component)?;
+
+                if !repo.remove_component(dish.id, component_id)? {
+                    return Err(
+                        format!("'{}' is not a component of '{}'", component, dish.name).into(),
+                    );
+                }
+                println!("Removed component '{}' from '{}'", component, dish.name);
+                Ok(())
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dish_file_name_unique() {
+        let mut used = Vec::new();
+        assert_eq!(
+            dish_file_name("Soup/Stew", "cook", &mut used),
+            "Soup_Stew.cook"
+        );
+        assert_eq!(
+            dish_file_name("Soup_Stew", "cook", &mut used),
+            "Soup_Stew (2).cook"
+        );
+        assert_eq!(
+            dish_file_name("soup_stew", "cook", &mut used),
+            "soup_stew (3).cook"
+        );
+        assert_eq!(dish_file_name(" Chili ", "cook", &mut used), "Chili.cook");
+    }
+}
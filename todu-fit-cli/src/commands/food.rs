@@ -0,0 +1,177 @@
+//! Food database commands.
+
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use todu_fit_core::nutrition_db::fdc;
+use todu_fit_core::FoodDatabase;
+
+use crate::commands::dish::OutputFormat;
+use crate::config::Config;
+
+/// Manage the local food composition database
+#[derive(Args)]
+pub struct FoodCommand {
+    #[command(subcommand)]
+    pub command: FoodSubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum FoodSubcommand {
+    /// Import foods from an extracted USDA FoodData Central CSV download
+    Import {
+        /// Directory containing food.csv and food_nutrient.csv
+        dir: PathBuf,
+
+        /// FDC data types to import (default: foundation_food, sr_legacy_food,
+        /// survey_fndds_food)
+        #[arg(long = "data-type", value_name = "TYPE")]
+        data_types: Vec<String>,
+    },
+
+    /// Search foods by name
+    Search {
+        /// Words to look for in the food name
+        query: String,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show a food's nutrients and portion weights
+    Show {
+        /// FoodData Central id
+        fdc_id: i64,
+
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Always match an ingredient name to a specific food
+    Alias {
+        /// Ingredient name as used in dishes
+        ingredient: String,
+
+        /// FoodData Central id
+        fdc_id: i64,
+    },
+
+    /// Remove an ingredient alias
+    Unalias {
+        /// Ingredient name
+        ingredient: String,
+    },
+
+    /// List ingredient aliases
+    Aliases,
+}
+
+impl FoodCommand {
+    pub fn run(&self, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        let path = FoodDatabase::path(&config.data_dir.value);
+        let mut db = FoodDatabase::load(&path)?;
+
+        match &self.command {
+            FoodSubcommand::Import { dir, data_types } => {
+                let foods = fdc::import_dir(dir, data_types)?;
+                let imported = foods.len();
+                let added = db.merge(foods);
+                db.save(&path)?;
+
+                println!(
+                    "Imported {} food(s) ({} new, {} updated)",
+                    imported,
+                    added,
+                    imported - added
+                );
+                println!("Food database: {} food(s)", db.foods.len());
+                Ok(())
+            }
+
+            FoodSubcommand::Search { query, limit } => {
+                let results = db.search(query);
+                if results.is_empty() {
+                    if db.foods.is_empty() {
+                        println!("Food database is empty. Run 'fit food import <DIR>' first.");
+                    } else {
+                        println!("No foods found matching '{}'", query);
+                    }
+                    return Ok(());
+                }
+
+                println!("{:<10}  NAME", "FDC ID");
+                println!("{}", "-".repeat(80));
+                for food in results.iter().take(*limit) {
+                    println!("{:<10}  {}", food.fdc_id, food.name);
+                }
+                if results.len() > *limit {
+                    println!("\n... and {} more", results.len() - limit);
+                }
+                Ok(())
+            }
+
+            FoodSubcommand::Show { fdc_id, format } => {
+                let food = db
+                    .get(*fdc_id)
+                    .ok_or_else(|| format!("Food not found: {}", fdc_id))?;
+
+                match format {
+                    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(food)?),
+                    OutputFormat::Text => {
+                        println!("{}", food.name);
+                        println!("{}", "=".repeat(food.name.len()));
+                        println!("FDC ID: {}", food.fdc_id);
+                        println!("\nPer 100 g:");
+                        for nutrient in &food.nutrients {
+                            println!("  {}: {} {}", nutrient.name, nutrient.amount, nutrient.unit);
+                        }
+                        if !food.portions.is_empty() {
+                            println!("\nPortions:");
+                            for portion in &food.portions {
+                                println!(
+                                    "  {} {} = {} g",
+                                    portion.amount, portion.unit, portion.grams
+                                );
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            FoodSubcommand::Alias { ingredient, fdc_id } => {
+                db.set_alias(ingredient, *fdc_id)?;
+                db.save(&path)?;
+                let food = db.get(*fdc_id).map(|f| f.name.as_str()).unwrap_or("");
+                println!("'{}' now matches {} ({})", ingredient, food, fdc_id);
+                Ok(())
+            }
+
+            FoodSubcommand::Unalias { ingredient } => {
+                if !db.remove_alias(ingredient) {
+                    return Err(format!("No alias for '{}'", ingredient).into());
+                }
+                db.save(&path)?;
+                println!("Removed alias for '{}'", ingredient);
+                Ok(())
+            }
+
+            FoodSubcommand::Aliases => {
+                if db.aliases.is_empty() {
+                    println!("No aliases defined.");
+                    return Ok(());
+                }
+                for (ingredient, fdc_id) in &db.aliases {
+                    let food = db
+                        .get(*fdc_id)
+                        .map(|f| f.name.as_str())
+                        .unwrap_or("(missing)");
+                    println!("{} -> {} ({})", ingredient, food, fdc_id);
+                }
+                Ok(())
+            }
+        }
+    }
+}
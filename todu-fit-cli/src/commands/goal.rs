@@ -0,0 +1,130 @@
+//! Nutrition goal commands.
+
+use clap::{Args, Subcommand};
+
+use todu_fit_core::{GoalKind, Identity, IdentityState, MultiDocStorage, NutritionGoal};
+
+use crate::commands::dish::OutputFormat;
+use crate::config::Config;
+
+/// Manage daily nutrition goals
+#[derive(Args)]
+pub struct GoalCommand {
+    #[command(subcommand)]
+    pub command: GoalSubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum GoalSubcommand {
+    /// Set the daily goal for a nutrient
+    Set {
+        /// Nutrient name (calories, protein, carbs, fat, fiber, ...)
+        nutrient: String,
+
+        /// Daily amount
+        amount: f64,
+
+        /// Unit (defaults to kcal for calories, mg for sodium, g otherwise)
+        #[arg(long)]
+        unit: Option<String>,
+
+        /// Goal kind: target (within 10%), min, or max
+        #[arg(long, default_value = "target")]
+        kind: GoalKind,
+    },
+
+    /// Remove the goal for a nutrient
+    Remove {
+        /// Nutrient name
+        nutrient: String,
+    },
+
+    /// List nutrition goals
+    List {
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+impl GoalCommand {
+    pub fn run(&self, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        let storage = MultiDocStorage::new(config.data_dir.value.clone());
+        let identity = Identity::new(storage);
+
+        match identity.state() {
+            IdentityState::Uninitialized => {
+                return Err("Identity not initialized. Run 'fit init --new' first.".into());
+            }
+            IdentityState::PendingSync => {
+                return Err("Identity is pending sync. Run 'fit sync' first.".into());
+            }
+            IdentityState::Initialized => {}
+        }
+
+        let mut doc = identity.load_identity()?;
+
+        match &self.command {
+            GoalSubcommand::Set {
+                nutrient,
+                amount,
+                unit,
+                kind,
+            } => {
+                if *amount <= 0.0 {
+                    return Err("Goal amount must be greater than zero".into());
+                }
+                let unit = unit
+                    .clone()
+                    .unwrap_or_else(|| NutritionGoal::default_unit(nutrient).to_string());
+                let goal = NutritionGoal::new(nutrient, *amount, unit).with_kind(*kind);
+
+                let description = describe_goal(&goal);
+                doc.set_goal(goal);
+                identity.save_identity(&doc)?;
+                println!("Set goal: {}", description);
+                Ok(())
+            }
+
+            GoalSubcommand::Remove { nutrient } => {
+                if !doc.remove_goal(nutrient) {
+                    return Err(format!("No goal set for '{}'", nutrient).into());
+                }
+                identity.save_identity(&doc)?;
+                println!("Removed goal for '{}'", nutrient);
+                Ok(())
+            }
+
+            GoalSubcommand::List { format } => {
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&doc.nutrition_goals)?)
+                    }
+                    OutputFormat::Text => {
+                        if doc.nutrition_goals.is_empty() {
+                            println!("No nutrition goals set.");
+                            println!("Use 'fit goal set <NUTRIENT> <AMOUNT>' to add one.");
+                        }
+                        for goal in &doc.nutrition_goals {
+                            println!("{}", describe_goal(goal));
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// "protein: at least 120 g per day"
+fn describe_goal(goal: &NutritionGoal) -> String {
+    let qualifier = match goal.kind {
+        GoalKind::Target => "",
+        GoalKind::Min => "at least ",
+        GoalKind::Max => "at most ",
+    };
+    format!(
+        "{}: {}{} {} per day",
+        goal.nutrient, qualifier, goal.amount, goal.unit
+    )
+}
@@ -4,9 +4,11 @@ use clap::{Args, Subcommand};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use todu_fit_core::{DocumentId, Identity, IdentityState, MultiDocStorage};
+use chrono::{NaiveTime, Weekday};
+use todu_fit_core::{DocumentId, Identity, IdentityState, MealType, MultiDocStorage};
 
 use crate::config::Config;
+use crate::sync::group_context::resolve_group_document;
 
 /// Manage groups for shared dishes and meal plans
 #[derive(Args)]
@@ -47,6 +49,54 @@ pub enum GroupSubcommand {
         #[arg(long, short)]
         force: bool,
     },
+    /// Manage the current group's meal slots
+    Slot {
+        #[command(subcommand)]
+        command: SlotSubcommand,
+    },
+    /// Show or set the first day of the group's shopping week.
+    /// Existing week carts keep their keys and show up in the new week
+    /// they overlap most.
+    WeekStart {
+        /// Day name (e.g. "thursday"); shows the current setting if omitted
+        day: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SlotSubcommand {
+    /// List meal slots in display order
+    List,
+    /// Add a meal slot (e.g. "brunch", "pre-workout")
+    Add {
+        /// Slot name
+        name: String,
+        /// Default time (HH:MM)
+        #[arg(long)]
+        time: Option<String>,
+        /// Position in the day (1 = first), defaults to last
+        #[arg(long)]
+        position: Option<usize>,
+    },
+    /// Remove a meal slot (existing plans and logs keep their meal type)
+    Remove {
+        /// Slot name
+        name: String,
+    },
+    /// Move a meal slot to a new position
+    Move {
+        /// Slot name
+        name: String,
+        /// New position (1 = first)
+        position: usize,
+    },
+    /// Set a meal slot's default time
+    Time {
+        /// Slot name
+        name: String,
+        /// Time (HH:MM), or "none" to clear it
+        time: String,
+    },
 }
 
 impl GroupCommand {
@@ -79,6 +129,10 @@ impl GroupCommand {
             GroupSubcommand::Switch { name } => self.switch(data_dir, name),
             GroupSubcommand::Show => self.show(&identity, data_dir),
             GroupSubcommand::Leave { name, force } => self.leave(&identity, data_dir, name, *force),
+            GroupSubcommand::Slot { command } => self.slot(&identity, data_dir, command),
+            GroupSubcommand::WeekStart { day } => {
+                self.week_start(&identity, data_dir, day.as_deref())
+            }
         }
     }
 
@@ -224,6 +278,7 @@ impl GroupCommand {
                         println!("ID:         {}", group_ref.doc_id.to_bs58check());
                         println!("Dishes ID:  {}", group_doc.dishes_doc_id.to_bs58check());
                         println!("Plans ID:   {}", group_doc.mealplans_doc_id.to_bs58check());
+                        println!("Week start: {}", weekday_name(group_doc.week_start));
                         println!();
                         println!("To invite others:");
                         println!(
@@ -302,6 +357,150 @@ impl GroupCommand {
     }
 }
 
+impl GroupCommand {
+    fn slot(
+        &self,
+        identity: &Identity,
+        data_dir: &Path,
+        command: &SlotSubcommand,
+    ) -> Result<(), GroupError> {
+        let (group_id, mut group_doc) = resolve_group_document(data_dir, None)
+            .map_err(|e| GroupError::MealSlot(e.to_string()))?;
+
+        match command {
+            SlotSubcommand::List => {
+                println!("Meal slots for '{}':", group_doc.name);
+                for (i, slot) in group_doc.meal_slots().iter().enumerate() {
+                    match slot.default_time {
+                        Some(time) => println!(
+                            "  {}. {:<20} {}",
+                            i + 1,
+                            slot.meal_type,
+                            time.format("%H:%M")
+                        ),
+                        None => println!("  {}. {}", i + 1, slot.meal_type),
+                    }
+                }
+                if group_doc.meal_slots.is_empty() {
+                    println!();
+                    println!("(built-in defaults)");
+                }
+                return Ok(());
+            }
+            SlotSubcommand::Add {
+                name,
+                time,
+                position,
+            } => {
+                let meal_type = parse_slot_name(name)?;
+                let time = time.as_deref().map(parse_slot_time).transpose()?;
+                group_doc
+                    .add_meal_slot(meal_type.clone(), time, position.map(to_index))
+                    .map_err(GroupError::MealSlot)?;
+                println!("Added meal slot '{}'", meal_type);
+            }
+            SlotSubcommand::Remove { name } => {
+                let meal_type = parse_slot_name(name)?;
+                if !group_doc.remove_meal_slot(&meal_type) {
+                    return Err(GroupError::MealSlot(format!(
+                        "Meal slot '{}' not found",
+                        meal_type
+                    )));
+                }
+                println!("Removed meal slot '{}'", meal_type);
+            }
+            SlotSubcommand::Move { name, position } => {
+                let meal_type = parse_slot_name(name)?;
+                group_doc
+                    .move_meal_slot(&meal_type, to_index(*position))
+                    .map_err(GroupError::MealSlot)?;
+                println!("Moved meal slot '{}' to position {}", meal_type, position);
+            }
+            SlotSubcommand::Time { name, time } => {
+                let meal_type = parse_slot_name(name)?;
+                let time = if time.eq_ignore_ascii_case("none") {
+                    None
+                } else {
+                    Some(parse_slot_time(time)?)
+                };
+                group_doc
+                    .set_meal_slot_time(&meal_type, time)
+                    .map_err(GroupError::MealSlot)?;
+                match time {
+                    Some(t) => println!("Set '{}' time to {}", meal_type, t.format("%H:%M")),
+                    None => println!("Cleared '{}' time", meal_type),
+                }
+            }
+        }
+
+        identity.save_group(&group_id, &group_doc)?;
+        Ok(())
+    }
+}
+
+impl GroupCommand {
+    fn week_start(
+        &self,
+        identity: &Identity,
+        data_dir: &Path,
+        day: Option<&str>,
+    ) -> Result<(), GroupError> {
+        let (group_id, mut group_doc) = resolve_group_document(data_dir, None)
+            .map_err(|e| GroupError::WeekStart(e.to_string()))?;
+
+        let Some(day) = day else {
+            println!(
+                "Shopping weeks for '{}' start on {}",
+                group_doc.name,
+                weekday_name(group_doc.week_start)
+            );
+            return Ok(());
+        };
+
+        let day: Weekday = day.trim().parse().map_err(|_| {
+            GroupError::WeekStart(format!(
+                "Invalid day '{}'. Use a name like 'thursday'.",
+                day
+            ))
+        })?;
+        group_doc.week_start = day;
+        identity.save_group(&group_id, &group_doc)?;
+        println!(
+            "Shopping weeks for '{}' now start on {}",
+            group_doc.name,
+            weekday_name(day)
+        );
+        Ok(())
+    }
+}
+
+/// Full English name of a weekday ("Thursday").
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn parse_slot_name(name: &str) -> Result<MealType, GroupError> {
+    name.parse().map_err(GroupError::MealSlot)
+}
+
+fn parse_slot_time(time: &str) -> Result<NaiveTime, GroupError> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| GroupError::MealSlot(format!("Invalid time '{}'. Use HH:MM.", time)))
+}
+
+/// Convert a 1-based position from the command line to an index.
+fn to_index(position: usize) -> usize {
+    position.saturating_sub(1)
+}
+
 // ==================== Current Group Persistence ====================
 
 fn current_group_path(data_dir: &Path) -> PathBuf {
@@ -338,6 +537,8 @@ pub enum GroupError {
     IdentityError(todu_fit_core::IdentityError),
     InvalidDocId(String, String),
     IoError(std::io::Error),
+    MealSlot(String),
+    WeekStart(String),
 }
 
 impl std::fmt::Display for GroupError {
@@ -346,6 +547,8 @@ impl std::fmt::Display for GroupError {
             GroupError::IdentityError(e) => write!(f, "{}", e),
             GroupError::InvalidDocId(id, e) => write!(f, "Invalid document ID '{}': {}", id, e),
             GroupError::IoError(e) => write!(f, "I/O error: {}", e),
+            GroupError::MealSlot(e) => write!(f, "{}", e),
+            GroupError::WeekStart(e) => write!(f, "{}", e),
         }
     }
 }
@@ -4,9 +4,18 @@ use std::collections::HashMap;
 use std::io::{self, Write};
 use uuid::Uuid;
 
+use todu_fit_core::text::capitalize;
+use todu_fit_core::{
+    evaluate_goals, GoalProgress, Identity, IdentityState, MultiDocStorage, NutritionGoal,
+    PantryItem,
+};
+
 use crate::config::Config;
-use crate::models::{MealLog, MealType};
-use crate::sync::{SyncDishRepository, SyncMealLogRepository, SyncMealPlanRepository};
+use crate::models::{Dish, FoodEntry, MealLog, MealSlot, Nutrient};
+use crate::sync::group_context::{resolve_meal_slots, resolve_meal_type};
+use crate::sync::{
+    SyncDishRepository, SyncMealLogRepository, SyncMealPlanRepository, SyncPantryRepository,
+};
 
 #[derive(Clone, ValueEnum, Default)]
 pub enum OutputFormat {
@@ -20,6 +29,7 @@ pub struct MealRepos<'a> {
     pub meallog: &'a SyncMealLogRepository,
     pub mealplan: &'a SyncMealPlanRepository,
     pub dish: &'a SyncDishRepository,
+    pub pantry: &'a SyncPantryRepository,
 }
 
 #[derive(Args)]
@@ -39,14 +49,19 @@ pub enum MealSubcommand {
         #[arg(long, short)]
         date: Option<String>,
 
-        /// Meal type (breakfast, lunch, dinner, snack) - required for unplanned meals
+        /// Meal type (breakfast, lunch, dinner, snack, or a group meal slot) - required for
+        /// unplanned meals
         #[arg(long = "type", short = 't', value_name = "TYPE")]
         meal_type: Option<String>,
 
-        /// Add dish by ID or name (can be repeated) - for unplanned meals
-        #[arg(long = "dish", value_name = "DISH")]
+        /// Add dish by ID or name (can be repeated) - for unplanned meals. Append
+        /// ':<servings>' for a portion other than one, e.g. 'lasagna:0.5'
+        #[arg(long = "dish", value_name = "DISH[:SERVINGS]")]
         dishes: Vec<String>,
 
+        #[command(flatten)]
+        food: FoodArgs,
+
         /// Add notes to the log
         #[arg(long)]
         notes: Option<String>,
@@ -67,6 +82,36 @@ pub enum MealSubcommand {
         to: Option<String>,
     },
 
+    /// Edit a logged meal, keeping its creation time and plan link
+    Update {
+        /// Meal log ID (UUID)
+        id: String,
+
+        /// New date (YYYY-MM-DD)
+        #[arg(long, short)]
+        date: Option<String>,
+
+        /// New meal type
+        #[arg(long = "type", short = 't', value_name = "TYPE")]
+        meal_type: Option<String>,
+
+        /// Add a dish by ID or name (can be repeated), optionally with ':<servings>'
+        #[arg(long = "add-dish", value_name = "DISH[:SERVINGS]")]
+        add_dishes: Vec<String>,
+
+        /// Remove a logged dish by ID or name (can be repeated)
+        #[arg(long = "remove-dish", value_name = "DISH")]
+        remove_dishes: Vec<String>,
+
+        /// Replace the notes
+        #[arg(long, conflicts_with = "clear_notes")]
+        notes: Option<String>,
+
+        /// Remove the notes
+        #[arg(long)]
+        clear_notes: bool,
+    },
+
     /// Delete a meal log
     Delete {
         /// Meal log ID (UUID)
@@ -78,6 +123,69 @@ pub enum MealSubcommand {
     },
 }
 
+/// An ad-hoc food to log without creating a dish
+#[derive(Args)]
+pub struct FoodArgs {
+    /// Log a food by name, e.g. 'banana' (kept in your meal log only)
+    #[arg(long, value_name = "NAME")]
+    food: Option<String>,
+
+    /// Amount of the food eaten (default 1)
+    #[arg(long, requires = "food")]
+    amount: Option<f64>,
+
+    /// Unit for the amount, e.g. g or cups
+    #[arg(long, requires = "food")]
+    unit: Option<String>,
+
+    /// Calories in the amount eaten
+    #[arg(long, requires = "food")]
+    calories: Option<f64>,
+
+    /// Protein in grams
+    #[arg(long, requires = "food")]
+    protein: Option<f64>,
+
+    /// Carbohydrates in grams
+    #[arg(long, requires = "food")]
+    carbs: Option<f64>,
+
+    /// Fat in grams
+    #[arg(long, requires = "food")]
+    fat: Option<f64>,
+}
+
+impl FoodArgs {
+    fn to_entry(&self) -> Result<Option<FoodEntry>, String> {
+        let Some(name) = &self.food else {
+            return Ok(None);
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Food name cannot be empty".to_string());
+        }
+        let amount = self.amount.unwrap_or(1.0);
+        if amount <= 0.0 {
+            return Err("Food amount must be greater than zero".to_string());
+        }
+
+        let nutrients: Vec<Nutrient> = [
+            ("calories", self.calories, "kcal"),
+            ("protein", self.protein, "g"),
+            ("carbs", self.carbs, "g"),
+            ("fat", self.fat, "g"),
+        ]
+        .into_iter()
+        .filter_map(|(nutrient, value, unit)| value.map(|v| Nutrient::new(nutrient, v, unit)))
+        .collect();
+
+        let unit = self.unit.clone().unwrap_or_default();
+        Ok(Some(
+            FoodEntry::new(name, amount, unit).with_nutrients(nutrients),
+        ))
+    }
+}
+
 impl MealCommand {
     pub fn run(
         &self,
@@ -90,31 +198,139 @@ impl MealCommand {
                 date,
                 meal_type,
                 dishes,
+                food,
                 notes,
             } => {
+                let food = food.to_entry()?;
+
                 // Determine if this is a planned or unplanned meal
-                if let Some(plan_id) = mealplan_id {
+                let (mut log, heading) = if let Some(plan_id) = mealplan_id {
                     // Logging from a plan
-                    self.log_from_plan(plan_id, notes, &repos, config)
+                    self.log_from_plan(plan_id, &repos, config)?
                 } else {
                     // Unplanned meal - require date and type
-                    self.log_unplanned(date, meal_type, dishes, notes, &repos, config)
+                    let log = self.log_unplanned(date, meal_type, dishes, &repos, config)?;
+                    (log, "Logged unplanned meal:".to_string())
+                };
+
+                if let Some(entry) = food {
+                    log.foods.push(entry);
+                }
+                if let Some(n) = notes {
+                    log = log.with_notes(n);
                 }
+
+                // A plan logged before already took its ingredients
+                let already_logged = match log.mealplan_id {
+                    Some(plan_id) => repos
+                        .meallog
+                        .list()?
+                        .iter()
+                        .any(|l| l.mealplan_id == Some(plan_id)),
+                    None => false,
+                };
+                let created = repos.meallog.create(&log)?;
+
+                println!("{}", heading);
+                println!();
+                print_log_details(&created);
+
+                // A logged plan was cooked, so its ingredients leave the pantry
+                if already_logged {
+                    println!("\nPantry: plan was logged before, stock left unchanged");
+                } else if let Some(plan_id) = created.mealplan_id {
+                    let used = use_pantry_stock(plan_id, &repos)?;
+                    if !used.is_empty() {
+                        println!("\nPantry:");
+                        for item in &used {
+                            println!("  - {}", item);
+                        }
+                    }
+                }
+
+                Ok(())
             }
             MealSubcommand::History { format, from, to } => {
-                self.show_history(format, from, to, &repos)
+                self.show_history(format, from, to, &repos, config)
+            }
+            MealSubcommand::Update {
+                id,
+                date,
+                meal_type,
+                add_dishes,
+                remove_dishes,
+                notes,
+                clear_notes,
+            } => {
+                let has_updates = date.is_some()
+                    || meal_type.is_some()
+                    || !add_dishes.is_empty()
+                    || !remove_dishes.is_empty()
+                    || notes.is_some()
+                    || *clear_notes;
+
+                if !has_updates {
+                    return Err("Nothing to update. Provide at least one option.".into());
+                }
+
+                let log_uuid =
+                    Uuid::parse_str(id).map_err(|_| format!("Invalid meal log UUID: {}", id))?;
+                let mut log = repos
+                    .meallog
+                    .get_by_id(log_uuid)?
+                    .ok_or_else(|| format!("Meal log not found: {}", id))?;
+
+                if let Some(d) = date {
+                    log.date = NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                        .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", d))?;
+                }
+                if let Some(mt) = meal_type {
+                    log.meal_type = resolve_meal_type(&config.data_dir.value, mt)?;
+                }
+
+                // Removals match the logged snapshots, so dishes deleted from the
+                // group can still be taken off a log
+                for dish_ref in remove_dishes {
+                    let dish_id = log
+                        .dishes
+                        .iter()
+                        .find(|d| {
+                            d.id.to_string() == *dish_ref || d.name.eq_ignore_ascii_case(dish_ref)
+                        })
+                        .map(|d| d.id)
+                        .ok_or_else(|| format!("Dish not in this meal log: {}", dish_ref))?;
+                    log.remove_dish(dish_id);
+                }
+                for (dish, portion) in resolve_dishes(add_dishes, &repos)? {
+                    log.add_dish(dish, portion);
+                }
+
+                if let Some(n) = notes {
+                    log.notes = Some(n.clone());
+                }
+                if *clear_notes {
+                    log.notes = None;
+                }
+
+                let updated = repos.meallog.update(&log)?;
+
+                println!("Updated meal log:");
+                println!();
+                print_log_details(&updated);
+
+                Ok(())
             }
             MealSubcommand::Delete { id, yes } => self.delete_log(id, *yes, &repos),
         }
     }
 
+    /// Build a log from a meal plan, returning it with the heading to print.
     fn log_from_plan(
         &self,
         mealplan_id: &str,
-        notes: &Option<String>,
         repos: &MealRepos<'_>,
         config: &Config,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(MealLog, String), Box<dyn std::error::Error>> {
         // Parse mealplan UUID
         let plan_uuid = Uuid::parse_str(mealplan_id)
             .map_err(|_| format!("Invalid mealplan UUID: {}", mealplan_id))?;
@@ -125,30 +341,20 @@ impl MealCommand {
             .get_by_id(plan_uuid)?
             .ok_or_else(|| format!("Meal plan not found: {}", mealplan_id))?;
 
-        // Load dishes for the plan (snapshots for the log)
+        // Load dishes for the plan (flattened snapshots for the log)
         let mut plan_dishes = Vec::new();
         for dish_id in &plan.dish_ids {
-            if let Some(dish) = repos.dish.get_by_id(*dish_id)? {
+            if let Some(dish) = repos.dish.get_flattened(*dish_id)? {
                 plan_dishes.push(dish);
             }
         }
 
         // Create meal log from plan with dish snapshots
-        let mut log = MealLog::new(plan.date, plan.meal_type, &config.created_by.value)
+        let log = MealLog::new(plan.date, plan.meal_type, &config.created_by.value)
             .with_mealplan_id(plan.id)
             .with_dishes(plan_dishes);
 
-        if let Some(n) = notes {
-            log = log.with_notes(n);
-        }
-
-        let created = repos.meallog.create(&log)?;
-
-        println!("Logged meal from plan '{}':", plan.title);
-        println!();
-        print_log_details(&created);
-
-        Ok(())
+        Ok((log, format!("Logged meal from plan '{}':", plan.title)))
     }
 
     fn log_unplanned(
@@ -156,10 +362,9 @@ impl MealCommand {
         date: &Option<String>,
         meal_type: &Option<String>,
         dishes: &[String],
-        notes: &Option<String>,
         repos: &MealRepos<'_>,
         config: &Config,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<MealLog, Box<dyn std::error::Error>> {
         // Validate required fields
         let date_str = date
             .as_ref()
@@ -172,39 +377,15 @@ impl MealCommand {
         let parsed_date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
             .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", date_str))?;
 
-        // Parse meal type
-        let parsed_meal_type: MealType = meal_type_str.parse().map_err(|e: String| e)?;
-
-        // Resolve dishes
-        let mut resolved_dishes = Vec::new();
-        for dish_ref in dishes {
-            let dish = if let Ok(uuid) = Uuid::parse_str(dish_ref) {
-                repos.dish.get_by_id(uuid)?
-            } else {
-                repos.dish.get_by_name(dish_ref)?
-            };
-
-            match dish {
-                Some(d) => resolved_dishes.push(d),
-                None => return Err(format!("Dish not found: {}", dish_ref).into()),
-            }
-        }
+        // Parse meal type against the group's meal slots
+        let parsed_meal_type = resolve_meal_type(&config.data_dir.value, meal_type_str)?;
 
-        // Create meal log
-        let mut log = MealLog::new(parsed_date, parsed_meal_type, &config.created_by.value)
-            .with_dishes(resolved_dishes);
-
-        if let Some(n) = notes {
-            log = log.with_notes(n);
+        // Create meal log; repeating a dish adds to its portion
+        let mut log = MealLog::new(parsed_date, parsed_meal_type, &config.created_by.value);
+        for (dish, portion) in resolve_dishes(dishes, repos)? {
+            log.add_dish(dish, portion);
         }
-
-        let created = repos.meallog.create(&log)?;
-
-        println!("Logged unplanned meal:");
-        println!();
-        print_log_details(&created);
-
-        Ok(())
+        Ok(log)
     }
 
     fn show_history(
@@ -213,6 +394,7 @@ impl MealCommand {
         from: &Option<String>,
         to: &Option<String>,
         repos: &MealRepos<'_>,
+        config: &Config,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Parse date range
         let today = Local::now().date_naive();
@@ -228,72 +410,89 @@ impl MealCommand {
         };
 
         // Fetch meal logs
-        let logs = repos.meallog.list_range(from_date, to_date)?;
+        let mut logs = repos.meallog.list_range(from_date, to_date)?;
 
         if logs.is_empty() {
             println!("No meal history found for {} to {}", from_date, to_date);
             return Ok(());
         }
 
+        let goals = load_goals(config);
+
+        // Group logs by day, in meal slot order
+        let slots = resolve_meal_slots(&config.data_dir.value);
+        logs.sort_by_key(|l| (l.date, MealSlot::rank(&slots, &l.meal_type)));
+        let mut days: Vec<(NaiveDate, Vec<&MealLog>)> = Vec::new();
+        for log in &logs {
+            match days.last_mut() {
+                Some((date, day_logs)) if *date == log.date => day_logs.push(log),
+                _ => days.push((log.date, vec![log])),
+            }
+        }
+
         match format {
             OutputFormat::Json => {
-                println!("{}", serde_json::to_string_pretty(&logs)?);
+                let days_json: Vec<serde_json::Value> = days
+                    .iter()
+                    .map(|(date, day_logs)| {
+                        let totals = sum_nutrients(day_logs);
+                        serde_json::json!({
+                            "date": date,
+                            "totals": totals,
+                            "goals": evaluate_goals(&goals, &totals),
+                        })
+                    })
+                    .collect();
+                let output = serde_json::json!({
+                    "logs": logs,
+                    "days": days_json,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
             }
             OutputFormat::Text => {
-                let mut current_date: Option<NaiveDate> = None;
-                let mut daily_nutrients: HashMap<String, f64> = HashMap::new();
-
-                for log in &logs {
-                    // Print date header and daily totals when date changes
-                    if current_date != Some(log.date) {
-                        // Print previous day's totals if we have them
-                        if current_date.is_some() && !daily_nutrients.is_empty() {
-                            print_daily_totals(&daily_nutrients);
-                            daily_nutrients.clear();
-                        }
-                        if current_date.is_some() {
-                            println!();
-                        }
-                        println!("{}", log.date);
-                        println!("{}", "-".repeat(60));
-                        current_date = Some(log.date);
+                for (i, (date, day_logs)) in days.iter().enumerate() {
+                    if i > 0 {
+                        println!();
                     }
+                    println!("{}", date);
+                    println!("{}", "-".repeat(60));
+
+                    for log in day_logs {
+                        // Determine if planned or unplanned
+                        let plan_indicator = if log.mealplan_id.is_some() {
+                            "(planned)"
+                        } else {
+                            "(unplanned)"
+                        };
+
+                        // Build dish and food summary
+                        let items = log_items(log);
+                        let dishes_str = if items.is_empty() {
+                            String::new()
+                        } else {
+                            format!(": {}", items.join(", "))
+                        };
+
+                        println!("  {:10} {}{}", log.meal_type, plan_indicator, dishes_str);
+
+                        // Calculate and display meal nutrients
+                        let meal_nutrients = log.nutrient_totals();
+                        if !meal_nutrients.is_empty() {
+                            println!("             {}", format_nutrients(&meal_nutrients));
+                        }
 
-                    // Determine if planned or unplanned
-                    let plan_indicator = if log.mealplan_id.is_some() {
-                        "(planned)"
-                    } else {
-                        "(unplanned)"
-                    };
-
-                    // Build dish summary
-                    let dishes_str = if log.dishes.is_empty() {
-                        String::new()
-                    } else {
-                        let names: Vec<&str> = log.dishes.iter().map(|d| d.name.as_str()).collect();
-                        format!(": {}", names.join(", "))
-                    };
-
-                    println!("  {:10} {}{}", log.meal_type, plan_indicator, dishes_str);
-
-                    // Calculate and display meal nutrients
-                    let meal_nutrients = calculate_meal_nutrients(log);
-                    if !meal_nutrients.is_empty() {
-                        println!("             {}", format_nutrients(&meal_nutrients));
-                        // Add to daily totals
-                        for (name, amount) in &meal_nutrients {
-                            *daily_nutrients.entry(name.clone()).or_insert(0.0) += amount;
+                        if let Some(notes) = &log.notes {
+                            println!("             Notes: {}", notes);
                         }
                     }
 
-                    if let Some(notes) = &log.notes {
-                        println!("             Notes: {}", notes);
+                    let daily_nutrients = sum_nutrients(day_logs);
+                    if !daily_nutrients.is_empty() {
+                        print_daily_totals(&daily_nutrients);
+                    }
+                    if !goals.is_empty() {
+                        print_goal_progress(&evaluate_goals(&goals, &daily_nutrients));
                     }
-                }
-
-                // Print final day's totals
-                if !daily_nutrients.is_empty() {
-                    print_daily_totals(&daily_nutrients);
                 }
 
                 println!("\nTotal: {} meal(s)", logs.len());
@@ -319,11 +518,11 @@ impl MealCommand {
             .ok_or_else(|| format!("Meal log not found: {}", id))?;
 
         // Build description for confirmation
-        let dishes_str = if log.dishes.is_empty() {
+        let items = log_items(&log);
+        let dishes_str = if items.is_empty() {
             String::new()
         } else {
-            let names: Vec<&str> = log.dishes.iter().map(|d| d.name.as_str()).collect();
-            format!(" ({})", names.join(", "))
+            format!(" ({})", items.join(", "))
         };
         let log_desc = format!(
             "{} {} {}{}",
@@ -357,23 +556,64 @@ impl MealCommand {
     }
 }
 
-/// Calculate total nutrients for a meal by summing all dish nutrients
-fn calculate_meal_nutrients(log: &MealLog) -> HashMap<String, f64> {
-    let mut totals: HashMap<String, f64> = HashMap::new();
+/// Take a cooked plan's ingredients out of the pantry.
+///
+/// Returns the pantry items whose stock changed. Staples and ingredients
+/// that aren't stocked are left alone.
+fn use_pantry_stock(
+    plan_id: Uuid,
+    repos: &MealRepos<'_>,
+) -> Result<Vec<PantryItem>, Box<dyn std::error::Error>> {
+    let mut pantry = repos.pantry.get()?;
+    let Some(mut plan) = repos.mealplan.get_by_id(plan_id)? else {
+        return Ok(Vec::new());
+    };
+    repos.mealplan.unlink_missing_source(&mut plan)?;
+    if pantry.items.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    for dish in &log.dishes {
-        if let Some(nutrients) = &dish.nutrients {
-            for nutrient in nutrients {
-                *totals.entry(nutrient.name.clone()).or_insert(0.0) += nutrient.amount;
-            }
+    let dishes = repos.dish.list()?;
+    let mut used: Vec<PantryItem> = Vec::new();
+    for ing in plan.ingredients(&dishes)? {
+        if pantry.is_staple(&ing.name) {
+            continue;
+        }
+        if let Some(item) = pantry.consume(&ing.name, ing.quantity, &ing.unit) {
+            used.retain(|u| u.key() != item.key());
+            used.push(item.clone());
         }
     }
 
+    repos.pantry.save_items(&used)?;
+    Ok(used)
+}
+
+/// Sum nutrients across all meals of a day
+fn sum_nutrients(logs: &[&MealLog]) -> HashMap<String, f64> {
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for log in logs {
+        for (name, amount) in log.nutrient_totals() {
+            *totals.entry(name).or_insert(0.0) += amount;
+        }
+    }
     totals
 }
 
+/// Load the user's nutrition goals, or none if the identity isn't available
+pub(crate) fn load_goals(config: &Config) -> Vec<NutritionGoal> {
+    let identity = Identity::new(MultiDocStorage::new(config.data_dir.value.clone()));
+    if identity.state() != IdentityState::Initialized {
+        return Vec::new();
+    }
+    identity
+        .load_identity()
+        .map(|doc| doc.nutrition_goals)
+        .unwrap_or_default()
+}
+
 /// Format nutrients for display: "Calories: 650 | Protein: 25g | Carbs: 80g | Fat: 28g"
-fn format_nutrients(nutrients: &HashMap<String, f64>) -> String {
+pub(crate) fn format_nutrients(nutrients: &HashMap<String, f64>) -> String {
     // Order: calories first, then protein, carbs, fat, then others
     let order = ["calories", "protein", "carbs", "fat"];
     let mut parts: Vec<String> = Vec::new();
@@ -401,21 +641,105 @@ fn print_daily_totals(nutrients: &HashMap<String, f64>) {
     println!("  Daily Total: {}", format_nutrients(nutrients));
 }
 
-fn capitalize(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+/// Print progress towards each goal: "Protein: 90 / 120 g (75%), 30 g left [under]"
+fn print_goal_progress(progress: &[GoalProgress]) {
+    println!("  Goals:");
+    for p in progress {
+        let remaining = if p.remaining >= 0.0 {
+            format!("{:.0} {} left", p.remaining, p.unit)
+        } else {
+            format!("{:.0} {} over", -p.remaining, p.unit)
+        };
+        println!(
+            "    {}: {:.0} / {:.0} {} ({:.0}%), {} [{}]",
+            capitalize(&p.nutrient),
+            p.actual,
+            p.goal,
+            p.unit,
+            p.percent,
+            remaining,
+            p.status
+        );
+    }
+}
+
+/// Look up `--dish` values in the group's dishes, with their portions.
+fn resolve_dishes(
+    dish_args: &[String],
+    repos: &MealRepos<'_>,
+) -> Result<Vec<(Dish, f64)>, Box<dyn std::error::Error>> {
+    let mut resolved = Vec::new();
+    for dish_arg in dish_args {
+        let (dish_ref, portion) = parse_dish_portion(dish_arg)?;
+        let dish = if let Ok(uuid) = Uuid::parse_str(dish_ref) {
+            repos.dish.get_by_id(uuid)?
+        } else {
+            repos.dish.get_by_name(dish_ref)?
+        };
+
+        // Snapshots are flattened so they keep sub-recipe nutrients
+        match dish {
+            Some(d) => resolved.push((repos.dish.flatten(&d)?, portion)),
+            None => return Err(format!("Dish not found: {}", dish_ref).into()),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Split a `--dish` value into the dish reference and its portion.
+///
+/// "lasagna:0.5" is half a serving of lasagna. The suffix only counts as a
+/// portion when it is a number, so names containing ':' still work.
+fn parse_dish_portion(value: &str) -> Result<(&str, f64), String> {
+    if let Some((name, suffix)) = value.rsplit_once(':') {
+        if let Ok(portion) = suffix.trim().parse::<f64>() {
+            if !name.trim().is_empty() {
+                if portion <= 0.0 || !portion.is_finite() {
+                    return Err(format!(
+                        "Invalid portion '{}' for {}: portion must be positive",
+                        suffix.trim(),
+                        name.trim()
+                    ));
+                }
+                return Ok((name.trim(), portion));
+            }
+        }
+    }
+    Ok((value, 1.0))
+}
+
+/// Dish name with its portion when it isn't a single serving: "Lasagna x0.5"
+fn dish_label(log: &MealLog, dish: &Dish) -> String {
+    let portion = log.portion_of(dish.id);
+    if portion == 1.0 {
+        dish.name.clone()
+    } else {
+        format!("{} x{}", dish.name, portion)
     }
 }
 
+/// Labels for everything eaten in a log: dishes first, then ad-hoc foods
+fn log_items(log: &MealLog) -> Vec<String> {
+    log.dishes
+        .iter()
+        .map(|d| dish_label(log, d))
+        .chain(log.foods.iter().map(|f| f.to_string()))
+        .collect()
+}
+
 fn print_log_details(log: &MealLog) {
     println!("  Date: {}", log.date);
     println!("  Meal: {}", log.meal_type);
     if !log.dishes.is_empty() {
         println!("  Dishes:");
         for dish in &log.dishes {
-            println!("    - {}", dish.name);
+            println!("    - {}", dish_label(log, dish));
+        }
+    }
+    if !log.foods.is_empty() {
+        println!("  Foods:");
+        for food in &log.foods {
+            println!("    - {}", food);
         }
     }
     if let Some(n) = &log.notes {
@@ -424,3 +748,19 @@ fn print_log_details(log: &MealLog) {
     println!();
     println!("Log ID: {}", log.id);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dish_portion() {
+        assert_eq!(parse_dish_portion("lasagna:0.5"), Ok(("lasagna", 0.5)));
+        assert_eq!(parse_dish_portion("lasagna"), Ok(("lasagna", 1.0)));
+        assert_eq!(parse_dish_portion("soup: miso"), Ok(("soup: miso", 1.0)));
+        for value in ["lasagna:0", "lasagna:-1"] {
+            let err = parse_dish_portion(value).unwrap_err();
+            assert!(err.contains("portion must be positive"), "{}", err);
+        }
+    }
+}
@@ -1,11 +1,25 @@
 use chrono::{Local, NaiveDate};
 use clap::{Args, Subcommand, ValueEnum};
-use std::io::{self, Write};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::commands::meal::{format_nutrients, load_goals};
 use crate::config::Config;
-use crate::models::{MealPlan, MealType};
-use crate::sync::{SyncDishRepository, SyncMealPlanRepository};
+use crate::models::{MealPlan, MealSlot, MealType};
+use crate::sync::group_context::{
+    resolve_group_document, resolve_meal_slots, resolve_meal_type, save_group_document,
+};
+use crate::sync::{SyncDishRepository, SyncMealLogRepository, SyncMealPlanRepository};
+use todu_fit_core::formats::ical::{self, CalendarEvent};
+use todu_fit_core::models::default_title;
+use todu_fit_core::{plan_generator, plan_ops};
+use todu_fit_core::{
+    ConflictStrategy, Dish, GeneratorOptions, PlanChanges, PlanOpError, PlanTemplate, Recurrence,
+    RecurringPlan, SlotRule,
+};
 
 #[derive(Clone, ValueEnum, Default)]
 pub enum OutputFormat {
@@ -28,7 +42,7 @@ pub enum MealPlanSubcommand {
         #[arg(long, short)]
         date: String,
 
-        /// Meal type (breakfast, lunch, dinner, snack)
+        /// Meal type (breakfast, lunch, dinner, snack, or a group meal slot)
         #[arg(long = "type", short = 't', value_name = "TYPE")]
         meal_type: String,
 
@@ -43,6 +57,16 @@ pub enum MealPlanSubcommand {
         /// Add dish by ID or name (can be repeated)
         #[arg(long = "dish", value_name = "DISH")]
         dishes: Vec<String>,
+
+        /// Servings being cooked (scales shopping list and nutrition), or
+        /// eaten for leftovers
+        #[arg(long)]
+        servings: Option<i32>,
+
+        /// Meal plan ID this meal eats the leftovers of (uses its dishes and
+        /// cook unless given, and adds nothing to the shopping list)
+        #[arg(long, value_name = "PLAN")]
+        leftovers_of: Option<String>,
     },
 
     /// List meal plans
@@ -98,6 +122,19 @@ pub enum MealPlanSubcommand {
         /// New cook
         #[arg(long)]
         cook: Option<String>,
+
+        /// Servings being cooked (scales shopping list and nutrition), or
+        /// eaten for leftovers
+        #[arg(long)]
+        servings: Option<i32>,
+
+        /// Meal plan ID this meal eats the leftovers of
+        #[arg(long, value_name = "PLAN", conflicts_with = "cooked")]
+        leftovers_of: Option<String>,
+
+        /// Cook this meal rather than eating leftovers
+        #[arg(long)]
+        cooked: bool,
     },
 
     /// Delete a meal plan
@@ -127,6 +164,284 @@ pub enum MealPlanSubcommand {
         /// Dish ID (UUID) or name
         dish: String,
     },
+
+    /// Copy a week of meal plans to another week
+    Copy {
+        /// Any date in the week to copy (YYYY-MM-DD)
+        #[arg(long)]
+        from_week: String,
+
+        /// Any date in the week to fill (YYYY-MM-DD)
+        #[arg(long)]
+        to_week: String,
+
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+
+    /// Move meal plans by a number of days, e.g. when a trip moves everything
+    Shift {
+        /// First date to move (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+
+        /// Last date to move (YYYY-MM-DD), defaults to every later plan
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Days to move by (negative moves plans earlier)
+        #[arg(long, allow_negative_numbers = true)]
+        days: i64,
+
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+
+    /// Swap the date and meal of two meal plans
+    Swap {
+        /// First meal plan ID (UUID)
+        plan_a: String,
+
+        /// Second meal plan ID (UUID)
+        plan_b: String,
+
+        /// Show the result without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Fill a week's empty meal slots with dishes, asking before saving
+    Generate {
+        /// Any date in the week to fill (YYYY-MM-DD), defaults to this week
+        #[arg(long, short)]
+        week: Option<String>,
+
+        /// Slot to fill, as TYPE or TYPE:TAG,TAG to use only dishes with one
+        /// of the tags (can be repeated; defaults to every meal slot)
+        #[arg(long = "slot", value_name = "SLOT")]
+        slots: Vec<String>,
+
+        /// Most prep and cook minutes on a weeknight (Monday to Thursday)
+        #[arg(long, value_name = "MINUTES")]
+        weeknight_max: Option<i32>,
+
+        /// Don't repeat a dish within this many days of it being planned or eaten
+        #[arg(long, value_name = "DAYS", default_value = "7")]
+        no_repeat_days: i64,
+
+        /// Prefer dishes that fit your daily nutrition goals (see 'fit goal')
+        #[arg(long)]
+        goals: bool,
+
+        /// Random seed, to get the same proposal again
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Save the proposal without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Export meal plans as calendar events
+    ///
+    /// Each plan's event keeps its UID across exports, so calendar apps
+    /// update the events of a re-exported file instead of adding them again.
+    Export {
+        /// Calendar format
+        #[arg(long, short, value_enum, default_value = "ics")]
+        format: CalendarFormat,
+
+        /// Start date (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End date (YYYY-MM-DD), defaults to 4 weeks from start
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Create or update meal plans from a calendar file
+    ///
+    /// Events exported by 'mealplan export' update the plans they came from.
+    /// Other events become plans in the slot named by their categories or
+    /// closest to their start time; importing them again updates those plans.
+    Import {
+        /// Calendar file; reads stdin when omitted
+        file: Option<PathBuf>,
+
+        /// Calendar format
+        #[arg(long, short, value_enum, default_value = "ics")]
+        format: CalendarFormat,
+
+        /// Show what would change without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Save and apply weekly plan templates
+    Template {
+        #[command(subcommand)]
+        command: TemplateSubcommand,
+    },
+
+    /// Manage meal plans that repeat on a schedule
+    Recurring {
+        #[command(subcommand)]
+        command: RecurringSubcommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RecurringSubcommand {
+    /// Add a recurring meal plan
+    Add {
+        /// Meal type (breakfast, lunch, dinner, snack, or a group meal slot)
+        #[arg(long = "type", short = 't', value_name = "TYPE")]
+        meal_type: String,
+
+        /// Recurrence rule, e.g. "FREQ=WEEKLY;BYDAY=TU",
+        /// "FREQ=WEEKLY;INTERVAL=2;BYDAY=FR" or "FREQ=MONTHLY;BYDAY=-1SU"
+        #[arg(long)]
+        rule: String,
+
+        /// Title (defaults to "<MealType> on <date>" for each occurrence)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Cook name
+        #[arg(long)]
+        cook: Option<String>,
+
+        /// Add dish by ID or name (can be repeated)
+        #[arg(long = "dish", value_name = "DISH")]
+        dishes: Vec<String>,
+
+        /// Servings being cooked (scales shopping list and nutrition)
+        #[arg(long)]
+        servings: Option<i32>,
+
+        /// First date of the series (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        start: Option<String>,
+
+        /// Last date of the series (YYYY-MM-DD), instead of UNTIL in the rule
+        #[arg(long)]
+        until: Option<String>,
+    },
+
+    /// List recurring meal plans
+    List {
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Skip one occurrence without changing the rest of the series
+    Skip {
+        /// Recurring plan ID or title
+        series: String,
+
+        /// Date of the occurrence (YYYY-MM-DD)
+        #[arg(long, short)]
+        date: String,
+    },
+
+    /// Bring back a skipped occurrence
+    Restore {
+        /// Recurring plan ID or title
+        series: String,
+
+        /// Date of the occurrence (YYYY-MM-DD)
+        #[arg(long, short)]
+        date: String,
+    },
+
+    /// Delete a recurring plan (edited occurrences are kept as plain plans)
+    Delete {
+        /// Recurring plan ID or title
+        series: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplateSubcommand {
+    /// Save a week of meal plans as a template (replaces one with the same name)
+    Save {
+        /// Template name
+        name: String,
+
+        /// Any date in the week to capture (YYYY-MM-DD), defaults to this week
+        #[arg(long, short)]
+        week: Option<String>,
+    },
+
+    /// Create a week of meal plans from a template
+    Apply {
+        /// Template name
+        name: String,
+
+        /// Any date in the week to fill (YYYY-MM-DD), defaults to this week
+        #[arg(long, short)]
+        week: Option<String>,
+
+        /// Replace existing plans for the same day and meal instead of skipping them
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// List plan templates
+    List,
+
+    /// Delete a plan template
+    Delete {
+        /// Template name
+        name: String,
+    },
+}
+
+/// Options shared by bulk meal plan operations.
+#[derive(Args)]
+pub struct BulkArgs {
+    /// What to do with plans already on a target day and meal
+    #[arg(long, value_enum, default_value = "skip")]
+    on_conflict: OnConflict,
+
+    /// Show what would change without changing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Calendar formats for `mealplan export` and `mealplan import`
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum CalendarFormat {
+    /// iCalendar (.ics)
+    #[default]
+    Ics,
+}
+
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum OnConflict {
+    /// Keep the existing plan
+    #[default]
+    Skip,
+    /// Overwrite the existing plan
+    Replace,
+    /// Change nothing if any target is taken
+    Fail,
+}
+
+impl From<OnConflict> for ConflictStrategy {
+    fn from(on_conflict: OnConflict) -> Self {
+        match on_conflict {
+            OnConflict::Skip => ConflictStrategy::Skip,
+            OnConflict::Replace => ConflictStrategy::Replace,
+            OnConflict::Fail => ConflictStrategy::Fail,
+        }
+    }
 }
 
 impl MealPlanCommand {
@@ -143,47 +458,57 @@ impl MealPlanCommand {
                 title,
                 cook,
                 dishes,
+                servings,
+                leftovers_of,
             } => {
                 // Parse date
                 let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
                     .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", date))?;
 
-                // Parse meal type
-                let meal_type: MealType = meal_type.parse().map_err(|e: String| e)?;
+                let source = match leftovers_of {
+                    Some(id) => Some(leftovers_source(mealplan_repo, id, None, date)?),
+                    None => None,
+                };
 
-                // Build title
-                let title = title.clone().unwrap_or_else(|| {
-                    format!("{} on {}", capitalize(&meal_type.to_string()), date)
-                });
+                // Parse meal type against the group's meal slots
+                let meal_type = resolve_meal_type(&config.data_dir.value, meal_type)?;
 
-                // Build cook
-                let cook = cook
+                // Build title
+                let title = title
                     .clone()
-                    .unwrap_or_else(|| config.created_by.value.clone());
+                    .unwrap_or_else(|| default_title(&meal_type, date));
+
+                // Build cook; leftovers were cooked by the source plan's cook
+                let cook = cook.clone().unwrap_or_else(|| match &source {
+                    Some(source) => source.cook.clone(),
+                    None => config.created_by.value.clone(),
+                });
 
                 // Create meal plan
                 let mut plan = MealPlan::new(date, meal_type, &title, &config.created_by.value)
                     .with_cook(&cook);
+                if let Some(s) = servings {
+                    if *s <= 0 {
+                        return Err("Servings must be greater than zero".into());
+                    }
+                    plan = plan.with_servings(*s);
+                }
 
                 // Resolve and add dishes
-                let mut resolved_dish_ids = Vec::new();
-                for dish_ref in dishes {
-                    let dish = if let Ok(uuid) = Uuid::parse_str(dish_ref) {
-                        dish_repo.get_by_id(uuid)?
-                    } else {
-                        dish_repo.get_by_name(dish_ref)?
-                    };
-
-                    match dish {
-                        Some(d) => resolved_dish_ids.push(d.id),
-                        None => return Err(format!("Dish not found: {}", dish_ref).into()),
+                plan.dish_ids = resolve_dish_ids(dish_repo, dishes)?;
+                if let Some(source) = &source {
+                    plan.leftovers_of = Some(source.id);
+                    if plan.dish_ids.is_empty() {
+                        plan.dish_ids = source.dish_ids.clone();
                     }
                 }
-                plan.dish_ids = resolved_dish_ids;
 
                 let created = mealplan_repo.create(&plan)?;
                 println!("Created meal plan:");
                 println!("{}", created);
+                if let Some(source) = &source {
+                    print_leftover_usage(source, mealplan_repo, dish_repo)?;
+                }
                 Ok(())
             }
 
@@ -220,6 +545,10 @@ impl MealPlanCommand {
                     plans.retain(|p| p.meal_type == mt);
                 }
 
+                // Order by date, then by the group's meal slot order
+                let slots = resolve_meal_slots(&config.data_dir.value);
+                plans.sort_by_key(|p| (p.date, MealSlot::rank(&slots, &p.meal_type)));
+
                 if plans.is_empty() {
                     println!("No meal plans found");
                     return Ok(());
@@ -248,7 +577,26 @@ impl MealPlanCommand {
                             } else {
                                 format!("{} dishes", dish_count)
                             };
-                            println!("  {:10} {} ({})", plan.meal_type, plan.title, dishes_str);
+                            let leftovers = match plan.leftovers_of {
+                                Some(source_id) => {
+                                    let source = match plans.iter().find(|p| p.id == source_id) {
+                                        Some(source) => Some(source.clone()),
+                                        None => mealplan_repo.get_by_id(source_id)?,
+                                    };
+                                    match source {
+                                        Some(source) => format!(
+                                            " [leftovers of {} {}]",
+                                            source.date, source.meal_type
+                                        ),
+                                        None => " [leftovers of a deleted plan]".to_string(),
+                                    }
+                                }
+                                None => String::new(),
+                            };
+                            println!(
+                                "  {:10} {} ({}){}",
+                                plan.meal_type, plan.title, dishes_str, leftovers
+                            );
                         }
                         println!("\nTotal: {} meal plan(s)", plans.len());
                     }
@@ -307,11 +655,27 @@ impl MealPlanCommand {
                                 println!("\n{}\n", "=".repeat(40));
                             }
                             println!("{}", plan);
+                            match plan.leftovers_of {
+                                Some(source_id) => {
+                                    if let Some(source) = mealplan_repo.get_by_id(source_id)? {
+                                        print_leftover_usage(&source, mealplan_repo, dish_repo)?;
+                                    }
+                                }
+                                None => print_leftover_usage(plan, mealplan_repo, dish_repo)?,
+                            }
 
                             // Show dish details (load from repository)
                             if !plan.dish_ids.is_empty() {
+                                let mut totals: HashMap<String, f64> = HashMap::new();
                                 for dish_id in &plan.dish_ids {
-                                    if let Some(dish) = dish_repo.get_by_id(*dish_id)? {
+                                    if let Some(dish) = dish_repo.get_flattened(*dish_id)? {
+                                        // Nutrients are per serving; count what is being cooked
+                                        let servings =
+                                            plan.servings.or(dish.servings).unwrap_or(1) as f64;
+                                        for n in dish.nutrients_for_servings(servings) {
+                                            *totals.entry(n.name).or_insert(0.0) += n.amount;
+                                        }
+
                                         println!("\n  {}", dish.name);
                                         println!("  {}", "-".repeat(dish.name.len()));
                                         if !dish.ingredients.is_empty() {
@@ -322,6 +686,10 @@ impl MealPlanCommand {
                                         }
                                     }
                                 }
+
+                                if !totals.is_empty() {
+                                    println!("\nNutrition Total: {}", format_nutrients(&totals));
+                                }
                             }
                         }
                     }
@@ -335,10 +703,18 @@ impl MealPlanCommand {
                 meal_type,
                 title,
                 cook,
+                servings,
+                leftovers_of,
+                cooked,
             } => {
                 // Check if any updates provided
-                let has_updates =
-                    date.is_some() || meal_type.is_some() || title.is_some() || cook.is_some();
+                let has_updates = date.is_some()
+                    || meal_type.is_some()
+                    || title.is_some()
+                    || cook.is_some()
+                    || servings.is_some()
+                    || leftovers_of.is_some()
+                    || *cooked;
 
                 if !has_updates {
                     return Err("Nothing to update. Provide at least one option.".into());
@@ -358,7 +734,7 @@ impl MealPlanCommand {
                         .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", d))?;
                 }
                 if let Some(mt) = meal_type {
-                    plan.meal_type = mt.parse().map_err(|e: String| e)?;
+                    plan.meal_type = resolve_meal_type(&config.data_dir.value, mt)?;
                 }
                 if let Some(t) = title {
                     plan.title = t.clone();
@@ -366,10 +742,40 @@ impl MealPlanCommand {
                 if let Some(c) = cook {
                     plan.cook = c.clone();
                 }
+                if let Some(s) = servings {
+                    if *s <= 0 {
+                        return Err("Servings must be greater than zero".into());
+                    }
+                    plan.servings = Some(*s);
+                }
+
+                let source = match leftovers_of {
+                    Some(source_id) => Some(leftovers_source(
+                        mealplan_repo,
+                        source_id,
+                        Some(plan.id),
+                        plan.date,
+                    )?),
+                    None => match plan.leftovers_of.filter(|_| !*cooked) {
+                        Some(source_id) => mealplan_repo.get_by_id(source_id)?,
+                        None => None,
+                    },
+                };
+                if *cooked {
+                    plan.leftovers_of = None;
+                } else if let Some(source) = &source {
+                    if source.date > plan.date {
+                        return Err(leftovers_too_early(source).into());
+                    }
+                    plan.leftovers_of = Some(source.id);
+                }
 
                 let updated = mealplan_repo.update(&plan)?;
                 println!("Updated meal plan:");
                 println!("{}", updated);
+                if let Some(source) = &source {
+                    print_leftover_usage(source, mealplan_repo, dish_repo)?;
+                }
                 Ok(())
             }
 
@@ -382,6 +788,20 @@ impl MealPlanCommand {
                     .get_by_id(uuid)?
                     .ok_or_else(|| format!("Meal plan not found: {}", id))?;
 
+                let plans = mealplan_repo.list()?;
+                let leftovers: Vec<&MealPlan> = plan.leftover_plans(&plans).collect();
+                if !leftovers.is_empty() {
+                    let titles: Vec<String> = leftovers
+                        .iter()
+                        .map(|p| format!("{} ({} {})", p.title, p.meal_type, p.date))
+                        .collect();
+                    println!(
+                        "Warning: these plans eat the leftovers of '{}' and will cook their own dishes instead: {}",
+                        plan.title,
+                        titles.join(", ")
+                    );
+                }
+
                 // Confirm unless --force
                 if !force {
                     print!(
@@ -399,7 +819,23 @@ impl MealPlanCommand {
                     }
                 }
 
-                mealplan_repo.delete(uuid)?;
+                if leftovers.is_empty() {
+                    mealplan_repo.delete(uuid)?;
+                } else {
+                    let changes = PlanChanges {
+                        writes: leftovers
+                            .iter()
+                            .map(|p| MealPlan {
+                                leftovers_of: None,
+                                ..(*p).clone()
+                            })
+                            .collect(),
+                        deletes: vec![uuid],
+                        conflicts: Vec::new(),
+                    };
+                    let message = format!("Delete meal plan {}", plan.title);
+                    mealplan_repo.apply_changes(&changes, &message)?;
+                }
                 println!("Deleted meal plan: {}", plan.title);
                 Ok(())
             }
@@ -453,14 +889,975 @@ impl MealPlanCommand {
                 println!("Removed '{}' from '{}'", resolved_dish.name, plan.title);
                 Ok(())
             }
+
+            MealPlanSubcommand::Copy {
+                from_week,
+                to_week,
+                bulk,
+            } => {
+                let group = resolve_group_document(&config.data_dir.value, None)?.1;
+                let source_start = group.week_containing(parse_date(from_week)?);
+                let target_start = group.week_containing(parse_date(to_week)?);
+                if source_start == target_start {
+                    return Err("Source and target are the same week".into());
+                }
+
+                let week_end = |start: NaiveDate| start + chrono::Duration::days(6);
+                let plans = mealplan_repo.list_range(source_start, week_end(source_start))?;
+                if plans.is_empty() {
+                    return Err(format!("No meal plans in the week of {}", source_start).into());
+                }
+                let existing = mealplan_repo.list_range(target_start, week_end(target_start))?;
+
+                let days = (target_start - source_start).num_days();
+                let changes = plan_ops::copy_plans(
+                    &plans,
+                    days,
+                    &existing,
+                    bulk.on_conflict.into(),
+                    &config.created_by.value,
+                )
+                .map_err(conflict_error)?;
+
+                let message = format!(
+                    "Copy meal plans from the week of {} to the week of {}",
+                    source_start, target_start
+                );
+                apply_bulk(mealplan_repo, &changes, bulk, config, &message)
+            }
+
+            MealPlanSubcommand::Shift {
+                from,
+                to,
+                days,
+                bulk,
+            } => {
+                if *days == 0 {
+                    return Err("--days must not be zero".into());
+                }
+                let from = parse_date(from)?;
+                let to = to.as_deref().map(parse_date).transpose()?;
+
+                let mut existing = mealplan_repo.list()?;
+
+                // Occurrences of recurring plans move along, saved as plans
+                // that override them; without --to that is up to the last
+                // plan that moves, and later ones keep their schedule
+                let last = to.or_else(|| existing.iter().map(|p| p.date).max());
+                let plans = match last {
+                    Some(last) if last >= from => mealplan_repo.list_range(from, last)?,
+                    _ => Vec::new(),
+                };
+                if plans.is_empty() {
+                    return Err(format!("No meal plans from {}", from).into());
+                }
+                let (_, group) = resolve_group_document(&config.data_dir.value, None)?;
+                let unmoved_series = match (to, last) {
+                    (None, Some(last)) => group
+                        .recurring_plans
+                        .iter()
+                        .filter(|r| r.rule.until.is_none_or(|until| until > last))
+                        .map(|r| r.title.as_str())
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                // Occurrences of recurring plans in the target dates are in
+                // the way too
+                let shifted = plans.iter().map(|p| p.date + chrono::Duration::days(*days));
+                let (first, last) = (shifted.clone().min(), shifted.max());
+                if let (Some(first), Some(last)) = (first, last) {
+                    for plan in mealplan_repo.list_range(first, last)? {
+                        if !existing.iter().any(|p| p.id == plan.id) {
+                            existing.push(plan);
+                        }
+                    }
+                }
+
+                let changes =
+                    plan_ops::shift_plans(&plans, *days, &existing, bulk.on_conflict.into())
+                        .map_err(conflict_error)?;
+
+                let message = format!("Shift meal plans from {} by {} day(s)", from, days);
+                apply_bulk(mealplan_repo, &changes, bulk, config, &message)?;
+                if !unmoved_series.is_empty() {
+                    println!(
+                        "Recurring plans after {} keep their schedule: {} (use --to to move more)",
+                        last.unwrap_or(from),
+                        unmoved_series.join(", ")
+                    );
+                }
+                Ok(())
+            }
+
+            MealPlanSubcommand::Swap {
+                plan_a,
+                plan_b,
+                dry_run,
+            } => {
+                let get = |id: &str| -> Result<MealPlan, Box<dyn std::error::Error>> {
+                    let uuid = Uuid::parse_str(id).map_err(|_| format!("Invalid UUID: {}", id))?;
+                    Ok(mealplan_repo
+                        .get_by_id(uuid)?
+                        .ok_or_else(|| format!("Meal plan not found: {}", id))?)
+                };
+                let (a, b) = (get(plan_a)?, get(plan_b)?);
+                let changes = plan_ops::swap_plans(&a, &b)?;
+
+                let bulk = BulkArgs {
+                    on_conflict: OnConflict::Fail,
+                    dry_run: *dry_run,
+                };
+                let message = format!("Swap meal plans '{}' and '{}'", a.title, b.title);
+                apply_bulk(mealplan_repo, &changes, &bulk, config, &message)
+            }
+
+            MealPlanSubcommand::Generate {
+                week,
+                slots,
+                weeknight_max,
+                no_repeat_days,
+                goals,
+                seed,
+                yes,
+            } => {
+                let group = resolve_group_document(&config.data_dir.value, None)?.1;
+                let week_start = group.week_containing(parse_date_or_today(week.as_deref())?);
+                let week_end = week_start + chrono::Duration::days(6);
+
+                let slots = if slots.is_empty() {
+                    group
+                        .meal_slots()
+                        .into_iter()
+                        .map(|s| SlotRule::new(s.meal_type, Vec::new()))
+                        .collect()
+                } else {
+                    slots
+                        .iter()
+                        .map(|s| parse_slot_rule(&group, s))
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                if *no_repeat_days < 0 {
+                    return Err("--no-repeat-days must be zero or more".into());
+                }
+                let options = GeneratorOptions {
+                    slots,
+                    weeknight_max_minutes: *weeknight_max,
+                    no_repeat_days: *no_repeat_days,
+                    goals: if *goals {
+                        load_goals(config)
+                    } else {
+                        Vec::new()
+                    },
+                    meals_per_day: group.meal_slots().len(),
+                };
+                if *goals && options.goals.is_empty() {
+                    println!("No nutrition goals set; use 'fit goal set' to add some.");
+                }
+
+                let all_dishes = dish_repo.list()?;
+                if all_dishes.is_empty() {
+                    return Err("No dishes to plan with. Add some with 'fit dish create'.".into());
+                }
+                // Dishes whose components form a cycle can't be measured
+                let dishes: Vec<Dish> = all_dishes
+                    .iter()
+                    .filter_map(|d| d.flatten(&all_dishes).ok())
+                    .collect();
+
+                // Planned and eaten dishes around the week, for the repeat check
+                let (from, to) = plan_generator::history_range(week_start, *no_repeat_days);
+                let plans = mealplan_repo.list_range(from, to)?;
+                let mut history = plan_generator::plan_history(&plans);
+                let logs = SyncMealLogRepository::new(config.data_dir.value.clone())
+                    .list_range(from, to)?;
+                history.extend(
+                    logs.iter()
+                        .flat_map(|log| log.dishes.iter().map(|d| (log.date, d.id))),
+                );
+                let existing: Vec<MealPlan> = plans
+                    .into_iter()
+                    .filter(|p| p.date >= week_start && p.date <= week_end)
+                    .collect();
+
+                let seed = seed.unwrap_or_else(rand::random);
+                let mut rng = plan_generator::seeded_rng(seed);
+                let proposal = plan_generator::generate_week(
+                    week_start, &options, &dishes, &history, &existing, &mut rng,
+                );
+
+                println!(
+                    "Proposed meals for the week of {} (seed {}):",
+                    week_start, seed
+                );
+                for meal in &proposal.meals {
+                    let dish = dishes.iter().find(|d| d.id == meal.dish_id);
+                    let name = dish.map(|d| d.name.as_str()).unwrap_or("?");
+                    let time = dish
+                        .and_then(|d| d.total_time())
+                        .map(|t| format!(" ({} min)", t))
+                        .unwrap_or_default();
+                    println!(
+                        "  {} {} {:10} {}{}",
+                        meal.date,
+                        meal.date.format("%a"),
+                        meal.meal_type,
+                        name,
+                        time
+                    );
+                }
+                if !proposal.unfilled.is_empty() {
+                    let unfilled: Vec<String> = proposal
+                        .unfilled
+                        .iter()
+                        .map(|(date, meal_type)| format!("{} {}", date, meal_type))
+                        .collect();
+                    println!("No dish fits: {}", unfilled.join(", "));
+                }
+                if proposal.meals.is_empty() {
+                    println!("Nothing to plan.");
+                    return Ok(());
+                }
+
+                if !yes {
+                    print!("Create {} meal plan(s)? [y/N] ", proposal.meals.len());
+                    io::stdout().flush()?;
+
+                    let mut input = String::new();
+                    io::stdin().read_line(&mut input)?;
+
+                    if !input.trim().eq_ignore_ascii_case("y") {
+                        println!(
+                            "Nothing was created. Use --seed {} to get this proposal again.",
+                            seed
+                        );
+                        return Ok(());
+                    }
+                }
+
+                let changes = PlanChanges {
+                    writes: proposal.to_plans(&config.created_by.value, &config.created_by.value),
+                    deletes: Vec::new(),
+                    conflicts: Vec::new(),
+                };
+                let message = format!("Generate meal plans for the week of {}", week_start);
+                mealplan_repo.apply_changes(&changes, &message)?;
+                println!("Created {} meal plan(s)", changes.writes.len());
+                Ok(())
+            }
+
+            MealPlanSubcommand::Export {
+                format,
+                from,
+                to,
+                output,
+            } => {
+                let from_date = parse_date_or_today(from.as_deref())?;
+                let to_date = match to {
+                    Some(d) => parse_date(d)?,
+                    None => from_date + chrono::Duration::days(27),
+                };
+
+                let slots = resolve_meal_slots(&config.data_dir.value);
+                let mut plans = mealplan_repo.list_range(from_date, to_date)?;
+                plans.sort_by_key(|p| (p.date, MealSlot::rank(&slots, &p.meal_type)));
+                let name = resolve_group_document(&config.data_dir.value, None)
+                    .map(|(_, group)| format!("{} meals", group.name))
+                    .unwrap_or_else(|_| "Meal plans".to_string());
+
+                let calendar = match format {
+                    CalendarFormat::Ics => {
+                        ical::write_calendar(&name, &plans, &dish_repo.list()?, &slots)
+                    }
+                };
+                match output {
+                    Some(path) => {
+                        fs::write(path, calendar)
+                            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                        println!(
+                            "Exported {} meal plan(s) to {}",
+                            plans.len(),
+                            path.display()
+                        );
+                    }
+                    None => print!("{}", calendar),
+                }
+                Ok(())
+            }
+
+            MealPlanSubcommand::Import {
+                file,
+                format,
+                dry_run,
+            } => {
+                let text = match file {
+                    Some(path) => fs::read_to_string(path)
+                        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+                    None => {
+                        let mut buf = String::new();
+                        io::stdin().read_to_string(&mut buf)?;
+                        buf
+                    }
+                };
+                let events = match format {
+                    CalendarFormat::Ics => ical::read_calendar(&text)?,
+                };
+                if events.is_empty() {
+                    return Err("No events found in the calendar".into());
+                }
+                import_events(&events, *dry_run, mealplan_repo, dish_repo, config)
+            }
+
+            MealPlanSubcommand::Template { command } => {
+                run_template(command, mealplan_repo, dish_repo, config)
+            }
+
+            MealPlanSubcommand::Recurring { command } => {
+                run_recurring(command, mealplan_repo, dish_repo, config)
+            }
         }
     }
 }
 
-fn capitalize(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+fn run_template(
+    command: &TemplateSubcommand,
+    mealplan_repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (group_id, mut group) = resolve_group_document(&config.data_dir.value, None)?;
+
+    match command {
+        TemplateSubcommand::Save { name, week } => {
+            if name.trim().is_empty() {
+                return Err("Template name cannot be empty".into());
+            }
+            let week_start = group.week_containing(parse_date_or_today(week.as_deref())?);
+            let plans =
+                mealplan_repo.list_range(week_start, week_start + chrono::Duration::days(6))?;
+            if plans.is_empty() {
+                return Err(format!("No meal plans in the week of {}", week_start).into());
+            }
+
+            let template = PlanTemplate::from_week(name.trim(), week_start, &plans);
+            let count = template.entries.len();
+            let replaced = group.set_plan_template(template);
+            save_group_document(&config.data_dir.value, &group_id, &group)?;
+
+            let action = if replaced { "Replaced" } else { "Saved" };
+            println!(
+                "{} template '{}' with {} meal(s) from the week of {}",
+                action,
+                name.trim(),
+                count,
+                week_start
+            );
+        }
+
+        TemplateSubcommand::Apply {
+            name,
+            week,
+            replace,
+        } => {
+            let template = group
+                .plan_template(name)
+                .ok_or_else(|| format!("Template '{}' not found", name))?;
+            let week_start = group.week_containing(parse_date_or_today(week.as_deref())?);
+            let existing =
+                mealplan_repo.list_range(week_start, week_start + chrono::Duration::days(6))?;
+            let dish_ids: Vec<Uuid> = dish_repo.list()?.iter().map(|d| d.id).collect();
+
+            let (mut created, mut replaced, mut skipped) = (0, 0, 0);
+            let mut missing_dishes = 0;
+            for mut plan in template.plans_for_week(week_start, &config.created_by.value) {
+                // Dishes deleted since the template was saved are dropped
+                let before = plan.dish_ids.len();
+                plan.dish_ids.retain(|id| dish_ids.contains(id));
+                missing_dishes += before - plan.dish_ids.len();
+
+                let current = existing
+                    .iter()
+                    .find(|p| p.date == plan.date && p.meal_type == plan.meal_type);
+                match current {
+                    Some(current) if *replace => {
+                        let mut updated = current.clone();
+                        updated.title = plan.title;
+                        updated.cook = plan.cook;
+                        updated.dish_ids = plan.dish_ids;
+                        updated.servings = plan.servings;
+                        updated.updated_at = chrono::Utc::now();
+                        mealplan_repo.update(&updated)?;
+                        replaced += 1;
+                    }
+                    Some(_) => skipped += 1,
+                    None => {
+                        mealplan_repo.create(&plan)?;
+                        created += 1;
+                    }
+                }
+            }
+
+            println!(
+                "Applied template '{}' to the week of {}: {} created, {} replaced, {} skipped",
+                template.name, week_start, created, replaced, skipped
+            );
+            if skipped > 0 {
+                println!("Use --replace to overwrite existing plans.");
+            }
+            if missing_dishes > 0 {
+                println!(
+                    "Warning: {} dish(es) in the template no longer exist and were left out",
+                    missing_dishes
+                );
+            }
+        }
+
+        TemplateSubcommand::List => {
+            if group.plan_templates.is_empty() {
+                println!("No plan templates.");
+                println!("Save one with 'fit mealplan template save <NAME> --week <DATE>'.");
+                return Ok(());
+            }
+
+            let dishes = dish_repo.list()?;
+            let slots = group.meal_slots();
+            for (i, template) in group.plan_templates.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{} ({} meal(s))", template.name, template.entries.len());
+
+                let mut entries: Vec<_> = template.entries.iter().collect();
+                entries.sort_by_key(|e| {
+                    (
+                        e.weekday.days_since(group.week_start),
+                        MealSlot::rank(&slots, &e.meal_type),
+                    )
+                });
+                for entry in entries {
+                    let names: Vec<&str> = entry
+                        .dish_ids
+                        .iter()
+                        .filter_map(|id| dishes.iter().find(|d| d.id == *id))
+                        .map(|d| d.name.as_str())
+                        .collect();
+                    let title = entry.title.as_deref().unwrap_or("");
+                    let dishes_str = if names.is_empty() {
+                        "no dishes".to_string()
+                    } else {
+                        names.join(", ")
+                    };
+                    println!(
+                        "  {} {:10} {}{} (cook: {})",
+                        entry.weekday,
+                        entry.meal_type,
+                        if title.is_empty() {
+                            String::new()
+                        } else {
+                            format!("{}: ", title)
+                        },
+                        dishes_str,
+                        entry.cook
+                    );
+                }
+            }
+            return Ok(());
+        }
+
+        TemplateSubcommand::Delete { name } => {
+            if !group.remove_plan_template(name) {
+                return Err(format!("Template '{}' not found", name).into());
+            }
+            save_group_document(&config.data_dir.value, &group_id, &group)?;
+            println!("Deleted template '{}'", name);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_recurring(
+    command: &RecurringSubcommand,
+    mealplan_repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (group_id, mut group) = resolve_group_document(&config.data_dir.value, None)?;
+
+    match command {
+        RecurringSubcommand::Add {
+            meal_type,
+            rule,
+            title,
+            cook,
+            dishes,
+            servings,
+            start,
+            until,
+        } => {
+            let meal_type = group.resolve_meal_type(meal_type)?;
+            let mut rule: Recurrence = rule.parse()?;
+            if let Some(until) = until {
+                rule.until = Some(parse_date(until)?);
+            }
+            let start = parse_date_or_today(start.as_deref())?;
+            if rule.until.is_some_and(|until| until < start) {
+                return Err("The series ends before it starts".into());
+            }
+
+            let mut series = RecurringPlan::new(
+                meal_type,
+                title.clone().unwrap_or_default(),
+                rule,
+                start,
+                &config.created_by.value,
+            );
+            if let Some(cook) = cook {
+                series.cook = cook.clone();
+            }
+            if let Some(s) = servings {
+                if *s <= 0 {
+                    return Err("Servings must be greater than zero".into());
+                }
+                series.servings = Some(*s);
+            }
+            series.dish_ids = resolve_dish_ids(dish_repo, dishes)?;
+
+            let next = series.occurrences(start, start + chrono::Duration::days(366));
+            group.recurring_plans.push(series.clone());
+            save_group_document(&config.data_dir.value, &group_id, &group)?;
+
+            println!("Created recurring meal plan:");
+            print_recurring(&series, &[]);
+            match next.first() {
+                Some(plan) => println!("  Next:    {}", plan.date),
+                None => println!("  Warning: no occurrences in the next year"),
+            }
+        }
+
+        RecurringSubcommand::List { format } => {
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&group.recurring_plans)?);
+                }
+                OutputFormat::Text => {
+                    if group.recurring_plans.is_empty() {
+                        println!("No recurring meal plans.");
+                        println!(
+                            "Add one with 'fit mealplan recurring add --type <TYPE> --rule <RULE>'."
+                        );
+                        return Ok(());
+                    }
+                    let dishes = dish_repo.list()?;
+                    for (i, series) in group.recurring_plans.iter().enumerate() {
+                        if i > 0 {
+                            println!();
+                        }
+                        print_recurring(series, &dishes);
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        RecurringSubcommand::Skip { series, date }
+        | RecurringSubcommand::Restore { series, date } => {
+            let skip = matches!(command, RecurringSubcommand::Skip { .. });
+            let date = parse_date(date)?;
+            let found = group
+                .recurring_plan_mut(series)
+                .ok_or_else(|| format!("Recurring meal plan not found: {}", series))?;
+            let name = recurring_name(found);
+
+            if skip {
+                if found.rule.dates(found.start, date, date).is_empty() {
+                    return Err(format!("'{}' doesn't occur on {}", name, date).into());
+                }
+                if found.skipped.contains(&date) {
+                    return Err(format!("'{}' on {} is already skipped", name, date).into());
+                }
+                found.skipped.push(date);
+                found.skipped.sort();
+            } else if found.skipped.contains(&date) {
+                found.skipped.retain(|d| *d != date);
+            } else {
+                return Err(format!("'{}' on {} isn't skipped", name, date).into());
+            }
+            let occurrence_id = found.occurrence_id(date);
+            save_group_document(&config.data_dir.value, &group_id, &group)?;
+
+            if skip {
+                // An edited copy of the occurrence would still show
+                if mealplan_repo.get_by_id(occurrence_id)?.is_some() {
+                    mealplan_repo.delete(occurrence_id)?;
+                }
+                println!("Skipped '{}' on {}", name, date);
+            } else {
+                println!("Restored '{}' on {}", name, date);
+            }
+        }
+
+        RecurringSubcommand::Delete { series } => {
+            let removed = group
+                .remove_recurring_plan(series)
+                .ok_or_else(|| format!("Recurring meal plan not found: {}", series))?;
+            save_group_document(&config.data_dir.value, &group_id, &group)?;
+            println!("Deleted recurring meal plan '{}'", recurring_name(&removed));
+        }
+    }
+
+    Ok(())
+}
+
+/// The cooked plan a meal eats the leftovers of.
+///
+/// Leftovers of leftovers lead back to the plan that was cooked, so every
+/// leftover plan links straight to its batch. `plan_id` is the plan being
+/// updated, which can't be its own source.
+fn leftovers_source(
+    repo: &SyncMealPlanRepository,
+    id: &str,
+    plan_id: Option<Uuid>,
+    date: NaiveDate,
+) -> Result<MealPlan, Box<dyn std::error::Error>> {
+    let uuid = Uuid::parse_str(id).map_err(|_| format!("Invalid plan UUID: {}", id))?;
+    let mut source = repo
+        .get_by_id(uuid)?
+        .ok_or_else(|| format!("Meal plan not found: {}", id))?;
+    let mut seen = vec![source.id];
+    while let Some(parent) = source.leftovers_of {
+        match repo.get_by_id(parent)? {
+            Some(parent) if !seen.contains(&parent.id) => {
+                seen.push(parent.id);
+                source = parent;
+            }
+            _ => break,
+        }
+    }
+
+    if plan_id.is_some_and(|id| seen.contains(&id)) {
+        return Err("A meal plan can't be leftovers of itself".into());
+    }
+    if source.date > date {
+        return Err(leftovers_too_early(&source).into());
+    }
+    Ok(source)
+}
+
+fn leftovers_too_early(source: &MealPlan) -> String {
+    format!(
+        "Leftovers must be on or after the meal they come from ('{}' on {})",
+        source.title, source.date
+    )
+}
+
+/// Print how much of a cooked plan its leftover plans eat, if any do.
+fn print_leftover_usage(
+    source: &MealPlan,
+    repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let plans = repo.list()?;
+    let usage = source.leftover_servings(&plans, &dish_repo.list()?);
+    if usage.plans == 0 {
+        return Ok(());
+    }
+
+    match usage.cooked {
+        Some(cooked) => println!(
+            "Leftovers of '{}': {} of {} serving(s) eaten by {} plan(s)",
+            source.title, usage.eaten, cooked, usage.plans
+        ),
+        None => println!(
+            "Leftovers of '{}': {} serving(s) eaten by {} plan(s) (set servings on it to track what's left)",
+            source.title, usage.eaten, usage.plans
+        ),
+    }
+    if let Some(remaining) = usage.remaining().filter(|r| *r < 0) {
+        println!(
+            "Warning: that's {} serving(s) more than '{}' cooks",
+            -remaining, source.title
+        );
+    }
+    Ok(())
+}
+
+/// Create or update a meal plan for each calendar event, as one change.
+fn import_events(
+    events: &[CalendarEvent],
+    dry_run: bool,
+    mealplan_repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let slots = resolve_meal_slots(&config.data_dir.value);
+    let dishes = dish_repo.list()?;
+    let mut writes: Vec<MealPlan> = Vec::new();
+    let mut lines: Vec<(NaiveDate, usize, String)> = Vec::new();
+    let (mut created, mut unchanged) = (0, 0);
+    let mut skipped: Vec<String> = Vec::new();
+    let mut unknown_dishes: Vec<&str> = Vec::new();
+
+    for event in events {
+        let Some(meal_type) = event.meal_type(&slots) else {
+            skipped.push(format!("{} '{}'", event.date, event.summary));
+            continue;
+        };
+        let existing = mealplan_repo.get_by_id(event.plan_id())?;
+        let mut plan = existing.clone().unwrap_or_else(|| {
+            let mut plan =
+                MealPlan::new(event.date, meal_type.clone(), "", &config.created_by.value);
+            plan.id = event.plan_id();
+            plan
+        });
+        plan.date = event.date;
+        plan.title = event.title(&meal_type);
+        plan.meal_type = meal_type;
+        if let Some(cook) = event.cook() {
+            plan.cook = cook.to_string();
+        }
+        if let Some(servings) = event.servings() {
+            plan.servings = Some(servings);
+        }
+        let names = event.dish_names();
+        if !names.is_empty() {
+            plan.dish_ids = names
+                .iter()
+                .filter_map(|name| {
+                    let dish = dishes.iter().find(|d| d.name.eq_ignore_ascii_case(name));
+                    if dish.is_none() && !unknown_dishes.contains(name) {
+                        unknown_dishes.push(name);
+                    }
+                    dish.map(|d| d.id)
+                })
+                .collect();
+        }
+
+        let note = match &existing {
+            None => {
+                created += 1;
+                "new"
+            }
+            Some(old)
+                if old.date == plan.date
+                    && old.meal_type == plan.meal_type
+                    && old.title == plan.title
+                    && old.cook == plan.cook
+                    && old.dish_ids == plan.dish_ids
+                    && old.servings == plan.servings =>
+            {
+                unchanged += 1;
+                continue;
+            }
+            Some(_) => {
+                plan.updated_at = chrono::Utc::now();
+                "updated"
+            }
+        };
+        lines.push((
+            plan.date,
+            MealSlot::rank(&slots, &plan.meal_type),
+            format!(
+                "  {} {:10} {} ({})",
+                plan.date, plan.meal_type, plan.title, note
+            ),
+        ));
+        writes.push(plan);
+    }
+
+    lines.sort();
+    for (_, _, line) in &lines {
+        println!("{}", line);
+    }
+    if !unknown_dishes.is_empty() {
+        println!("Unknown dishes left out: {}", unknown_dishes.join(", "));
+    }
+    if !skipped.is_empty() {
+        println!("No meal slot for: {}", skipped.join(", "));
+    }
+
+    let summary = format!(
+        "{} meal plan(s) created, {} updated, {} unchanged, {} skipped",
+        created,
+        writes.len() - created,
+        unchanged,
+        skipped.len()
+    );
+    if dry_run {
+        println!("Dry run: {}. Nothing was changed.", summary);
+        return Ok(());
+    }
+    if !writes.is_empty() {
+        let changes = PlanChanges {
+            writes,
+            deletes: Vec::new(),
+            conflicts: Vec::new(),
+        };
+        mealplan_repo.apply_changes(&changes, "Import meal plans from calendar")?;
+    }
+    println!("Imported: {}", summary);
+    Ok(())
+}
+
+/// Parse a generator slot: "dinner" or "dinner:quick,vegetarian".
+fn parse_slot_rule(
+    group: &todu_fit_core::GroupDocument,
+    slot: &str,
+) -> Result<SlotRule, Box<dyn std::error::Error>> {
+    let (name, tags) = slot.split_once(':').unwrap_or((slot, ""));
+    let meal_type = group.resolve_meal_type(name.trim())?;
+    let tags = tags
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    Ok(SlotRule::new(meal_type, tags))
+}
+
+/// Title of a recurring plan, or its meal type when it uses default titles.
+fn recurring_name(series: &RecurringPlan) -> String {
+    if series.title.is_empty() {
+        series.meal_type.to_string()
+    } else {
+        series.title.clone()
+    }
+}
+
+fn print_recurring(series: &RecurringPlan, dishes: &[Dish]) {
+    println!("{}: {}", recurring_name(series), series.rule.describe());
+    println!("  ID:      {}", series.id);
+    println!("  Meal:    {}", series.meal_type);
+    println!("  Starts:  {}", series.start);
+    println!("  Rule:    {}", series.rule);
+    println!("  Cook:    {}", series.cook);
+    let names: Vec<&str> = series
+        .dish_ids
+        .iter()
+        .filter_map(|id| dishes.iter().find(|d| d.id == *id))
+        .map(|d| d.name.as_str())
+        .collect();
+    if !names.is_empty() {
+        println!("  Dishes:  {}", names.join(", "));
+    }
+    if !series.skipped.is_empty() {
+        let skipped: Vec<String> = series.skipped.iter().map(|d| d.to_string()).collect();
+        println!("  Skipped: {}", skipped.join(", "));
+    }
+}
+
+/// Resolve dishes given by ID or name.
+fn resolve_dish_ids(
+    dish_repo: &SyncDishRepository,
+    dishes: &[String],
+) -> Result<Vec<Uuid>, Box<dyn std::error::Error>> {
+    let mut resolved_dish_ids = Vec::new();
+    for dish_ref in dishes {
+        let dish = if let Ok(uuid) = Uuid::parse_str(dish_ref) {
+            dish_repo.get_by_id(uuid)?
+        } else {
+            dish_repo.get_by_name(dish_ref)?
+        };
+
+        match dish {
+            Some(d) => resolved_dish_ids.push(d.id),
+            None => return Err(format!("Dish not found: {}", dish_ref).into()),
+        }
+    }
+    Ok(resolved_dish_ids)
+}
+
+/// Print the changes of a bulk operation and write them unless it's a dry run.
+fn apply_bulk(
+    mealplan_repo: &SyncMealPlanRepository,
+    changes: &PlanChanges,
+    bulk: &BulkArgs,
+    config: &Config,
+    message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let replace = matches!(bulk.on_conflict, OnConflict::Replace);
+    let in_conflict = |id: Uuid| {
+        changes
+            .conflicts
+            .iter()
+            .any(|c| c.incoming.id == id || c.existing.id == id)
+    };
+
+    // One line per target slot, in date and meal slot order
+    let mut lines: Vec<(&MealPlan, String)> = changes
+        .writes
+        .iter()
+        .filter(|p| !in_conflict(p.id))
+        .map(|p| (p, p.title.clone()))
+        .collect();
+    for conflict in &changes.conflicts {
+        let note = if replace {
+            format!("replaces '{}'", conflict.existing.title)
+        } else {
+            format!("skipped, '{}' is already there", conflict.existing.title)
+        };
+        lines.push((&conflict.incoming, note));
+    }
+    let slots = resolve_meal_slots(&config.data_dir.value);
+    lines.sort_by_key(|(p, _)| (p.date, MealSlot::rank(&slots, &p.meal_type)));
+    for (plan, text) in lines {
+        println!("  {} {:10} {}", plan.date, plan.meal_type, text);
+    }
+
+    let replaced = if replace { changes.conflicts.len() } else { 0 };
+    let skipped = changes.conflicts.len() - replaced;
+    let summary = format!(
+        "{} meal plan(s) written, {} replaced, {} skipped",
+        changes.writes.len() - replaced,
+        replaced,
+        skipped
+    );
+
+    if bulk.dry_run {
+        println!("Dry run: {}. Nothing was changed.", summary);
+        return Ok(());
+    }
+    if changes.is_empty() {
+        println!("Nothing to change: {}", summary);
+        return Ok(());
+    }
+    mealplan_repo.apply_changes(changes, message)?;
+    println!("{}: {}", message, summary);
+    Ok(())
+}
+
+/// Describe the plans blocking a bulk operation run with `--on-conflict fail`.
+fn conflict_error(error: PlanOpError) -> Box<dyn std::error::Error> {
+    let PlanOpError::Conflicts(conflicts) = &error else {
+        return error.into();
+    };
+    let slots: Vec<String> = conflicts
+        .iter()
+        .map(|c| {
+            format!(
+                "{} {} ('{}')",
+                c.existing.date, c.existing.meal_type, c.existing.title
+            )
+        })
+        .collect();
+    format!(
+        "{}: {}. Use --on-conflict skip or replace.",
+        error,
+        slots.join(", ")
+    )
+    .into()
+}
+
+/// Parse a YYYY-MM-DD date.
+fn parse_date(date: &str) -> Result<NaiveDate, Box<dyn std::error::Error>> {
+    Ok(NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", date))?)
+}
+
+/// Parse a date, defaulting to today.
+fn parse_date_or_today(date: Option<&str>) -> Result<NaiveDate, Box<dyn std::error::Error>> {
+    match date {
+        Some(d) => Ok(NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", d))?),
+        None => Ok(Local::now().date_naive()),
     }
 }
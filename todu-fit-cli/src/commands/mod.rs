@@ -1,19 +1,27 @@
 mod config_cmd;
 mod device;
 mod dish;
+mod food;
+mod goal;
 mod group;
 mod init;
 pub mod meal;
 mod mealplan;
+mod pantry;
+mod report;
 mod shopping;
 mod sync_cmd;
 
 pub use config_cmd::ConfigCommand;
 pub use device::DeviceCommand;
 pub use dish::{DishCommand, DishSubcommand};
-pub use group::{GroupCommand, GroupSubcommand};
+pub use food::FoodCommand;
+pub use goal::{GoalCommand, GoalSubcommand};
+pub use group::{GroupCommand, GroupSubcommand, SlotSubcommand};
 pub use init::InitCommand;
 pub use meal::{MealCommand, MealSubcommand};
-pub use mealplan::{MealPlanCommand, MealPlanSubcommand};
-pub use shopping::{ShoppingCommand, ShoppingSubcommand};
+pub use mealplan::{MealPlanCommand, MealPlanSubcommand, RecurringSubcommand, TemplateSubcommand};
+pub use pantry::{PantryCommand, PantrySubcommand};
+pub use report::{ReportCommand, ReportSubcommand};
+pub use shopping::{CategorySubcommand, ShoppingCommand, ShoppingSubcommand, StoreSubcommand};
 pub use sync_cmd::SyncCommand;
@@ -0,0 +1,168 @@
+//! Pantry CLI commands.
+//!
+//! Track ingredients on hand so shopping lists only include what is missing.
+
+use clap::{Args, Subcommand};
+
+use crate::commands::dish::OutputFormat;
+use crate::sync::SyncPantryRepository;
+use todu_fit_core::PantryItem;
+
+/// Track ingredients on hand
+#[derive(Args)]
+pub struct PantryCommand {
+    #[command(subcommand)]
+    pub command: PantrySubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum PantrySubcommand {
+    /// List pantry stock and staples
+    List {
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Add stock of an ingredient
+    Add {
+        /// Ingredient name
+        name: String,
+
+        /// Quantity to add
+        #[arg(long, short)]
+        qty: f64,
+
+        /// Unit (converted to the unit already stocked when possible)
+        #[arg(long, short, default_value = "")]
+        unit: String,
+    },
+
+    /// Set the amount on hand, replacing the current stock
+    Set {
+        /// Ingredient name
+        name: String,
+
+        /// Quantity on hand
+        #[arg(long, short)]
+        qty: f64,
+
+        /// Unit
+        #[arg(long, short, default_value = "")]
+        unit: String,
+    },
+
+    /// Remove an ingredient from the pantry
+    Remove {
+        /// Ingredient name
+        name: String,
+    },
+
+    /// Mark an ingredient as a staple that is never added to shopping lists
+    Staple {
+        /// Ingredient name
+        name: String,
+    },
+
+    /// Stop treating an ingredient as a staple
+    Unstaple {
+        /// Ingredient name
+        name: String,
+    },
+}
+
+impl PantryCommand {
+    pub fn run(&self, repo: &SyncPantryRepository) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.command {
+            PantrySubcommand::List { format } => {
+                let pantry = repo.get()?;
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&pantry.items)?);
+                    }
+                    OutputFormat::Text => {
+                        if pantry.items.is_empty() {
+                            println!("Pantry is empty.");
+                            println!("Use 'fit pantry add <NAME> --qty <QTY>' to stock it.");
+                        }
+                        for item in &pantry.items {
+                            println!("{}", item);
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            PantrySubcommand::Add { name, qty, unit } => {
+                check_stock_args(name, *qty)?;
+                let mut pantry = repo.get()?;
+                let Some(item) = pantry.add(name, *qty, unit).cloned() else {
+                    let stocked = pantry.find(name).map(|i| i.unit.as_str()).unwrap_or("");
+                    return Err(format!(
+                        "Can't add '{}' to stock kept in '{}'; use 'fit pantry set' to replace it",
+                        unit, stocked
+                    )
+                    .into());
+                };
+                repo.save_items(std::slice::from_ref(&item))?;
+                println!("Pantry: {}", item);
+                Ok(())
+            }
+
+            PantrySubcommand::Set { name, qty, unit } => {
+                check_stock_args(name, *qty)?;
+                let pantry = repo.get()?;
+                // Keep the stored spelling and staple flag
+                let mut item = pantry
+                    .find(name)
+                    .cloned()
+                    .unwrap_or_else(|| PantryItem::new(name, 0.0, ""));
+                item.quantity = *qty;
+                item.unit = unit.clone();
+                repo.save_items(std::slice::from_ref(&item))?;
+                println!("Pantry: {}", item);
+                Ok(())
+            }
+
+            PantrySubcommand::Remove { name } => {
+                if !repo.remove(name)? {
+                    return Err(format!("'{}' is not in the pantry", name).into());
+                }
+                println!("Removed '{}' from the pantry", name);
+                Ok(())
+            }
+
+            PantrySubcommand::Staple { name } | PantrySubcommand::Unstaple { name } => {
+                let staple = matches!(self.command, PantrySubcommand::Staple { .. });
+                let pantry = repo.get()?;
+                let mut item = match pantry.find(name) {
+                    Some(item) => item.clone(),
+                    None if staple => PantryItem::staple(name),
+                    None => return Err(format!("'{}' is not in the pantry", name).into()),
+                };
+                item.staple = staple;
+                repo.save_items(std::slice::from_ref(&item))?;
+
+                if staple {
+                    println!(
+                        "'{}' is now a staple and won't be added to shopping lists",
+                        name
+                    );
+                } else {
+                    println!("'{}' is no longer a staple", name);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn check_stock_args(name: &str, qty: f64) -> Result<(), Box<dyn std::error::Error>> {
+    if name.trim().is_empty() {
+        return Err("Ingredient name cannot be empty".into());
+    }
+    if !qty.is_finite() || qty < 0.0 {
+        return Err("Quantity must be zero or more".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,248 @@
+//! Nutrition report commands.
+
+use chrono::{Duration, Local, NaiveDate};
+use clap::{Args, Subcommand, ValueEnum};
+
+use todu_fit_core::report::{NutrientStats, Streaks};
+use todu_fit_core::{nutrition_report, GroupBy, NutritionReport};
+
+use crate::sync::SyncMealLogRepository;
+
+/// Width of the longest bar in chart output
+const CHART_WIDTH: usize = 40;
+
+/// Sparkline levels, lowest to highest
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Clone, ValueEnum, Default)]
+pub enum ReportFormat {
+    #[default]
+    Table,
+    Json,
+    Chart,
+}
+
+/// Summarize logged meals over time
+#[derive(Args)]
+pub struct ReportCommand {
+    #[command(subcommand)]
+    pub command: ReportSubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum ReportSubcommand {
+    /// Nutrient averages, ranges and logging streaks
+    Nutrition {
+        /// Start date (YYYY-MM-DD), defaults to 30 days before the end date
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End date (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Group periods by day, week or month
+        #[arg(long, default_value = "day")]
+        group_by: GroupBy,
+
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "table")]
+        format: ReportFormat,
+
+        /// Nutrient to plot in chart output
+        #[arg(long, default_value = "calories")]
+        nutrient: String,
+    },
+}
+
+impl ReportCommand {
+    pub fn run(&self, repo: &SyncMealLogRepository) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.command {
+            ReportSubcommand::Nutrition {
+                from,
+                to,
+                group_by,
+                format,
+                nutrient,
+            } => {
+                let to_date = match to {
+                    Some(d) => parse_date(d)?,
+                    None => Local::now().date_naive(),
+                };
+                let from_date = match from {
+                    Some(d) => parse_date(d)?,
+                    None => to_date - Duration::days(29),
+                };
+                if from_date > to_date {
+                    return Err(
+                        format!("Start date {} is after end date {}", from_date, to_date).into(),
+                    );
+                }
+
+                let logs = repo.list_range(from_date, to_date)?;
+                let report = nutrition_report(&logs, from_date, to_date, *group_by);
+
+                match format {
+                    ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                    ReportFormat::Table => print_table(&report),
+                    ReportFormat::Chart => print_chart(&report, &nutrient.to_lowercase()),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", s))
+}
+
+fn print_header(report: &NutritionReport) {
+    println!(
+        "Nutrition report: {} to {} (by {})",
+        report.from, report.to, report.group_by
+    );
+    println!(
+        "Logged {} of {} day(s), {} meal(s)",
+        report.days_logged, report.days, report.meals
+    );
+}
+
+fn print_table(report: &NutritionReport) {
+    print_header(report);
+
+    if report.days_logged == 0 {
+        println!("\nNo meals logged in this range.");
+        return;
+    }
+
+    let nutrients: Vec<&str> = report.overall.iter().map(|s| s.nutrient.as_str()).collect();
+
+    println!("\nAverage per logged day:");
+    let mut header = format!("{:<12} {:>5}", "PERIOD", "DAYS");
+    for name in &nutrients {
+        header.push_str(&format!(" {:>10}", name.to_uppercase()));
+    }
+    println!("{}", header);
+    println!("{}", "-".repeat(header.chars().count()));
+
+    for period in &report.periods {
+        let mut row = format!("{:<12} {:>5}", period.label, period.days_logged);
+        for name in &nutrients {
+            let cell = find_stats(&period.nutrients, name)
+                .map(|s| format!("{:.0}", s.average))
+                .unwrap_or_else(|| "-".to_string());
+            row.push_str(&format!(" {:>10}", cell));
+        }
+        println!("{}", row);
+    }
+
+    println!("\nOverall (per logged day):");
+    println!(
+        "{:<14} {:>10} {:>10} {:>10} {:>10}",
+        "NUTRIENT", "AVG", "MIN", "MAX", "STD DEV"
+    );
+    println!("{}", "-".repeat(58));
+    for stats in &report.overall {
+        println!(
+            "{:<14} {:>10.0} {:>10.0} {:>10.0} {:>10.1}",
+            stats.nutrient, stats.average, stats.min, stats.max, stats.std_dev
+        );
+    }
+
+    println!();
+    print_streaks(&report.streaks);
+}
+
+fn print_chart(report: &NutritionReport, nutrient: &str) {
+    print_header(report);
+
+    let values: Vec<(&str, Option<f64>)> = report
+        .periods
+        .iter()
+        .map(|p| {
+            (
+                p.label.as_str(),
+                find_stats(&p.nutrients, nutrient).map(|s| s.average),
+            )
+        })
+        .collect();
+    let max = values
+        .iter()
+        .filter_map(|(_, v)| *v)
+        .fold(0.0_f64, f64::max);
+
+    if max <= 0.0 {
+        println!("\nNo {} logged in this range.", nutrient);
+        return;
+    }
+
+    println!("\n{} (average per logged day):", nutrient);
+    for (label, value) in &values {
+        match value {
+            Some(v) => {
+                let width = ((v / max) * CHART_WIDTH as f64).round() as usize;
+                println!(
+                    "{:<12} {:<w$} {:.0}",
+                    label,
+                    "█".repeat(width),
+                    v,
+                    w = CHART_WIDTH
+                );
+            }
+            None => println!("{:<12} {:<w$} -", label, "", w = CHART_WIDTH),
+        }
+    }
+
+    println!("\nDaily: {}", sparkline(report, nutrient));
+    println!();
+    print_streaks(&report.streaks);
+}
+
+/// One character per day in the range, scaled to the highest day; unlogged
+/// days are blank.
+fn sparkline(report: &NutritionReport, nutrient: &str) -> String {
+    let max = report
+        .daily
+        .iter()
+        .filter_map(|d| d.nutrients.get(nutrient))
+        .fold(0.0_f64, |a, b| a.max(*b));
+
+    let mut line = String::new();
+    let mut date = report.from;
+    while date <= report.to {
+        let value = report
+            .daily
+            .iter()
+            .find(|d| d.date == date)
+            .map(|d| d.nutrients.get(nutrient).copied().unwrap_or(0.0));
+        line.push(match value {
+            Some(v) if max > 0.0 => {
+                let level = ((v / max) * (SPARK_LEVELS.len() - 1) as f64).round() as usize;
+                SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+            }
+            Some(_) => SPARK_LEVELS[0],
+            None => ' ',
+        });
+        date += Duration::days(1);
+    }
+    line
+}
+
+fn print_streaks(streaks: &Streaks) {
+    print!(
+        "Streaks: current {} day(s), longest {} day(s)",
+        streaks.current, streaks.longest
+    );
+    if let (Some(start), Some(end)) = (streaks.longest_start, streaks.longest_end) {
+        print!(" ({} to {})", start, end);
+    }
+    println!();
+}
+
+fn find_stats<'a>(stats: &'a [NutrientStats], nutrient: &str) -> Option<&'a NutrientStats> {
+    stats
+        .iter()
+        .find(|s| s.nutrient.eq_ignore_ascii_case(nutrient))
+}
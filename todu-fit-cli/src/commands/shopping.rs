@@ -1,19 +1,93 @@
 //! Shopping cart CLI commands.
 //!
 //! Manage weekly shopping carts with items from meal plans and manual additions.
+//! Weeks start on the group's configured day; a cart can also cover a
+//! shopping trip over any range of dates, with its own checked and manual items.
+//! Pantry stock is subtracted from the list, and checked items restock the pantry.
+//! Lists can be exported for other apps, and ticked-off Markdown checklists
+//! imported back.
 
-use chrono::{Datelike, Local, NaiveDate};
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 use clap::{Args, Subcommand, ValueEnum};
 
 use crate::config::Config;
-use crate::sync::{SyncDishRepository, SyncMealPlanRepository, SyncShoppingRepository};
-use todu_fit_core::{Ingredient, ManualItem, ShoppingItem};
+use crate::sync::group_context::{resolve_group_document, save_group_document};
+use crate::sync::{
+    SyncDishRepository, SyncMealPlanRepository, SyncPantryRepository, SyncShoppingRepository,
+};
+use todu_fit_core::categories::{self, DEFAULT_ORDER};
+use todu_fit_core::formats::shopping_list::{parse_markdown_checklist, ShoppingList};
+use todu_fit_core::text::capitalize;
+use todu_fit_core::{
+    units, CartPeriod, GroupDocument, Ingredient, ManualItem, PantryItem, ShoppingCart,
+    ShoppingItem, StoreLayout,
+};
 
 #[derive(Clone, ValueEnum, Default)]
 pub enum OutputFormat {
     #[default]
     Table,
     Json,
+    /// Markdown checklist (re-import with 'fit shopping import')
+    Markdown,
+    /// Plain text of what is left to buy, for messaging apps
+    Text,
+    /// CSV, one row per item
+    Csv,
+    /// todo.txt tasks
+    Todo,
+}
+
+/// Which cart a command works on: a week, or a trip over a date range.
+#[derive(Args)]
+pub struct CartArgs {
+    /// Week date (YYYY-MM-DD), defaults to current week
+    #[arg(long, short, conflicts_with = "from")]
+    week: Option<String>,
+
+    /// First day of a shopping trip (YYYY-MM-DD), instead of a week
+    #[arg(long, requires = "to")]
+    from: Option<String>,
+
+    /// Last day of a shopping trip (YYYY-MM-DD)
+    #[arg(long, requires = "from")]
+    to: Option<String>,
+}
+
+impl CartArgs {
+    /// The period of the cart, for weeks starting on the group's day.
+    ///
+    /// A `--week` date that is exactly the key of a stored cart picks that
+    /// cart, so carts keyed by another week start (such as Sunday carts
+    /// from before the group changed it) stay reachable.
+    fn period(
+        &self,
+        config: &Config,
+        shopping_repo: &SyncShoppingRepository,
+    ) -> Result<CartPeriod, Box<dyn std::error::Error>> {
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            let (from, to) = (parse_date(from)?, parse_date(to)?);
+            if to < from {
+                return Err(format!("Trip ends ({}) before it starts ({})", to, from).into());
+            }
+            return Ok(CartPeriod::Trip(from, to));
+        }
+
+        if let Some(week) = &self.week {
+            let date = parse_date(week)?;
+            if shopping_repo.get(&date.to_string())?.is_some() {
+                return Ok(CartPeriod::Week(date));
+            }
+        }
+
+        let (_, group) = resolve_group_document(&config.data_dir.value, None)?;
+        let start = parse_week_or_current(self.week.as_deref(), group.week_start)?;
+        Ok(CartPeriod::Week(start))
+    }
 }
 
 #[derive(Args)]
@@ -24,11 +98,14 @@ pub struct ShoppingCommand {
 
 #[derive(Subcommand)]
 pub enum ShoppingSubcommand {
-    /// List shopping cart items for a week
+    /// List shopping cart items for a week or trip
     List {
-        /// Week date (YYYY-MM-DD), defaults to current week
-        #[arg(long, short)]
-        week: Option<String>,
+        #[command(flatten)]
+        cart_args: CartArgs,
+
+        /// Order the category sections by this store's walking route
+        #[arg(long)]
+        store: Option<String>,
 
         /// Output format
         #[arg(long, short, value_enum, default_value = "table")]
@@ -48,9 +125,8 @@ pub enum ShoppingSubcommand {
         #[arg(long, short)]
         unit: Option<String>,
 
-        /// Week date (YYYY-MM-DD), defaults to current week
-        #[arg(long, short)]
-        week: Option<String>,
+        #[command(flatten)]
+        cart_args: CartArgs,
     },
 
     /// Remove a manual item from the shopping cart
@@ -58,36 +134,99 @@ pub enum ShoppingSubcommand {
         /// Item name
         name: String,
 
-        /// Week date (YYYY-MM-DD), defaults to current week
-        #[arg(long, short)]
-        week: Option<String>,
+        #[command(flatten)]
+        cart_args: CartArgs,
     },
 
-    /// Mark an item as checked (purchased)
+    /// Mark an item as checked (purchased) and add it to the pantry
     Check {
         /// Item name
         name: String,
 
-        /// Week date (YYYY-MM-DD), defaults to current week
-        #[arg(long, short)]
-        week: Option<String>,
+        #[command(flatten)]
+        cart_args: CartArgs,
     },
 
-    /// Uncheck a previously checked item
+    /// Uncheck a previously checked item and take it back out of the pantry
     Uncheck {
         /// Item name
         name: String,
 
-        /// Week date (YYYY-MM-DD), defaults to current week
-        #[arg(long, short)]
-        week: Option<String>,
+        #[command(flatten)]
+        cart_args: CartArgs,
+    },
+
+    /// List saved carts (weeks and trips)
+    Carts,
+
+    /// Check and uncheck items from a Markdown checklist
+    ///
+    /// Reads a list exported with '--format markdown' (e.g. after ticking
+    /// items off in a notes app). Lines are matched to the cart's items by
+    /// name; lines that match nothing are reported and skipped.
+    Import {
+        /// Read the checklist from this file instead of stdin
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        #[command(flatten)]
+        cart_args: CartArgs,
     },
 
-    /// Uncheck all checked items
+    /// Uncheck all checked items (pantry stock is kept)
     ClearChecked {
-        /// Week date (YYYY-MM-DD), defaults to current week
-        #[arg(long, short)]
-        week: Option<String>,
+        #[command(flatten)]
+        cart_args: CartArgs,
+    },
+
+    /// Manage the group's ingredient categories
+    Category {
+        #[command(subcommand)]
+        command: CategorySubcommand,
+    },
+
+    /// Manage store layouts (category walking order per store)
+    Store {
+        #[command(subcommand)]
+        command: StoreSubcommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CategorySubcommand {
+    /// List the group's category overrides
+    List,
+    /// Put an ingredient in a category (e.g. "tofu" in "refrigerated")
+    Set {
+        /// Ingredient name
+        ingredient: String,
+        /// Category name
+        category: String,
+    },
+    /// Go back to the default category for an ingredient
+    Remove {
+        /// Ingredient name
+        ingredient: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StoreSubcommand {
+    /// List store layouts
+    List,
+    /// Add or replace a store layout
+    Set {
+        /// Store name
+        name: String,
+        /// Categories in walking order, comma-separated
+        /// (e.g. "produce,bakery,meat,dairy,frozen")
+        #[arg(value_delimiter = ',', required = true)]
+        categories: Vec<String>,
+    },
+    /// Remove a store layout
+    Remove {
+        /// Store name
+        name: String,
     },
 }
 
@@ -97,70 +236,79 @@ impl ShoppingCommand {
         shopping_repo: &SyncShoppingRepository,
         mealplan_repo: &SyncMealPlanRepository,
         dish_repo: &SyncDishRepository,
-        _config: &Config,
+        pantry_repo: &SyncPantryRepository,
+        config: &Config,
     ) -> Result<(), Box<dyn std::error::Error>> {
         match &self.command {
-            ShoppingSubcommand::List { week, format } => {
-                let week_start = parse_week_or_current(week.as_deref())?;
-                let week_str = week_start.to_string();
-                let week_end = week_start + chrono::Duration::days(6);
+            ShoppingSubcommand::List {
+                cart_args,
+                store,
+                format,
+            } => {
+                // Resolve the store first so a typo fails before any work
+                let (_, group) = resolve_group_document(&config.data_dir.value, None)?;
+                let store = match store {
+                    Some(name) => Some(group.store_layout(name).cloned().ok_or_else(|| {
+                        format!(
+                            "Store layout '{}' not found. Add one with 'fit shopping store set'.",
+                            name
+                        )
+                    })?),
+                    None => None,
+                };
 
-                // Get shopping cart (checked items and manual items)
-                let cart = shopping_repo.get_or_create(&week_str)?;
-
-                // Get ingredients from meal plans for this week
-                let ingredients =
-                    collect_ingredients_for_week(mealplan_repo, dish_repo, week_start, week_end)?;
-
-                // Aggregate and deduplicate ingredients
-                let aggregated = aggregate_ingredients(&ingredients);
-
-                // Build shopping items with checked status
-                let mut items: Vec<ShoppingItem> = aggregated
-                    .iter()
-                    .map(|ing| ShoppingItem::from_ingredient(ing, cart.is_checked(&ing.name)))
-                    .collect();
-
-                // Add manual items
-                for manual in &cart.manual_items {
-                    items.push(ShoppingItem::from_manual(
-                        manual,
-                        cart.is_checked(&manual.name),
-                    ));
-                }
+                let period = cart_args.period(config, shopping_repo)?;
 
-                // Sort: unchecked first, then alphabetical
-                items.sort_by(|a, b| {
-                    if a.checked != b.checked {
-                        a.checked.cmp(&b.checked) // unchecked (false) comes first
-                    } else {
-                        a.name.to_lowercase().cmp(&b.name.to_lowercase())
-                    }
-                });
+                // Get shopping cart (checked items and manual items)
+                let cart = shopping_repo.get_or_create_for(period)?;
+                let (list, covered) = build_list(
+                    &cart,
+                    period,
+                    &group,
+                    store.as_ref(),
+                    mealplan_repo,
+                    dish_repo,
+                    pantry_repo,
+                )?;
+                let ShoppingList {
+                    items, categories, ..
+                } = &list;
 
                 match format {
                     OutputFormat::Json => {
                         let output = serde_json::json!({
-                            "week": week_str,
+                            "week": period.key(),
+                            "from": period.start(),
+                            "to": period.end(),
+                            "store": store.as_ref().map(|layout| &layout.name),
                             "items": items.iter()
-                                .filter(|i| !i.is_manual)
-                                .map(|i| serde_json::json!({
+                                .zip(categories.iter().map(Some).chain(std::iter::repeat(None)))
+                                .filter(|(i, _)| !i.is_manual)
+                                .map(|(i, category)| serde_json::json!({
                                     "name": i.name,
                                     "quantity": i.quantity,
                                     "unit": i.unit,
                                     "checked": i.checked,
+                                    "category": category,
                                 }))
                                 .collect::<Vec<_>>(),
                             "manual_items": cart.manual_items,
                             "checked": cart.checked,
+                            "pantry_covered": covered,
                         });
                         println!("{}", serde_json::to_string_pretty(&output)?);
                     }
                     OutputFormat::Table => {
-                        println!(
-                            "Shopping Cart - Week of {}",
-                            format_week_display(&week_start)
-                        );
+                        match &store {
+                            Some(layout) => println!(
+                                "Shopping Cart - {} at {}",
+                                capitalize(&format_period(&period)),
+                                layout.name
+                            ),
+                            None => {
+                                println!("Shopping Cart - {}", capitalize(&format_period(&period)))
+                            }
+                        }
                         println!("{}", "=".repeat(44));
 
                         let regular_items: Vec<_> = items.iter().filter(|i| !i.is_manual).collect();
@@ -168,52 +316,29 @@ impl ShoppingCommand {
 
                         if regular_items.is_empty() && manual_items.is_empty() {
                             println!("No items in cart.");
-                            println!("\nNo meal plans found for this week.");
-                        } else {
-                            // Display regular (from recipes) items
-                            for item in &regular_items {
-                                let check = if item.checked { "[x]" } else { "[ ]" };
-                                if item.unit.is_empty() {
-                                    println!(
-                                        "{} {:<25} {}",
-                                        check,
-                                        item.name,
-                                        format_quantity(item.quantity)
-                                    );
-                                } else {
-                                    println!(
-                                        "{} {:<25} {} {}",
-                                        check,
-                                        item.name,
-                                        format_quantity(item.quantity),
-                                        item.unit
-                                    );
-                                }
+                            if covered > 0 {
+                                println!(
+                                    "\nEverything this {} is covered by the pantry.",
+                                    period_noun(&period)
+                                );
+                            } else {
+                                println!(
+                                    "\nNo meal plans found for this {}.",
+                                    period_noun(&period)
+                                );
                             }
-
-                            // Separator and manual items
-                            if !manual_items.is_empty() {
-                                println!("{}", "-".repeat(44));
-                                println!("Manual items:");
-                                for item in &manual_items {
-                                    let check = if item.checked { "[x]" } else { "[ ]" };
-                                    if item.unit.is_empty() {
-                                        println!(
-                                            "{} {:<25} {}",
-                                            check,
-                                            item.name,
-                                            format_quantity(item.quantity)
-                                        );
-                                    } else {
-                                        println!(
-                                            "{} {:<25} {} {}",
-                                            check,
-                                            item.name,
-                                            format_quantity(item.quantity),
-                                            item.unit
-                                        );
+                        } else {
+                            // One section per category, recipe and manual items together
+                            let mut current: Option<&str> = None;
+                            for (item, category) in items.iter().zip(categories) {
+                                if current != Some(category.as_str()) {
+                                    if current.is_some() {
+                                        println!();
                                     }
+                                    println!("{}", capitalize(category));
+                                    current = Some(category);
                                 }
+                                println!("{}", format_item_line(item));
                             }
 
                             // Summary
@@ -221,8 +346,15 @@ impl ShoppingCommand {
                             let total = items.len();
                             println!("{}", "-".repeat(44));
                             println!("{} of {} items checked", checked_count, total);
+                            if covered > 0 {
+                                println!("{} more covered by the pantry", covered);
+                            }
                         }
                     }
+                    OutputFormat::Markdown => print!("{}", list.to_markdown()),
+                    OutputFormat::Text => print!("{}", list.to_text()),
+                    OutputFormat::Csv => print!("{}", list.to_csv()?),
+                    OutputFormat::Todo => print!("{}", list.to_todo_txt()),
                 }
                 Ok(())
             }
@@ -231,16 +363,15 @@ impl ShoppingCommand {
                 name,
                 qty,
                 unit,
-                week,
+                cart_args,
             } => {
-                let week_start = parse_week_or_current(week.as_deref())?;
-                let week_str = week_start.to_string();
+                let period = cart_args.period(config, shopping_repo)?;
 
                 if name.trim().is_empty() {
                     return Err("Item name cannot be empty".into());
                 }
 
-                let mut cart = shopping_repo.get_or_create(&week_str)?;
+                let mut cart = shopping_repo.get_or_create_for(period)?;
 
                 // Check for existing item
                 if cart.find_manual_item(name).is_some() {
@@ -263,18 +394,17 @@ impl ShoppingCommand {
                 shopping_repo.save(&cart)?;
 
                 println!(
-                    "Added '{}' to shopping cart for week of {}",
+                    "Added '{}' to shopping cart for {}",
                     name,
-                    format_week_display(&week_start)
+                    format_period(&period)
                 );
                 Ok(())
             }
 
-            ShoppingSubcommand::Remove { name, week } => {
-                let week_start = parse_week_or_current(week.as_deref())?;
-                let week_str = week_start.to_string();
+            ShoppingSubcommand::Remove { name, cart_args } => {
+                let period = cart_args.period(config, shopping_repo)?;
 
-                let mut cart = shopping_repo.get_or_create(&week_str)?;
+                let mut cart = shopping_repo.get_or_create_for(period)?;
 
                 if cart.remove_manual_item(name) {
                     shopping_repo.save(&cart)?;
@@ -288,43 +418,155 @@ impl ShoppingCommand {
                 Ok(())
             }
 
-            ShoppingSubcommand::Check { name, week } => {
-                let week_start = parse_week_or_current(week.as_deref())?;
-                let week_str = week_start.to_string();
+            ShoppingSubcommand::Check { name, cart_args } => {
+                let period = cart_args.period(config, shopping_repo)?;
 
-                let mut cart = shopping_repo.get_or_create(&week_str)?;
+                let mut cart = shopping_repo.get_or_create_for(period)?;
 
                 if cart.is_checked(name) {
                     println!("'{}' is already checked", name);
                 } else {
-                    cart.check(name);
+                    let stocked = check_item(
+                        name,
+                        &mut cart,
+                        period,
+                        pantry_repo,
+                        mealplan_repo,
+                        dish_repo,
+                    )?;
                     shopping_repo.save(&cart)?;
                     println!("Checked '{}' ✓", name);
+                    if let Some(item) = stocked {
+                        println!("Pantry: {}", item);
+                    }
                 }
                 Ok(())
             }
 
-            ShoppingSubcommand::Uncheck { name, week } => {
-                let week_start = parse_week_or_current(week.as_deref())?;
-                let week_str = week_start.to_string();
+            ShoppingSubcommand::Uncheck { name, cart_args } => {
+                let period = cart_args.period(config, shopping_repo)?;
 
-                let mut cart = shopping_repo.get_or_create(&week_str)?;
+                let mut cart = shopping_repo.get_or_create_for(period)?;
 
                 if !cart.is_checked(name) {
                     println!("'{}' is not checked", name);
                 } else {
-                    cart.uncheck(name);
+                    let restored = uncheck_item(name, &mut cart, pantry_repo)?;
                     shopping_repo.save(&cart)?;
                     println!("Unchecked '{}'", name);
+                    if let Some(item) = restored {
+                        println!("Pantry: {}", item);
+                    }
+                }
+                Ok(())
+            }
+
+            ShoppingSubcommand::Carts => {
+                let carts = shopping_repo.list()?;
+                if carts.is_empty() {
+                    println!("No saved carts.");
+                    return Ok(());
+                }
+                for cart in &carts {
+                    let Some(period) = cart.period() else {
+                        continue;
+                    };
+                    println!(
+                        "{:<24} {:<30} {} checked, {} manual",
+                        cart.week,
+                        format_period(&period),
+                        cart.checked.len(),
+                        cart.manual_items.len()
+                    );
+                }
+                Ok(())
+            }
+
+            ShoppingSubcommand::Import { file, cart_args } => {
+                let period = cart_args.period(config, shopping_repo)?;
+
+                let text = match file {
+                    Some(path) => fs::read_to_string(path)
+                        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+                    None => {
+                        let mut buf = String::new();
+                        io::stdin().read_to_string(&mut buf)?;
+                        buf
+                    }
+                };
+                let entries = parse_markdown_checklist(&text);
+                if entries.is_empty() {
+                    return Err("No checklist items ('- [ ]' or '- [x]') found".into());
+                }
+
+                let mut cart = shopping_repo.get_or_create_for(period)?;
+                let (_, group) = resolve_group_document(&config.data_dir.value, None)?;
+                let (list, _) = build_list(
+                    &cart,
+                    period,
+                    &group,
+                    None,
+                    mealplan_repo,
+                    dish_repo,
+                    pantry_repo,
+                )?;
+
+                let mut changed = 0;
+                let mut skipped = Vec::new();
+                for entry in &entries {
+                    let Some(item) = entry.find_item(&list.items) else {
+                        skipped.push(entry.text.as_str());
+                        continue;
+                    };
+                    if cart.is_checked(&item.name) == entry.checked {
+                        continue;
+                    }
+
+                    let pantry_item = if entry.checked {
+                        let stocked = check_item(
+                            &item.name,
+                            &mut cart,
+                            period,
+                            pantry_repo,
+                            mealplan_repo,
+                            dish_repo,
+                        )?;
+                        println!("Checked '{}' ✓", item.name);
+                        stocked
+                    } else {
+                        let restored = uncheck_item(&item.name, &mut cart, pantry_repo)?;
+                        println!("Unchecked '{}'", item.name);
+                        restored
+                    };
+                    if let Some(pantry_item) = pantry_item {
+                        println!("  Pantry: {}", pantry_item);
+                    }
+                    changed += 1;
+                }
+
+                if changed > 0 {
+                    shopping_repo.save(&cart)?;
+                }
+                println!(
+                    "Updated {} of {} items for {}",
+                    changed,
+                    entries.len(),
+                    format_period(&period)
+                );
+                for text in skipped {
+                    println!(
+                        "Skipped '{}': not on this {}'s list",
+                        text,
+                        period_noun(&period)
+                    );
                 }
                 Ok(())
             }
 
-            ShoppingSubcommand::ClearChecked { week } => {
-                let week_start = parse_week_or_current(week.as_deref())?;
-                let week_str = week_start.to_string();
+            ShoppingSubcommand::ClearChecked { cart_args } => {
+                let period = cart_args.period(config, shopping_repo)?;
 
-                let mut cart = shopping_repo.get_or_create(&week_str)?;
+                let mut cart = shopping_repo.get_or_create_for(period)?;
 
                 let count = cart.checked.len();
                 if count == 0 {
@@ -336,33 +578,168 @@ impl ShoppingCommand {
                 }
                 Ok(())
             }
+
+            ShoppingSubcommand::Category { command } => {
+                let (group_id, mut group) = resolve_group_document(&config.data_dir.value, None)?;
+
+                match command {
+                    CategorySubcommand::List => {
+                        if group.ingredient_categories.is_empty() {
+                            println!("No category overrides for '{}'.", group.name);
+                            println!("Built-in categories: {}", DEFAULT_ORDER.join(", "));
+                        }
+                        for (ingredient, category) in &group.ingredient_categories {
+                            println!("{:<25} {}", ingredient, category);
+                        }
+                        return Ok(());
+                    }
+                    CategorySubcommand::Set {
+                        ingredient,
+                        category,
+                    } => {
+                        if categories::normalize(category).is_empty() {
+                            return Err("Category cannot be empty".into());
+                        }
+                        group.set_ingredient_category(ingredient, category);
+                        println!(
+                            "'{}' is now in '{}'",
+                            ingredient,
+                            group.category_for(ingredient)
+                        );
+                    }
+                    CategorySubcommand::Remove { ingredient } => {
+                        if !group.remove_ingredient_category(ingredient) {
+                            return Err(format!("No category override for '{}'", ingredient).into());
+                        }
+                        println!(
+                            "'{}' is back in its default category '{}'",
+                            ingredient,
+                            group.category_for(ingredient)
+                        );
+                    }
+                }
+
+                save_group_document(&config.data_dir.value, &group_id, &group)?;
+                Ok(())
+            }
+
+            ShoppingSubcommand::Store { command } => {
+                let (group_id, mut group) = resolve_group_document(&config.data_dir.value, None)?;
+
+                match command {
+                    StoreSubcommand::List => {
+                        if group.store_layouts.is_empty() {
+                            println!("No store layouts for '{}'.", group.name);
+                            println!("Add one with 'fit shopping store set <NAME> <CATEGORIES>'.");
+                        }
+                        for layout in &group.store_layouts {
+                            println!("{}: {}", layout.name, layout.categories.join(" > "));
+                        }
+                        return Ok(());
+                    }
+                    StoreSubcommand::Set { name, categories } => {
+                        if name.trim().is_empty() {
+                            return Err("Store name cannot be empty".into());
+                        }
+                        let layout = StoreLayout::new(name.trim(), categories);
+                        if layout.categories.is_empty() {
+                            return Err("A store layout needs at least one category".into());
+                        }
+                        println!(
+                            "Saved store layout '{}': {}",
+                            layout.name,
+                            layout.categories.join(" > ")
+                        );
+                        group.set_store_layout(layout);
+                    }
+                    StoreSubcommand::Remove { name } => {
+                        if !group.remove_store_layout(name) {
+                            return Err(format!("Store layout '{}' not found", name).into());
+                        }
+                        println!("Removed store layout '{}'", name);
+                    }
+                }
+
+                save_group_document(&config.data_dir.value, &group_id, &group)?;
+                Ok(())
+            }
         }
     }
 }
 
-/// Parse a week date or return the current week's Sunday.
-fn parse_week_or_current(week_str: Option<&str>) -> Result<NaiveDate, Box<dyn std::error::Error>> {
+/// Parse a week date or return the start of the current week.
+fn parse_week_or_current(
+    week_str: Option<&str>,
+    first_day: Weekday,
+) -> Result<NaiveDate, Box<dyn std::error::Error>> {
     match week_str {
-        Some(s) => {
-            let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
-                .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", s))?;
-            Ok(get_week_start(date))
-        }
-        None => Ok(get_week_start(Local::now().date_naive())),
+        Some(s) => Ok(get_week_start(parse_date(s)?, first_day)),
+        None => Ok(get_week_start(Local::now().date_naive(), first_day)),
     }
 }
 
-/// Get the Sunday that starts the week containing the given date.
-fn get_week_start(date: NaiveDate) -> NaiveDate {
-    let days_since_sunday = date.weekday().num_days_from_sunday();
-    date - chrono::Duration::days(days_since_sunday as i64)
+/// Parse a YYYY-MM-DD date.
+fn parse_date(s: &str) -> Result<NaiveDate, Box<dyn std::error::Error>> {
+    Ok(NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date format '{}'. Use YYYY-MM-DD.", s))?)
 }
 
-/// Format the week start date for display (e.g., "Jan 11, 2026").
+/// Get the first day of the week containing the given date.
+fn get_week_start(date: NaiveDate, first_day: Weekday) -> NaiveDate {
+    CartPeriod::week_containing(date, first_day).start()
+}
+
+/// Format a date for display (e.g., "Jan 11, 2026").
 fn format_week_display(date: &NaiveDate) -> String {
     date.format("%b %d, %Y").to_string()
 }
 
+/// Describe a cart's period ("week of Jan 11, 2026", "Jan 15 to Jan 18, 2026").
+fn format_period(period: &CartPeriod) -> String {
+    match period {
+        CartPeriod::Week(start) => format!("week of {}", format_week_display(start)),
+        CartPeriod::Trip(from, to) if from.year() == to.year() => {
+            format!("{} to {}", from.format("%b %d"), format_week_display(to))
+        }
+        CartPeriod::Trip(from, to) => {
+            format!(
+                "{} to {}",
+                format_week_display(from),
+                format_week_display(to)
+            )
+        }
+    }
+}
+
+/// "week" or "trip", for messages about the cart.
+fn period_noun(period: &CartPeriod) -> &'static str {
+    match period {
+        CartPeriod::Week(_) => "week",
+        CartPeriod::Trip(..) => "trip",
+    }
+}
+
+/// One checkbox line of the shopping list.
+fn format_item_line(item: &ShoppingItem) -> String {
+    let check = if item.checked { "[x]" } else { "[ ]" };
+    if item.unit.is_empty() {
+        format!(
+            "{} {:<25} {}",
+            check,
+            item.name,
+            format_quantity(item.quantity)
+        )
+    } else {
+        format!(
+            "{} {:<25} {} {}",
+            check,
+            item.name,
+            format_quantity(item.quantity),
+            item.unit
+        )
+    }
+}
+
 /// Format a quantity, removing unnecessary decimal places.
 fn format_quantity(qty: f64) -> String {
     if qty.fract() == 0.0 {
@@ -372,7 +749,7 @@ fn format_quantity(qty: f64) -> String {
     }
 }
 
-/// Collect all ingredients from meal plans for a week.
+/// Collect all ingredients from meal plans between two dates (inclusive).
 fn collect_ingredients_for_week(
     mealplan_repo: &SyncMealPlanRepository,
     dish_repo: &SyncDishRepository,
@@ -382,44 +759,210 @@ fn collect_ingredients_for_week(
     let mut all_ingredients = Vec::new();
 
     let plans = mealplan_repo.list_range(from, to)?;
-
-    for plan in plans {
-        for dish_id in &plan.dish_ids {
-            if let Some(dish) = dish_repo.get_by_id(*dish_id)? {
-                all_ingredients.extend(dish.ingredients.clone());
-            }
-        }
+    let dishes = dish_repo.list()?;
+
+    for mut plan in plans {
+        // Sub-recipes are expanded and quantities scaled to the plan's
+        // servings; leftover plans add nothing, as their batch is counted
+        // once on the plan that cooks it, unless that plan is gone
+        mealplan_repo.unlink_missing_source(&mut plan)?;
+        all_ingredients.extend(plan.ingredients(&dishes)?);
     }
 
     Ok(all_ingredients)
 }
 
-/// Aggregate ingredients by name (case-insensitive), combining quantities where units match.
-fn aggregate_ingredients(ingredients: &[Ingredient]) -> Vec<Ingredient> {
-    use std::collections::HashMap;
+/// Build the shopping list for a cart's period, in display order.
+///
+/// Staples are left out, and unchecked items only show what the pantry
+/// doesn't cover; checked items show what they added to the pantry. Items
+/// are grouped by category, in the store's walking order when one is given
+/// and the default order otherwise. Also returns how many planned
+/// ingredients the pantry covers.
+fn build_list(
+    cart: &ShoppingCart,
+    period: CartPeriod,
+    group: &GroupDocument,
+    store: Option<&StoreLayout>,
+    mealplan_repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+    pantry_repo: &SyncPantryRepository,
+) -> Result<(ShoppingList, usize), Box<dyn std::error::Error>> {
+    // Get ingredients from meal plans for this period
+    let ingredients =
+        collect_ingredients_for_week(mealplan_repo, dish_repo, period.start(), period.end())?;
+
+    // Aggregate and deduplicate ingredients
+    let aggregated = aggregate_ingredients(&ingredients);
+    let planned = aggregated.len();
+
+    let pantry = pantry_repo.get()?;
+    let (checked, unchecked): (Vec<_>, Vec<_>) = aggregated
+        .into_iter()
+        .filter(|ing| !pantry.is_staple(&ing.name))
+        .partition(|ing| cart.is_checked(&ing.name));
+    let needed = pantry.subtract(&unchecked);
+    let covered = planned - checked.len() - needed.len();
+
+    // Build shopping items with checked status
+    let mut items: Vec<ShoppingItem> = needed
+        .iter()
+        .map(|ing| ShoppingItem::from_ingredient(ing, false))
+        .chain(checked.iter().map(|ing| {
+            let bought = cart.stocked_for(&ing.name).unwrap_or(ing);
+            ShoppingItem::from_ingredient(bought, true)
+        }))
+        .collect();
+
+    // Add manual items
+    for manual in &cart.manual_items {
+        items.push(ShoppingItem::from_manual(
+            manual,
+            cart.is_checked(&manual.name),
+        ));
+    }
 
-    // Group by (lowercase name, lowercase unit)
-    let mut grouped: HashMap<(String, String), f64> = HashMap::new();
+    // Sort: unchecked first, then alphabetical
+    items.sort_by(|a, b| {
+        if a.checked != b.checked {
+            a.checked.cmp(&b.checked) // unchecked (false) comes first
+        } else {
+            a.name.to_lowercase().cmp(&b.name.to_lowercase())
+        }
+    });
+
+    // Group by category in the store's walking order, or the default one
+    // (stable, so each category keeps the order above)
+    let mut title = format!("Shopping list - {}", capitalize(&format_period(&period)));
+    let default_layout = StoreLayout::new("", &[]);
+    let layout = match store {
+        Some(layout) => {
+            title.push_str(&format!(" at {}", layout.name));
+            layout
+        }
+        None => &default_layout,
+    };
+    items.sort_by_cached_key(|i| layout.rank(&group.category_for(&i.name)));
+    let categories: Vec<String> = items.iter().map(|i| group.category_for(&i.name)).collect();
+
+    let list = ShoppingList {
+        title,
+        items,
+        categories,
+    };
+    Ok((list, covered))
+}
 
-    for ing in ingredients {
-        let key = (ing.name.to_lowercase(), ing.unit.to_lowercase());
-        *grouped.entry(key).or_insert(0.0) += ing.quantity;
+/// Check an item and restock the pantry with what it still needed.
+fn check_item(
+    name: &str,
+    cart: &mut ShoppingCart,
+    period: CartPeriod,
+    pantry_repo: &SyncPantryRepository,
+    mealplan_repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+) -> Result<Option<PantryItem>, Box<dyn std::error::Error>> {
+    cart.check(name);
+    restock_pantry(name, cart, period, pantry_repo, mealplan_repo, dish_repo)
+}
+
+/// Uncheck an item and take back what checking it added to the pantry.
+fn uncheck_item(
+    name: &str,
+    cart: &mut ShoppingCart,
+    pantry_repo: &SyncPantryRepository,
+) -> Result<Option<PantryItem>, Box<dyn std::error::Error>> {
+    cart.uncheck(name);
+    let Some(bought) = cart.take_stocked(name) else {
+        return Ok(None);
+    };
+    let mut pantry = pantry_repo.get()?;
+    let restored = pantry
+        .consume(&bought.name, bought.quantity, &bought.unit)
+        .cloned();
+    if let Some(item) = &restored {
+        pantry_repo.save_items(std::slice::from_ref(item))?;
     }
+    Ok(restored)
+}
 
-    // Convert back to Ingredient, preserving original case from first occurrence
+/// Add the amount a checked item still needed to the pantry, and record it on
+/// the cart so unchecking can take it back out.
+///
+/// Returns the updated pantry item, or `None` if nothing was stocked (a staple,
+/// an item without a quantity, or stock kept in a unit that can't be converted).
+fn restock_pantry(
+    name: &str,
+    cart: &mut ShoppingCart,
+    period: CartPeriod,
+    pantry_repo: &SyncPantryRepository,
+    mealplan_repo: &SyncMealPlanRepository,
+    dish_repo: &SyncDishRepository,
+) -> Result<Option<PantryItem>, Box<dyn std::error::Error>> {
+    let mut pantry = pantry_repo.get()?;
+    if pantry.is_staple(name) {
+        return Ok(None);
+    }
+
+    let needs = aggregate_ingredients(&collect_ingredients_for_week(
+        mealplan_repo,
+        dish_repo,
+        period.start(),
+        period.end(),
+    )?);
+    let name_lower = name.to_lowercase();
+    let bought = match needs.iter().find(|i| i.name.to_lowercase() == name_lower) {
+        Some(ing) => pantry.subtract(std::slice::from_ref(ing)).pop(),
+        None => cart.find_manual_item(name).and_then(manual_ingredient),
+    };
+
+    let Some(bought) = bought else {
+        return Ok(None);
+    };
+    let Some(item) = pantry
+        .add(&bought.name, bought.quantity, &bought.unit)
+        .cloned()
+    else {
+        return Ok(None);
+    };
+    pantry_repo.save_items(std::slice::from_ref(&item))?;
+    cart.record_stocked(bought);
+    Ok(Some(item))
+}
+
+/// A manual item as an ingredient, when its quantity is a number.
+fn manual_ingredient(item: &ManualItem) -> Option<Ingredient> {
+    let quantity = item.quantity.as_ref()?.trim().parse::<f64>().ok()?;
+    let unit = item.unit.clone().unwrap_or_default();
+    Some(Ingredient::new(&item.name, quantity, unit))
+}
+
+/// Aggregate ingredients by name (case-insensitive), combining quantities across units.
+///
+/// Quantities are converted into the unit of the first occurrence when the units
+/// are compatible (including volume/mass for ingredients with a known density).
+/// Lines whose units can't be converted are kept as separate entries.
+fn aggregate_ingredients(ingredients: &[Ingredient]) -> Vec<Ingredient> {
     let mut result: Vec<Ingredient> = Vec::new();
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
 
     for ing in ingredients {
-        let key = ing.name.to_lowercase();
-        if seen.contains(&key) {
-            continue;
-        }
-        seen.insert(key.clone());
+        let name_key = ing.name.to_lowercase();
+        let merged = result
+            .iter_mut()
+            .filter(|existing| existing.name.to_lowercase() == name_key)
+            .find_map(|existing| {
+                let quantity = if existing.unit.eq_ignore_ascii_case(&ing.unit) {
+                    ing.quantity
+                } else {
+                    units::convert_ingredient(&ing.name, ing.quantity, &ing.unit, &existing.unit)
+                        .ok()?
+                };
+                existing.quantity += quantity;
+                Some(())
+            });
 
-        let unit_key = ing.unit.to_lowercase();
-        if let Some(&qty) = grouped.get(&(key, unit_key)) {
-            result.push(Ingredient::new(&ing.name, qty, &ing.unit));
+        if merged.is_none() {
+            result.push(ing.clone());
         }
     }
 
@@ -433,21 +976,21 @@ mod tests {
     #[test]
     fn test_get_week_start_from_sunday() {
         let sunday = NaiveDate::from_ymd_opt(2026, 1, 11).unwrap();
-        assert_eq!(get_week_start(sunday), sunday);
+        assert_eq!(get_week_start(sunday, Weekday::Sun), sunday);
     }
 
     #[test]
     fn test_get_week_start_from_wednesday() {
         let wednesday = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
         let sunday = NaiveDate::from_ymd_opt(2026, 1, 11).unwrap();
-        assert_eq!(get_week_start(wednesday), sunday);
+        assert_eq!(get_week_start(wednesday, Weekday::Sun), sunday);
     }
 
     #[test]
     fn test_get_week_start_from_saturday() {
         let saturday = NaiveDate::from_ymd_opt(2026, 1, 17).unwrap();
         let sunday = NaiveDate::from_ymd_opt(2026, 1, 11).unwrap();
-        assert_eq!(get_week_start(saturday), sunday);
+        assert_eq!(get_week_start(saturday, Weekday::Sun), sunday);
     }
 
     #[test]
@@ -476,17 +1019,79 @@ mod tests {
 
     #[test]
     fn test_aggregate_ingredients_same_name_different_units() {
-        // When the same ingredient has different units, we keep only the first one
-        // (limitation: could be improved to convert units in the future)
+        // Compatible units are converted into the first unit seen
         let ingredients = vec![
             Ingredient::new("chicken", 1.0, "lb"),
             Ingredient::new("chicken", 500.0, "g"),
         ];
 
         let aggregated = aggregate_ingredients(&ingredients);
-        // Only first unit is kept (current behavior)
         assert_eq!(aggregated.len(), 1);
         assert_eq!(aggregated[0].unit, "lb");
+        assert!((aggregated[0].quantity - 2.102).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_aggregate_ingredients_volume_and_mass() {
+        // Flour has a known density, so cups and grams merge
+        let ingredients = vec![
+            Ingredient::new("flour", 200.0, "g"),
+            Ingredient::new("Flour", 1.0, "cup"),
+        ];
+
+        let aggregated = aggregate_ingredients(&ingredients);
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated[0].unit, "g");
+        assert!((aggregated[0].quantity - 325.4).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_aggregate_ingredients_without_density_kept_separate() {
+        // "cream cheese" isn't cream, so cups and grams can't be merged
+        let ingredients = vec![
+            Ingredient::new("cream cheese", 500.0, "g"),
+            Ingredient::new("cream cheese", 1.0, "cup"),
+        ];
+
+        let aggregated = aggregate_ingredients(&ingredients);
+        assert_eq!(aggregated.len(), 2);
+    }
+
+    #[test]
+    fn test_aggregate_ingredients_incompatible_units_kept_separate() {
+        let ingredients = vec![
+            Ingredient::new("garlic", 3.0, "cloves"),
+            Ingredient::new("garlic", 1.0, "tbsp"),
+            Ingredient::new("garlic", 2.0, "cloves"),
+        ];
+
+        let aggregated = aggregate_ingredients(&ingredients);
+        assert_eq!(aggregated.len(), 2);
+        assert_eq!(aggregated[0].quantity, 5.0);
+        assert_eq!(aggregated[0].unit, "cloves");
+        assert_eq!(aggregated[1].unit, "tbsp");
+    }
+
+    #[test]
+    fn test_manual_ingredient_needs_numeric_quantity() {
+        let soap = manual_ingredient(&ManualItem::with_quantity("Soap", "3", "bars")).unwrap();
+        assert_eq!(soap.quantity, 3.0);
+        assert_eq!(soap.unit, "bars");
+
+        assert!(manual_ingredient(&ManualItem::with_quantity("Chips", "a few", "")).is_none());
+        assert!(manual_ingredient(&ManualItem::new("Napkins")).is_none());
+    }
+
+    #[test]
+    fn test_format_item_line() {
+        let eggs = ShoppingItem::from_ingredient(&Ingredient::new("eggs", 6.0, ""), false);
+        assert_eq!(
+            format_item_line(&eggs).trim_end(),
+            "[ ] eggs                      6"
+        );
+
+        let milk = ShoppingItem::from_ingredient(&Ingredient::new("milk", 1.5, "l"), true);
+        assert!(format_item_line(&milk).ends_with("1.5 l"));
     }
 
     #[test]
@@ -501,16 +1106,40 @@ mod tests {
         assert_eq!(format_quantity(2.25), "2.2"); // rounds to 1 decimal (truncation)
     }
 
+    #[test]
+    fn test_get_week_start_thursday() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+        let thursday = NaiveDate::from_ymd_opt(2026, 1, 8).unwrap();
+        assert_eq!(get_week_start(wednesday, Weekday::Thu), thursday);
+    }
+
+    #[test]
+    fn test_format_period() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            format_period(&CartPeriod::Week(date(2026, 1, 11))),
+            "week of Jan 11, 2026"
+        );
+        assert_eq!(
+            format_period(&CartPeriod::Trip(date(2026, 1, 15), date(2026, 1, 18))),
+            "Jan 15 to Jan 18, 2026"
+        );
+        assert_eq!(
+            format_period(&CartPeriod::Trip(date(2026, 12, 30), date(2027, 1, 2))),
+            "Dec 30, 2026 to Jan 02, 2027"
+        );
+    }
+
     #[test]
     fn test_parse_week_or_current_valid() {
-        let result = parse_week_or_current(Some("2026-01-14")).unwrap();
+        let result = parse_week_or_current(Some("2026-01-14"), Weekday::Sun).unwrap();
         // Should return the Sunday of that week
         assert_eq!(result, NaiveDate::from_ymd_opt(2026, 1, 11).unwrap());
     }
 
     #[test]
     fn test_parse_week_or_current_invalid() {
-        let result = parse_week_or_current(Some("invalid"));
+        let result = parse_week_or_current(Some("invalid"), Weekday::Sun);
         assert!(result.is_err());
     }
 }
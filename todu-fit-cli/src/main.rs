@@ -7,14 +7,16 @@ mod models;
 mod sync;
 
 use commands::{
-    meal::MealRepos, ConfigCommand, DeviceCommand, DishCommand, DishSubcommand, GroupCommand,
-    GroupSubcommand, InitCommand, MealCommand, MealPlanCommand, MealPlanSubcommand, MealSubcommand,
-    ShoppingCommand, ShoppingSubcommand, SyncCommand,
+    meal::MealRepos, CategorySubcommand, ConfigCommand, DeviceCommand, DishCommand, DishSubcommand,
+    FoodCommand, GoalCommand, GoalSubcommand, GroupCommand, GroupSubcommand, InitCommand,
+    MealCommand, MealPlanCommand, MealPlanSubcommand, MealSubcommand, PantryCommand,
+    PantrySubcommand, RecurringSubcommand, ReportCommand, ReportSubcommand, ShoppingCommand,
+    ShoppingSubcommand, SlotSubcommand, StoreSubcommand, SyncCommand, TemplateSubcommand,
 };
 use config::Config;
 use sync::{
     try_auto_sync, SyncDishRepository, SyncMealLogRepository, SyncMealPlanRepository,
-    SyncShoppingRepository,
+    SyncPantryRepository, SyncShoppingRepository,
 };
 
 #[derive(Parser)]
@@ -44,12 +46,24 @@ enum Commands {
     /// Manage dishes (recipes)
     Dish(DishCommand),
 
+    /// Manage the local food composition database
+    Food(FoodCommand),
+
+    /// Manage daily nutrition goals
+    Goal(GoalCommand),
+
     /// Log and track meals
     Meal(MealCommand),
 
     /// Manage meal plans
     Mealplan(MealPlanCommand),
 
+    /// Track ingredients on hand
+    Pantry(PantryCommand),
+
+    /// Nutrition reports over time
+    Report(ReportCommand),
+
     /// Manage shopping carts
     Shopping(ShoppingCommand),
 
@@ -111,15 +125,23 @@ fn execute_command(
             let repo = SyncDishRepository::new(config.data_dir.value.clone());
             cmd.run(&repo, config)?;
         }
+        Some(Commands::Food(cmd)) => {
+            cmd.run(config)?;
+        }
+        Some(Commands::Goal(cmd)) => {
+            cmd.run(config)?;
+        }
         Some(Commands::Meal(cmd)) => {
             let data_dir = config.data_dir.value.clone();
             let meallog_repo = SyncMealLogRepository::new(data_dir.clone());
             let mealplan_repo = SyncMealPlanRepository::new(data_dir.clone());
-            let dish_repo = SyncDishRepository::new(data_dir);
+            let dish_repo = SyncDishRepository::new(data_dir.clone());
+            let pantry_repo = SyncPantryRepository::new(data_dir);
             let repos = MealRepos {
                 meallog: &meallog_repo,
                 mealplan: &mealplan_repo,
                 dish: &dish_repo,
+                pantry: &pantry_repo,
             };
             cmd.run(repos, config)?;
         }
@@ -129,12 +151,27 @@ fn execute_command(
             let dish_repo = SyncDishRepository::new(data_dir);
             cmd.run(&mealplan_repo, &dish_repo, config)?;
         }
+        Some(Commands::Pantry(cmd)) => {
+            let repo = SyncPantryRepository::new(config.data_dir.value.clone());
+            cmd.run(&repo)?;
+        }
+        Some(Commands::Report(cmd)) => {
+            let repo = SyncMealLogRepository::new(config.data_dir.value.clone());
+            cmd.run(&repo)?;
+        }
         Some(Commands::Shopping(cmd)) => {
             let data_dir = config.data_dir.value.clone();
             let shopping_repo = SyncShoppingRepository::new(data_dir.clone());
             let mealplan_repo = SyncMealPlanRepository::new(data_dir.clone());
-            let dish_repo = SyncDishRepository::new(data_dir);
-            cmd.run(&shopping_repo, &mealplan_repo, &dish_repo, config)?;
+            let dish_repo = SyncDishRepository::new(data_dir.clone());
+            let pantry_repo = SyncPantryRepository::new(data_dir);
+            cmd.run(
+                &shopping_repo,
+                &mealplan_repo,
+                &dish_repo,
+                &pantry_repo,
+                config,
+            )?;
         }
         Some(Commands::Config(cmd)) => {
             cmd.run(config, cli_config_path)?;
@@ -155,23 +192,47 @@ fn is_read_command(cmd: &Option<Commands>) -> bool {
     matches!(
         cmd,
         Some(Commands::Dish(d)) if matches!(d.command,
-            DishSubcommand::List { .. } | DishSubcommand::Show { .. })
+            DishSubcommand::List { .. }
+            | DishSubcommand::Show { .. }
+            | DishSubcommand::History { .. }
+            | DishSubcommand::Scale { .. }
+            | DishSubcommand::Nutrition { save: false, .. }
+            | DishSubcommand::Export { .. })
     ) || matches!(
         cmd,
         Some(Commands::Meal(m)) if matches!(m.command,
             MealSubcommand::History { .. })
+    ) || matches!(
+        cmd,
+        Some(Commands::Goal(g)) if matches!(g.command, GoalSubcommand::List { .. })
     ) || matches!(
         cmd,
         Some(Commands::Mealplan(mp)) if matches!(mp.command,
-            MealPlanSubcommand::List { .. } | MealPlanSubcommand::Show { .. })
+            MealPlanSubcommand::List { .. }
+            | MealPlanSubcommand::Show { .. }
+            | MealPlanSubcommand::Export { .. }
+            | MealPlanSubcommand::Template { command: TemplateSubcommand::List }
+            | MealPlanSubcommand::Recurring { command: RecurringSubcommand::List { .. } })
+    ) || matches!(
+        cmd,
+        Some(Commands::Pantry(p)) if matches!(p.command, PantrySubcommand::List { .. })
+    ) || matches!(
+        cmd,
+        Some(Commands::Report(r)) if matches!(r.command, ReportSubcommand::Nutrition { .. })
     ) || matches!(
         cmd,
         Some(Commands::Group(g)) if matches!(g.command,
-            GroupSubcommand::List | GroupSubcommand::Show)
+            GroupSubcommand::List
+            | GroupSubcommand::Show
+            | GroupSubcommand::Slot { command: SlotSubcommand::List }
+            | GroupSubcommand::WeekStart { day: None })
     ) || matches!(
         cmd,
         Some(Commands::Shopping(s)) if matches!(s.command,
-            ShoppingSubcommand::List { .. })
+            ShoppingSubcommand::List { .. }
+            | ShoppingSubcommand::Carts
+            | ShoppingSubcommand::Category { command: CategorySubcommand::List }
+            | ShoppingSubcommand::Store { command: StoreSubcommand::List })
     )
 }
 
@@ -181,26 +242,62 @@ fn is_write_command(cmd: &Option<Commands>) -> bool {
         cmd,
         Some(Commands::Dish(d)) if matches!(d.command,
             DishSubcommand::Create { .. }
+            | DishSubcommand::Import { .. }
             | DishSubcommand::Update { .. }
             | DishSubcommand::Delete { .. }
+            | DishSubcommand::Revert { .. }
             | DishSubcommand::AddIngredient { .. }
-            | DishSubcommand::RemoveIngredient { .. })
+            | DishSubcommand::AddIngredients { .. }
+            | DishSubcommand::RemoveIngredient { .. }
+            | DishSubcommand::AddComponent { .. }
+            | DishSubcommand::RemoveComponent { .. }
+            | DishSubcommand::Nutrition { save: true, .. })
     ) || matches!(
         cmd,
         Some(Commands::Meal(m)) if matches!(m.command,
-            MealSubcommand::Log { .. })
+            MealSubcommand::Log { .. } | MealSubcommand::Update { .. })
+    ) || matches!(
+        cmd,
+        Some(Commands::Goal(g)) if matches!(g.command,
+            GoalSubcommand::Set { .. } | GoalSubcommand::Remove { .. })
     ) || matches!(
         cmd,
         Some(Commands::Mealplan(mp)) if matches!(mp.command,
             MealPlanSubcommand::Create { .. }
             | MealPlanSubcommand::Update { .. }
-            | MealPlanSubcommand::Delete { .. })
+            | MealPlanSubcommand::Delete { .. }
+            | MealPlanSubcommand::Copy { .. }
+            | MealPlanSubcommand::Shift { .. }
+            | MealPlanSubcommand::Swap { .. }
+            | MealPlanSubcommand::Generate { .. }
+            | MealPlanSubcommand::Import { .. }
+            | MealPlanSubcommand::Template {
+                command: TemplateSubcommand::Save { .. }
+                    | TemplateSubcommand::Apply { .. }
+                    | TemplateSubcommand::Delete { .. }
+            }
+            | MealPlanSubcommand::Recurring {
+                command: RecurringSubcommand::Add { .. }
+                    | RecurringSubcommand::Skip { .. }
+                    | RecurringSubcommand::Restore { .. }
+                    | RecurringSubcommand::Delete { .. }
+            })
+    ) || matches!(
+        cmd,
+        Some(Commands::Pantry(p)) if !matches!(p.command, PantrySubcommand::List { .. })
     ) || matches!(
         cmd,
         Some(Commands::Group(g)) if matches!(g.command,
             GroupSubcommand::Create { .. }
             | GroupSubcommand::Join { .. }
-            | GroupSubcommand::Leave { .. })
+            | GroupSubcommand::Leave { .. }
+            | GroupSubcommand::WeekStart { day: Some(_) }
+            | GroupSubcommand::Slot {
+                command: SlotSubcommand::Add { .. }
+                    | SlotSubcommand::Remove { .. }
+                    | SlotSubcommand::Move { .. }
+                    | SlotSubcommand::Time { .. }
+            })
     ) || matches!(
         cmd,
         Some(Commands::Shopping(s)) if matches!(s.command,
@@ -208,6 +305,13 @@ fn is_write_command(cmd: &Option<Commands>) -> bool {
             | ShoppingSubcommand::Remove { .. }
             | ShoppingSubcommand::Check { .. }
             | ShoppingSubcommand::Uncheck { .. }
-            | ShoppingSubcommand::ClearChecked { .. })
+            | ShoppingSubcommand::Import { .. }
+            | ShoppingSubcommand::ClearChecked { .. }
+            | ShoppingSubcommand::Category {
+                command: CategorySubcommand::Set { .. } | CategorySubcommand::Remove { .. }
+            }
+            | ShoppingSubcommand::Store {
+                command: StoreSubcommand::Set { .. } | StoreSubcommand::Remove { .. }
+            })
     )
 }
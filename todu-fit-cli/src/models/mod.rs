@@ -1,2 +1,4 @@
 // Re-export models from todu-fit-core
-pub use todu_fit_core::{Dish, Ingredient, MealLog, MealPlan, MealType, Nutrient};
+pub use todu_fit_core::{
+    Dish, DishComponent, FoodEntry, Ingredient, MealLog, MealPlan, MealSlot, MealType, Nutrient,
+};
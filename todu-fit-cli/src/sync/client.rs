@@ -210,6 +210,11 @@ impl SyncClient {
                         self.sync_document(&group_doc.shopping_carts_doc_id, &shopping_name)
                             .await?,
                     );
+
+                    // Sync pantry
+                    let pantry_doc_id = group_doc.pantry_doc_id(&group_ref.doc_id);
+                    let pantry_name = format!("{}:pantry", group_ref.name);
+                    results.push(self.sync_document(&pantry_doc_id, &pantry_name).await?);
                 }
                 Err(_) => {
                     // Group document not synced yet, will get it next time
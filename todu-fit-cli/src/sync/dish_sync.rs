@@ -8,9 +8,12 @@ use std::path::PathBuf;
 use automerge::AutoCommit;
 use uuid::Uuid;
 
-use todu_fit_core::{DocumentId, MultiDocStorage};
+use todu_fit_core::automerge::{doc_at, key_history, resolve_revision, SHORT_HASH_LEN};
+use todu_fit_core::{
+    commit_with_message, CompositionError, DocumentId, HistoryError, MultiDocStorage, Revision,
+};
 
-use crate::models::{Dish, Ingredient};
+use crate::models::{Dish, DishComponent, Ingredient};
 use crate::sync::group_context::{resolve_group_context, GroupContextError};
 use crate::sync::reader::{
     filter_dishes_by_tag, find_dish_by_name, read_all_dishes, read_dish_by_id,
@@ -29,6 +32,10 @@ pub enum SyncDishError {
     GroupContext(GroupContextError),
     /// Multi-storage error.
     MultiStorage(todu_fit_core::MultiStorageError),
+    /// Revision lookup error.
+    History(HistoryError),
+    /// Invalid dish components (e.g. a cycle).
+    Composition(CompositionError),
 }
 
 impl std::fmt::Display for SyncDishError {
@@ -38,6 +45,8 @@ impl std::fmt::Display for SyncDishError {
             SyncDishError::NotFound(id) => write!(f, "Dish not found: {}", id),
             SyncDishError::GroupContext(e) => write!(f, "{}", e),
             SyncDishError::MultiStorage(e) => write!(f, "Storage error: {}", e),
+            SyncDishError::History(e) => write!(f, "{}", e),
+            SyncDishError::Composition(e) => write!(f, "{}", e),
         }
     }
 }
@@ -62,6 +71,18 @@ impl From<todu_fit_core::MultiStorageError> for SyncDishError {
     }
 }
 
+impl From<HistoryError> for SyncDishError {
+    fn from(e: HistoryError) -> Self {
+        SyncDishError::History(e)
+    }
+}
+
+impl From<CompositionError> for SyncDishError {
+    fn from(e: CompositionError) -> Self {
+        SyncDishError::Composition(e)
+    }
+}
+
 /// Sync-aware dish repository.
 ///
 /// All operations work directly with Automerge documents.
@@ -109,8 +130,14 @@ impl SyncDishRepository {
         Ok((doc, doc_id))
     }
 
-    /// Saves the document to storage.
-    fn save_doc(&self, doc: &mut AutoCommit, doc_id: &DocumentId) -> Result<(), SyncDishError> {
+    /// Commits pending changes with `message` and saves the document to storage.
+    fn save_doc(
+        &self,
+        doc: &mut AutoCommit,
+        doc_id: &DocumentId,
+        message: &str,
+    ) -> Result<(), SyncDishError> {
+        commit_with_message(doc, message);
         let bytes = doc.save();
         self.storage.save(doc_id, &bytes)?;
         Ok(())
@@ -124,13 +151,28 @@ impl SyncDishRepository {
         writer::write_dish(&mut doc, dish);
 
         // Save
-        self.save_doc(&mut doc, &doc_id)?;
+        self.save_doc(&mut doc, &doc_id, &format!("Create dish '{}'", dish.name))?;
 
         // Return the dish (read back to confirm)
         self.get_by_id(dish.id)?
             .ok_or_else(|| SyncDishError::NotFound(dish.id.to_string()))
     }
 
+    /// Creates several dishes with a single document load and save.
+    pub fn create_many(&self, dishes: &[Dish]) -> Result<(), SyncDishError> {
+        let (mut doc, doc_id) = self.load_or_create_doc()?;
+
+        for dish in dishes {
+            writer::write_dish(&mut doc, dish);
+        }
+
+        self.save_doc(
+            &mut doc,
+            &doc_id,
+            &format!("Import {} dish(es)", dishes.len()),
+        )
+    }
+
     /// Updates an existing dish.
     pub fn update(&self, dish: &Dish) -> Result<Dish, SyncDishError> {
         let (mut doc, doc_id) = self.load_or_create_doc()?;
@@ -139,7 +181,7 @@ impl SyncDishRepository {
         writer::write_dish(&mut doc, dish);
 
         // Save
-        self.save_doc(&mut doc, &doc_id)?;
+        self.save_doc(&mut doc, &doc_id, &format!("Update dish '{}'", dish.name))?;
 
         // Return the updated dish
         self.get_by_id(dish.id)?
@@ -154,11 +196,53 @@ impl SyncDishRepository {
         writer::delete_dish(&mut doc, id);
 
         // Save
-        self.save_doc(&mut doc, &doc_id)?;
+        self.save_doc(&mut doc, &doc_id, &format!("Delete dish {}", id))?;
 
         Ok(())
     }
 
+    /// Lists the changes that touched a dish, oldest first.
+    pub fn history(&self, id: Uuid) -> Result<Vec<Revision>, SyncDishError> {
+        let (mut doc, _) = self.load_or_create_doc()?;
+        Ok(key_history(&mut doc, &id.to_string()))
+    }
+
+    /// Gets a dish as it was right after the revision matching `rev` (a
+    /// change hash or unique prefix). Returns `None` if the dish didn't
+    /// exist at that revision.
+    pub fn get_at_revision(&self, id: Uuid, rev: &str) -> Result<Option<Dish>, SyncDishError> {
+        let (mut doc, _) = self.load_or_create_doc()?;
+        let hash = resolve_revision(&mut doc, rev)?;
+        let old = doc_at(&mut doc, hash)?;
+        Ok(read_dish_by_id(&old, id)?)
+    }
+
+    /// Restores a dish to an earlier revision as a new change, so the revert
+    /// itself shows up in history and syncs like any other edit.
+    pub fn revert(&self, id: Uuid, rev: &str) -> Result<Dish, SyncDishError> {
+        let (mut doc, doc_id) = self.load_or_create_doc()?;
+        let hash = resolve_revision(&mut doc, rev)?;
+        let old = doc_at(&mut doc, hash)?;
+        let mut dish = read_dish_by_id(&old, id)?
+            .ok_or_else(|| SyncDishError::NotFound(format!("{} at revision {}", id, rev)))?;
+        dish.updated_at = chrono::Utc::now();
+
+        writer::write_dish(&mut doc, &dish);
+        let rev_hash = hash.to_string();
+        self.save_doc(
+            &mut doc,
+            &doc_id,
+            &format!(
+                "Revert dish '{}' to {}",
+                dish.name,
+                &rev_hash[..SHORT_HASH_LEN]
+            ),
+        )?;
+
+        self.get_by_id(id)?
+            .ok_or_else(|| SyncDishError::NotFound(id.to_string()))
+    }
+
     /// Gets a dish by ID.
     pub fn get_by_id(&self, id: Uuid) -> Result<Option<Dish>, SyncDishError> {
         let (doc, _) = self.load_or_create_doc()?;
@@ -178,6 +262,7 @@ impl SyncDishRepository {
     }
 
     /// Adds an ingredient to a dish.
+    #[allow(dead_code)]
     pub fn add_ingredient(
         &self,
         dish_id: Uuid,
@@ -198,6 +283,7 @@ impl SyncDishRepository {
     }
 
     /// Removes an ingredient from a dish by name.
+    #[allow(dead_code)]
     pub fn remove_ingredient(
         &self,
         dish_id: Uuid,
@@ -219,6 +305,70 @@ impl SyncDishRepository {
         Ok(())
     }
 
+    /// Adds another dish as a component, or changes its scale if it is
+    /// already one. Fails if the component would lead back to the dish.
+    pub fn add_component(
+        &self,
+        dish_id: Uuid,
+        component_id: Uuid,
+        scale: f64,
+    ) -> Result<Dish, SyncDishError> {
+        let mut dish = self
+            .get_by_id(dish_id)?
+            .ok_or_else(|| SyncDishError::NotFound(dish_id.to_string()))?;
+
+        match dish
+            .components
+            .iter_mut()
+            .find(|c| c.dish_id == component_id)
+        {
+            Some(existing) => existing.scale = scale,
+            None => dish
+                .components
+                .push(DishComponent::new(component_id, scale)),
+        }
+        dish.check_components(&self.list()?)?;
+
+        self.update(&dish)
+    }
+
+    /// Removes a component from a dish. Returns false if it wasn't one.
+    pub fn remove_component(
+        &self,
+        dish_id: Uuid,
+        component_id: Uuid,
+    ) -> Result<bool, SyncDishError> {
+        let mut dish = self
+            .get_by_id(dish_id)?
+            .ok_or_else(|| SyncDishError::NotFound(dish_id.to_string()))?;
+
+        let before = dish.components.len();
+        dish.components.retain(|c| c.dish_id != component_id);
+        if dish.components.len() == before {
+            return Ok(false);
+        }
+
+        self.update(&dish)?;
+        Ok(true)
+    }
+
+    /// Returns a copy of a dish with its components expanded, ready for
+    /// scaling, nutrition totals, shopping lists and meal log snapshots.
+    pub fn flatten(&self, dish: &Dish) -> Result<Dish, SyncDishError> {
+        if dish.components.is_empty() {
+            return Ok(dish.clone());
+        }
+        Ok(dish.flatten(&self.list()?)?)
+    }
+
+    /// Gets a dish by ID with its components expanded.
+    pub fn get_flattened(&self, id: Uuid) -> Result<Option<Dish>, SyncDishError> {
+        match self.get_by_id(id)? {
+            Some(dish) => Ok(Some(self.flatten(&dish)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Searches dishes by name (partial, case-insensitive).
     #[allow(dead_code)]
     pub fn search(&self, query: &str) -> Result<Vec<Dish>, SyncDishError> {
@@ -274,6 +424,14 @@ mod tests {
             self.get_by_id(dish.id).unwrap()
         }
 
+        fn create_many(&self, dishes: &[Dish]) {
+            let mut doc = self.load_or_create_doc();
+            for dish in dishes {
+                writer::write_dish(&mut doc, dish);
+            }
+            self.save_doc(&mut doc);
+        }
+
         fn update(&self, dish: &Dish) -> Dish {
             let mut doc = self.load_or_create_doc();
             writer::write_dish(&mut doc, dish);
@@ -347,6 +505,20 @@ mod tests {
         assert_eq!(fetched.id, dish.id);
     }
 
+    #[test]
+    fn test_create_many() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestDishRepo::new(&temp_dir);
+
+        repo.create(&Dish::new("Existing", "chef"));
+        repo.create_many(&[Dish::new("Soup", "chef"), Dish::new("Salad", "chef")]);
+
+        let dishes = repo.list();
+        assert_eq!(dishes.len(), 3);
+        assert!(repo.get_by_name("Soup").is_some());
+        assert!(repo.get_by_name("Salad").is_some());
+    }
+
     #[test]
     fn test_update() {
         let temp_dir = TempDir::new().unwrap();
@@ -464,4 +636,81 @@ mod tests {
         assert_eq!(fetched.ingredients.len(), 1);
         assert_eq!(fetched.ingredients[0].name, "Pepper");
     }
+
+    #[test]
+    fn test_read_dish_at_earlier_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestDishRepo::new(&temp_dir);
+
+        let mut dish = Dish::new("Chili", "chef");
+        repo.create(&dish);
+        dish.name = "Chili con Carne".to_string();
+        repo.update(&dish);
+        repo.create(&Dish::new("Salad", "chef"));
+
+        let mut doc = repo.load_or_create_doc();
+        let history = key_history(&mut doc, &dish.id.to_string());
+        assert_eq!(history.len(), 2);
+
+        let rev = history[0].short_hash();
+        let hash = resolve_revision(&mut doc, &rev).unwrap();
+        let old = doc_at(&mut doc, hash).unwrap();
+        let old_dish = read_dish_by_id(&old, dish.id).unwrap().unwrap();
+        assert_eq!(old_dish.name, "Chili");
+    }
+
+    #[test]
+    fn test_revert_adds_a_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let identity =
+            todu_fit_core::Identity::new(MultiDocStorage::new(temp_dir.path().to_path_buf()));
+        identity.initialize_new().unwrap();
+        identity.create_group("Home").unwrap();
+        let repo = SyncDishRepository::new(temp_dir.path().to_path_buf());
+
+        let mut dish = Dish::new("Chili", "chef").with_servings(4);
+        repo.create(&dish).unwrap();
+        dish.name = "Chili con Carne".to_string();
+        dish.servings = Some(6);
+        repo.update(&dish).unwrap();
+        dish.servings = Some(8);
+        repo.update(&dish).unwrap();
+
+        let before = repo.history(dish.id).unwrap();
+        assert_eq!(before.len(), 3);
+        let reverted = repo.revert(dish.id, &before[0].short_hash()).unwrap();
+        assert_eq!(reverted.name, "Chili");
+        assert_eq!(reverted.servings, Some(4));
+
+        // The revert is added on top; earlier revisions are untouched
+        let after = repo.history(dish.id).unwrap();
+        assert_eq!(after.len(), 4);
+        assert_eq!(after[..3], before[..]);
+        assert!(after[3]
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("Revert dish"));
+        let latest = repo.get_at_revision(dish.id, &before[2].short_hash());
+        assert_eq!(latest.unwrap().unwrap().servings, Some(8));
+    }
+
+    #[test]
+    fn test_components_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestDishRepo::new(&temp_dir);
+
+        let dough = Dish::new("Pizza Dough", "chef")
+            .with_ingredients(vec![Ingredient::new("flour", 500.0, "g")]);
+        let pizza =
+            Dish::new("Pizza", "chef").with_components(vec![DishComponent::new(dough.id, 0.5)]);
+        repo.create_many(&[dough.clone(), pizza.clone()]);
+
+        let fetched = repo.get_by_id(pizza.id).unwrap();
+        assert_eq!(fetched.components, vec![DishComponent::new(dough.id, 0.5)]);
+
+        let flat = fetched.flatten(&repo.list()).unwrap();
+        assert_eq!(flat.ingredients[0].quantity, 250.0);
+        assert!(repo.get_by_id(dough.id).unwrap().components.is_empty());
+    }
 }
@@ -8,7 +8,9 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use todu_fit_core::{DocumentId, Identity, IdentityState, MultiDocStorage};
+use todu_fit_core::{
+    DocumentId, GroupDocument, Identity, IdentityState, MealSlot, MealType, MultiDocStorage,
+};
 
 /// Errors that can occur when resolving group context.
 #[derive(Debug)]
@@ -89,6 +91,22 @@ pub fn resolve_group_context(
     data_dir: &Path,
     group_override: Option<&str>,
 ) -> Result<GroupContext, GroupContextError> {
+    let (_, group_doc) = resolve_group_document(data_dir, group_override)?;
+
+    Ok(GroupContext {
+        dishes_doc_id: group_doc.dishes_doc_id,
+        mealplans_doc_id: group_doc.mealplans_doc_id,
+        shopping_carts_doc_id: group_doc.shopping_carts_doc_id,
+    })
+}
+
+/// Resolve and load the current group document.
+///
+/// Returns the group document's ID along with its contents.
+pub fn resolve_group_document(
+    data_dir: &Path,
+    group_override: Option<&str>,
+) -> Result<(DocumentId, GroupDocument), GroupContextError> {
     let storage = MultiDocStorage::new(data_dir.to_path_buf());
     let identity = Identity::new(storage);
 
@@ -116,16 +134,40 @@ pub fn resolve_group_context(
         .find(|g| g.name.eq_ignore_ascii_case(&target_name))
         .ok_or_else(|| GroupContextError::GroupNotFound(target_name.clone()))?;
 
-    // Load the group document to get dish/mealplan doc IDs
+    // Load the group document
     let group_doc = identity
         .load_group(&group_ref.doc_id)
         .map_err(|_| GroupContextError::GroupNotSynced(group_ref.name.clone()))?;
 
-    Ok(GroupContext {
-        dishes_doc_id: group_doc.dishes_doc_id,
-        mealplans_doc_id: group_doc.mealplans_doc_id,
-        shopping_carts_doc_id: group_doc.shopping_carts_doc_id,
-    })
+    Ok((group_ref.doc_id, group_doc))
+}
+
+/// Save a group document loaded with [`resolve_group_document`].
+///
+/// The change builds on the stored document's history, so edits made on
+/// other devices in the meantime are merged rather than overwritten.
+pub fn save_group_document(
+    data_dir: &Path,
+    group_doc_id: &DocumentId,
+    group_doc: &GroupDocument,
+) -> Result<(), GroupContextError> {
+    let identity = Identity::new(MultiDocStorage::new(data_dir.to_path_buf()));
+    identity.save_group(group_doc_id, group_doc)?;
+    Ok(())
+}
+
+/// The current group's meal slots, or the built-in slots when no group is
+/// available.
+pub fn resolve_meal_slots(data_dir: &Path) -> Vec<MealSlot> {
+    match resolve_group_document(data_dir, None) {
+        Ok((_, group_doc)) => group_doc.meal_slots(),
+        Err(_) => MealSlot::builtin(),
+    }
+}
+
+/// Parse a meal type and check it against the current group's meal slots.
+pub fn resolve_meal_type(data_dir: &Path, name: &str) -> Result<MealType, String> {
+    MealSlot::resolve(&resolve_meal_slots(data_dir), name)
 }
 
 /// Resolve the user context for personal documents.
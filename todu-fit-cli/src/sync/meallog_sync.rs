@@ -121,7 +121,6 @@ impl SyncMealLogRepository {
     }
 
     /// Updates an existing meal log.
-    #[allow(dead_code)]
     pub fn update(&self, log: &MealLog) -> Result<MealLog, SyncMealLogError> {
         let (mut doc, doc_id) = self.load_or_create_doc()?;
 
@@ -177,7 +176,7 @@ impl SyncMealLogRepository {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Dish, MealType};
+    use crate::models::{Dish, FoodEntry, MealType, Nutrient};
     use crate::sync::writer;
     use tempfile::TempDir;
 
@@ -265,6 +264,64 @@ mod tests {
         assert_eq!(created.dishes[1].name, "Salad");
     }
 
+    #[test]
+    fn test_dish_snapshot_keeps_nutrients() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestMealLogRepo::new(&temp_dir);
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let dish = Dish::new("Pasta", "chef").with_nutrients(vec![
+            Nutrient::new("calories", 650.0, "kcal"),
+            Nutrient::new("protein", 25.0, "g"),
+        ]);
+
+        let log = MealLog::new(date, MealType::Lunch, "chef").with_dishes(vec![dish.clone()]);
+        let created = repo.create(&log);
+
+        assert_eq!(created.dishes[0].nutrients, dish.nutrients);
+    }
+
+    #[test]
+    fn test_dish_portions_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestMealLogRepo::new(&temp_dir);
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let lasagna = Dish::new("Lasagna", "chef")
+            .with_nutrients(vec![Nutrient::new("calories", 600.0, "kcal")]);
+        let salad = Dish::new("Salad", "chef")
+            .with_nutrients(vec![Nutrient::new("calories", 100.0, "kcal")]);
+
+        let log = MealLog::new(date, MealType::Dinner, "chef")
+            .with_dishes(vec![lasagna.clone(), salad.clone()])
+            .with_portion(lasagna.id, 0.5);
+        let created = repo.create(&log);
+
+        assert_eq!(created.portion_of(lasagna.id), 0.5);
+        assert_eq!(created.portion_of(salad.id), 1.0);
+        assert!(!created.portions.contains_key(&salad.id));
+        assert_eq!(created.nutrient_totals()["calories"], 400.0);
+    }
+
+    #[test]
+    fn test_food_entries_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestMealLogRepo::new(&temp_dir);
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let banana = FoodEntry::new("banana", 1.0, "")
+            .with_nutrients(vec![Nutrient::new("calories", 105.0, "kcal")]);
+        let almonds = FoodEntry::new("almonds", 30.0, "g");
+
+        let log = MealLog::new(date, MealType::Snack, "chef")
+            .with_foods(vec![banana.clone(), almonds.clone()]);
+        let created = repo.create(&log);
+
+        assert!(created.dishes.is_empty());
+        assert_eq!(created.foods, vec![banana, almonds]);
+        assert_eq!(created.nutrient_totals()["calories"], 105.0);
+    }
+
     #[test]
     fn test_list_range() {
         let temp_dir = TempDir::new().unwrap();
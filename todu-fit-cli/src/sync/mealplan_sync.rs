@@ -6,13 +6,15 @@
 use std::path::PathBuf;
 
 use automerge::AutoCommit;
-use chrono::NaiveDate;
+use chrono::{Duration, Local, NaiveDate};
 use uuid::Uuid;
 
-use todu_fit_core::{DocumentId, MultiDocStorage};
+use todu_fit_core::{commit_with_message, DocumentId, GroupDocument, MultiDocStorage, PlanChanges};
 
 use crate::models::{MealPlan, MealType};
-use crate::sync::group_context::{resolve_group_context, GroupContextError};
+use crate::sync::group_context::{
+    resolve_group_context, resolve_group_document, save_group_document, GroupContextError,
+};
 use crate::sync::reader::{
     get_mealplan_by_date_and_type, get_mealplans_by_date, list_mealplans_by_date_range,
     read_all_mealplans, read_mealplan_by_id, ReaderError,
@@ -77,6 +79,9 @@ impl From<todu_fit_core::MultiStorageError> for SyncMealPlanError {
     }
 }
 
+/// How far ahead of today to look when resolving a recurring occurrence by ID.
+const OCCURRENCE_SEARCH_DAYS: i64 = 5 * 366;
+
 /// Sync-aware meal plan repository.
 ///
 /// All operations work directly with Automerge documents.
@@ -132,6 +137,19 @@ impl SyncMealPlanRepository {
         Ok(())
     }
 
+    /// Loads the current group document, which holds the recurring plans.
+    fn load_group(&self) -> Result<(DocumentId, GroupDocument), SyncMealPlanError> {
+        Ok(resolve_group_document(
+            &self.data_dir,
+            self.group_override.as_deref(),
+        )?)
+    }
+
+    /// Last date searched when resolving a recurring occurrence by ID.
+    fn occurrence_search_end() -> NaiveDate {
+        Local::now().date_naive() + Duration::days(OCCURRENCE_SEARCH_DAYS)
+    }
+
     /// Creates a new meal plan.
     pub fn create(&self, plan: &MealPlan) -> Result<MealPlan, SyncMealPlanError> {
         let (mut doc, doc_id) = self.load_or_create_doc()?;
@@ -163,6 +181,9 @@ impl SyncMealPlanRepository {
     }
 
     /// Deletes a meal plan by ID.
+    ///
+    /// Deleting an occurrence of a recurring plan (or an override of one)
+    /// skips that date in the series.
     #[allow(dead_code)]
     pub fn delete(&self, id: Uuid) -> Result<(), SyncMealPlanError> {
         let (mut doc, doc_id) = self.load_or_create_doc()?;
@@ -173,37 +194,110 @@ impl SyncMealPlanRepository {
         // Save
         self.save_doc(&mut doc, &doc_id)?;
 
+        self.skip_occurrences(&[id], Self::occurrence_search_end())
+    }
+
+    /// Writes and deletes the plans of a bulk operation as one change.
+    ///
+    /// Deleted occurrences of recurring plans are skipped in their series.
+    pub fn apply_changes(
+        &self,
+        changes: &PlanChanges,
+        message: &str,
+    ) -> Result<(), SyncMealPlanError> {
+        let (mut doc, doc_id) = self.load_or_create_doc()?;
+        write_changes(&mut doc, changes, message);
+        self.save_doc(&mut doc, &doc_id)?;
+
+        if changes.deletes.is_empty() {
+            return Ok(());
+        }
+        let search_end = changes
+            .conflicts
+            .iter()
+            .map(|c| c.existing.date)
+            .chain([Self::occurrence_search_end()])
+            .max()
+            .unwrap_or_else(Self::occurrence_search_end);
+        self.skip_occurrences(&changes.deletes, search_end)
+    }
+
+    /// Skips the dates of any recurring occurrences among `ids` (searching
+    /// dates up to `to`) in their series, so deleted occurrences stay gone.
+    fn skip_occurrences(&self, ids: &[Uuid], to: NaiveDate) -> Result<(), SyncMealPlanError> {
+        let (group_id, mut group) = self.load_group()?;
+        let mut changed = false;
+        for id in ids {
+            let Some((index, date)) = group.find_occurrence(*id, to) else {
+                continue;
+            };
+            let series = &mut group.recurring_plans[index];
+            if !series.skipped.contains(&date) {
+                series.skipped.push(date);
+                series.skipped.sort();
+                changed = true;
+            }
+        }
+
+        if changed {
+            save_group_document(&self.data_dir, &group_id, &group)?;
+        }
         Ok(())
     }
 
-    /// Gets a meal plan by ID.
+    /// Gets a meal plan by ID, including occurrences of recurring plans.
     pub fn get_by_id(&self, id: Uuid) -> Result<Option<MealPlan>, SyncMealPlanError> {
         let (doc, _) = self.load_or_create_doc()?;
-        Ok(read_mealplan_by_id(&doc, id)?)
+        if let Some(plan) = read_mealplan_by_id(&doc, id)? {
+            return Ok(Some(plan));
+        }
+
+        let (_, group) = self.load_group()?;
+        Ok(group
+            .find_occurrence(id, Self::occurrence_search_end())
+            .and_then(|(index, date)| group.recurring_plans[index].occurrences(date, date).pop()))
+    }
+
+    /// Clears the leftover link of a plan whose source plan no longer
+    /// exists, such as one deleted by another client, so the plan counts as
+    /// cooking its own dishes. Only the given copy is changed.
+    pub fn unlink_missing_source(&self, plan: &mut MealPlan) -> Result<(), SyncMealPlanError> {
+        if let Some(source) = plan.leftovers_of {
+            if self.get_by_id(source)?.is_none() {
+                plan.leftovers_of = None;
+            }
+        }
+        Ok(())
     }
 
     /// Lists all meal plans.
-    #[allow(dead_code)]
     pub fn list(&self) -> Result<Vec<MealPlan>, SyncMealPlanError> {
         let (doc, _) = self.load_or_create_doc()?;
         Ok(read_all_mealplans(&doc)?)
     }
 
-    /// Lists meal plans within a date range.
+    /// Lists meal plans within a date range, expanding recurring plans.
     pub fn list_range(
         &self,
         from: NaiveDate,
         to: NaiveDate,
     ) -> Result<Vec<MealPlan>, SyncMealPlanError> {
         let (doc, _) = self.load_or_create_doc()?;
-        Ok(list_mealplans_by_date_range(&doc, from, to)?)
+        let (_, group) = self.load_group()?;
+        Ok(list_mealplans_by_date_range(
+            &doc,
+            from,
+            to,
+            &group.recurring_plans,
+        )?)
     }
 
     /// Gets meal plans for a specific date.
     #[allow(dead_code)]
     pub fn get_by_date(&self, date: NaiveDate) -> Result<Vec<MealPlan>, SyncMealPlanError> {
         let (doc, _) = self.load_or_create_doc()?;
-        Ok(get_mealplans_by_date(&doc, date)?)
+        let (_, group) = self.load_group()?;
+        Ok(get_mealplans_by_date(&doc, date, &group.recurring_plans)?)
     }
 
     /// Gets a meal plan for a specific date and meal type.
@@ -213,7 +307,13 @@ impl SyncMealPlanRepository {
         meal_type: MealType,
     ) -> Result<Option<MealPlan>, SyncMealPlanError> {
         let (doc, _) = self.load_or_create_doc()?;
-        Ok(get_mealplan_by_date_and_type(&doc, date, meal_type)?)
+        let (_, group) = self.load_group()?;
+        Ok(get_mealplan_by_date_and_type(
+            &doc,
+            date,
+            meal_type,
+            &group.recurring_plans,
+        )?)
     }
 
     /// Adds a dish to a meal plan.
@@ -253,6 +353,18 @@ impl SyncMealPlanRepository {
     }
 }
 
+/// Applies `changes` to the mealplans document and commits them as a single
+/// change, so a bulk operation syncs and shows in history as one step.
+fn write_changes(doc: &mut AutoCommit, changes: &PlanChanges, message: &str) {
+    for plan in &changes.writes {
+        writer::write_mealplan(doc, plan);
+    }
+    for id in &changes.deletes {
+        writer::delete_mealplan(doc, *id);
+    }
+    commit_with_message(doc, message);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,12 +422,12 @@ mod tests {
 
         fn list_range(&self, from: NaiveDate, to: NaiveDate) -> Vec<MealPlan> {
             let doc = self.load_or_create_doc();
-            list_mealplans_by_date_range(&doc, from, to).unwrap()
+            list_mealplans_by_date_range(&doc, from, to, &[]).unwrap()
         }
 
         fn get_by_date(&self, date: NaiveDate) -> Vec<MealPlan> {
             let doc = self.load_or_create_doc();
-            get_mealplans_by_date(&doc, date).unwrap()
+            get_mealplans_by_date(&doc, date, &[]).unwrap()
         }
 
         fn add_dish(&self, plan_id: Uuid, dish_id: Uuid) -> Result<(), SyncMealPlanError> {
@@ -361,6 +473,19 @@ mod tests {
         assert_eq!(plans[0].date, date);
     }
 
+    #[test]
+    fn test_servings_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestMealPlanRepo::new(&temp_dir);
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let plan = MealPlan::new(date, MealType::Dinner, "Dinner Plan", "chef").with_servings(8);
+        repo.create(&plan);
+
+        let loaded = repo.get_by_id(plan.id).unwrap();
+        assert_eq!(loaded.servings, Some(8));
+    }
+
     #[test]
     fn test_get_by_date() {
         let temp_dir = TempDir::new().unwrap();
@@ -473,6 +598,35 @@ mod tests {
         assert!(matches!(result, Err(SyncMealPlanError::DishNotInPlan(_))));
     }
 
+    #[test]
+    fn test_apply_changes_single_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestMealPlanRepo::new(&temp_dir);
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let old = MealPlan::new(date, MealType::Dinner, "Old", "chef");
+        repo.create(&old);
+
+        let mut doc = repo.load_or_create_doc();
+        let before = doc.get_changes(&[]).len();
+        let changes = PlanChanges {
+            writes: vec![
+                MealPlan::new(date, MealType::Lunch, "Lunch", "chef"),
+                MealPlan::new(date, MealType::Breakfast, "Breakfast", "chef"),
+            ],
+            deletes: vec![old.id],
+            conflicts: Vec::new(),
+        };
+        write_changes(&mut doc, &changes, "Copy 2 meal plan(s)");
+        repo.save_doc(&mut doc);
+
+        let mut doc = repo.load_or_create_doc();
+        assert_eq!(doc.get_changes(&[]).len(), before + 1);
+        let plans = repo.list();
+        assert_eq!(plans.len(), 2);
+        assert!(plans.iter().all(|p| p.id != old.id));
+    }
+
     #[test]
     fn test_update_mealplan() {
         let temp_dir = TempDir::new().unwrap();
@@ -27,6 +27,7 @@ pub mod dish_sync;
 pub mod group_context;
 pub mod meallog_sync;
 pub mod mealplan_sync;
+pub mod pantry_sync;
 pub mod reader;
 #[cfg(test)]
 pub mod schema;
@@ -38,4 +39,5 @@ pub use client::{SyncClient, SyncClientError};
 pub use dish_sync::SyncDishRepository;
 pub use meallog_sync::SyncMealLogRepository;
 pub use mealplan_sync::SyncMealPlanRepository;
+pub use pantry_sync::SyncPantryRepository;
 pub use shopping_sync::SyncShoppingRepository;
@@ -0,0 +1,197 @@
+//! Sync-aware pantry repository that reads/writes Automerge documents.
+//!
+//! This module provides a repository layer that uses the current group's
+//! pantry document. Identity must be initialized first.
+
+use std::path::PathBuf;
+
+use automerge::AutoCommit;
+
+use todu_fit_core::{
+    delete_pantry_item, write_pantry_item, DocumentId, MultiDocStorage, Pantry, PantryItem,
+};
+
+use crate::sync::group_context::{resolve_group_document, save_group_document, GroupContextError};
+use crate::sync::reader::{read_pantry, ReaderError};
+
+/// Error type for sync pantry operations.
+#[derive(Debug)]
+pub enum SyncPantryError {
+    /// Reader error (parsing Automerge data).
+    Reader(ReaderError),
+    /// Group context error.
+    GroupContext(GroupContextError),
+    /// Multi-storage error.
+    MultiStorage(todu_fit_core::MultiStorageError),
+}
+
+impl std::fmt::Display for SyncPantryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncPantryError::Reader(e) => write!(f, "Reader error: {}", e),
+            SyncPantryError::GroupContext(e) => write!(f, "{}", e),
+            SyncPantryError::MultiStorage(e) => write!(f, "Storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SyncPantryError {}
+
+impl From<ReaderError> for SyncPantryError {
+    fn from(e: ReaderError) -> Self {
+        SyncPantryError::Reader(e)
+    }
+}
+
+impl From<GroupContextError> for SyncPantryError {
+    fn from(e: GroupContextError) -> Self {
+        SyncPantryError::GroupContext(e)
+    }
+}
+
+impl From<todu_fit_core::MultiStorageError> for SyncPantryError {
+    fn from(e: todu_fit_core::MultiStorageError) -> Self {
+        SyncPantryError::MultiStorage(e)
+    }
+}
+
+/// Sync-aware pantry repository.
+///
+/// All operations work directly with Automerge documents.
+/// Uses the current group's pantry document.
+pub struct SyncPantryRepository {
+    storage: MultiDocStorage,
+    data_dir: PathBuf,
+}
+
+impl SyncPantryRepository {
+    /// Creates a new sync pantry repository.
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self {
+            storage: MultiDocStorage::new(data_dir.clone()),
+            data_dir,
+        }
+    }
+
+    /// Resolves the pantry document ID from the current group context.
+    ///
+    /// Groups created before the pantry existed use an ID derived from the
+    /// group's; with `create` it is also stored in the group document.
+    fn resolve_doc_id(&self, create: bool) -> Result<DocumentId, SyncPantryError> {
+        let (group_id, mut group_doc) = resolve_group_document(&self.data_dir, None)?;
+        if group_doc.pantry_doc_id.is_some() || !create {
+            return Ok(group_doc.pantry_doc_id(&group_id));
+        }
+
+        let doc_id = group_doc.ensure_pantry_doc_id(&group_id);
+        save_group_document(&self.data_dir, &group_id, &group_doc)?;
+        Ok(doc_id)
+    }
+
+    /// Loads the pantry Automerge document, or creates a new empty one.
+    fn load_doc(&self, doc_id: &DocumentId) -> Result<AutoCommit, SyncPantryError> {
+        match self.storage.load(doc_id)? {
+            Some(bytes) => AutoCommit::load(&bytes)
+                .map_err(|e| SyncPantryError::Reader(ReaderError::AutomergeError(e.to_string()))),
+            None => Ok(AutoCommit::new()),
+        }
+    }
+
+    /// Saves the document to storage.
+    fn save_doc(&self, doc: &mut AutoCommit, doc_id: &DocumentId) -> Result<(), SyncPantryError> {
+        let bytes = doc.save();
+        self.storage.save(doc_id, &bytes)?;
+        Ok(())
+    }
+
+    /// Gets the group's pantry. Empty if nothing has been stocked yet.
+    pub fn get(&self) -> Result<Pantry, SyncPantryError> {
+        let doc_id = self.resolve_doc_id(false)?;
+        Ok(read_pantry(&self.load_doc(&doc_id)?)?)
+    }
+
+    /// Saves pantry items. Stock changes are recorded as adjustments, so they
+    /// add up with changes made on other devices.
+    pub fn save_items(&self, items: &[PantryItem]) -> Result<(), SyncPantryError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let doc_id = self.resolve_doc_id(true)?;
+        let mut doc = self.load_doc(&doc_id)?;
+        for item in items {
+            write_pantry_item(&mut doc, item);
+        }
+        self.save_doc(&mut doc, &doc_id)
+    }
+
+    /// Removes an item by name. Returns true if it was in the pantry.
+    pub fn remove(&self, name: &str) -> Result<bool, SyncPantryError> {
+        let mut pantry = self.get()?;
+        if !pantry.remove(name) {
+            return Ok(false);
+        }
+        let doc_id = self.resolve_doc_id(true)?;
+        let mut doc = self.load_doc(&doc_id)?;
+        delete_pantry_item(&mut doc, name);
+        self.save_doc(&mut doc, &doc_id)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Test helper that bypasses identity/group requirements.
+    struct TestPantryRepo {
+        storage: MultiDocStorage,
+        doc_id: DocumentId,
+    }
+
+    impl TestPantryRepo {
+        fn new(temp_dir: &TempDir) -> Self {
+            let storage = MultiDocStorage::new(temp_dir.path().to_path_buf());
+            let doc_id = DocumentId::new();
+            Self { storage, doc_id }
+        }
+
+        fn load_or_create_doc(&self) -> AutoCommit {
+            match self.storage.load(&self.doc_id).unwrap() {
+                Some(bytes) => AutoCommit::load(&bytes).unwrap(),
+                None => AutoCommit::new(),
+            }
+        }
+
+        fn get(&self) -> Pantry {
+            read_pantry(&self.load_or_create_doc()).unwrap()
+        }
+
+        fn save_items(&self, items: &[PantryItem]) {
+            let mut doc = self.load_or_create_doc();
+            for item in items {
+                write_pantry_item(&mut doc, item);
+            }
+            self.storage.save(&self.doc_id, &doc.save()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_stock_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = TestPantryRepo::new(&temp_dir);
+
+        let mut pantry = repo.get();
+        let item = pantry.add("Olive Oil", 500.0, "ml").unwrap().clone();
+        repo.save_items(&[item, PantryItem::staple("salt")]);
+
+        let mut loaded = repo.get();
+        assert_eq!(loaded.items.len(), 2);
+        let item = loaded.consume("olive oil", 100.0, "ml").unwrap().clone();
+        repo.save_items(&[item]);
+
+        let reloaded = repo.get();
+        assert_eq!(reloaded.find("olive oil").unwrap().quantity, 400.0);
+        assert!(reloaded.is_staple("salt"));
+    }
+}
@@ -3,11 +3,17 @@
 //! This module provides functions to read and query data directly from
 //! Automerge documents without SQLite.
 
+use std::collections::HashMap;
+
 use automerge::{AutoCommit, ObjId, ReadDoc, ROOT};
 use chrono::{DateTime, NaiveDate, Utc};
+use todu_fit_core::models::expand_recurring;
+use todu_fit_core::RecurringPlan;
 use uuid::Uuid;
 
-use crate::models::{Dish, Ingredient, MealLog, MealPlan, MealType, Nutrient};
+use crate::models::{
+    Dish, DishComponent, FoodEntry, Ingredient, MealLog, MealPlan, MealType, Nutrient,
+};
 
 /// Error type for reader operations.
 #[derive(Debug)]
@@ -130,15 +136,23 @@ fn read_dish(doc: &AutoCommit, obj_id: &ObjId, id_str: &str) -> Result<Option<Di
     let source_url = get_string(doc, obj_id, "source_url")?;
 
     let tags = read_string_list(doc, obj_id, "tags")?;
-    let ingredients = read_ingredients(doc, obj_id)?;
+    let ingredients = read_ingredients(doc, obj_id, "ingredients")?;
+    let components = read_components(doc, obj_id)?;
     let nutrients = read_nutrients(doc, obj_id)?;
+    let nutrients_calculated = doc
+        .get(obj_id, "nutrients_calculated")
+        .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+        .and_then(|(value, _)| value.to_bool())
+        .unwrap_or(false);
 
     Ok(Some(Dish {
         id,
         name,
         ingredients,
+        components,
         instructions,
         nutrients,
+        nutrients_calculated,
         prep_time,
         cook_time,
         servings,
@@ -151,11 +165,41 @@ fn read_dish(doc: &AutoCommit, obj_id: &ObjId, id_str: &str) -> Result<Option<Di
     }))
 }
 
-fn read_ingredients(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<Ingredient>, ReaderError> {
+fn read_components(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<DishComponent>, ReaderError> {
+    let mut components = Vec::new();
+
+    if let Some((_, list_id)) = doc
+        .get(obj_id, "components")
+        .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+    {
+        let len = doc.length(&list_id);
+        for i in 0..len {
+            if let Some((_, comp_id)) = doc
+                .get(&list_id, i)
+                .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+            {
+                let dish_id =
+                    get_string(doc, &comp_id, "dish_id")?.and_then(|s| Uuid::parse_str(&s).ok());
+                let scale = get_f64(doc, &comp_id, "scale")?.unwrap_or(1.0);
+                if let Some(dish_id) = dish_id {
+                    components.push(DishComponent::new(dish_id, scale));
+                }
+            }
+        }
+    }
+
+    Ok(components)
+}
+
+fn read_ingredients(
+    doc: &AutoCommit,
+    obj_id: &ObjId,
+    key: &str,
+) -> Result<Vec<Ingredient>, ReaderError> {
     let mut ingredients = Vec::new();
 
     if let Some((_, list_id)) = doc
-        .get(obj_id, "ingredients")
+        .get(obj_id, key)
         .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
     {
         let len = doc.length(&list_id);
@@ -167,8 +211,11 @@ fn read_ingredients(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<Ingredient>,
                 let name = get_string(doc, &ing_id, "name")?.unwrap_or_default();
                 let quantity = get_quantity(doc, &ing_id, "quantity")?.unwrap_or(0.0);
                 let unit = get_string(doc, &ing_id, "unit")?.unwrap_or_default();
+                let note = get_string(doc, &ing_id, "note")?;
 
-                ingredients.push(Ingredient::new(name, quantity, unit));
+                let mut ingredient = Ingredient::new(name, quantity, unit);
+                ingredient.note = note;
+                ingredients.push(ingredient);
             }
         }
     }
@@ -243,17 +290,21 @@ pub fn read_mealplan_by_id(doc: &AutoCommit, id: Uuid) -> Result<Option<MealPlan
     }
 }
 
-/// Lists meal plans within a date range.
+/// Lists meal plans within a date range, including occurrences of
+/// `recurring` plans that haven't been overridden.
 pub fn list_mealplans_by_date_range(
     doc: &AutoCommit,
     from: NaiveDate,
     to: NaiveDate,
+    recurring: &[RecurringPlan],
 ) -> Result<Vec<MealPlan>, ReaderError> {
     let plans = read_all_mealplans(doc)?;
+    let occurrences = expand_recurring(recurring, &plans, from, to);
 
     Ok(plans
         .into_iter()
         .filter(|p| p.date >= from && p.date <= to)
+        .chain(occurrences)
         .collect())
 }
 
@@ -261,10 +312,9 @@ pub fn list_mealplans_by_date_range(
 pub fn get_mealplans_by_date(
     doc: &AutoCommit,
     date: NaiveDate,
+    recurring: &[RecurringPlan],
 ) -> Result<Vec<MealPlan>, ReaderError> {
-    let plans = read_all_mealplans(doc)?;
-
-    Ok(plans.into_iter().filter(|p| p.date == date).collect())
+    list_mealplans_by_date_range(doc, date, date, recurring)
 }
 
 /// Gets a meal plan by date and type.
@@ -272,12 +322,11 @@ pub fn get_mealplan_by_date_and_type(
     doc: &AutoCommit,
     date: NaiveDate,
     meal_type: MealType,
+    recurring: &[RecurringPlan],
 ) -> Result<Option<MealPlan>, ReaderError> {
-    let plans = read_all_mealplans(doc)?;
+    let plans = get_mealplans_by_date(doc, date, recurring)?;
 
-    Ok(plans
-        .into_iter()
-        .find(|p| p.date == date && p.meal_type == meal_type))
+    Ok(plans.into_iter().find(|p| p.meal_type == meal_type))
 }
 
 fn read_mealplan(
@@ -303,6 +352,9 @@ fn read_mealplan(
 
     let title = get_string(doc, obj_id, "title")?.unwrap_or_default();
     let cook = get_string(doc, obj_id, "cook")?.unwrap_or_default();
+    let servings = get_i64(doc, obj_id, "servings")?.map(|v| v as i32);
+    let leftovers_of =
+        get_string(doc, obj_id, "leftovers_of")?.and_then(|s| Uuid::parse_str(&s).ok());
     let created_by = get_string(doc, obj_id, "created_by")?.unwrap_or_default();
 
     let created_at = get_string(doc, obj_id, "created_at")?
@@ -324,6 +376,8 @@ fn read_mealplan(
         title,
         cook,
         dish_ids,
+        servings,
+        leftovers_of,
         created_by,
         created_at,
         updated_at,
@@ -413,7 +467,8 @@ fn read_meallog(
         .unwrap_or_else(Utc::now);
 
     // Read dish snapshots
-    let dishes = read_dish_snapshots(doc, obj_id)?;
+    let (dishes, portions) = read_dish_snapshots(doc, obj_id)?;
+    let foods = read_food_entries(doc, obj_id)?;
 
     Ok(Some(MealLog {
         id,
@@ -421,14 +476,21 @@ fn read_meallog(
         meal_type,
         mealplan_id,
         dishes,
+        portions,
+        foods,
         notes,
         created_by,
         created_at,
     }))
 }
 
-fn read_dish_snapshots(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<Dish>, ReaderError> {
+/// Reads a meal log's dish snapshots along with any non-default portions.
+fn read_dish_snapshots(
+    doc: &AutoCommit,
+    obj_id: &ObjId,
+) -> Result<(Vec<Dish>, HashMap<Uuid, f64>), ReaderError> {
     let mut dishes = Vec::new();
+    let mut portions = HashMap::new();
 
     if let Some((_, list_id)) = doc
         .get(obj_id, "dishes")
@@ -449,18 +511,25 @@ fn read_dish_snapshots(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<Dish>, Re
                 let created_by = get_string(doc, &dish_id, "created_by")?.unwrap_or_default();
 
                 let tags = read_string_list(doc, &dish_id, "tags")?;
-                let ingredients = read_ingredients(doc, &dish_id)?;
+                let ingredients = read_ingredients(doc, &dish_id, "ingredients")?;
 
                 let prep_time = get_i64(doc, &dish_id, "prep_time")?.map(|v| v as i32);
                 let cook_time = get_i64(doc, &dish_id, "cook_time")?.map(|v| v as i32);
                 let servings = get_i64(doc, &dish_id, "servings")?.map(|v| v as i32);
+                let nutrients = read_nutrients(doc, &dish_id)?;
+
+                if let Some(portion) = get_f64(doc, &dish_id, "portion")? {
+                    portions.insert(id, portion);
+                }
 
                 dishes.push(Dish {
                     id,
                     name,
                     ingredients,
+                    components: Vec::new(),
                     instructions,
-                    nutrients: None,
+                    nutrients,
+                    nutrients_calculated: false,
                     prep_time,
                     cook_time,
                     servings,
@@ -475,7 +544,33 @@ fn read_dish_snapshots(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<Dish>, Re
         }
     }
 
-    Ok(dishes)
+    Ok((dishes, portions))
+}
+
+fn read_food_entries(doc: &AutoCommit, obj_id: &ObjId) -> Result<Vec<FoodEntry>, ReaderError> {
+    let mut foods = Vec::new();
+
+    if let Some((_, list_id)) = doc
+        .get(obj_id, "foods")
+        .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+    {
+        let len = doc.length(&list_id);
+        for i in 0..len {
+            if let Some((_, food_id)) = doc
+                .get(&list_id, i)
+                .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+            {
+                let name = get_string(doc, &food_id, "name")?.unwrap_or_default();
+                let amount = get_f64(doc, &food_id, "amount")?.unwrap_or(1.0);
+                let unit = get_string(doc, &food_id, "unit")?.unwrap_or_default();
+                let nutrients = read_nutrients(doc, &food_id)?.unwrap_or_default();
+
+                foods.push(FoodEntry::new(name, amount, unit).with_nutrients(nutrients));
+            }
+        }
+    }
+
+    Ok(foods)
 }
 
 // =============================================================================
@@ -609,7 +704,7 @@ fn read_dish_ids(doc: &AutoCommit, obj_id: &ObjId, key: &str) -> Result<Vec<Uuid
 // Shopping Cart Reader
 // =============================================================================
 
-use todu_fit_core::{ManualItem, ShoppingCart};
+use todu_fit_core::{CartPeriod, ManualItem, ShoppingCart};
 
 /// Reads a shopping cart for a specific week from an Automerge document.
 pub fn read_shopping_cart_by_week(
@@ -631,8 +726,8 @@ pub fn read_all_shopping_carts(doc: &AutoCommit) -> Result<Vec<ShoppingCart>, Re
     let mut carts = Vec::new();
 
     for key in doc.keys(ROOT) {
-        // Shopping cart keys are dates in YYYY-MM-DD format
-        if key.len() == 10 && key.chars().nth(4) == Some('-') && key.chars().nth(7) == Some('-') {
+        // Shopping cart keys are week start dates or trip date ranges
+        if CartPeriod::parse(&key).is_some() {
             if let Some((_, obj_id)) = doc
                 .get(ROOT, &key)
                 .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
@@ -702,9 +797,49 @@ fn read_shopping_cart(
         }
     }
 
+    // Read pantry stock added by checked items
+    cart.stocked = read_ingredients(doc, obj_id, "stocked")?;
+
     Ok(Some(cart))
 }
 
+// =============================================================================
+// Pantry Reader
+// =============================================================================
+
+use todu_fit_core::{pantry_item_stock, Pantry, PantryItem};
+
+/// Reads the pantry from an Automerge document, sorted by name.
+pub fn read_pantry(doc: &AutoCommit) -> Result<Pantry, ReaderError> {
+    let mut items = Vec::new();
+
+    for key in doc.keys(ROOT) {
+        if let Some((_, obj_id)) = doc
+            .get(ROOT, &key)
+            .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+        {
+            let name = get_string(doc, &obj_id, "name")?.unwrap_or(key);
+            let quantity = pantry_item_stock(doc, &obj_id);
+            let unit = get_string(doc, &obj_id, "unit")?.unwrap_or_default();
+            let staple = doc
+                .get(&obj_id, "staple")
+                .map_err(|e| ReaderError::AutomergeError(e.to_string()))?
+                .and_then(|(value, _)| value.to_bool())
+                .unwrap_or(false);
+
+            items.push(PantryItem {
+                name,
+                quantity,
+                unit,
+                staple,
+            });
+        }
+    }
+
+    items.sort_by_key(|i| i.key());
+    Ok(Pantry::new(items))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -828,26 +963,100 @@ mod tests {
         assert_eq!(plans[0].title, "Test Dinner");
     }
 
+    #[test]
+    fn test_read_mealplan_leftovers_of() {
+        let mut doc = create_test_mealplan_doc();
+        let source = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440002").unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 16).unwrap();
+        let lunch = MealPlan::new(date, MealType::Lunch, "Leftover chili", "alice")
+            .with_servings(2)
+            .with_leftovers_of(source);
+        crate::sync::writer::write_mealplan(&mut doc, &lunch);
+
+        let plans = get_mealplans_by_date(&doc, date, &[]).unwrap();
+        assert_eq!(plans[0].leftovers_of, Some(source));
+        assert_eq!(plans[0].servings, Some(2));
+    }
+
     #[test]
     fn test_list_mealplans_by_date_range() {
         let doc = create_test_mealplan_doc();
         let from = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
         let to = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
 
-        let plans = list_mealplans_by_date_range(&doc, from, to).unwrap();
+        let plans = list_mealplans_by_date_range(&doc, from, to, &[]).unwrap();
         assert_eq!(plans.len(), 1);
     }
 
+    #[test]
+    fn test_list_mealplans_expands_recurring() {
+        use chrono::Weekday;
+        use todu_fit_core::Recurrence;
+
+        let mut doc = create_test_mealplan_doc();
+        let from = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let mut tacos = RecurringPlan::new(
+            MealType::Dinner,
+            "Taco Tuesday",
+            Recurrence::weekly(vec![Weekday::Tue]),
+            from,
+            "alice",
+        );
+        tacos
+            .skipped
+            .push(NaiveDate::from_ymd_opt(2025, 1, 14).unwrap());
+
+        // Override the last Tuesday with a stored plan under its ID
+        let last = NaiveDate::from_ymd_opt(2025, 1, 28).unwrap();
+        let mut override_plan = tacos.occurrences(last, last).remove(0);
+        override_plan.title = "Fajitas".to_string();
+        crate::sync::writer::write_mealplan(&mut doc, &override_plan);
+
+        let recurring = vec![tacos];
+        let plans = list_mealplans_by_date_range(&doc, from, to, &recurring).unwrap();
+        // Test Dinner, the override, and two of the other three Tuesdays
+        assert_eq!(plans.len(), 4);
+        let on_last: Vec<&str> = plans
+            .iter()
+            .filter(|p| p.date == last)
+            .map(|p| p.title.as_str())
+            .collect();
+        assert_eq!(on_last, vec!["Fajitas"]);
+
+        let tuesday = NaiveDate::from_ymd_opt(2025, 1, 7).unwrap();
+        let plan = get_mealplan_by_date_and_type(&doc, tuesday, MealType::Dinner, &recurring)
+            .unwrap()
+            .unwrap();
+        assert_eq!(plan.title, "Taco Tuesday");
+    }
+
     #[test]
     fn test_get_mealplan_by_date_and_type() {
         let doc = create_test_mealplan_doc();
         let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
 
-        let plan = get_mealplan_by_date_and_type(&doc, date, MealType::Dinner).unwrap();
+        let plan = get_mealplan_by_date_and_type(&doc, date, MealType::Dinner, &[]).unwrap();
         assert!(plan.is_some());
         assert_eq!(plan.unwrap().title, "Test Dinner");
     }
 
+    #[test]
+    fn test_read_mealplan_with_custom_meal_type() {
+        let mut doc = create_test_mealplan_doc();
+        let plan_obj = match doc.get(ROOT, "550e8400-e29b-41d4-a716-446655440002") {
+            Ok(Some((_, obj))) => obj,
+            _ => panic!("plan not found"),
+        };
+        doc.put(&plan_obj, "meal_type", "Second Breakfast").unwrap();
+
+        let plans = read_all_mealplans(&doc).unwrap();
+        assert_eq!(
+            plans[0].meal_type,
+            MealType::Custom("second breakfast".to_string())
+        );
+    }
+
     fn create_test_meallog_doc() -> AutoCommit {
         let mut doc = AutoCommit::new();
         let log_id = "550e8400-e29b-41d4-a716-446655440003";
@@ -977,4 +1186,43 @@ mod tests {
         assert_eq!(carts[0].week, "2026-01-18");
         assert_eq!(carts[1].week, "2026-01-11");
     }
+
+    #[test]
+    fn test_read_trip_shopping_carts() {
+        use todu_fit_core::write_shopping_cart;
+
+        let mut doc = create_test_shopping_cart_doc();
+        let from = chrono::NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let to = chrono::NaiveDate::from_ymd_opt(2026, 1, 18).unwrap();
+        let mut trip = ShoppingCart::for_period(CartPeriod::Trip(from, to));
+        trip.check("milk");
+        write_shopping_cart(&mut doc, &trip);
+        doc.put(ROOT, "not-a-cart", "ignored").unwrap();
+
+        let carts = read_all_shopping_carts(&doc).unwrap();
+        assert_eq!(carts.len(), 2);
+        assert_eq!(carts[0].week, "2026-01-15..2026-01-18");
+        assert!(carts[0].is_checked("milk"));
+
+        let cart = read_shopping_cart_by_week(&doc, &trip.week)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cart.period(), Some(CartPeriod::Trip(from, to)));
+    }
+
+    #[test]
+    fn test_read_pantry() {
+        use todu_fit_core::write_pantry_item;
+
+        let mut doc = AutoCommit::new();
+        write_pantry_item(&mut doc, &PantryItem::new("Rice", 2.0, "kg"));
+        write_pantry_item(&mut doc, &PantryItem::staple("salt"));
+        write_pantry_item(&mut doc, &PantryItem::new("eggs", 6.0, ""));
+
+        let pantry = read_pantry(&doc).unwrap();
+        let names: Vec<_> = pantry.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["eggs", "Rice", "salt"]);
+        assert_eq!(pantry.find("rice").unwrap().quantity, 2.0);
+        assert!(pantry.is_staple("Salt"));
+    }
 }
@@ -37,10 +37,12 @@
 //!   "<uuid>": {
 //!     "id": "<uuid>",
 //!     "date": "YYYY-MM-DD",
-//!     "meal_type": "breakfast" | "lunch" | "dinner" | "snack",
+//!     "meal_type": "breakfast" | "lunch" | "dinner" | "snack" | <group meal slot>,
 //!     "title": "string",
 //!     "cook": "string",
 //!     "dishes": [...],
+//!     "servings": number | null,
+//!     "leftovers_of": "<uuid>" | null,
 //!     "created_by": "string",
 //!     "created_at": "iso8601",
 //!     "updated_at": "iso8601"
@@ -55,7 +57,7 @@
 //!   "<uuid>": {
 //!     "id": "<uuid>",
 //!     "date": "YYYY-MM-DD",
-//!     "meal_type": "breakfast" | "lunch" | "dinner" | "snack",
+//!     "meal_type": "breakfast" | "lunch" | "dinner" | "snack" | <group meal slot>,
 //!     "mealplan_id": "<uuid>" | null,
 //!     "dishes": [...],
 //!     "notes": "string" | null,
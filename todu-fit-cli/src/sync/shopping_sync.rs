@@ -6,8 +6,9 @@
 use std::path::PathBuf;
 
 use automerge::AutoCommit;
+use chrono::Datelike;
 
-use todu_fit_core::{write_shopping_cart, DocumentId, MultiDocStorage, ShoppingCart};
+use todu_fit_core::{write_shopping_cart, CartPeriod, DocumentId, MultiDocStorage, ShoppingCart};
 
 use crate::sync::group_context::{resolve_group_context, GroupContextError};
 use crate::sync::reader::{read_all_shopping_carts, read_shopping_cart_by_week, ReaderError};
@@ -128,6 +129,7 @@ impl SyncShoppingRepository {
 
     /// Gets a shopping cart for a specific week.
     /// Returns an empty cart if none exists.
+    #[allow(dead_code)]
     pub fn get_or_create(&self, week: &str) -> Result<ShoppingCart, SyncShoppingError> {
         let (doc, _) = self.load_or_create_doc()?;
         match read_shopping_cart_by_week(&doc, week)? {
@@ -136,8 +138,30 @@ impl SyncShoppingRepository {
         }
     }
 
+    /// Gets the shopping cart for a period, or a new empty one.
+    ///
+    /// A week without a cart of its own uses the stored week cart that
+    /// overlaps it most, such as a Sunday-keyed cart from before the group
+    /// changed its week start. Carts keep the key they were created under,
+    /// so clients that still use the old keys see the same cart.
+    pub fn get_or_create_for(&self, period: CartPeriod) -> Result<ShoppingCart, SyncShoppingError> {
+        let (doc, _) = self.load_or_create_doc()?;
+        if let Some(cart) = read_shopping_cart_by_week(&doc, &period.key())? {
+            return Ok(cart);
+        }
+        if let CartPeriod::Week(start) = period {
+            let first_day = start.weekday();
+            let overlapping = read_all_shopping_carts(&doc)?.into_iter().find(|cart| {
+                matches!(cart.period(), Some(p @ CartPeriod::Week(_)) if p.realigned(first_day) == period)
+            });
+            if let Some(cart) = overlapping {
+                return Ok(cart);
+            }
+        }
+        Ok(ShoppingCart::for_period(period))
+    }
+
     /// Gets a shopping cart for a specific week.
-    #[allow(dead_code)]
     pub fn get(&self, week: &str) -> Result<Option<ShoppingCart>, SyncShoppingError> {
         let (doc, _) = self.load_or_create_doc()?;
         Ok(read_shopping_cart_by_week(&doc, week)?)
@@ -0,0 +1,231 @@
+//! Reading entities from the change history of an Automerge document.
+//!
+//! Every write to a document is an Automerge change with a hash, an actor and
+//! (when committed through [`commit_with_message`]) a timestamp and message.
+//! These helpers list the changes that touched one entry of a root map, such
+//! as a single dish in a dishes document, and fork the document at an earlier
+//! change so the usual readers can read an old revision.
+
+use automerge::{
+    transaction::CommitOptions, AutoCommit, ChangeHash, ObjId, ObjType, ReadDoc, ScalarValue,
+    Value, ROOT,
+};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value as Json};
+use thiserror::Error;
+
+/// Number of hex characters shown for a revision.
+pub const SHORT_HASH_LEN: usize = 8;
+
+/// Timestamps above this are taken to be milliseconds rather than seconds.
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Errors that can occur when looking up revisions
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum HistoryError {
+    #[error("No revision matches '{0}'")]
+    NotFound(String),
+
+    #[error("Revision '{0}' is ambiguous; use more characters")]
+    Ambiguous(String),
+
+    #[error("Automerge error: {0}")]
+    Automerge(String),
+}
+
+/// One change in a document's history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub hash: ChangeHash,
+    /// Hex actor ID of the device that made the change
+    pub actor: String,
+    /// When the change was committed, if the writer recorded a time
+    pub timestamp: Option<DateTime<Utc>>,
+    pub message: Option<String>,
+}
+
+impl Revision {
+    /// The first [`SHORT_HASH_LEN`] characters of the change hash.
+    pub fn short_hash(&self) -> String {
+        let hash = self.hash.to_string();
+        hash[..SHORT_HASH_LEN.min(hash.len())].to_string()
+    }
+
+    /// The first [`SHORT_HASH_LEN`] characters of the actor ID.
+    pub fn short_actor(&self) -> &str {
+        &self.actor[..SHORT_HASH_LEN.min(self.actor.len())]
+    }
+}
+
+/// Commit pending operations with a message and the current time.
+///
+/// Without this, changes are committed with a timestamp of zero and no
+/// message, which leaves nothing useful to show in history.
+pub fn commit_with_message(doc: &mut AutoCommit, message: &str) -> Option<ChangeHash> {
+    doc.commit_with(
+        CommitOptions::default()
+            .with_message(message)
+            .with_time(Utc::now().timestamp()),
+    )
+}
+
+/// Changes that modified `root[key]`, oldest first.
+///
+/// A change counts when the value at `key` differs between the change's
+/// dependencies and the change itself, so creation and deletion are included.
+pub fn key_history(doc: &mut AutoCommit, key: &str) -> Vec<Revision> {
+    let changes = doc.get_changes(&[]);
+
+    changes
+        .iter()
+        .filter(|change| {
+            let before = value_at(doc, key, change.deps());
+            let after = value_at(doc, key, &[change.hash()]);
+            before != after
+        })
+        .map(|change| Revision {
+            hash: change.hash(),
+            actor: change.actor_id().to_hex_string(),
+            timestamp: to_datetime(change.timestamp()),
+            message: change.message().cloned(),
+        })
+        .collect()
+}
+
+/// Find the change whose hash starts with `prefix`.
+pub fn resolve_revision(doc: &mut AutoCommit, prefix: &str) -> Result<ChangeHash, HistoryError> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() {
+        return Err(HistoryError::NotFound(prefix));
+    }
+
+    let mut matches = doc
+        .get_changes(&[])
+        .iter()
+        .map(|c| c.hash())
+        .filter(|h| h.to_string().starts_with(&prefix))
+        .collect::<Vec<_>>();
+
+    match matches.len() {
+        0 => Err(HistoryError::NotFound(prefix)),
+        1 => Ok(matches.remove(0)),
+        _ => Err(HistoryError::Ambiguous(prefix)),
+    }
+}
+
+/// A copy of the document as it was right after `hash`.
+pub fn doc_at(doc: &mut AutoCommit, hash: ChangeHash) -> Result<AutoCommit, HistoryError> {
+    doc.fork_at(&[hash])
+        .map_err(|e| HistoryError::Automerge(e.to_string()))
+}
+
+fn to_datetime(timestamp: i64) -> Option<DateTime<Utc>> {
+    match timestamp {
+        t if t <= 0 => None,
+        t if t >= MILLIS_THRESHOLD => DateTime::from_timestamp_millis(t),
+        t => DateTime::from_timestamp(t, 0),
+    }
+}
+
+/// `root[key]` at `heads` as JSON, for comparing revisions.
+fn value_at(doc: &AutoCommit, key: &str, heads: &[ChangeHash]) -> Option<Json> {
+    doc.get_at(ROOT, key, heads)
+        .ok()
+        .flatten()
+        .map(|(value, id)| to_json(doc, value, &id, heads))
+}
+
+fn to_json(doc: &AutoCommit, value: Value<'_>, id: &ObjId, heads: &[ChangeHash]) -> Json {
+    match value {
+        Value::Object(ObjType::Map) | Value::Object(ObjType::Table) => {
+            let mut map = Map::new();
+            for key in doc.keys_at(id, heads) {
+                if let Ok(Some((v, child))) = doc.get_at(id, key.as_str(), heads) {
+                    map.insert(key, to_json(doc, v, &child, heads));
+                }
+            }
+            Json::Object(map)
+        }
+        Value::Object(ObjType::List) => {
+            let items = (0..doc.length_at(id, heads))
+                .filter_map(|i| doc.get_at(id, i, heads).ok().flatten())
+                .map(|(v, child)| to_json(doc, v, &child, heads))
+                .collect();
+            Json::Array(items)
+        }
+        Value::Object(ObjType::Text) => Json::String(doc.text_at(id, heads).unwrap_or_default()),
+        Value::Scalar(scalar) => match scalar.as_ref() {
+            ScalarValue::Str(s) => Json::String(s.to_string()),
+            ScalarValue::Int(i) => Json::from(*i),
+            ScalarValue::Uint(u) => Json::from(*u),
+            ScalarValue::F64(f) => Json::from(*f),
+            ScalarValue::Boolean(b) => Json::Bool(*b),
+            ScalarValue::Null => Json::Null,
+            other => Json::String(other.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use automerge::transaction::Transactable;
+
+    fn put_dish(doc: &mut AutoCommit, key: &str, name: &str, message: &str) {
+        let obj = doc.put_object(ROOT, key, ObjType::Map).unwrap();
+        doc.put(&obj, "name", name).unwrap();
+        commit_with_message(doc, message);
+    }
+
+    #[test]
+    fn test_key_history_only_lists_touching_changes() {
+        let mut doc = AutoCommit::new();
+        put_dish(&mut doc, "a", "Soup", "create a");
+        put_dish(&mut doc, "b", "Salad", "create b");
+        put_dish(&mut doc, "a", "Tomato Soup", "rename a");
+        doc.delete(ROOT, "a").unwrap();
+        commit_with_message(&mut doc, "delete a");
+
+        let history = key_history(&mut doc, "a");
+        let messages: Vec<_> = history
+            .iter()
+            .filter_map(|r| r.message.as_deref())
+            .collect();
+        assert_eq!(messages, vec!["create a", "rename a", "delete a"]);
+        assert!(history[0].timestamp.is_some());
+        assert_eq!(history[0].short_hash().len(), SHORT_HASH_LEN);
+    }
+
+    #[test]
+    fn test_doc_at_reads_old_revision() {
+        let mut doc = AutoCommit::new();
+        put_dish(&mut doc, "a", "Soup", "create");
+        let first = key_history(&mut doc, "a")[0].hash;
+        put_dish(&mut doc, "a", "Tomato Soup", "rename");
+
+        let old = doc_at(&mut doc, first).unwrap();
+        let (_, obj) = old.get(ROOT, "a").unwrap().unwrap();
+        let (name, _) = old.get(&obj, "name").unwrap().unwrap();
+        assert_eq!(name.into_string().unwrap(), "Soup");
+    }
+
+    #[test]
+    fn test_resolve_revision_prefix() {
+        let mut doc = AutoCommit::new();
+        put_dish(&mut doc, "a", "Soup", "create");
+        let hash = doc.get_heads()[0];
+
+        let prefix = &hash.to_string()[..6];
+        assert_eq!(resolve_revision(&mut doc, prefix), Ok(hash));
+        assert_eq!(
+            resolve_revision(&mut doc, "zzzz"),
+            Err(HistoryError::NotFound("zzzz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_untimed_changes_have_no_timestamp() {
+        assert_eq!(to_datetime(0), None);
+        assert_eq!(to_datetime(1_700_000_000), to_datetime(1_700_000_000_000));
+    }
+}
@@ -17,14 +17,20 @@
 //! - `meallogs.automerge`: Map of meallog_id (UUID string) -> MealLog object
 
 mod doc_type;
+mod history;
 mod multi_storage;
 mod storage;
 mod writer;
 
 pub use doc_type::DocType;
+pub use history::{
+    commit_with_message, doc_at, key_history, resolve_revision, HistoryError, Revision,
+    SHORT_HASH_LEN,
+};
 pub use multi_storage::{MultiDocStorage, MultiStorageError};
 pub use storage::{DocumentStorage, StorageError};
 pub use writer::{
-    delete_dish, delete_meallog, delete_mealplan, delete_shopping_cart, write_dish, write_meallog,
-    write_mealplan, write_shopping_cart,
+    delete_dish, delete_meallog, delete_mealplan, delete_pantry_item, delete_shopping_cart,
+    pantry_item_stock, write_dish, write_meallog, write_mealplan, write_pantry_item,
+    write_shopping_cart,
 };
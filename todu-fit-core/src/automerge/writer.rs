@@ -2,10 +2,10 @@
 //!
 //! These functions handle converting Rust structs into Automerge document structure.
 
-use automerge::{transaction::Transactable, AutoCommit, ObjType, ROOT};
+use automerge::{transaction::Transactable, AutoCommit, ObjId, ObjType, ReadDoc, Value, ROOT};
 use uuid::Uuid;
 
-use crate::models::{Dish, MealLog, MealPlan, ShoppingCart};
+use crate::models::{Dish, MealLog, MealPlan, PantryItem, ShoppingCart};
 
 /// Writes a dish to an Automerge document.
 ///
@@ -68,6 +68,22 @@ pub fn write_dish(doc: &mut AutoCommit, dish: &Dish) {
         doc.put(&ing_id, "name", ingredient.name.as_str()).unwrap();
         doc.put(&ing_id, "quantity", ingredient.quantity).unwrap();
         doc.put(&ing_id, "unit", ingredient.unit.as_str()).unwrap();
+        if let Some(ref note) = ingredient.note {
+            doc.put(&ing_id, "note", note.as_str()).unwrap();
+        }
+    }
+
+    // Components (other dishes used as sub-recipes)
+    if !dish.components.is_empty() {
+        let components_id = doc
+            .put_object(&dish_id, "components", ObjType::List)
+            .unwrap();
+        for (i, component) in dish.components.iter().enumerate() {
+            let comp_id = doc.insert_object(&components_id, i, ObjType::Map).unwrap();
+            doc.put(&comp_id, "dish_id", component.dish_id.to_string().as_str())
+                .unwrap();
+            doc.put(&comp_id, "scale", component.scale).unwrap();
+        }
     }
 
     // Nutrients
@@ -81,6 +97,9 @@ pub fn write_dish(doc: &mut AutoCommit, dish: &Dish) {
             doc.put(&nut_id, "amount", nutrient.amount).unwrap();
             doc.put(&nut_id, "unit", nutrient.unit.as_str()).unwrap();
         }
+        if dish.nutrients_calculated {
+            doc.put(&dish_id, "nutrients_calculated", true).unwrap();
+        }
     }
 }
 
@@ -111,6 +130,13 @@ pub fn write_mealplan(doc: &mut AutoCommit, mealplan: &MealPlan) {
     .unwrap();
     doc.put(&plan_id, "title", mealplan.title.as_str()).unwrap();
     doc.put(&plan_id, "cook", mealplan.cook.as_str()).unwrap();
+    if let Some(servings) = mealplan.servings {
+        doc.put(&plan_id, "servings", servings as i64).unwrap();
+    }
+    if let Some(source) = mealplan.leftovers_of {
+        doc.put(&plan_id, "leftovers_of", source.to_string().as_str())
+            .unwrap();
+    }
     doc.put(&plan_id, "created_by", mealplan.created_by.as_str())
         .unwrap();
     doc.put(
@@ -201,6 +227,9 @@ pub fn write_meallog(doc: &mut AutoCommit, meallog: &MealLog) {
             doc.put(&ing_id, "name", ingredient.name.as_str()).unwrap();
             doc.put(&ing_id, "quantity", ingredient.quantity).unwrap();
             doc.put(&ing_id, "unit", ingredient.unit.as_str()).unwrap();
+            if let Some(ref note) = ingredient.note {
+                doc.put(&ing_id, "note", note.as_str()).unwrap();
+            }
         }
 
         // Optional fields
@@ -213,6 +242,41 @@ pub fn write_meallog(doc: &mut AutoCommit, meallog: &MealLog) {
         if let Some(servings) = dish.servings {
             doc.put(&dish_obj, "servings", servings as i64).unwrap();
         }
+        if let Some(portion) = meallog.portions.get(&dish.id) {
+            doc.put(&dish_obj, "portion", *portion).unwrap();
+        }
+
+        // Nutrients (per serving) so history totals don't depend on the live dish
+        if let Some(ref nutrients) = dish.nutrients {
+            let nutrients_id = doc
+                .put_object(&dish_obj, "nutrients", ObjType::List)
+                .unwrap();
+            for (j, nutrient) in nutrients.iter().enumerate() {
+                let nut_id = doc.insert_object(&nutrients_id, j, ObjType::Map).unwrap();
+                doc.put(&nut_id, "name", nutrient.name.as_str()).unwrap();
+                doc.put(&nut_id, "amount", nutrient.amount).unwrap();
+                doc.put(&nut_id, "unit", nutrient.unit.as_str()).unwrap();
+            }
+        }
+    }
+
+    // Ad-hoc food entries
+    let foods_list = doc.put_object(&log_id, "foods", ObjType::List).unwrap();
+    for (i, food) in meallog.foods.iter().enumerate() {
+        let food_obj = doc.insert_object(&foods_list, i, ObjType::Map).unwrap();
+        doc.put(&food_obj, "name", food.name.as_str()).unwrap();
+        doc.put(&food_obj, "amount", food.amount).unwrap();
+        doc.put(&food_obj, "unit", food.unit.as_str()).unwrap();
+
+        let nutrients_id = doc
+            .put_object(&food_obj, "nutrients", ObjType::List)
+            .unwrap();
+        for (j, nutrient) in food.nutrients.iter().enumerate() {
+            let nut_id = doc.insert_object(&nutrients_id, j, ObjType::Map).unwrap();
+            doc.put(&nut_id, "name", nutrient.name.as_str()).unwrap();
+            doc.put(&nut_id, "amount", nutrient.amount).unwrap();
+            doc.put(&nut_id, "unit", nutrient.unit.as_str()).unwrap();
+        }
     }
 }
 
@@ -255,6 +319,17 @@ pub fn write_shopping_cart(doc: &mut AutoCommit, cart: &ShoppingCart) {
             doc.put(&item_id, "unit", unit.as_str()).unwrap();
         }
     }
+
+    // Write pantry stock added by checked items
+    if !cart.stocked.is_empty() {
+        let stocked_id = doc.put_object(&cart_id, "stocked", ObjType::List).unwrap();
+        for (i, ing) in cart.stocked.iter().enumerate() {
+            let ing_id = doc.insert_object(&stocked_id, i, ObjType::Map).unwrap();
+            doc.put(&ing_id, "name", ing.name.as_str()).unwrap();
+            doc.put(&ing_id, "quantity", ing.quantity).unwrap();
+            doc.put(&ing_id, "unit", ing.unit.as_str()).unwrap();
+        }
+    }
 }
 
 /// Deletes a shopping cart for a specific week from an Automerge document.
@@ -262,6 +337,87 @@ pub fn delete_shopping_cart(doc: &mut AutoCommit, week: &str) {
     let _ = doc.delete(ROOT, week);
 }
 
+/// Writes a pantry item to an Automerge document.
+///
+/// Each item is stored at root[lowercased name], so devices editing
+/// different items merge cleanly. Stock changes to an existing item are
+/// appended to its "adjustments" list rather than overwriting the
+/// quantity, so devices using and adding the same item concurrently both
+/// count. A new item, or one changing unit, starts over from `quantity`.
+pub fn write_pantry_item(doc: &mut AutoCommit, item: &PantryItem) {
+    let existing = doc
+        .get(ROOT, item.key())
+        .ok()
+        .flatten()
+        .map(|(_, id)| id)
+        .filter(|id| {
+            doc.get(id, "unit")
+                .ok()
+                .flatten()
+                .and_then(|(value, _)| value.into_string().ok())
+                .is_some_and(|unit| unit.eq_ignore_ascii_case(&item.unit))
+        });
+
+    let item_id = match existing {
+        Some(item_id) => {
+            let change = item.quantity - pantry_item_stock(doc, &item_id);
+            if change.abs() > 1e-9 {
+                let adjustments_id = match doc.get(&item_id, "adjustments").ok().flatten() {
+                    Some((_, id)) => id,
+                    None => doc
+                        .put_object(&item_id, "adjustments", ObjType::List)
+                        .unwrap(),
+                };
+                let len = doc.length(&adjustments_id);
+                doc.insert(&adjustments_id, len, change).unwrap();
+            }
+            item_id
+        }
+        None => {
+            let item_id = doc
+                .put_object(ROOT, item.key(), ObjType::Map)
+                .expect("Failed to create pantry item object");
+            doc.put(&item_id, "quantity", item.quantity).unwrap();
+            doc.put(&item_id, "unit", item.unit.as_str()).unwrap();
+            doc.put_object(&item_id, "adjustments", ObjType::List)
+                .unwrap();
+            item_id
+        }
+    };
+
+    doc.put(&item_id, "name", item.name.as_str()).unwrap();
+    doc.put(&item_id, "staple", item.staple).unwrap();
+}
+
+/// Stock of a stored pantry item: its starting quantity plus every
+/// adjustment in order. Stock never drops below zero, so when several
+/// devices use up the same item, stock added later isn't eaten by the
+/// overdraw.
+pub fn pantry_item_stock(doc: &AutoCommit, item_id: &ObjId) -> f64 {
+    fn number(value: Value) -> Option<f64> {
+        value.to_f64().or_else(|| value.to_i64().map(|i| i as f64))
+    }
+
+    let base = doc
+        .get(item_id, "quantity")
+        .ok()
+        .flatten()
+        .and_then(|(value, _)| number(value))
+        .unwrap_or(0.0);
+    match doc.get(item_id, "adjustments").ok().flatten() {
+        Some((_, list_id)) => (0..doc.length(&list_id))
+            .filter_map(|i| doc.get(&list_id, i).ok().flatten())
+            .filter_map(|(value, _)| number(value))
+            .fold(base.max(0.0), |stock, change| (stock + change).max(0.0)),
+        None => base.max(0.0),
+    }
+}
+
+/// Deletes a pantry item by name from an Automerge document.
+pub fn delete_pantry_item(doc: &mut AutoCommit, name: &str) {
+    let _ = doc.delete(ROOT, name.to_lowercase());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,4 +559,46 @@ mod tests {
         delete_shopping_cart(&mut doc, "2026-01-11");
         assert!(doc.get(ROOT, "2026-01-11").unwrap().is_none());
     }
+
+    #[test]
+    fn test_write_and_delete_pantry_item() {
+        let mut doc = AutoCommit::new();
+        write_pantry_item(&mut doc, &PantryItem::new("Olive Oil", 500.0, "ml"));
+
+        let (_, item_obj) = doc.get(ROOT, "olive oil").unwrap().unwrap();
+        let (val, _) = doc.get(&item_obj, "name").unwrap().unwrap();
+        assert_eq!(val.into_string().unwrap(), "Olive Oil");
+
+        delete_pantry_item(&mut doc, "OLIVE OIL");
+        assert!(doc.get(ROOT, "olive oil").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_concurrent_pantry_changes_both_count() {
+        let mut doc = AutoCommit::new();
+        write_pantry_item(&mut doc, &PantryItem::new("Rice", 1000.0, "g"));
+
+        // One device cooks with rice while another stocks more
+        let mut other = doc.fork();
+        write_pantry_item(&mut doc, &PantryItem::new("Rice", 800.0, "g"));
+        write_pantry_item(&mut other, &PantryItem::new("Rice", 1500.0, "g"));
+        doc.merge(&mut other).unwrap();
+
+        let (_, item_obj) = doc.get(ROOT, "rice").unwrap().unwrap();
+        assert_eq!(pantry_item_stock(&doc, &item_obj), 1300.0);
+
+        // Both devices use it all up, and new stock still counts in full
+        let mut other = doc.fork();
+        write_pantry_item(&mut doc, &PantryItem::new("Rice", 0.0, "g"));
+        write_pantry_item(&mut other, &PantryItem::new("Rice", 0.0, "g"));
+        doc.merge(&mut other).unwrap();
+        assert_eq!(pantry_item_stock(&doc, &item_obj), 0.0);
+        write_pantry_item(&mut doc, &PantryItem::new("Rice", 500.0, "g"));
+        assert_eq!(pantry_item_stock(&doc, &item_obj), 500.0);
+
+        // A new unit starts over
+        write_pantry_item(&mut doc, &PantryItem::new("Rice", 2.0, "cup"));
+        let (_, item_obj) = doc.get(ROOT, "rice").unwrap().unwrap();
+        assert_eq!(pantry_item_stock(&doc, &item_obj), 2.0);
+    }
 }
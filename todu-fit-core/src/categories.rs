@@ -0,0 +1,263 @@
+//! Grocery categories and store layouts for shopping lists.
+//!
+//! Ingredients are sorted into store sections (produce, dairy, ...) by name,
+//! using built-in defaults that a group can override per ingredient. A store
+//! layout lists the categories in the order you walk past them in one store.
+
+use serde::{Deserialize, Serialize};
+
+/// Category for anything that matches no other.
+pub const OTHER: &str = "other";
+
+/// Built-in categories in a typical walking order.
+pub const DEFAULT_ORDER: &[&str] = &[
+    "produce",
+    "bakery",
+    "meat",
+    "seafood",
+    "dairy",
+    "frozen",
+    "pantry",
+    "spices",
+    "beverages",
+    "household",
+    OTHER,
+];
+
+/// Name fragments and their default category.
+const DEFAULT_CATEGORIES: &[(&str, &str)] = &[
+    // Produce
+    ("apple", "produce"),
+    ("avocado", "produce"),
+    ("banana", "produce"),
+    ("basil", "produce"),
+    ("bell pepper", "produce"),
+    ("berries", "produce"),
+    ("broccoli", "produce"),
+    ("cabbage", "produce"),
+    ("carrot", "produce"),
+    ("celery", "produce"),
+    ("cilantro", "produce"),
+    ("cucumber", "produce"),
+    ("eggplant", "produce"),
+    ("garlic", "produce"),
+    ("ginger", "produce"),
+    ("kale", "produce"),
+    ("lemon", "produce"),
+    ("lettuce", "produce"),
+    ("lime", "produce"),
+    ("mushroom", "produce"),
+    ("onion", "produce"),
+    ("orange", "produce"),
+    ("parsley", "produce"),
+    ("potato", "produce"),
+    ("spinach", "produce"),
+    ("tomato", "produce"),
+    ("zucchini", "produce"),
+    // Bakery
+    ("bagel", "bakery"),
+    ("bread", "bakery"),
+    ("bun", "bakery"),
+    ("tortilla", "bakery"),
+    // Meat
+    ("bacon", "meat"),
+    ("beef", "meat"),
+    ("chicken", "meat"),
+    ("ham", "meat"),
+    ("lamb", "meat"),
+    ("pork", "meat"),
+    ("sausage", "meat"),
+    ("steak", "meat"),
+    ("turkey", "meat"),
+    // Seafood
+    ("cod", "seafood"),
+    ("fish", "seafood"),
+    ("salmon", "seafood"),
+    ("shrimp", "seafood"),
+    ("tuna", "seafood"),
+    // Dairy and eggs
+    ("butter", "dairy"),
+    ("cheese", "dairy"),
+    ("cream", "dairy"),
+    ("egg", "dairy"),
+    ("milk", "dairy"),
+    ("mozzarella", "dairy"),
+    ("parmesan", "dairy"),
+    ("yogurt", "dairy"),
+    // Frozen
+    ("frozen", "frozen"),
+    ("ice cream", "frozen"),
+    // Pantry
+    ("beans", "pantry"),
+    ("broth", "pantry"),
+    ("chicken broth", "pantry"),
+    ("chicken stock", "pantry"),
+    ("flour", "pantry"),
+    ("honey", "pantry"),
+    ("lentils", "pantry"),
+    ("oats", "pantry"),
+    ("oil", "pantry"),
+    ("pasta", "pantry"),
+    ("peanut butter", "pantry"),
+    ("rice", "pantry"),
+    ("soy sauce", "pantry"),
+    ("stock", "pantry"),
+    ("sugar", "pantry"),
+    ("vinegar", "pantry"),
+    // Spices
+    ("baking powder", "spices"),
+    ("baking soda", "spices"),
+    ("cinnamon", "spices"),
+    ("cumin", "spices"),
+    ("oregano", "spices"),
+    ("paprika", "spices"),
+    ("pepper", "spices"),
+    ("salt", "spices"),
+    ("vanilla", "spices"),
+    // Beverages
+    ("coffee", "beverages"),
+    ("juice", "beverages"),
+    ("tea", "beverages"),
+    ("wine", "beverages"),
+    // Household
+    ("paper towels", "household"),
+    ("soap", "household"),
+    ("toilet paper", "household"),
+];
+
+/// Default category for an ingredient name.
+///
+/// Matches whole words of the name (case-insensitive, allowing a plural
+/// "s" or "es"), so "graham crackers" isn't meat. The longest matching
+/// fragment wins, so "peanut butter" is pantry rather than dairy.
+pub fn default_category(ingredient_name: &str) -> &'static str {
+    let name = ingredient_name.to_lowercase();
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let word_matches = |word: &str, key: &str| {
+        word.strip_prefix(key)
+            .is_some_and(|rest| matches!(rest, "" | "s" | "es"))
+    };
+    let contains = |key: &str| {
+        let key: Vec<&str> = key.split(' ').collect();
+        words.windows(key.len()).any(|window| {
+            window
+                .iter()
+                .zip(&key)
+                .all(|(word, key)| word_matches(word, key))
+        })
+    };
+
+    DEFAULT_CATEGORIES
+        .iter()
+        .filter(|(key, _)| contains(key))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, category)| *category)
+        .unwrap_or(OTHER)
+}
+
+/// Normalize a category name for storage and comparison.
+pub fn normalize(category: &str) -> String {
+    category.trim().to_lowercase()
+}
+
+/// The order categories are walked in one store.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoreLayout {
+    /// Store name (e.g. "costco")
+    pub name: String,
+    /// Categories in walking order
+    pub categories: Vec<String>,
+}
+
+impl StoreLayout {
+    /// Create a layout, normalizing and de-duplicating category names.
+    pub fn new(name: impl Into<String>, categories: &[String]) -> Self {
+        let mut ordered: Vec<String> = Vec::new();
+        for category in categories.iter().map(|c| normalize(c)) {
+            if !category.is_empty() && !ordered.contains(&category) {
+                ordered.push(category);
+            }
+        }
+        Self {
+            name: name.into(),
+            categories: ordered,
+        }
+    }
+
+    /// Sort key for a category in this store.
+    ///
+    /// Categories missing from the layout come after the listed ones in the
+    /// default order, with "other" always last.
+    pub fn rank(&self, category: &str) -> usize {
+        let category = normalize(category);
+        let last = self.categories.len() + DEFAULT_ORDER.len();
+        if category == OTHER {
+            return last;
+        }
+        if let Some(index) = self.categories.iter().position(|c| *c == category) {
+            return index;
+        }
+        // Built-in categories other than "other", then custom ones
+        let fallback = DEFAULT_ORDER
+            .iter()
+            .position(|c| *c == category)
+            .unwrap_or(DEFAULT_ORDER.len() - 1);
+        self.categories.len() + fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_category_longest_match() {
+        assert_eq!(default_category("Yellow Onion"), "produce");
+        assert_eq!(default_category("peanut butter"), "pantry");
+        assert_eq!(default_category("butter"), "dairy");
+        assert_eq!(default_category("eggs"), "dairy");
+        assert_eq!(default_category("olive oil"), "pantry");
+        assert_eq!(default_category("chicken stock"), "pantry");
+        assert_eq!(default_category("flank steak"), "meat");
+        assert_eq!(default_category("dragon fruit"), OTHER);
+        assert_eq!(default_category("Roma Tomatoes"), "produce");
+        assert_eq!(default_category("all-purpose flour"), "pantry");
+    }
+
+    #[test]
+    fn test_default_category_needs_whole_words() {
+        assert_eq!(default_category("graham crackers"), OTHER);
+        assert_eq!(default_category("licorice"), OTHER);
+        assert_eq!(default_category("butternut squash"), OTHER);
+        assert_eq!(default_category("steamed buns"), "bakery");
+        assert_eq!(default_category("eggplant"), "produce");
+    }
+
+    #[test]
+    fn test_store_layout_rank() {
+        let layout = StoreLayout::new(
+            "costco",
+            &[
+                "Bakery".to_string(),
+                " dairy ".to_string(),
+                "bakery".to_string(),
+            ],
+        );
+        assert_eq!(layout.categories, vec!["bakery", "dairy"]);
+
+        assert_eq!(layout.rank("bakery"), 0);
+        assert_eq!(layout.rank("Dairy"), 1);
+        // Unlisted categories follow in the default order, then custom ones
+        assert!(layout.rank("produce") < layout.rank("spices"));
+        assert!(layout.rank("spices") < layout.rank("snacks"));
+        assert!(layout.rank("snacks") < layout.rank(OTHER));
+
+        // "other" stays last even when the layout lists it
+        let layout = StoreLayout::new("corner", &[OTHER.to_string(), "dairy".to_string()]);
+        assert!(layout.rank("dairy") < layout.rank(OTHER));
+        assert!(layout.rank("snacks") < layout.rank(OTHER));
+    }
+}
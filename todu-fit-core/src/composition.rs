@@ -0,0 +1,292 @@
+//! Composite dishes
+//!
+//! A dish can use other dishes as components, such as a pizza using a dough
+//! recipe. [`Dish::flatten`] expands components recursively into a plain
+//! dish, so scaling, nutrition and shopping lists work on the full recipe.
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::models::{Dish, Ingredient, MealPlan, Nutrient};
+
+/// Errors that can occur when expanding dish components
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CompositionError {
+    #[error("Dish components form a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+
+    #[error("Component scale must be greater than zero (got {0})")]
+    InvalidScale(f64),
+}
+
+/// One batch of a dish with its components expanded.
+struct Batch {
+    ingredients: Vec<Ingredient>,
+    /// Nutrients for the whole batch (per-serving amounts times servings)
+    nutrients: Vec<Nutrient>,
+}
+
+impl Dish {
+    /// Return a copy of this dish with its components expanded recursively.
+    ///
+    /// Component ingredients are multiplied by the component's scale and
+    /// appended to the dish's own. Nutrients stay per serving: each
+    /// component's batch nutrients are scaled and spread over this dish's
+    /// servings, then added to the dish's own. Components are looked up in
+    /// `dishes`; ones that no longer exist are skipped.
+    pub fn flatten(&self, dishes: &[Dish]) -> Result<Dish, CompositionError> {
+        let mut path = vec![self];
+        let batch = expand(self, dishes, &mut path)?;
+
+        let servings = batch_servings(self);
+        let mut flat = self.clone();
+        flat.ingredients = batch.ingredients;
+        flat.components.clear();
+        if self.nutrients.is_some() || !batch.nutrients.is_empty() {
+            flat.nutrients = Some(
+                batch
+                    .nutrients
+                    .into_iter()
+                    .map(|n| Nutrient::new(n.name, n.amount / servings, n.unit))
+                    .collect(),
+            );
+        }
+        Ok(flat)
+    }
+
+    /// Check that this dish's components don't lead back to itself.
+    pub fn check_components(&self, dishes: &[Dish]) -> Result<(), CompositionError> {
+        self.flatten(dishes).map(|_| ())
+    }
+
+    /// Whether this dish uses `dish_id` as a component, directly or through
+    /// other components.
+    pub fn uses_component(&self, dish_id: Uuid, dishes: &[Dish]) -> bool {
+        let mut stack: Vec<Uuid> = self.components.iter().map(|c| c.dish_id).collect();
+        let mut seen: Vec<Uuid> = Vec::new();
+        while let Some(id) = stack.pop() {
+            if id == dish_id {
+                return true;
+            }
+            if seen.contains(&id) {
+                continue;
+            }
+            seen.push(id);
+            if let Some(dish) = dishes.iter().find(|d| d.id == id) {
+                stack.extend(dish.components.iter().map(|c| c.dish_id));
+            }
+        }
+        false
+    }
+}
+
+impl MealPlan {
+    /// Ingredients used to cook this plan.
+    ///
+    /// Each planned dish is expanded with [`Dish::flatten`] and scaled to the
+    /// plan's servings when both sides know their servings. Dishes missing
+    /// from `dishes` are skipped. Leftover plans cook nothing, so they have
+    /// no ingredients.
+    pub fn ingredients(&self, dishes: &[Dish]) -> Result<Vec<Ingredient>, CompositionError> {
+        if self.is_leftovers() {
+            return Ok(Vec::new());
+        }
+        let mut ingredients = Vec::new();
+        for dish_id in &self.dish_ids {
+            if let Some(dish) = dishes.iter().find(|d| d.id == *dish_id) {
+                let dish = dish.flatten(dishes)?;
+                let factor = self
+                    .servings
+                    .and_then(|servings| dish.scale_factor(servings).ok())
+                    .unwrap_or(1.0);
+                ingredients.extend(dish.scaled_ingredients(factor));
+            }
+        }
+        Ok(ingredients)
+    }
+}
+
+fn expand<'a>(
+    dish: &'a Dish,
+    dishes: &'a [Dish],
+    path: &mut Vec<&'a Dish>,
+) -> Result<Batch, CompositionError> {
+    let servings = batch_servings(dish);
+    let mut batch = Batch {
+        ingredients: dish.ingredients.clone(),
+        nutrients: Vec::new(),
+    };
+    add_nutrients(
+        &mut batch.nutrients,
+        dish.nutrients.iter().flatten(),
+        servings,
+    );
+
+    for component in &dish.components {
+        if component.scale <= 0.0 {
+            return Err(CompositionError::InvalidScale(component.scale));
+        }
+        let Some(child) = dishes.iter().find(|d| d.id == component.dish_id) else {
+            continue;
+        };
+        if path.iter().any(|d| d.id == child.id) {
+            let mut names: Vec<String> = path.iter().map(|d| d.name.clone()).collect();
+            names.push(child.name.clone());
+            return Err(CompositionError::Cycle(names));
+        }
+
+        path.push(child);
+        let child_batch = expand(child, dishes, path)?;
+        path.pop();
+
+        batch
+            .ingredients
+            .extend(child_batch.ingredients.into_iter().map(|mut ing| {
+                ing.quantity *= component.scale;
+                ing
+            }));
+        add_nutrients(
+            &mut batch.nutrients,
+            child_batch.nutrients.iter(),
+            component.scale,
+        );
+    }
+
+    Ok(batch)
+}
+
+/// Servings in one batch; dishes without servings count as one.
+fn batch_servings(dish: &Dish) -> f64 {
+    dish.servings.filter(|s| *s > 0).unwrap_or(1) as f64
+}
+
+fn add_nutrients<'a>(
+    totals: &mut Vec<Nutrient>,
+    nutrients: impl Iterator<Item = &'a Nutrient>,
+    factor: f64,
+) {
+    for nutrient in nutrients {
+        let amount = nutrient.amount * factor;
+        match totals.iter_mut().find(|n| n.name == nutrient.name) {
+            Some(existing) => existing.amount += amount,
+            None => totals.push(Nutrient::new(&nutrient.name, amount, &nutrient.unit)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DishComponent;
+
+    fn dough() -> Dish {
+        Dish::new("Pizza Dough", "user1")
+            .with_servings(2)
+            .with_ingredients(vec![
+                Ingredient::new("flour", 500.0, "g"),
+                Ingredient::new("water", 300.0, "ml"),
+            ])
+            .with_nutrients(vec![Nutrient::new("calories", 900.0, "kcal")])
+    }
+
+    #[test]
+    fn test_flatten_expands_components() {
+        let dough = dough();
+        let pizza = Dish::new("Pizza", "user1")
+            .with_servings(4)
+            .with_ingredients(vec![Ingredient::new("mozzarella", 250.0, "g")])
+            .with_nutrients(vec![Nutrient::new("calories", 200.0, "kcal")])
+            .with_components(vec![DishComponent::new(dough.id, 0.5)]);
+
+        let flat = pizza.flatten(&[dough]).unwrap();
+        assert!(flat.components.is_empty());
+        assert_eq!(flat.ingredients.len(), 3);
+        assert_eq!(flat.ingredients[1].name, "flour");
+        assert_eq!(flat.ingredients[1].quantity, 250.0);
+
+        // Half a dough batch (1800 kcal) spread over 4 servings, plus 200 own
+        let calories = &flat.nutrients.unwrap()[0];
+        assert_eq!(calories.amount, 425.0);
+    }
+
+    #[test]
+    fn test_flatten_nested_components() {
+        let dough = dough();
+        let base = Dish::new("Pizza Base", "user1")
+            .with_components(vec![DishComponent::new(dough.id, 2.0)]);
+        let pizza =
+            Dish::new("Pizza", "user1").with_components(vec![DishComponent::new(base.id, 1.0)]);
+
+        let flat = pizza.flatten(&[dough, base]).unwrap();
+        assert_eq!(flat.ingredients[0].quantity, 1000.0);
+        assert_eq!(flat.nutrients.unwrap()[0].amount, 3600.0);
+    }
+
+    #[test]
+    fn test_uses_component_follows_nesting() {
+        let dough = dough();
+        let base = Dish::new("Pizza Base", "user1")
+            .with_components(vec![DishComponent::new(dough.id, 1.0)]);
+        let pizza =
+            Dish::new("Pizza", "user1").with_components(vec![DishComponent::new(base.id, 1.0)]);
+        let dishes = vec![dough.clone(), base];
+
+        assert!(pizza.uses_component(dough.id, &dishes));
+        assert!(!dough.uses_component(pizza.id, &dishes));
+    }
+
+    #[test]
+    fn test_cycle_detected() {
+        let mut a = Dish::new("A", "user1");
+        let mut b = Dish::new("B", "user1");
+        b.components.push(DishComponent::new(a.id, 1.0));
+        a.components.push(DishComponent::new(b.id, 1.0));
+
+        let err = a.flatten(&[a.clone(), b]).unwrap_err();
+        assert_eq!(
+            err,
+            CompositionError::Cycle(vec!["A".into(), "B".into(), "A".into()])
+        );
+        assert_eq!(err.to_string(), "Dish components form a cycle: A -> B -> A");
+    }
+
+    #[test]
+    fn test_self_reference_is_a_cycle() {
+        let mut soup = Dish::new("Soup", "user1");
+        soup.components.push(DishComponent::new(soup.id, 1.0));
+        assert!(soup.check_components(&[soup.clone()]).is_err());
+    }
+
+    #[test]
+    fn test_meal_plan_ingredients_scaled_and_expanded() {
+        use crate::models::MealType;
+        use chrono::NaiveDate;
+
+        let dough = dough();
+        let pizza = Dish::new("Pizza", "user1")
+            .with_servings(4)
+            .with_ingredients(vec![Ingredient::new("mozzarella", 250.0, "g")])
+            .with_components(vec![DishComponent::new(dough.id, 0.5)]);
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let plan = MealPlan::new(date, MealType::Dinner, "Pizza night", "user1")
+            .with_dish_ids(vec![pizza.id, Uuid::new_v4()])
+            .with_servings(8);
+
+        let ingredients = plan.ingredients(&[dough, pizza]).unwrap();
+        assert_eq!(ingredients.len(), 3);
+        assert_eq!(ingredients[0].quantity, 500.0);
+        assert_eq!(ingredients[1].name, "flour");
+        assert_eq!(ingredients[1].quantity, 500.0);
+    }
+
+    #[test]
+    fn test_missing_component_skipped() {
+        let salad = Dish::new("Salad", "user1")
+            .with_ingredients(vec![Ingredient::new("lettuce", 1.0, "head")])
+            .with_components(vec![DishComponent::new(Uuid::new_v4(), 1.0)]);
+
+        let flat = salad.flatten(&[]).unwrap();
+        assert_eq!(flat.ingredients.len(), 1);
+        assert!(flat.nutrients.is_none());
+    }
+}
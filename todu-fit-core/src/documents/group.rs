@@ -3,9 +3,16 @@
 //! A group document represents a shared context (e.g., family, household)
 //! where multiple users can collaborate on dishes and meal plans.
 
+use chrono::{NaiveDate, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 
+use crate::categories::{self, StoreLayout};
 use crate::document_id::DocumentId;
+use uuid::Uuid;
+
+use crate::models::{CartPeriod, MealSlot, MealType, PlanTemplate, RecurringPlan};
 
 /// Reference to a group, stored in identity documents.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,7 +37,7 @@ impl GroupRef {
 /// Shared group document.
 ///
 /// Contains group metadata and references to shared documents
-/// (dishes, meal plans, and shopping carts).
+/// (dishes, meal plans, shopping carts and pantry).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupDocument {
     /// Schema version for migration support
@@ -48,11 +55,47 @@ pub struct GroupDocument {
     /// Reference to shared shopping carts document
     #[serde(default = "DocumentId::new")]
     pub shopping_carts_doc_id: DocumentId,
+
+    /// Reference to the shared pantry document. Groups created before the
+    /// pantry existed get one from [`GroupDocument::ensure_pantry_doc_id`],
+    /// derived from the group's own ID so every device picks the same one.
+    #[serde(default)]
+    pub pantry_doc_id: Option<DocumentId>,
+
+    /// Meal slots defined by the group (empty means the built-in four)
+    #[serde(default)]
+    pub meal_slots: Vec<MealSlot>,
+
+    /// Shopping categories by lowercased ingredient name, overriding the
+    /// built-in defaults
+    #[serde(default)]
+    pub ingredient_categories: BTreeMap<String, String>,
+
+    /// Category walking order for each store the group shops at
+    #[serde(default)]
+    pub store_layouts: Vec<StoreLayout>,
+
+    /// First day of the group's shopping week
+    #[serde(default = "default_week_start")]
+    pub week_start: Weekday,
+
+    /// Named weeks of meal plans
+    #[serde(default)]
+    pub plan_templates: Vec<PlanTemplate>,
+
+    /// Meal plans that repeat on a schedule
+    #[serde(default)]
+    pub recurring_plans: Vec<RecurringPlan>,
+}
+
+/// Weeks started on Sunday before the start day was configurable.
+fn default_week_start() -> Weekday {
+    Weekday::Sun
 }
 
 impl GroupDocument {
     /// Current schema version
-    pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+    pub const CURRENT_SCHEMA_VERSION: u32 = 8;
 
     /// Create a new group document with generated document IDs.
     pub fn new(name: impl Into<String>) -> Self {
@@ -62,6 +105,13 @@ impl GroupDocument {
             dishes_doc_id: DocumentId::new(),
             mealplans_doc_id: DocumentId::new(),
             shopping_carts_doc_id: DocumentId::new(),
+            pantry_doc_id: Some(DocumentId::new()),
+            meal_slots: Vec::new(),
+            ingredient_categories: BTreeMap::new(),
+            store_layouts: Vec::new(),
+            week_start: default_week_start(),
+            plan_templates: Vec::new(),
+            recurring_plans: Vec::new(),
         }
     }
 
@@ -77,18 +127,250 @@ impl GroupDocument {
             dishes_doc_id,
             mealplans_doc_id,
             shopping_carts_doc_id: DocumentId::new(),
+            pantry_doc_id: Some(DocumentId::new()),
+            meal_slots: Vec::new(),
+            ingredient_categories: BTreeMap::new(),
+            store_layouts: Vec::new(),
+            week_start: default_week_start(),
+            plan_templates: Vec::new(),
+            recurring_plans: Vec::new(),
         }
     }
 
+    /// The pantry document ID. Groups without one use an ID derived from
+    /// `group_doc_id` rather than a random one, so devices that add it while
+    /// offline agree on the document and no stock is lost when their group
+    /// documents sync.
+    pub fn pantry_doc_id(&self, group_doc_id: &DocumentId) -> DocumentId {
+        self.pantry_doc_id.unwrap_or_else(|| {
+            let mut hasher = Sha256::new();
+            hasher.update(group_doc_id.as_bytes());
+            hasher.update(b"pantry");
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&hasher.finalize()[..16]);
+            DocumentId::from_bytes(bytes)
+        })
+    }
+
+    /// The pantry document ID, storing it in the group if it is missing.
+    ///
+    /// The group document must be saved afterwards for a new ID to stick.
+    pub fn ensure_pantry_doc_id(&mut self, group_doc_id: &DocumentId) -> DocumentId {
+        let doc_id = self.pantry_doc_id(group_doc_id);
+        self.pantry_doc_id = Some(doc_id);
+        doc_id
+    }
+
     /// Rename the group.
     pub fn rename(&mut self, name: impl Into<String>) {
         self.name = name.into();
     }
+
+    /// The group's meal slots in display order, or the built-in slots if
+    /// none are defined.
+    pub fn meal_slots(&self) -> Vec<MealSlot> {
+        if self.meal_slots.is_empty() {
+            return MealSlot::builtin();
+        }
+        let mut slots = self.meal_slots.clone();
+        slots.sort_by_key(|s| s.order);
+        slots
+    }
+
+    /// Parse a meal type name and check it is one of the group's slots.
+    pub fn resolve_meal_type(&self, name: &str) -> Result<MealType, String> {
+        MealSlot::resolve(&self.meal_slots(), name)
+    }
+
+    /// Add a meal slot at `position` (0-based), or at the end.
+    ///
+    /// The first change to a group without slots starts from the built-in four.
+    pub fn add_meal_slot(
+        &mut self,
+        meal_type: MealType,
+        default_time: Option<NaiveTime>,
+        position: Option<usize>,
+    ) -> Result<(), String> {
+        let mut slots = self.meal_slots();
+        if slots.iter().any(|s| s.meal_type == meal_type) {
+            return Err(format!("Meal slot '{}' already exists", meal_type));
+        }
+        let mut slot = MealSlot::new(meal_type, 0);
+        slot.default_time = default_time;
+        let index = position.unwrap_or(slots.len()).min(slots.len());
+        slots.insert(index, slot);
+        self.set_meal_slots(slots);
+        Ok(())
+    }
+
+    /// Remove a meal slot. Returns false if the group has no such slot.
+    pub fn remove_meal_slot(&mut self, meal_type: &MealType) -> bool {
+        let mut slots = self.meal_slots();
+        let before = slots.len();
+        slots.retain(|s| &s.meal_type != meal_type);
+        if slots.len() == before {
+            return false;
+        }
+        self.set_meal_slots(slots);
+        true
+    }
+
+    /// Move a meal slot to `position` (0-based).
+    pub fn move_meal_slot(&mut self, meal_type: &MealType, position: usize) -> Result<(), String> {
+        let mut slots = self.meal_slots();
+        let index = slots
+            .iter()
+            .position(|s| &s.meal_type == meal_type)
+            .ok_or_else(|| format!("Meal slot '{}' not found", meal_type))?;
+        let slot = slots.remove(index);
+        slots.insert(position.min(slots.len()), slot);
+        self.set_meal_slots(slots);
+        Ok(())
+    }
+
+    /// Set or clear a meal slot's default time.
+    pub fn set_meal_slot_time(
+        &mut self,
+        meal_type: &MealType,
+        default_time: Option<NaiveTime>,
+    ) -> Result<(), String> {
+        let mut slots = self.meal_slots();
+        let slot = slots
+            .iter_mut()
+            .find(|s| &s.meal_type == meal_type)
+            .ok_or_else(|| format!("Meal slot '{}' not found", meal_type))?;
+        slot.default_time = default_time;
+        self.set_meal_slots(slots);
+        Ok(())
+    }
+
+    /// Shopping category for an ingredient: the group's own mapping, then the
+    /// built-in defaults.
+    pub fn category_for(&self, ingredient: &str) -> String {
+        self.ingredient_categories
+            .get(&ingredient.trim().to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| categories::default_category(ingredient).to_string())
+    }
+
+    /// Put an ingredient in a category for this group.
+    pub fn set_ingredient_category(&mut self, ingredient: &str, category: &str) {
+        self.ingredient_categories.insert(
+            ingredient.trim().to_lowercase(),
+            categories::normalize(category),
+        );
+    }
+
+    /// Go back to the default category for an ingredient. Returns false if
+    /// the group had no mapping for it.
+    pub fn remove_ingredient_category(&mut self, ingredient: &str) -> bool {
+        self.ingredient_categories
+            .remove(&ingredient.trim().to_lowercase())
+            .is_some()
+    }
+
+    /// Find a store layout by name (case-insensitive).
+    pub fn store_layout(&self, name: &str) -> Option<&StoreLayout> {
+        self.store_layouts
+            .iter()
+            .find(|l| l.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add a store layout, replacing one with the same name.
+    pub fn set_store_layout(&mut self, layout: StoreLayout) {
+        self.remove_store_layout(&layout.name);
+        self.store_layouts.push(layout);
+    }
+
+    /// Remove a store layout. Returns false if there was none by that name.
+    pub fn remove_store_layout(&mut self, name: &str) -> bool {
+        let before = self.store_layouts.len();
+        self.store_layouts
+            .retain(|l| !l.name.eq_ignore_ascii_case(name));
+        self.store_layouts.len() != before
+    }
+
+    /// First day of the group's week containing `date`.
+    pub fn week_containing(&self, date: NaiveDate) -> NaiveDate {
+        CartPeriod::week_containing(date, self.week_start).start()
+    }
+
+    /// Find a plan template by name (case-insensitive).
+    pub fn plan_template(&self, name: &str) -> Option<&PlanTemplate> {
+        self.plan_templates
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add a plan template, replacing one with the same name. Returns true
+    /// if one was replaced.
+    pub fn set_plan_template(&mut self, template: PlanTemplate) -> bool {
+        let replaced = self.remove_plan_template(&template.name);
+        self.plan_templates.push(template);
+        replaced
+    }
+
+    /// Remove a plan template. Returns false if there was none by that name.
+    pub fn remove_plan_template(&mut self, name: &str) -> bool {
+        let before = self.plan_templates.len();
+        self.plan_templates
+            .retain(|t| !t.name.eq_ignore_ascii_case(name));
+        self.plan_templates.len() != before
+    }
+
+    /// Find a recurring plan by ID, or by title (case-insensitive).
+    pub fn recurring_plan(&self, id_or_title: &str) -> Option<&RecurringPlan> {
+        let index = self.recurring_plan_index(id_or_title)?;
+        self.recurring_plans.get(index)
+    }
+
+    /// Mutable access to a recurring plan by ID or title.
+    pub fn recurring_plan_mut(&mut self, id_or_title: &str) -> Option<&mut RecurringPlan> {
+        let index = self.recurring_plan_index(id_or_title)?;
+        self.recurring_plans.get_mut(index)
+    }
+
+    /// Remove a recurring plan by ID or title, returning it.
+    pub fn remove_recurring_plan(&mut self, id_or_title: &str) -> Option<RecurringPlan> {
+        let index = self.recurring_plan_index(id_or_title)?;
+        Some(self.recurring_plans.remove(index))
+    }
+
+    /// The index in `recurring_plans` of the series with the occurrence
+    /// `id`, and the occurrence's date, searching dates up to `to`.
+    pub fn find_occurrence(&self, id: Uuid, to: NaiveDate) -> Option<(usize, NaiveDate)> {
+        self.recurring_plans
+            .iter()
+            .enumerate()
+            .find_map(|(index, series)| {
+                series
+                    .find_occurrence(id, to)
+                    .map(|occurrence| (index, occurrence.date))
+            })
+    }
+
+    fn recurring_plan_index(&self, id_or_title: &str) -> Option<usize> {
+        if let Ok(id) = Uuid::parse_str(id_or_title) {
+            return self.recurring_plans.iter().position(|r| r.id == id);
+        }
+        self.recurring_plans
+            .iter()
+            .position(|r| r.title.eq_ignore_ascii_case(id_or_title.trim()))
+    }
+
+    /// Store slots, numbering their order from their position.
+    fn set_meal_slots(&mut self, mut slots: Vec<MealSlot>) {
+        for (order, slot) in slots.iter_mut().enumerate() {
+            slot.order = order as u32;
+        }
+        self.meal_slots = slots;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::Recurrence;
 
     #[test]
     fn test_group_ref_new() {
@@ -177,4 +459,201 @@ mod tests {
         // shopping_carts_doc_id should be auto-generated via serde default
         assert!(!parsed.shopping_carts_doc_id.to_string().is_empty());
     }
+
+    #[test]
+    fn test_default_meal_slots() {
+        let group = GroupDocument::new("Family");
+        let slots = group.meal_slots();
+        assert_eq!(slots.len(), 4);
+        assert_eq!(slots[0].meal_type, MealType::Breakfast);
+
+        assert_eq!(group.resolve_meal_type("Dinner"), Ok(MealType::Dinner));
+        assert!(group.resolve_meal_type("brunch").is_err());
+    }
+
+    #[test]
+    fn test_custom_meal_slots() {
+        let mut group = GroupDocument::new("Family");
+        let brunch: MealType = "brunch".parse().unwrap();
+        let time = NaiveTime::from_hms_opt(10, 30, 0);
+
+        group.add_meal_slot(brunch.clone(), time, Some(1)).unwrap();
+        assert!(group.add_meal_slot(brunch.clone(), None, None).is_err());
+
+        let slots = group.meal_slots();
+        assert_eq!(slots.len(), 5);
+        assert_eq!(slots[1].meal_type, brunch);
+        assert_eq!(slots[1].default_time, time);
+        assert_eq!(slots[2].order, 2);
+        assert_eq!(group.resolve_meal_type("Brunch"), Ok(brunch.clone()));
+
+        group.move_meal_slot(&brunch, 10).unwrap();
+        assert_eq!(group.meal_slots()[4].meal_type, brunch);
+
+        assert!(group.remove_meal_slot(&MealType::Snack));
+        assert!(!group.remove_meal_slot(&MealType::Snack));
+        assert!(group.resolve_meal_type("snack").is_err());
+        assert_eq!(group.meal_slots().len(), 4);
+    }
+
+    #[test]
+    fn test_group_document_migration_from_v2() {
+        let json = format!(
+            r#"{{
+                "schema_version": 2,
+                "name": "Family",
+                "dishes_doc_id": "{}",
+                "mealplans_doc_id": "{}",
+                "shopping_carts_doc_id": "{}"
+            }}"#,
+            DocumentId::new(),
+            DocumentId::new(),
+            DocumentId::new()
+        );
+
+        let parsed: GroupDocument = serde_json::from_str(&json).unwrap();
+        assert!(parsed.meal_slots.is_empty());
+        assert_eq!(parsed.meal_slots().len(), 4);
+    }
+
+    #[test]
+    fn test_ingredient_categories_and_store_layouts() {
+        let mut group = GroupDocument::new("Family");
+        assert_eq!(group.category_for("Milk"), "dairy");
+
+        group.set_ingredient_category(" Tofu ", "Refrigerated");
+        assert_eq!(group.category_for("tofu"), "refrigerated");
+        assert!(group.remove_ingredient_category("TOFU"));
+        assert!(!group.remove_ingredient_category("tofu"));
+        assert_eq!(group.category_for("tofu"), categories::OTHER);
+
+        group.set_store_layout(StoreLayout::new("Costco", &["dairy".to_string()]));
+        group.set_store_layout(StoreLayout::new("costco", &["produce".to_string()]));
+        assert_eq!(group.store_layouts.len(), 1);
+        assert_eq!(
+            group.store_layout("COSTCO").unwrap().categories,
+            vec!["produce"]
+        );
+        assert!(group.remove_store_layout("Costco"));
+        assert!(group.store_layout("costco").is_none());
+    }
+
+    #[test]
+    fn test_group_document_migration_from_v3() {
+        let json = format!(
+            r#"{{
+                "schema_version": 3,
+                "name": "Family",
+                "dishes_doc_id": "{}",
+                "mealplans_doc_id": "{}",
+                "shopping_carts_doc_id": "{}"
+            }}"#,
+            DocumentId::new(),
+            DocumentId::new(),
+            DocumentId::new()
+        );
+
+        let mut parsed: GroupDocument = serde_json::from_str(&json).unwrap();
+        assert!(parsed.pantry_doc_id.is_none());
+
+        let group_id = DocumentId::new();
+        let pantry_id = parsed.pantry_doc_id(&group_id);
+        assert!(parsed.pantry_doc_id.is_none());
+        assert_eq!(parsed.ensure_pantry_doc_id(&group_id), pantry_id);
+        assert_eq!(parsed.pantry_doc_id, Some(pantry_id));
+        assert_eq!(parsed.ensure_pantry_doc_id(&DocumentId::new()), pantry_id);
+
+        // Another device migrating the same group picks the same pantry
+        let mut other: GroupDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.ensure_pantry_doc_id(&group_id), pantry_id);
+        assert_ne!(pantry_id, group_id);
+    }
+
+    #[test]
+    fn test_week_start_roundtrip_and_default() {
+        let json = format!(
+            r#"{{
+                "schema_version": 5,
+                "name": "Family",
+                "dishes_doc_id": "{}",
+                "mealplans_doc_id": "{}"
+            }}"#,
+            DocumentId::new(),
+            DocumentId::new()
+        );
+        let mut parsed: GroupDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.week_start, Weekday::Sun);
+
+        parsed.week_start = Weekday::Thu;
+        let wednesday = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+        assert_eq!(
+            parsed.week_containing(wednesday),
+            NaiveDate::from_ymd_opt(2026, 1, 8).unwrap()
+        );
+        let json = serde_json::to_string(&parsed).unwrap();
+        let reparsed: GroupDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed.week_start, Weekday::Thu);
+    }
+
+    #[test]
+    fn test_plan_templates() {
+        let mut group = GroupDocument::new("Family");
+        let template = |name: &str| PlanTemplate {
+            name: name.to_string(),
+            entries: Vec::new(),
+        };
+
+        assert!(!group.set_plan_template(template("Week A")));
+        assert!(group.set_plan_template(template("week a")));
+        assert!(!group.set_plan_template(template("week b")));
+        assert_eq!(group.plan_templates.len(), 2);
+        assert_eq!(group.plan_template("WEEK A").unwrap().name, "week a");
+
+        assert!(group.remove_plan_template("Week B"));
+        assert!(!group.remove_plan_template("week b"));
+    }
+
+    #[test]
+    fn test_recurring_plans() {
+        let mut group = GroupDocument::new("Family");
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let tacos = RecurringPlan::new(
+            MealType::Dinner,
+            "Taco Tuesday",
+            Recurrence::weekly(vec![Weekday::Tue]),
+            start,
+            "alice",
+        );
+        let id = tacos.id;
+        group.recurring_plans.push(tacos);
+
+        let json = serde_json::to_string(&group).unwrap();
+        let mut parsed: GroupDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.recurring_plans, group.recurring_plans);
+
+        assert_eq!(parsed.recurring_plan("taco tuesday").unwrap().id, id);
+        let tuesday = NaiveDate::from_ymd_opt(2026, 1, 13).unwrap();
+        let occurrence_id = parsed.recurring_plans[0].occurrence_id(tuesday);
+        let (index, date) = parsed
+            .find_occurrence(
+                occurrence_id,
+                NaiveDate::from_ymd_opt(2026, 12, 31).unwrap(),
+            )
+            .unwrap();
+        assert_eq!((parsed.recurring_plans[index].id, date), (id, tuesday));
+
+        parsed
+            .recurring_plan_mut(&id.to_string())
+            .unwrap()
+            .skipped
+            .push(tuesday);
+        assert_eq!(
+            parsed
+                .remove_recurring_plan(&id.to_string())
+                .unwrap()
+                .skipped,
+            vec![tuesday]
+        );
+        assert!(parsed.recurring_plan("Taco Tuesday").is_none());
+    }
 }
@@ -3,10 +3,12 @@
 //! The identity document is personal to each user and contains:
 //! - Reference to their personal meal logs document
 //! - List of groups they belong to
+//! - Daily nutrition goals
 
 use serde::{Deserialize, Serialize};
 
 use crate::document_id::DocumentId;
+use crate::goals::NutritionGoal;
 
 use super::GroupRef;
 
@@ -24,11 +26,15 @@ pub struct IdentityDocument {
 
     /// Groups this user belongs to
     pub groups: Vec<GroupRef>,
+
+    /// Daily nutrition goals, one per nutrient
+    #[serde(default)]
+    pub nutrition_goals: Vec<NutritionGoal>,
 }
 
 impl IdentityDocument {
     /// Current schema version
-    pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+    pub const CURRENT_SCHEMA_VERSION: u32 = 2;
 
     /// Create a new identity document with a new meal logs document ID.
     pub fn new() -> Self {
@@ -36,6 +42,7 @@ impl IdentityDocument {
             schema_version: Self::CURRENT_SCHEMA_VERSION,
             meallogs_doc_id: DocumentId::new(),
             groups: Vec::new(),
+            nutrition_goals: Vec::new(),
         }
     }
 
@@ -45,6 +52,7 @@ impl IdentityDocument {
             schema_version: Self::CURRENT_SCHEMA_VERSION,
             meallogs_doc_id,
             groups: Vec::new(),
+            nutrition_goals: Vec::new(),
         }
     }
 
@@ -70,6 +78,33 @@ impl IdentityDocument {
     pub fn has_group(&self, doc_id: &DocumentId) -> bool {
         self.groups.iter().any(|g| &g.doc_id == doc_id)
     }
+
+    /// Set a nutrition goal, replacing any existing goal for the same nutrient.
+    pub fn set_goal(&mut self, goal: NutritionGoal) {
+        match self
+            .nutrition_goals
+            .iter_mut()
+            .find(|g| g.nutrient.eq_ignore_ascii_case(&goal.nutrient))
+        {
+            Some(existing) => *existing = goal,
+            None => self.nutrition_goals.push(goal),
+        }
+    }
+
+    /// Remove the goal for a nutrient. Returns true if one existed.
+    pub fn remove_goal(&mut self, nutrient: &str) -> bool {
+        let before = self.nutrition_goals.len();
+        self.nutrition_goals
+            .retain(|g| !g.nutrient.eq_ignore_ascii_case(nutrient));
+        self.nutrition_goals.len() != before
+    }
+
+    /// Get the goal for a nutrient.
+    pub fn get_goal(&self, nutrient: &str) -> Option<&NutritionGoal> {
+        self.nutrition_goals
+            .iter()
+            .find(|g| g.nutrient.eq_ignore_ascii_case(nutrient))
+    }
 }
 
 impl Default for IdentityDocument {
@@ -154,4 +189,34 @@ mod tests {
         assert_eq!(parsed.groups.len(), 1);
         assert_eq!(parsed.groups[0].name, "Family");
     }
+
+    #[test]
+    fn test_set_and_remove_goal() {
+        let mut identity = IdentityDocument::new();
+        identity.set_goal(NutritionGoal::new("calories", 2000.0, "kcal"));
+        identity.set_goal(NutritionGoal::new("protein", 100.0, "g"));
+        identity.set_goal(NutritionGoal::new("Calories", 1800.0, "kcal"));
+
+        assert_eq!(identity.nutrition_goals.len(), 2);
+        assert_eq!(identity.get_goal("calories").unwrap().amount, 1800.0);
+
+        assert!(identity.remove_goal("PROTEIN"));
+        assert!(!identity.remove_goal("protein"));
+        assert!(identity.get_goal("protein").is_none());
+    }
+
+    #[test]
+    fn test_identity_document_migration_from_v1() {
+        let json = format!(
+            r#"{{
+                "schema_version": 1,
+                "meallogs_doc_id": "{}",
+                "groups": []
+            }}"#,
+            DocumentId::new()
+        );
+
+        let parsed: IdentityDocument = serde_json::from_str(&json).unwrap();
+        assert!(parsed.nutrition_goals.is_empty());
+    }
 }
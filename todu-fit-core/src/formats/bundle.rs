@@ -0,0 +1,119 @@
+//! todu-fit dish bundle
+//!
+//! A lossless JSON export of dishes, including ids and timestamps:
+//!
+//! ```json
+//! { "format": "todu-fit-dishes", "version": 1, "exported_at": "...", "dishes": [...] }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::FormatError;
+use crate::models::Dish;
+
+/// Value of the `format` field identifying a bundle.
+pub const BUNDLE_FORMAT: &str = "todu-fit-dishes";
+
+/// Newest bundle version this build can read.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DishBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub dishes: Vec<Dish>,
+}
+
+/// Serialize dishes as a pretty-printed bundle.
+pub fn write_bundle(dishes: &[Dish]) -> Result<String, FormatError> {
+    let bundle = DishBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        dishes: dishes.to_vec(),
+    };
+    serde_json::to_string_pretty(&bundle).map_err(|e| FormatError::InvalidJson(e.to_string()))
+}
+
+/// Read dishes from a bundle.
+pub fn read_bundle(json: &str) -> Result<Vec<Dish>, FormatError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| FormatError::InvalidJson(e.to_string()))?;
+
+    match value.get("format").and_then(Value::as_str) {
+        Some(BUNDLE_FORMAT) => {}
+        Some(other) => {
+            return Err(FormatError::UnsupportedBundle(format!(
+                "unknown format '{}'",
+                other
+            )))
+        }
+        None => return Err(FormatError::MissingField("format")),
+    }
+
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version == 0 || version > BUNDLE_VERSION as u64 {
+        return Err(FormatError::UnsupportedBundle(format!(
+            "version {} (expected {})",
+            version, BUNDLE_VERSION
+        )));
+    }
+
+    let bundle: DishBundle =
+        serde_json::from_value(value).map_err(|e| FormatError::InvalidJson(e.to_string()))?;
+    Ok(bundle.dishes)
+}
+
+/// Whether `json` looks like a dish bundle (without fully parsing it).
+pub fn is_bundle(json: &str) -> bool {
+    json.trim_start().starts_with('{') && json.contains(BUNDLE_FORMAT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Ingredient, Nutrient};
+
+    #[test]
+    fn test_bundle_roundtrip_is_lossless() {
+        let mut dish = Dish::new("Pancakes", "user1")
+            .with_servings(4)
+            .with_prep_time(10)
+            .with_ingredients(vec![
+                Ingredient::new("flour", 1.5, "cups").with_note("sifted")
+            ])
+            .with_nutrients(vec![Nutrient::new("calories", 250.0, "kcal")])
+            .with_tags(vec!["breakfast".to_string()]);
+        dish.source_url = Some("https://example.com/pancakes".to_string());
+
+        let json = write_bundle(std::slice::from_ref(&dish)).unwrap();
+        assert!(is_bundle(&json));
+
+        let dishes = read_bundle(&json).unwrap();
+        assert_eq!(dishes, vec![dish]);
+    }
+
+    #[test]
+    fn test_read_bundle_rejects_other_formats() {
+        let err = read_bundle(r#"{"format": "other", "version": 1, "dishes": []}"#).unwrap_err();
+        assert!(matches!(err, FormatError::UnsupportedBundle(_)));
+
+        let err = read_bundle(r#"[{"name": "Pancakes"}]"#).unwrap_err();
+        assert!(matches!(err, FormatError::MissingField("format")));
+    }
+
+    #[test]
+    fn test_read_bundle_rejects_newer_version() {
+        let json = format!(
+            r#"{{"format": "{}", "version": 99, "exported_at": "2026-01-01T00:00:00Z", "dishes": []}}"#,
+            BUNDLE_FORMAT
+        );
+        assert!(matches!(
+            read_bundle(&json),
+            Err(FormatError::UnsupportedBundle(_))
+        ));
+    }
+}
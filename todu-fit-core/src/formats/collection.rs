@@ -0,0 +1,214 @@
+//! Recipe collections and duplicate detection
+
+use std::collections::HashSet;
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::models::Dish;
+
+/// Dishes read from a collection file, plus the entries that couldn't be read.
+#[derive(Debug, Default)]
+pub struct Collection {
+    pub dishes: Vec<Dish>,
+    /// (entry name, error message) for each entry that failed to convert
+    pub failed: Vec<(String, String)>,
+}
+
+/// Why an incoming dish was considered a duplicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateReason {
+    Id,
+    Name,
+    SourceUrl,
+}
+
+impl fmt::Display for DuplicateReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateReason::Id => write!(f, "same id"),
+            DuplicateReason::Name => write!(f, "same name"),
+            DuplicateReason::SourceUrl => write!(f, "same source URL"),
+        }
+    }
+}
+
+/// An incoming dish that matches one already present
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    pub dish: Dish,
+    /// Name of the dish it matched
+    pub existing: String,
+    /// Id of the dish it matched
+    pub existing_id: Uuid,
+    pub reason: DuplicateReason,
+}
+
+/// Result of [`split_duplicates`]
+#[derive(Debug, Default)]
+pub struct DuplicateCheck {
+    pub unique: Vec<Dish>,
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// Sort incoming dishes into new ones and duplicates.
+///
+/// A dish is a duplicate when it shares an id, a (case-insensitive) name or a
+/// source URL with an existing dish or with an earlier dish in `incoming`.
+pub fn split_duplicates(incoming: Vec<Dish>, existing: &[Dish]) -> DuplicateCheck {
+    let mut seen: Vec<Dish> = existing.to_vec();
+    let mut check = DuplicateCheck::default();
+
+    for dish in incoming {
+        match find_match(&dish, &seen) {
+            Some((existing, reason)) => check.duplicates.push(Duplicate {
+                existing: existing.name.clone(),
+                existing_id: existing.id,
+                dish,
+                reason,
+            }),
+            None => {
+                seen.push(dish.clone());
+                check.unique.push(dish);
+            }
+        }
+    }
+
+    check
+}
+
+impl DuplicateCheck {
+    /// Point the components of the new dishes at dishes that will exist.
+    ///
+    /// A component that is a skipped duplicate is linked to the dish it
+    /// matched. One that is neither new nor in `existing` (e.g. an entry
+    /// that failed to read) is dropped. Returns the names of the dishes
+    /// that lost a component.
+    pub fn relink_components(&mut self, existing: &[Dish]) -> Vec<String> {
+        let known: HashSet<Uuid> = existing.iter().chain(&self.unique).map(|d| d.id).collect();
+        let mut changed = Vec::new();
+
+        for dish in &mut self.unique {
+            let before = dish.components.len();
+            for component in &mut dish.components {
+                if let Some(duplicate) = self
+                    .duplicates
+                    .iter()
+                    .find(|d| d.dish.id == component.dish_id)
+                {
+                    component.dish_id = duplicate.existing_id;
+                }
+            }
+            dish.components.retain(|c| known.contains(&c.dish_id));
+            if dish.components.len() != before {
+                changed.push(dish.name.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn find_match<'a>(dish: &Dish, dishes: &'a [Dish]) -> Option<(&'a Dish, DuplicateReason)> {
+    let name = dish.name.trim().to_lowercase();
+    let url = dish.source_url.as_deref().map(normalize_url);
+
+    dishes.iter().find_map(|other| {
+        if other.id == dish.id {
+            Some((other, DuplicateReason::Id))
+        } else if other.name.trim().to_lowercase() == name {
+            Some((other, DuplicateReason::Name))
+        } else if url.is_some() && other.source_url.as_deref().map(normalize_url) == url {
+            Some((other, DuplicateReason::SourceUrl))
+        } else {
+            None
+        }
+    })
+}
+
+/// Ignore scheme, "www." and trailing slashes when comparing source URLs.
+fn normalize_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(&url);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    url.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DishComponent;
+
+    fn dish(name: &str, url: Option<&str>) -> Dish {
+        let mut dish = Dish::new(name, "user1");
+        dish.source_url = url.map(str::to_string);
+        dish
+    }
+
+    #[test]
+    fn test_split_duplicates_by_name() {
+        let existing = vec![dish("Pancakes", None)];
+        let check = split_duplicates(
+            vec![dish("pancakes ", None), dish("Waffles", None)],
+            &existing,
+        );
+
+        assert_eq!(check.unique.len(), 1);
+        assert_eq!(check.unique[0].name, "Waffles");
+        assert_eq!(check.duplicates.len(), 1);
+        assert_eq!(check.duplicates[0].existing, "Pancakes");
+        assert_eq!(check.duplicates[0].reason, DuplicateReason::Name);
+    }
+
+    #[test]
+    fn test_split_duplicates_by_source_url() {
+        let existing = vec![dish("Chili", Some("https://example.com/chili/"))];
+        let check = split_duplicates(
+            vec![dish("Best Chili", Some("http://www.example.com/chili"))],
+            &existing,
+        );
+
+        assert!(check.unique.is_empty());
+        assert_eq!(check.duplicates[0].reason, DuplicateReason::SourceUrl);
+    }
+
+    #[test]
+    fn test_split_duplicates_by_id() {
+        let original = dish("Soup", None);
+        let mut renamed = original.clone();
+        renamed.name = "Tomato Soup".to_string();
+
+        let check = split_duplicates(vec![renamed], &[original]);
+        assert_eq!(check.duplicates[0].reason, DuplicateReason::Id);
+    }
+
+    #[test]
+    fn test_relink_components() {
+        let existing = vec![dish("Pizza Dough", None)];
+        let dough = dish("pizza dough", None);
+        let sauce = dish("Tomato Sauce", None);
+        let pizza = dish("Pizza", None).with_components(vec![
+            DishComponent::new(dough.id, 0.5),
+            DishComponent::new(sauce.id, 1.0),
+            DishComponent::new(Uuid::new_v4(), 1.0),
+        ]);
+
+        let mut check = split_duplicates(vec![dough, sauce.clone(), pizza], &existing);
+        assert_eq!(check.relink_components(&existing), vec!["Pizza"]);
+        let ids: Vec<Uuid> = check.unique[1]
+            .components
+            .iter()
+            .map(|c| c.dish_id)
+            .collect();
+        assert_eq!(ids, vec![existing[0].id, sauce.id]);
+    }
+
+    #[test]
+    fn test_split_duplicates_within_batch() {
+        let check = split_duplicates(vec![dish("Salad", None), dish("Salad", None)], &[]);
+        assert_eq!(check.unique.len(), 1);
+        assert_eq!(check.duplicates.len(), 1);
+    }
+}
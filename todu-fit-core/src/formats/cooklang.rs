@@ -0,0 +1,733 @@
+//! Cooklang (`.cook`) import and export
+//!
+//! Cooklang keeps a recipe as plain-text steps with inline markup:
+//! - `@ingredient{qty%unit}` becomes an [`Ingredient`]; `@salt` alone (or
+//!   `@sea salt{}`) has no quantity, and a following `(note)` becomes its note.
+//! - `#cookware{}` has no counterpart on [`Dish`], so it is flattened to its
+//!   name in the instructions.
+//! - `~timer{qty%unit}` is flattened to "qty unit" in the instructions. When
+//!   the metadata has no cook time, the timers are summed into `cook_time`.
+//!
+//! Metadata may come from YAML-style front matter (`---` block) or `>> key: value`
+//! lines. `title`, `servings`, `tags`, `prep time`, `cook time`, `source` and
+//! `image` map onto the matching [`Dish`] fields.
+//!
+//! Exporting writes front matter followed by one paragraph per instruction
+//! line, marking up the first exact mention of each ingredient. Ingredients
+//! that aren't mentioned in the instructions are listed in a leading step
+//! made of markup only, which import skips when rebuilding instructions.
+//! Nutrients have no Cooklang equivalent and are not exported.
+
+use super::{duration_to_minutes, leading_integer, parse_minutes, FormatError};
+use crate::ingredient_parser::parse_quantity_text;
+use crate::models::{Dish, Ingredient};
+
+/// Parse a Cooklang recipe into a Dish.
+///
+/// `fallback_name` (e.g. the file name) is used when the recipe has no title.
+pub fn parse_cooklang(
+    text: &str,
+    fallback_name: Option<&str>,
+    created_by: &str,
+) -> Result<Dish, FormatError> {
+    let text = strip_block_comments(text);
+    let (metadata, body) = split_metadata(&text);
+
+    let name = metadata_value(&metadata, &["title", "name"])
+        .or(fallback_name)
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or(FormatError::MissingField("title"))?;
+
+    let mut dish = Dish::new(name, created_by);
+    let mut steps: Vec<String> = Vec::new();
+    let mut timer_minutes = 0.0;
+
+    for paragraph in paragraphs(&body) {
+        if let Some(section) = section_name(&paragraph) {
+            steps.push(format!("{}:", section));
+            continue;
+        }
+
+        let step = parse_step(&paragraph)?;
+        for ingredient in step.ingredients {
+            // A bare mention of an ingredient that's already listed is a reference
+            let duplicate_reference = ingredient.quantity == 0.0
+                && ingredient.note.is_none()
+                && dish
+                    .ingredients
+                    .iter()
+                    .any(|i| i.name.eq_ignore_ascii_case(&ingredient.name));
+            if !duplicate_reference {
+                dish.ingredients.push(ingredient);
+            }
+        }
+        timer_minutes += step.timer_minutes;
+
+        if !step.markup_only {
+            steps.push(step.text);
+        }
+    }
+    dish.instructions = steps.join("\n");
+
+    if let Some(servings) = metadata_value(&metadata, &["servings", "serves", "yield"]) {
+        dish.servings = leading_integer(servings);
+    }
+    dish.prep_time =
+        metadata_value(&metadata, &["prep time", "time.prep", "prep_time"]).and_then(parse_minutes);
+    dish.cook_time =
+        metadata_value(&metadata, &["cook time", "time.cook", "cook_time"]).and_then(parse_minutes);
+    if dish.cook_time.is_none() && timer_minutes > 0.0 {
+        dish.cook_time = Some(timer_minutes.round() as i32);
+    }
+
+    if let Some(tags) = metadata_value(&metadata, &["tags"]) {
+        dish.tags = parse_list(tags);
+    }
+    dish.source_url =
+        metadata_value(&metadata, &["source", "source.url", "url"]).map(str::to_string);
+    dish.image_url = metadata_value(&metadata, &["image", "image_url"]).map(str::to_string);
+
+    Ok(dish)
+}
+
+/// Write a Dish as a Cooklang recipe.
+pub fn to_cooklang(dish: &Dish) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&format!("title: {}\n", dish.name));
+    if let Some(servings) = dish.servings {
+        out.push_str(&format!("servings: {}\n", servings));
+    }
+    if !dish.tags.is_empty() {
+        out.push_str(&format!("tags: [{}]\n", dish.tags.join(", ")));
+    }
+    if let Some(prep) = dish.prep_time {
+        out.push_str(&format!("prep time: {} minutes\n", prep));
+    }
+    if let Some(cook) = dish.cook_time {
+        out.push_str(&format!("cook time: {} minutes\n", cook));
+    }
+    if let Some(source) = &dish.source_url {
+        out.push_str(&format!("source: {}\n", source));
+    }
+    if let Some(image) = &dish.image_url {
+        out.push_str(&format!("image: {}\n", image));
+    }
+    out.push_str("---\n");
+
+    let mut steps: Vec<String> = dish
+        .instructions
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(escape_step)
+        .collect();
+
+    let mut unmentioned = Vec::new();
+    for ingredient in &dish.ingredients {
+        let annotated = steps
+            .iter_mut()
+            .any(|step| annotate_first_mention(step, ingredient));
+        if !annotated {
+            unmentioned.push(ingredient_markup(ingredient));
+        }
+    }
+
+    if !unmentioned.is_empty() {
+        steps.insert(0, unmentioned.join(", "));
+    }
+
+    for step in steps {
+        out.push('\n');
+        out.push_str(&step);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// A parsed step: plain text plus the markup found in it.
+struct Step {
+    text: String,
+    ingredients: Vec<Ingredient>,
+    timer_minutes: f64,
+    /// True when the step contains nothing but ingredient markup and punctuation
+    markup_only: bool,
+}
+
+fn parse_step(line: &str) -> Result<Step, FormatError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut text = String::new();
+    let mut ingredients = Vec::new();
+    let mut timer_minutes = 0.0;
+    let mut plain_text = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // Escaped markup characters stay literal: "\@"
+        if c == '\\'
+            && chars
+                .get(i + 1)
+                .is_some_and(|n| matches!(n, '@' | '#' | '~'))
+        {
+            text.push(c);
+            text.push(chars[i + 1]);
+            plain_text.push(chars[i + 1]);
+            i += 2;
+            continue;
+        }
+
+        let starts_markup = matches!(c, '@' | '#' | '~')
+            && chars
+                .get(i + 1)
+                .is_some_and(|n| !n.is_whitespace() && *n != c);
+
+        if !starts_markup {
+            text.push(c);
+            plain_text.push(c);
+            i += 1;
+            continue;
+        }
+
+        let (name, amount, next) = read_component(&chars, i + 1, c == '~')?;
+        i = next;
+
+        match c {
+            '@' => {
+                let (quantity, unit) = parse_amount(amount.as_deref().unwrap_or(""));
+                let mut ingredient = Ingredient::new(name.clone(), quantity, unit);
+
+                // Optional "(note)" right after the component
+                if chars.get(i) == Some(&'(') {
+                    if let Some(close) = chars[i..].iter().position(|c| *c == ')') {
+                        let note: String = chars[i + 1..i + close].iter().collect();
+                        if !note.trim().is_empty() {
+                            ingredient = ingredient.with_note(note.trim());
+                        }
+                        i += close + 1;
+                    }
+                }
+
+                text.push_str(&name);
+                ingredients.push(ingredient);
+            }
+            '#' => {
+                text.push_str(&name);
+                plain_text.push_str(&name);
+            }
+            _ => {
+                let (quantity, unit) = parse_amount(amount.as_deref().unwrap_or(""));
+                let display = if quantity == 0.0 {
+                    name.clone()
+                } else if unit.is_empty() {
+                    format_number(quantity)
+                } else {
+                    format!("{} {}", format_number(quantity), unit)
+                };
+                timer_minutes += duration_to_minutes(quantity, &unit);
+                text.push_str(&display);
+                plain_text.push_str(&display);
+            }
+        }
+    }
+
+    let markup_only = !ingredients.is_empty()
+        && plain_text
+            .chars()
+            .all(|c| c.is_whitespace() || c.is_ascii_punctuation());
+
+    Ok(Step {
+        text: unescape(text.trim()),
+        ingredients,
+        timer_minutes,
+        markup_only,
+    })
+}
+
+/// Read a component name and optional `{amount}` starting at `start`.
+///
+/// Returns (name, amount, index after the component). Multi-word names must
+/// be closed with braces; otherwise the name ends at the first non-word char.
+fn read_component(
+    chars: &[char],
+    start: usize,
+    allow_empty_name: bool,
+) -> Result<(String, Option<String>, usize), FormatError> {
+    let rest: String = chars[start..].iter().collect();
+
+    // Multi-word form: "@sea salt{}" - only if no other markup or sentence break
+    // appears before the brace
+    if let Some(brace) = rest.find('{') {
+        let candidate = &rest[..brace];
+        let is_name = !candidate.contains(['@', '#', '~', '.', ',', ';', ':', '!', '?', '}'])
+            && (allow_empty_name || !candidate.trim().is_empty());
+        if is_name {
+            let close = rest[brace..].find('}').ok_or_else(|| {
+                FormatError::InvalidCooklang(format!("unclosed '{{' in \"{}\"", rest))
+            })?;
+            let amount = rest[brace + 1..brace + close].to_string();
+            let consumed = rest[..brace + close + 1].chars().count();
+            return Ok((candidate.trim().to_string(), Some(amount), start + consumed));
+        }
+    }
+
+    // Single-word form: "@salt"
+    let name: String = chars[start..]
+        .iter()
+        .take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '-')
+        .collect();
+    let consumed = name.chars().count();
+    Ok((name, None, start + consumed))
+}
+
+/// Parse "qty%unit" into a quantity and unit. Non-numeric quantities become 0.
+fn parse_amount(amount: &str) -> (f64, String) {
+    let (qty, unit) = match amount.split_once('%') {
+        Some((qty, unit)) => (qty.trim(), unit.trim()),
+        None => (amount.trim(), ""),
+    };
+
+    if qty.is_empty() {
+        return (0.0, unit.to_string());
+    }
+
+    (parse_quantity_text(qty).unwrap_or(0.0), unit.to_string())
+}
+
+/// Split off front matter and `>>` metadata lines from the body.
+fn split_metadata(text: &str) -> (Vec<(String, String)>, String) {
+    let mut metadata = Vec::new();
+    let mut body = String::new();
+    let mut lines = text.lines().peekable();
+
+    if lines.peek().map(|l| l.trim()) == Some("---") {
+        lines.next();
+        let mut last_key: Option<String> = None;
+        for line in lines.by_ref() {
+            if line.trim() == "---" {
+                break;
+            }
+            let trimmed = line.trim();
+            // YAML block list item continuing the previous key
+            if let (Some(item), Some(key)) = (trimmed.strip_prefix("- "), &last_key) {
+                if let Some((_, value)) = metadata.iter_mut().find(|(k, _)| k == key) {
+                    let value: &mut String = value;
+                    if !value.is_empty() {
+                        value.push_str(", ");
+                    }
+                    value.push_str(item.trim());
+                }
+                continue;
+            }
+            if let Some((key, value)) = trimmed.split_once(':') {
+                let key = key.trim().to_lowercase();
+                metadata.push((key.clone(), unquote(value.trim()).to_string()));
+                last_key = Some(key);
+            }
+        }
+    }
+
+    for line in lines {
+        if let Some(meta) = line.trim().strip_prefix(">>") {
+            if let Some((key, value)) = meta.split_once(':') {
+                metadata.push((key.trim().to_lowercase(), unquote(value.trim()).to_string()));
+            }
+            continue;
+        }
+        body.push_str(strip_line_comment(line));
+        body.push('\n');
+    }
+
+    (metadata, body)
+}
+
+fn metadata_value<'a>(metadata: &'a [(String, String)], keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| {
+        metadata
+            .iter()
+            .find(|(k, v)| k == key && !v.is_empty())
+            .map(|(_, v)| v.as_str())
+    })
+}
+
+/// Non-empty paragraphs, with their lines joined by spaces.
+fn paragraphs(body: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for line in body.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !current.is_empty() {
+                result.push(current.join(" "));
+                current.clear();
+            }
+        } else if section_name(line).is_some() {
+            if !current.is_empty() {
+                result.push(current.join(" "));
+                current.clear();
+            }
+            result.push(line.to_string());
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        result.push(current.join(" "));
+    }
+
+    result
+}
+
+/// "= Dough" or "== Dough ==" section headers.
+fn section_name(line: &str) -> Option<&str> {
+    let name = line.strip_prefix('=')?.trim_matches('=').trim();
+    (!name.is_empty()).then_some(name)
+}
+
+fn strip_block_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[-") {
+        out.push_str(&rest[..start]);
+        match rest[start..].find("-]") {
+            Some(end) => rest = &rest[start + end + 2..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn strip_line_comment(line: &str) -> &str {
+    match line.find("--") {
+        Some(pos) => &line[..pos],
+        None => line,
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches('"').trim_matches('\'')
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|t| unquote(t.trim()).to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn format_number(value: f64) -> String {
+    let formatted = format!("{:.3}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn ingredient_markup(ingredient: &Ingredient) -> String {
+    let amount = match (ingredient.quantity == 0.0, ingredient.unit.is_empty()) {
+        (true, true) => String::new(),
+        (true, false) => format!("%{}", ingredient.unit),
+        (false, true) => format_number(ingredient.quantity),
+        (false, false) => format!("{}%{}", format_number(ingredient.quantity), ingredient.unit),
+    };
+
+    let mut markup = format!("@{}{{{}}}", ingredient.name, amount);
+    if let Some(note) = &ingredient.note {
+        markup.push_str(&format!("({})", note));
+    }
+    markup
+}
+
+/// Replace the first exact, whole-word mention of the ingredient with markup.
+fn annotate_first_mention(step: &mut String, ingredient: &Ingredient) -> bool {
+    let name = ingredient.name.as_str();
+    if name.is_empty() {
+        return false;
+    }
+
+    let mut search_from = 0;
+    while let Some(pos) = step[search_from..].find(name) {
+        let start = search_from + pos;
+        let end = start + name.len();
+        let before = step[..start].chars().next_back();
+        let after = step[end..].chars().next();
+
+        let boundary_before = before.is_none_or(|c| !c.is_alphanumeric() && c != '@');
+        let boundary_after = after.is_none_or(|c| !c.is_alphanumeric() && c != '{');
+        // Don't annotate inside existing markup
+        let inside_markup = step[..start]
+            .rfind('{')
+            .is_some_and(|open| !step[open..start].contains('}'));
+
+        if boundary_before && boundary_after && !inside_markup {
+            step.replace_range(start..end, &ingredient_markup(ingredient));
+            return true;
+        }
+        search_from = end;
+    }
+
+    false
+}
+
+/// Plain-text characters that would read as markup are escaped on export.
+///
+/// Besides `@ # ~`, hyphens that would start a `--` or `[-` comment are
+/// escaped, as are a leading `=` (section) and `>>` (metadata).
+fn escape_step(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let comment_dash = c == '-'
+            && (i > 0 && matches!(chars[i - 1], '-' | '[') || chars.get(i + 1) == Some(&'-'));
+        let line_markup = i == 0 && (c == '=' || line.starts_with(">>"));
+        if matches!(c, '@' | '#' | '~') || comment_dash || line_markup {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Undo [`escape_step`]. Only `\@ \# \~` and the hyphens, `=` and `>>`
+/// that would otherwise start markup are escapes; any other backslash is
+/// kept as written.
+fn unescape(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let Some(&next) = chars.get(i + 1).filter(|_| c == '\\') else {
+            out.push(c);
+            i += 1;
+            continue;
+        };
+        let line_start = out.is_empty() || out.ends_with('\n');
+        let after = &chars[i + 2..];
+        let escaped = match next {
+            '@' | '#' | '~' => true,
+            '-' => {
+                out.ends_with(['-', '['])
+                    || after.first() == Some(&'-')
+                    || after.starts_with(&['\\', '-'])
+            }
+            '=' => line_start,
+            '>' => line_start && after.first() == Some(&'>'),
+            _ => false,
+        };
+        if !escaped {
+            out.push(c);
+        }
+        out.push(next);
+        i += 2;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = r#"---
+title: Garlic Pasta
+servings: 2
+tags: [pasta, quick]
+prep time: 5 minutes
+source: https://example.com/garlic-pasta
+---
+
+-- Classic weeknight dinner
+Boil @spaghetti{200%g} in a #large pot{} of salted water for ~{10%minutes}.
+
+Meanwhile warm @olive oil{3%tbsp} in a #pan and add @garlic{4%cloves}(thinly sliced).
+Cook for ~{2%minutes}.
+
+= Finish
+
+Toss with the spaghetti, @salt and @black pepper{}. [- to taste -]
+"#;
+
+    #[test]
+    fn test_parse_metadata() {
+        let dish = parse_cooklang(RECIPE, None, "chef").unwrap();
+
+        assert_eq!(dish.name, "Garlic Pasta");
+        assert_eq!(dish.created_by, "chef");
+        assert_eq!(dish.servings, Some(2));
+        assert_eq!(dish.tags, vec!["pasta", "quick"]);
+        assert_eq!(dish.prep_time, Some(5));
+        assert_eq!(
+            dish.source_url.as_deref(),
+            Some("https://example.com/garlic-pasta")
+        );
+    }
+
+    #[test]
+    fn test_parse_ingredients() {
+        let dish = parse_cooklang(RECIPE, None, "chef").unwrap();
+
+        assert_eq!(dish.ingredients.len(), 5);
+        assert_eq!(
+            dish.ingredients[0],
+            Ingredient::new("spaghetti", 200.0, "g")
+        );
+        assert_eq!(
+            dish.ingredients[1],
+            Ingredient::new("olive oil", 3.0, "tbsp")
+        );
+        assert_eq!(dish.ingredients[2].name, "garlic");
+        assert_eq!(dish.ingredients[2].unit, "cloves");
+        assert_eq!(dish.ingredients[2].note.as_deref(), Some("thinly sliced"));
+        assert_eq!(dish.ingredients[3], Ingredient::new("salt", 0.0, ""));
+        assert_eq!(
+            dish.ingredients[4],
+            Ingredient::new("black pepper", 0.0, "")
+        );
+    }
+
+    #[test]
+    fn test_parse_instructions_flatten_cookware_and_timers() {
+        let dish = parse_cooklang(RECIPE, None, "chef").unwrap();
+
+        let lines: Vec<&str> = dish.instructions.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Boil spaghetti in a large pot of salted water for 10 minutes."
+        );
+        assert_eq!(
+            lines[1],
+            "Meanwhile warm olive oil in a pan and add garlic. Cook for 2 minutes."
+        );
+        assert_eq!(lines[2], "Finish:");
+        assert_eq!(lines[3], "Toss with the spaghetti, salt and black pepper.");
+    }
+
+    #[test]
+    fn test_parse_timers_sum_into_cook_time() {
+        let dish = parse_cooklang(RECIPE, None, "chef").unwrap();
+        assert_eq!(dish.cook_time, Some(12));
+    }
+
+    #[test]
+    fn test_parse_legacy_metadata_and_fallback_name() {
+        let text =
+            ">> servings: 4\n>> cook time: 1 hour 15 min\n\nMix @flour{1 1/2%cups} and @eggs{2}.\n";
+        let dish = parse_cooklang(text, Some("pancakes"), "chef").unwrap();
+
+        assert_eq!(dish.name, "pancakes");
+        assert_eq!(dish.servings, Some(4));
+        assert_eq!(dish.cook_time, Some(75));
+        assert_eq!(dish.ingredients[0], Ingredient::new("flour", 1.5, "cups"));
+        assert_eq!(dish.ingredients[1], Ingredient::new("eggs", 2.0, ""));
+        assert_eq!(dish.instructions, "Mix flour and eggs.");
+    }
+
+    #[test]
+    fn test_parse_missing_title() {
+        let result = parse_cooklang("Mix @flour{1%cup}.", None, "chef");
+        assert!(matches!(result, Err(FormatError::MissingField("title"))));
+    }
+
+    #[test]
+    fn test_parse_unclosed_brace() {
+        let result = parse_cooklang("Mix @flour{1%cup", Some("x"), "chef");
+        assert!(matches!(result, Err(FormatError::InvalidCooklang(_))));
+    }
+
+    #[test]
+    fn test_export_annotates_first_mention() {
+        let dish = Dish::new("Toast", "chef")
+            .with_servings(1)
+            .with_ingredients(vec![
+                Ingredient::new("bread", 2.0, "slices"),
+                Ingredient::new("butter", 1.0, "tbsp").with_note("softened"),
+            ])
+            .with_instructions("Toast the bread.\nSpread butter on the bread.");
+
+        let text = to_cooklang(&dish);
+        assert!(text.starts_with("---\ntitle: Toast\nservings: 1\n---\n"));
+        assert!(text.contains("\nToast the @bread{2%slices}.\n"));
+        assert!(text.contains("\nSpread @butter{1%tbsp}(softened) on the bread.\n"));
+    }
+
+    #[test]
+    fn test_export_lists_unmentioned_ingredients() {
+        let dish = Dish::new("Salad", "chef")
+            .with_ingredients(vec![
+                Ingredient::new("lettuce", 1.0, "head"),
+                Ingredient::new("sea salt", 0.0, ""),
+            ])
+            .with_instructions("Toss everything together.");
+
+        let text = to_cooklang(&dish);
+        assert!(text.contains("\n@lettuce{1%head}, @sea salt{}\n"));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dish = Dish::new("Garlic Pasta", "chef")
+            .with_servings(2)
+            .with_prep_time(5)
+            .with_cook_time(12)
+            .with_tags(vec!["pasta".to_string(), "quick".to_string()])
+            .with_ingredients(vec![
+                Ingredient::new("spaghetti", 200.0, "g"),
+                Ingredient::new("olive oil", 3.0, "tbsp"),
+                Ingredient::new("garlic", 4.0, "cloves").with_note("sliced"),
+                Ingredient::new("parsley", 0.25, "cup"),
+            ])
+            .with_instructions(
+                "Boil the spaghetti for 10 minutes.\nWarm the olive oil, add garlic.\nServe with #1 sauce & love @home.",
+            );
+        let mut dish = dish;
+        dish.source_url = Some("https://example.com/p".to_string());
+        dish.image_url = Some("https://example.com/p.jpg".to_string());
+
+        let parsed = parse_cooklang(&to_cooklang(&dish), None, "chef").unwrap();
+
+        assert_eq!(parsed.name, dish.name);
+        assert_eq!(parsed.servings, dish.servings);
+        assert_eq!(parsed.prep_time, dish.prep_time);
+        assert_eq!(parsed.cook_time, dish.cook_time);
+        assert_eq!(parsed.tags, dish.tags);
+        assert_eq!(parsed.source_url, dish.source_url);
+        assert_eq!(parsed.image_url, dish.image_url);
+        assert_eq!(parsed.instructions, dish.instructions);
+
+        // Unmentioned ingredients are listed first, the rest follow the steps
+        let mut expected = dish.ingredients.clone();
+        expected.rotate_right(1);
+        assert_eq!(parsed.ingredients, expected);
+    }
+
+    #[test]
+    fn test_roundtrip_comment_and_section_markers() {
+        let instructions = "Stir gently -- don't boil.\n\
+                            Season [- lightly -] to taste, a---b.\n\
+                            = not a section\n\
+                            >> serves: not metadata";
+        let dish = Dish::new("Careful Soup", "chef").with_instructions(instructions);
+
+        let text = to_cooklang(&dish);
+        assert!(text.contains("Stir gently \\-\\- don't boil."));
+
+        let parsed = parse_cooklang(&text, None, "chef").unwrap();
+        assert_eq!(parsed.instructions, instructions);
+        assert_eq!(parsed.servings, None);
+
+        // Backslashes that don't escape markup are kept
+        let text = "---\ntitle: Paths\n---\n\nSave to C:\\-temp\\notes, 1 \\= 1.\n";
+        let parsed = parse_cooklang(text, None, "chef").unwrap();
+        assert_eq!(parsed.instructions, "Save to C:\\-temp\\notes, 1 \\= 1.");
+    }
+}
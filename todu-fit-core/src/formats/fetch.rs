@@ -0,0 +1,110 @@
+//! Fetching remote recipe sources
+
+use super::FormatError;
+
+/// Fetches the text body of a URL.
+pub trait Fetcher {
+    fn fetch(&self, url: &str) -> Result<String, FormatError>;
+}
+
+/// Blocking HTTP fetcher backed by reqwest.
+///
+/// Runs each request on its own single-threaded runtime, so it must not be
+/// called from inside an async context.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HttpFetcher;
+
+impl HttpFetcher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, url: &str) -> Result<String, FormatError> {
+        let fetch_error = |message: String| FormatError::Fetch {
+            url: url.to_string(),
+            message,
+        };
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| fetch_error(e.to_string()))?;
+
+        rt.block_on(async {
+            let response = reqwest::get(url)
+                .await
+                .map_err(|e| fetch_error(e.to_string()))?
+                .error_for_status()
+                .map_err(|e| fetch_error(e.to_string()))?;
+
+            response
+                .text()
+                .await
+                .map_err(|e| fetch_error(e.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve a single HTTP response on a local port and return its URL.
+    fn serve_once(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        format!("http://{}/recipe", addr)
+    }
+
+    #[test]
+    fn test_http_fetcher_reads_body() {
+        let url = serve_once("200 OK", "<html>hello</html>");
+        let body = HttpFetcher::new().fetch(&url).unwrap();
+        assert_eq!(body, "<html>hello</html>");
+    }
+
+    #[test]
+    fn test_import_recipe_from_fixture_server() {
+        let url = serve_once(
+            "200 OK",
+            r#"<html><script type="application/ld+json">
+            {"@type": "Recipe", "name": "Served Soup", "recipeYield": "6 bowls",
+             "recipeIngredient": ["2 cups stock"]}
+            </script></html>"#,
+        );
+
+        let dish =
+            crate::formats::jsonld::import_recipe_from_url(&url, &HttpFetcher::new(), "chef")
+                .unwrap();
+        assert_eq!(dish.name, "Served Soup");
+        assert_eq!(dish.servings, Some(6));
+        assert_eq!(dish.ingredients[0].unit, "cups");
+        assert_eq!(dish.source_url.as_deref(), Some(url.as_str()));
+    }
+
+    #[test]
+    fn test_http_fetcher_error_status() {
+        let url = serve_once("404 Not Found", "missing");
+        let result = HttpFetcher::new().fetch(&url);
+        assert!(matches!(result, Err(FormatError::Fetch { .. })));
+    }
+}
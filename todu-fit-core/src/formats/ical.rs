@@ -0,0 +1,556 @@
+//! iCalendar (RFC 5545) meal plan export and import
+//!
+//! [`write_calendar`] turns meal plans into a `.ics` file with one `VEVENT`
+//! per plan, at the time of its meal slot. Each event's UID is
+//! `<plan id>@todu-fit`, so calendar apps update the events of a re-exported
+//! file instead of adding them again. Slots without a default time become
+//! all-day events.
+//!
+//! [`read_calendar`] reads the events of a `.ics` file back. Events exported
+//! here map onto the plan they came from; other events get an ID derived
+//! from their UID, so importing the same calendar twice updates the plans it
+//! created the first time.
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use uuid::{Builder, Uuid};
+
+use super::FormatError;
+use crate::models::{Dish, MealPlan, MealSlot, MealType};
+use crate::text::capitalize;
+
+/// Domain part of the UIDs of exported events.
+const UID_DOMAIN: &str = "todu-fit";
+
+/// Longest line, in octets, before it is folded.
+const MAX_LINE: usize = 75;
+
+/// An iCalendar file of meal plans, named `name` in calendar apps.
+///
+/// Events are timed by `slots`' default times; `dishes` provides the dish
+/// names listed in each event's description.
+pub fn write_calendar(
+    name: &str,
+    plans: &[MealPlan],
+    dishes: &[Dish],
+    slots: &[MealSlot],
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//todu-fit//Meal Plans//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+
+    for plan in plans {
+        let meal = capitalize(plan.meal_type.as_str());
+        // The plan's last change stamps the event, so unchanged plans
+        // export identically
+        let stamp = plan.updated_at.format("%Y%m%dT%H%M%SZ");
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@{}", plan.id, UID_DOMAIN));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("LAST-MODIFIED:{}", stamp));
+        match slot_time(slots, &plan.meal_type) {
+            Some(time) => {
+                let start = plan.date.and_time(time);
+                let end = start + Duration::minutes(meal_minutes(&plan.meal_type));
+                lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+                lines.push(format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")));
+            }
+            None => {
+                let end = plan.date + Duration::days(1);
+                lines.push(format!("DTSTART;VALUE=DATE:{}", plan.date.format("%Y%m%d")));
+                lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+            }
+        }
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape_text(&format!("{}: {}", meal, plan.title))
+        ));
+
+        let description = describe(plan, dishes);
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        }
+        lines.push(format!("CATEGORIES:{},Meal Plan", escape_text(&meal)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// An event read from an iCalendar file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// The event's UID, if it has one
+    pub uid: Option<String>,
+    /// Day the event starts
+    pub date: NaiveDate,
+    /// Local start time; `None` for all-day events
+    pub time: Option<NaiveTime>,
+    pub summary: String,
+    pub description: String,
+    pub categories: Vec<String>,
+}
+
+impl CalendarEvent {
+    /// ID of the meal plan this event stands for.
+    ///
+    /// Events exported by [`write_calendar`] give back their plan's ID. Other
+    /// events get one derived from their UID (or, without a UID, their date
+    /// and summary), which stays the same across imports.
+    pub fn plan_id(&self) -> Uuid {
+        if let Some(id) = self
+            .uid
+            .as_deref()
+            .and_then(|uid| uid.strip_suffix(&format!("@{}", UID_DOMAIN)))
+            .and_then(|id| Uuid::parse_str(id).ok())
+        {
+            return id;
+        }
+
+        let mut hasher = Sha256::new();
+        match &self.uid {
+            Some(uid) => hasher.update(uid.as_bytes()),
+            None => {
+                hasher.update(self.date.to_string().as_bytes());
+                hasher.update(self.summary.as_bytes());
+            }
+        }
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        Builder::from_custom_bytes(bytes).into_uuid()
+    }
+
+    /// The meal slot of the event.
+    ///
+    /// Taken from its categories or a "Meal: " prefix on the summary, or else
+    /// the slot whose default time is closest to the event's start.
+    pub fn meal_type(&self, slots: &[MealSlot]) -> Option<MealType> {
+        let named = self
+            .categories
+            .iter()
+            .map(String::as_str)
+            .chain(self.summary.split_once(": ").map(|(meal, _)| meal))
+            .find_map(|name| MealSlot::resolve(slots, &name.to_lowercase()).ok());
+        if named.is_some() {
+            return named;
+        }
+
+        let time = self.time?;
+        slots
+            .iter()
+            .filter_map(|slot| {
+                let default = slot.default_time?;
+                Some(((default - time).num_minutes().abs(), &slot.meal_type))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, meal_type)| meal_type.clone())
+    }
+
+    /// Plan title: the summary without a "Meal: " prefix naming `meal_type`.
+    pub fn title(&self, meal_type: &MealType) -> String {
+        match self.summary.split_once(": ") {
+            Some((meal, title))
+                if meal.eq_ignore_ascii_case(meal_type.as_str()) && !title.is_empty() =>
+            {
+                title.to_string()
+            }
+            _ => self.summary.clone(),
+        }
+    }
+
+    /// The cook, from a "Cook: " line in the description.
+    pub fn cook(&self) -> Option<&str> {
+        self.field("Cook")
+    }
+
+    /// Dish names, from a "Dishes: " line in the description.
+    pub fn dish_names(&self) -> Vec<&str> {
+        self.field("Dishes")
+            .map(|names| {
+                names
+                    .split(", ")
+                    .map(str::trim)
+                    .filter(|n| !n.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Servings, from a "Servings: " line in the description.
+    pub fn servings(&self) -> Option<i32> {
+        self.field("Servings")
+            .and_then(|s| s.parse().ok())
+            .filter(|s| *s > 0)
+    }
+
+    fn field(&self, name: &str) -> Option<&str> {
+        self.description.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then_some(value.trim())
+                .filter(|v| !v.is_empty())
+        })
+    }
+}
+
+/// Read the events of an iCalendar file.
+///
+/// Only `VEVENT`s are read; times in UTC are converted to local time, and
+/// other time zones are taken as local. Recurring events give their first
+/// occurrence only.
+pub fn read_calendar(input: &str) -> Result<Vec<CalendarEvent>, FormatError> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String)>> = None;
+
+    for line in unfold(input) {
+        let Some((name, value)) = split_property(&line) else {
+            continue;
+        };
+        let key = name
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match (key.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => current = Some(Vec::new()),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(properties) = current.take() {
+                    events.push(parse_event(&properties)?);
+                }
+            }
+            // Components nested in an event, such as alarms
+            ("BEGIN" | "END", Some(_)) => {}
+            (_, Some(properties)) => properties.push((name.to_string(), value.to_string())),
+            _ => {}
+        }
+    }
+    if current.is_some() {
+        return Err(FormatError::InvalidCalendar(
+            "an event is missing END:VEVENT".to_string(),
+        ));
+    }
+    Ok(events)
+}
+
+fn parse_event(properties: &[(String, String)]) -> Result<CalendarEvent, FormatError> {
+    let get = |key: &str| {
+        properties.iter().find(|(name, _)| {
+            name.split(';')
+                .next()
+                .is_some_and(|n| n.eq_ignore_ascii_case(key))
+        })
+    };
+
+    let summary = get("SUMMARY")
+        .map(|(_, v)| unescape_text(v))
+        .unwrap_or_default();
+    let (start_name, start) = get("DTSTART").ok_or_else(|| {
+        FormatError::InvalidCalendar(format!("event '{}' has no DTSTART", summary))
+    })?;
+    let (date, time) = parse_start(start_name, start).ok_or_else(|| {
+        FormatError::InvalidCalendar(format!("event '{}' has an invalid DTSTART", summary))
+    })?;
+
+    Ok(CalendarEvent {
+        uid: get("UID").map(|(_, v)| v.trim().to_string()),
+        date,
+        time,
+        summary,
+        description: get("DESCRIPTION")
+            .map(|(_, v)| unescape_text(v))
+            .unwrap_or_default(),
+        categories: properties
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("CATEGORIES"))
+            .flat_map(|(_, v)| split_list(v))
+            .collect(),
+    })
+}
+
+/// Date and local time of a DTSTART property.
+fn parse_start(name: &str, value: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let value = value.trim();
+    let all_day = name.split(';').skip(1).any(|param| {
+        param.split_once('=').is_some_and(|(key, kind)| {
+            key.trim().eq_ignore_ascii_case("VALUE")
+                && kind.trim().trim_matches('"').eq_ignore_ascii_case("DATE")
+        })
+    });
+    if all_day || value.len() == 8 {
+        return Some((NaiveDate::parse_from_str(value, "%Y%m%d").ok()?, None));
+    }
+
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let mut start = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    if utc {
+        start = Utc
+            .from_utc_datetime(&start)
+            .with_timezone(&Local)
+            .naive_local();
+    }
+    Some((start.date(), Some(start.time())))
+}
+
+/// Lines with folded continuations joined back up.
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in input.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split "NAME;PARAM=x:value" at the first colon outside quoted parameters.
+fn split_property(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&line[..i], &line[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Items of a comma-separated text list, unescaped.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                item.push('\\');
+                item.extend(chars.next());
+            }
+            ',' => items.push(std::mem::take(&mut item)),
+            _ => item.push(c),
+        }
+    }
+    items.push(item);
+    items
+        .iter()
+        .map(|item| unescape_text(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Fold a line into chunks of at most 75 octets, without splitting a
+/// character; continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+/// Cook, dish names and servings, one per line.
+fn describe(plan: &MealPlan, dishes: &[Dish]) -> String {
+    let mut parts = Vec::new();
+    if !plan.cook.is_empty() && plan.cook != "Unknown" {
+        parts.push(format!("Cook: {}", plan.cook));
+    }
+    let names: Vec<&str> = plan
+        .dish_ids
+        .iter()
+        .filter_map(|id| dishes.iter().find(|d| d.id == *id))
+        .map(|d| d.name.as_str())
+        .collect();
+    if !names.is_empty() {
+        parts.push(format!("Dishes: {}", names.join(", ")));
+    }
+    if let Some(servings) = plan.servings {
+        parts.push(format!("Servings: {}", servings));
+    }
+    parts.join("\n")
+}
+
+fn slot_time(slots: &[MealSlot], meal_type: &MealType) -> Option<NaiveTime> {
+    slots
+        .iter()
+        .find(|s| &s.meal_type == meal_type)
+        .and_then(|s| s.default_time)
+}
+
+/// How long a meal's event lasts.
+fn meal_minutes(meal_type: &MealType) -> i64 {
+    match meal_type {
+        MealType::Breakfast => 30,
+        MealType::Lunch => 45,
+        MealType::Dinner => 60,
+        MealType::Snack => 15,
+        _ => 30,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_write_calendar() {
+        let chili = Dish::new("Chili, mild", "alice");
+        let plan = MealPlan::new(
+            date("2026-01-11"),
+            MealType::Dinner,
+            "Sunday dinner",
+            "alice",
+        )
+        .with_cook("Bob")
+        .with_dish_ids(vec![chili.id])
+        .with_servings(6);
+        let brunch = MealPlan::new(
+            date("2026-01-12"),
+            "brunch".parse().unwrap(),
+            "A very long brunch title that needs folding onto a second line",
+            "alice",
+        );
+        let mut slots = MealSlot::builtin();
+        slots.push(MealSlot::new("brunch".parse().unwrap(), 4));
+
+        let plans = vec![plan.clone(), brunch];
+        let ics = write_calendar("Family meals", &plans, std::slice::from_ref(&chili), &slots);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains(&format!("UID:{}@todu-fit\r\n", plan.id)));
+        assert!(ics.contains("DTSTART:20260111T180000\r\nDTEND:20260111T190000\r\n"));
+        assert!(ics.contains("SUMMARY:Dinner: Sunday dinner\r\n"));
+        assert!(ics.contains("DESCRIPTION:Cook: Bob\\nDishes: Chili\\, mild\\nServings: 6\r\n"));
+        // No default time for brunch, so it's an all-day event
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260112\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(ics.contains("a second l\r\n ine\r\n"));
+
+        // Unchanged plans export the same events
+        assert_eq!(
+            ics,
+            write_calendar("Family meals", &plans, &[chili], &slots)
+        );
+    }
+
+    #[test]
+    fn test_read_calendar_roundtrip() {
+        let plan = MealPlan::new(date("2026-01-11"), MealType::Dinner, "Tacos", "alice")
+            .with_cook("Bob")
+            .with_servings(4);
+        let slots = MealSlot::builtin();
+        let ics = write_calendar("Meals", std::slice::from_ref(&plan), &[], &slots);
+
+        let events = read_calendar(&ics).unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.plan_id(), plan.id);
+        assert_eq!(event.date, plan.date);
+        assert_eq!(event.meal_type(&slots), Some(MealType::Dinner));
+        assert_eq!(event.title(&MealType::Dinner), "Tacos");
+        assert_eq!(event.cook(), Some("Bob"));
+        assert_eq!(event.servings(), Some(4));
+    }
+
+    #[test]
+    fn test_read_foreign_calendar() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VTIMEZONE\r\n\
+                   TZID:Europe/Berlin\r\n\
+                   BEGIN:STANDARD\r\n\
+                   DTSTART:19701025T030000\r\n\
+                   END:STANDARD\r\n\
+                   END:VTIMEZONE\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:abc123@example.com\r\n\
+                   DTSTART;TZID=Europe/Berlin:20260113T123000\r\n\
+                   SUMMARY:Soup with\r\n  friends\r\n\
+                   DESCRIPTION:Dishes: Minestrone\\, Bread\r\n\
+                   BEGIN:VALARM\r\n\
+                   TRIGGER:-PT15M\r\n\
+                   END:VALARM\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+        let events = read_calendar(ics).unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.summary, "Soup with friends");
+        assert_eq!(event.time, NaiveTime::from_hms_opt(12, 30, 0));
+        // Closest slot to 12:30 is lunch
+        assert_eq!(event.meal_type(&MealSlot::builtin()), Some(MealType::Lunch));
+        assert_eq!(event.dish_names(), vec!["Minestrone", "Bread"]);
+        assert_eq!(event.cook(), None);
+        // The same UID always gives the same plan
+        assert_eq!(event.plan_id(), read_calendar(ics).unwrap()[0].plan_id());
+
+        // Only an exact VALUE=DATE makes an all-day event
+        let timed = "BEGIN:VEVENT\r\n\
+                     UID:def456@example.com\r\n\
+                     DTSTART;TZID=\"America/New_York\";VALUE=DATE-TIME:20260115T180000\r\n\
+                     SUMMARY:Tacos\r\n\
+                     END:VEVENT\r\n";
+        let event = &read_calendar(timed).unwrap()[0];
+        assert_eq!(event.date, NaiveDate::from_ymd_opt(2026, 1, 15).unwrap());
+        assert_eq!(event.time, NaiveTime::from_hms_opt(18, 0, 0));
+        let all_day = timed.replace("VALUE=DATE-TIME:20260115T180000", "value=date:20260115");
+        assert_eq!(read_calendar(&all_day).unwrap()[0].time, None);
+
+        let broken = "BEGIN:VEVENT\r\nSUMMARY:Lunch\r\nEND:VEVENT\r\n";
+        assert!(matches!(
+            read_calendar(broken),
+            Err(FormatError::InvalidCalendar(_))
+        ));
+    }
+}
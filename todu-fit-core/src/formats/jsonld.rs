@@ -0,0 +1,594 @@
+//! schema.org `Recipe` JSON-LD import
+//!
+//! Most recipe sites embed a `<script type="application/ld+json">` block that
+//! describes the recipe. This module extracts it and maps it onto a [`Dish`]:
+//!
+//! | schema.org              | Dish                              |
+//! |-------------------------|-----------------------------------|
+//! | `name`                  | `name`                            |
+//! | `recipeIngredient`      | `ingredients` (parsed per line)   |
+//! | `recipeInstructions`    | `instructions` (one step per line)|
+//! | `prepTime`, `cookTime`  | `prep_time`, `cook_time` (minutes)|
+//! | `recipeYield`           | `servings`                        |
+//! | `recipeCategory/Cuisine`| `tags`                            |
+//! | `image`, `url`          | `image_url`, `source_url`         |
+//! | `nutrition`             | `nutrients` (per serving)         |
+
+use serde_json::Value;
+
+use super::{Fetcher, FormatError};
+use crate::ingredient_parser::parse_ingredient_line;
+use crate::models::{Dish, Ingredient, Nutrient};
+
+/// schema.org NutritionInformation properties mapped to nutrient names and default units.
+pub(crate) const NUTRITION_FIELDS: &[(&str, &str, &str)] = &[
+    ("calories", "calories", "kcal"),
+    ("proteinContent", "protein", "g"),
+    ("carbohydrateContent", "carbs", "g"),
+    ("fatContent", "fat", "g"),
+    ("saturatedFatContent", "saturated_fat", "g"),
+    ("fiberContent", "fiber", "g"),
+    ("sugarContent", "sugar", "g"),
+    ("sodiumContent", "sodium", "mg"),
+    ("cholesterolContent", "cholesterol", "mg"),
+];
+
+/// Import the first recipe found in an HTML page (or a raw JSON-LD document).
+pub fn import_recipe(
+    content: &str,
+    created_by: &str,
+    source_url: Option<&str>,
+) -> Result<Dish, FormatError> {
+    let recipes = extract_recipes(content)?;
+    let recipe = recipes.first().ok_or(FormatError::NoRecipe)?;
+    recipe_to_dish(recipe, created_by, source_url)
+}
+
+/// Fetch a URL and import the first recipe on the page.
+pub fn import_recipe_from_url(
+    url: &str,
+    fetcher: &dyn Fetcher,
+    created_by: &str,
+) -> Result<Dish, FormatError> {
+    let html = fetcher.fetch(url)?;
+    import_recipe(&html, created_by, Some(url))
+}
+
+/// Find all schema.org `Recipe` objects in an HTML page or JSON-LD document.
+pub fn extract_recipes(content: &str) -> Result<Vec<Value>, FormatError> {
+    let trimmed = content.trim_start();
+    let blocks: Vec<String> = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        vec![trimmed.to_string()]
+    } else {
+        find_jsonld_blocks(content)
+    };
+
+    let mut recipes = Vec::new();
+    let mut last_error = None;
+    for block in blocks {
+        match serde_json::from_str::<Value>(&block) {
+            Ok(value) => collect_recipes(&value, &mut recipes),
+            Err(e) => last_error = Some(e.to_string()),
+        }
+    }
+
+    if recipes.is_empty() {
+        if let Some(e) = last_error {
+            return Err(FormatError::InvalidJson(e));
+        }
+    }
+
+    Ok(recipes)
+}
+
+/// Build a Dish from a schema.org Recipe object.
+pub fn recipe_to_dish(
+    recipe: &Value,
+    created_by: &str,
+    source_url: Option<&str>,
+) -> Result<Dish, FormatError> {
+    let name = recipe
+        .get("name")
+        .and_then(Value::as_str)
+        .map(clean_text)
+        .filter(|n| !n.is_empty())
+        .ok_or(FormatError::MissingField("name"))?;
+
+    let mut dish = Dish::new(name, created_by);
+
+    if let Some(lines) = recipe.get("recipeIngredient").or(recipe.get("ingredients")) {
+        let lines = match lines {
+            Value::String(text) => text.lines().map(str::to_string).collect(),
+            other => string_list(other),
+        };
+        dish.ingredients = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let line = clean_text(line);
+                parse_ingredient_line(&line).unwrap_or_else(|_| Ingredient::new(line, 0.0, ""))
+            })
+            .collect();
+    }
+
+    if let Some(instructions) = recipe.get("recipeInstructions") {
+        let mut steps = Vec::new();
+        collect_instructions(instructions, &mut steps);
+        dish.instructions = steps.join("\n");
+    }
+
+    let prep = duration_field(recipe, "prepTime");
+    let cook = duration_field(recipe, "cookTime");
+    let total = duration_field(recipe, "totalTime");
+    dish.prep_time = prep;
+    dish.cook_time = match (prep, cook, total) {
+        (_, Some(cook), _) => Some(cook),
+        (Some(prep), None, Some(total)) if total > prep => Some(total - prep),
+        (None, None, Some(total)) => Some(total),
+        _ => None,
+    };
+
+    dish.servings = recipe.get("recipeYield").and_then(parse_yield);
+
+    let mut tags: Vec<String> = Vec::new();
+    for field in ["recipeCategory", "recipeCuisine"] {
+        if let Some(value) = recipe.get(field) {
+            for tag in string_list(value) {
+                let tag = clean_text(&tag).to_lowercase();
+                if !tag.is_empty() && !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+    }
+    dish.tags = tags;
+
+    dish.image_url = recipe.get("image").and_then(image_url);
+    dish.source_url = source_url.map(str::to_string).or_else(|| {
+        recipe
+            .get("url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    });
+
+    if let Some(nutrition) = recipe.get("nutrition") {
+        let nutrients = parse_nutrition(nutrition);
+        if !nutrients.is_empty() {
+            dish.nutrients = Some(nutrients);
+        }
+    }
+
+    Ok(dish)
+}
+
+/// Parse an ISO-8601 duration ("PT1H30M", "P0DT20M") into whole minutes.
+pub fn parse_iso8601_duration(s: &str) -> Option<i32> {
+    let s = s.trim();
+    let rest = s.strip_prefix('P').or_else(|| s.strip_prefix('p'))?;
+
+    let mut minutes = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut seen_component = false;
+
+    for c in rest.chars() {
+        match c.to_ascii_uppercase() {
+            'T' => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                minutes += match (unit, in_time) {
+                    ('W', false) => value * 7.0 * 24.0 * 60.0,
+                    ('D', false) => value * 24.0 * 60.0,
+                    ('H', true) => value * 60.0,
+                    ('M', true) => value,
+                    ('S', true) => value / 60.0,
+                    _ => return None,
+                };
+                seen_component = true;
+            }
+        }
+    }
+
+    if !number.is_empty() || !seen_component {
+        return None;
+    }
+
+    Some(minutes.round() as i32)
+}
+
+/// Find the contents of all `<script type="application/ld+json">` tags.
+fn find_jsonld_blocks(html: &str) -> Vec<String> {
+    // ASCII lowercasing keeps byte offsets aligned with the original
+    let lower = html.to_ascii_lowercase();
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = lower[pos..].find("<script") {
+        let tag_start = pos + start;
+        let Some(tag_len) = lower[tag_start..].find('>') else {
+            break;
+        };
+        let content_start = tag_start + tag_len + 1;
+        let Some(content_len) = lower[content_start..].find("</script") else {
+            break;
+        };
+        let content_end = content_start + content_len;
+
+        if lower[tag_start..content_start].contains("application/ld+json") {
+            let content = html[content_start..content_end]
+                .trim()
+                .trim_start_matches("<!--")
+                .trim_end_matches("-->")
+                .trim();
+            blocks.push(content.to_string());
+        }
+
+        pos = content_end;
+    }
+
+    blocks
+}
+
+fn is_recipe(value: &Value) -> bool {
+    match value.get("@type") {
+        Some(Value::String(t)) => t == "Recipe" || t.ends_with(":Recipe"),
+        Some(Value::Array(types)) => types
+            .iter()
+            .any(|t| t.as_str().is_some_and(|t| t == "Recipe")),
+        _ => false,
+    }
+}
+
+fn collect_recipes(value: &Value, out: &mut Vec<Value>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_recipes(item, out);
+            }
+        }
+        Value::Object(map) => {
+            if is_recipe(value) {
+                out.push(value.clone());
+                return;
+            }
+            for key in ["@graph", "mainEntity", "mainEntityOfPage"] {
+                if let Some(nested) = map.get(key) {
+                    collect_recipes(nested, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A string, array of strings, or comma-separated string as a list.
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => s
+            .split(',')
+            .map(|part| part.trim().to_string())
+            .filter(|part| !part.is_empty())
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Flatten recipeInstructions (text, HowToStep, HowToSection) into steps.
+fn collect_instructions(value: &Value, steps: &mut Vec<String>) {
+    match value {
+        Value::String(text) => {
+            for line in text.lines() {
+                let line = clean_text(line);
+                if !line.is_empty() {
+                    steps.push(line);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_instructions(item, steps);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(items) = map.get("itemListElement") {
+                if let Some(name) = map.get("name").and_then(Value::as_str) {
+                    steps.push(format!("{}:", clean_text(name)));
+                }
+                collect_instructions(items, steps);
+            } else if let Some(text) = map.get("text").or(map.get("name")) {
+                collect_instructions(text, steps);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn duration_field(recipe: &Value, field: &str) -> Option<i32> {
+    recipe
+        .get(field)
+        .and_then(Value::as_str)
+        .and_then(parse_iso8601_duration)
+}
+
+/// Servings from recipeYield: 4, "4", "4 servings", ["4", "4 servings"].
+fn parse_yield(value: &Value) -> Option<i32> {
+    match value {
+        Value::Number(n) => n.as_f64().map(|n| n.round() as i32),
+        Value::String(s) => leading_number(s).map(|n| n.round() as i32),
+        Value::Array(items) => items.iter().find_map(parse_yield),
+        _ => None,
+    }
+    .filter(|n| *n > 0)
+}
+
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(url) => Some(url.clone()),
+        Value::Array(items) => items.iter().find_map(image_url),
+        Value::Object(map) => map.get("url").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_nutrition(nutrition: &Value) -> Vec<Nutrient> {
+    NUTRITION_FIELDS
+        .iter()
+        .filter_map(|(field, name, default_unit)| {
+            let value = nutrition.get(*field)?;
+            let (amount, unit) = match value {
+                Value::Number(n) => (n.as_f64()?, None),
+                Value::String(s) => (leading_number(s)?, unit_suffix(s)),
+                _ => return None,
+            };
+            // Calories are always stored in kcal, whatever the page calls them
+            let unit = match unit {
+                Some(u) if *name != "calories" => u,
+                _ => default_unit.to_string(),
+            };
+            Some(Nutrient::new(*name, amount, unit))
+        })
+        .collect()
+}
+
+/// The first number in a string ("250 calories" -> 250).
+pub(crate) fn leading_number(s: &str) -> Option<f64> {
+    let start = s.find(|c: char| c.is_ascii_digit())?;
+    let number: String = s[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .filter(|c| *c != ',')
+        .collect();
+    number.parse().ok()
+}
+
+/// The unit following a number ("12.5 g" -> "g").
+pub(crate) fn unit_suffix(s: &str) -> Option<String> {
+    let unit = s
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == ',' || c == ' ')
+        .trim();
+    if unit.is_empty() {
+        None
+    } else {
+        Some(unit.to_lowercase())
+    }
+}
+
+/// Strip HTML tags, decode common entities and collapse whitespace.
+fn clean_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+
+    let decoded = out
+        .replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+
+    decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head>
+<script type="application/ld+json">{"@context":"https://schema.org","@type":"WebSite","name":"Food Blog"}</script>
+<script type="application/ld+json">
+{
+  "@context": "https://schema.org",
+  "@graph": [
+    {"@type": "WebPage", "name": "Pancakes page"},
+    {
+      "@type": "Recipe",
+      "name": "Fluffy Pancakes",
+      "url": "https://example.com/pancakes",
+      "image": [{"@type": "ImageObject", "url": "https://example.com/pancakes.jpg"}],
+      "recipeYield": ["4", "4 servings"],
+      "prepTime": "PT10M",
+      "cookTime": "PT20M",
+      "totalTime": "PT30M",
+      "recipeCategory": "Breakfast",
+      "recipeCuisine": ["American"],
+      "recipeIngredient": ["1 1/2 cups flour, sifted", "2 eggs", "salt to taste"],
+      "recipeInstructions": [
+        {"@type": "HowToSection", "name": "Batter", "itemListElement": [
+          {"@type": "HowToStep", "text": "Whisk the flour &amp; eggs."}
+        ]},
+        {"@type": "HowToStep", "text": "Cook on a <b>hot</b> griddle."}
+      ],
+      "nutrition": {
+        "@type": "NutritionInformation",
+        "calories": "250 calories",
+        "proteinContent": "8 g",
+        "carbohydrateContent": "30g",
+        "fatContent": "10.5 g",
+        "sodiumContent": "300 mg"
+      }
+    }
+  ]
+}
+</script>
+</head><body></body></html>"#;
+
+    fn nutrient(dish: &Dish, name: &str) -> Option<Nutrient> {
+        dish.nutrients
+            .as_ref()?
+            .iter()
+            .find(|n| n.name == name)
+            .cloned()
+    }
+
+    #[test]
+    fn test_import_recipe_from_html() {
+        let dish = import_recipe(PAGE, "chef", None).unwrap();
+
+        assert_eq!(dish.name, "Fluffy Pancakes");
+        assert_eq!(dish.created_by, "chef");
+        assert_eq!(dish.servings, Some(4));
+        assert_eq!(dish.prep_time, Some(10));
+        assert_eq!(dish.cook_time, Some(20));
+        assert_eq!(dish.tags, vec!["breakfast", "american"]);
+        assert_eq!(
+            dish.image_url.as_deref(),
+            Some("https://example.com/pancakes.jpg")
+        );
+        assert_eq!(
+            dish.source_url.as_deref(),
+            Some("https://example.com/pancakes")
+        );
+    }
+
+    #[test]
+    fn test_import_ingredients() {
+        let dish = import_recipe(PAGE, "chef", None).unwrap();
+
+        assert_eq!(dish.ingredients.len(), 3);
+        assert_eq!(dish.ingredients[0].name, "flour");
+        assert_eq!(dish.ingredients[0].quantity, 1.5);
+        assert_eq!(dish.ingredients[0].unit, "cups");
+        assert_eq!(dish.ingredients[0].note.as_deref(), Some("sifted"));
+        assert_eq!(dish.ingredients[2].name, "salt");
+        assert_eq!(dish.ingredients[2].quantity, 0.0);
+    }
+
+    #[test]
+    fn test_import_instructions() {
+        let dish = import_recipe(PAGE, "chef", None).unwrap();
+        assert_eq!(
+            dish.instructions,
+            "Batter:\nWhisk the flour & eggs.\nCook on a hot griddle."
+        );
+    }
+
+    #[test]
+    fn test_import_nutrition() {
+        let dish = import_recipe(PAGE, "chef", None).unwrap();
+
+        let calories = nutrient(&dish, "calories").unwrap();
+        assert_eq!(calories.amount, 250.0);
+        assert_eq!(calories.unit, "kcal");
+        assert_eq!(nutrient(&dish, "carbs").unwrap().amount, 30.0);
+        assert_eq!(nutrient(&dish, "fat").unwrap().amount, 10.5);
+        assert_eq!(nutrient(&dish, "sodium").unwrap().unit, "mg");
+    }
+
+    #[test]
+    fn test_source_url_override() {
+        let dish = import_recipe(PAGE, "chef", Some("http://localhost/p")).unwrap();
+        assert_eq!(dish.source_url.as_deref(), Some("http://localhost/p"));
+    }
+
+    #[test]
+    fn test_import_raw_json_with_string_instructions() {
+        let json = r#"{"@type": ["Recipe"], "name": "Toast",
+            "recipeYield": 2, "totalTime": "PT5M",
+            "recipeInstructions": "Toast the bread.\nButter it."}"#;
+        let dish = import_recipe(json, "chef", None).unwrap();
+
+        assert_eq!(dish.servings, Some(2));
+        assert_eq!(dish.cook_time, Some(5));
+        assert_eq!(dish.instructions, "Toast the bread.\nButter it.");
+    }
+
+    #[test]
+    fn test_import_no_recipe() {
+        let html = r#"<script type="application/ld+json">{"@type":"WebSite"}</script>"#;
+        assert!(matches!(
+            import_recipe(html, "chef", None),
+            Err(FormatError::NoRecipe)
+        ));
+        assert!(matches!(
+            import_recipe("<html></html>", "chef", None),
+            Err(FormatError::NoRecipe)
+        ));
+    }
+
+    #[test]
+    fn test_import_invalid_json() {
+        let html = r#"<script type="application/ld+json">{not json</script>"#;
+        assert!(matches!(
+            import_recipe(html, "chef", None),
+            Err(FormatError::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_missing_name() {
+        let json = r#"{"@type": "Recipe"}"#;
+        assert!(matches!(
+            import_recipe(json, "chef", None),
+            Err(FormatError::MissingField("name"))
+        ));
+    }
+
+    #[test]
+    fn test_parse_iso8601_duration() {
+        assert_eq!(parse_iso8601_duration("PT1H30M"), Some(90));
+        assert_eq!(parse_iso8601_duration("PT45M"), Some(45));
+        assert_eq!(parse_iso8601_duration("P0DT0H20M"), Some(20));
+        assert_eq!(parse_iso8601_duration("PT90S"), Some(2));
+        assert_eq!(parse_iso8601_duration("P1D"), Some(1440));
+        assert_eq!(parse_iso8601_duration("PT"), None);
+        assert_eq!(parse_iso8601_duration("20 minutes"), None);
+    }
+
+    #[test]
+    fn test_import_from_url_with_fetcher() {
+        struct FixtureFetcher;
+
+        impl Fetcher for FixtureFetcher {
+            fn fetch(&self, url: &str) -> Result<String, FormatError> {
+                assert_eq!(url, "https://example.com/fixture");
+                Ok(PAGE.to_string())
+            }
+        }
+
+        let dish =
+            import_recipe_from_url("https://example.com/fixture", &FixtureFetcher, "chef").unwrap();
+        assert_eq!(dish.name, "Fluffy Pancakes");
+        assert_eq!(
+            dish.source_url.as_deref(),
+            Some("https://example.com/fixture")
+        );
+    }
+}
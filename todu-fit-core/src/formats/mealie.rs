@@ -0,0 +1,379 @@
+//! Mealie recipe JSON
+//!
+//! Mealie exports recipes as JSON objects (one per file, or an array). Parsed
+//! ingredients carry structured `quantity`, `unit` and `food` fields; unparsed
+//! ones only have the original text in `note`.
+//!
+//! | Mealie                           | Dish                              |
+//! |----------------------------------|-----------------------------------|
+//! | `recipeIngredient`               | `ingredients`                     |
+//! | `recipeInstructions[].text`      | `instructions` (one step per line)|
+//! | `recipeServings`, `recipeYield`  | `servings`                        |
+//! | `prepTime`, `cookTime`           | `prep_time`, `cook_time` (minutes)|
+//! | `tags`, `recipeCategory`         | `tags`                            |
+//! | `orgURL`                         | `source_url`                      |
+//! | `nutrition`                      | `nutrients` (per serving)         |
+
+use serde_json::{json, Map, Value};
+
+use super::jsonld::{parse_nutrition, NUTRITION_FIELDS};
+use super::{leading_integer, parse_minutes, Collection, FormatError};
+use crate::ingredient_parser::parse_ingredient_line;
+use crate::models::{Dish, Ingredient};
+
+/// Read one recipe, an array of recipes, or an `{"items": [...]}` page.
+pub fn read_recipes(json: &str, created_by: &str) -> Result<Collection, FormatError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| FormatError::InvalidJson(e.to_string()))?;
+
+    let recipes = match &value {
+        Value::Array(items) => items.clone(),
+        Value::Object(map) => match map.get("items") {
+            Some(Value::Array(items)) => items.clone(),
+            _ => vec![value.clone()],
+        },
+        _ => {
+            return Err(FormatError::InvalidJson(
+                "expected an object or array".into(),
+            ))
+        }
+    };
+
+    let mut collection = Collection::default();
+    for (i, recipe) in recipes.iter().enumerate() {
+        match recipe_to_dish(recipe, created_by) {
+            Ok(dish) => collection.dishes.push(dish),
+            Err(e) => {
+                let label = recipe
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("recipe {}", i + 1));
+                collection.failed.push((label, e.to_string()));
+            }
+        }
+    }
+
+    Ok(collection)
+}
+
+/// Serialize dishes as a pretty-printed array of Mealie recipes.
+pub fn write_recipes(dishes: &[Dish]) -> Result<String, FormatError> {
+    let recipes: Vec<Value> = dishes.iter().map(dish_to_recipe).collect();
+    serde_json::to_string_pretty(&recipes).map_err(|e| FormatError::InvalidJson(e.to_string()))
+}
+
+/// Build a Dish from a Mealie recipe object.
+pub fn recipe_to_dish(recipe: &Value, created_by: &str) -> Result<Dish, FormatError> {
+    let name = recipe
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or(FormatError::MissingField("name"))?;
+
+    let mut dish = Dish::new(name, created_by);
+
+    if let Some(Value::Array(items)) = recipe.get("recipeIngredient") {
+        dish.ingredients = items.iter().filter_map(read_ingredient).collect();
+    }
+
+    if let Some(Value::Array(steps)) = recipe.get("recipeInstructions") {
+        let mut lines = Vec::new();
+        for step in steps {
+            if let Some(title) = step
+                .get("title")
+                .and_then(Value::as_str)
+                .filter(|t| !t.trim().is_empty())
+            {
+                lines.push(format!("{}:", title.trim()));
+            }
+            let text = step
+                .as_str()
+                .or_else(|| step.get("text").and_then(Value::as_str));
+            lines.extend(
+                text.unwrap_or("")
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        dish.instructions = lines.join("\n");
+    }
+
+    dish.servings = recipe
+        .get("recipeServings")
+        .and_then(Value::as_f64)
+        .map(|s| s.round() as i32)
+        .filter(|s| *s > 0)
+        .or_else(|| {
+            recipe
+                .get("recipeYield")
+                .and_then(Value::as_str)
+                .and_then(leading_integer)
+        });
+
+    let minutes = |field: &str| {
+        recipe
+            .get(field)
+            .and_then(Value::as_str)
+            .and_then(parse_minutes)
+    };
+    let prep = minutes("prepTime");
+    let cook = minutes("cookTime").or_else(|| minutes("performTime"));
+    let total = minutes("totalTime");
+    dish.prep_time = prep;
+    dish.cook_time = match (prep, cook, total) {
+        (_, Some(cook), _) => Some(cook),
+        (Some(prep), None, Some(total)) if total > prep => Some(total - prep),
+        (None, None, Some(total)) => Some(total),
+        _ => None,
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for field in ["recipeCategory", "tags"] {
+        if let Some(Value::Array(items)) = recipe.get(field) {
+            for item in items {
+                let label = item
+                    .as_str()
+                    .or_else(|| item.get("name").and_then(Value::as_str));
+                if let Some(tag) = label.map(|t| t.trim().to_lowercase()) {
+                    if !tag.is_empty() && !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+            }
+        }
+    }
+    dish.tags = tags;
+
+    dish.source_url = recipe
+        .get("orgURL")
+        .and_then(Value::as_str)
+        .filter(|url| !url.trim().is_empty())
+        .map(str::to_string);
+    // Mealie's `image` is an internal asset id, so only keep real URLs
+    dish.image_url = recipe
+        .get("image")
+        .and_then(Value::as_str)
+        .filter(|url| url.starts_with("http"))
+        .map(str::to_string);
+
+    if let Some(nutrition) = recipe.get("nutrition") {
+        let nutrients = parse_nutrition(nutrition);
+        if !nutrients.is_empty() {
+            dish.nutrients = Some(nutrients);
+        }
+    }
+
+    Ok(dish)
+}
+
+/// Build a Mealie recipe object from a Dish.
+pub fn dish_to_recipe(dish: &Dish) -> Value {
+    let ingredients: Vec<Value> = dish
+        .ingredients
+        .iter()
+        .map(|ing| {
+            json!({
+                "quantity": ing.quantity,
+                "unit": if ing.unit.is_empty() { Value::Null } else { json!({ "name": ing.unit }) },
+                "food": { "name": ing.name },
+                "note": ing.note.clone().unwrap_or_default(),
+                "originalText": ing.to_string(),
+            })
+        })
+        .collect();
+
+    let instructions: Vec<Value> = dish
+        .instructions
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| json!({ "text": line }))
+        .collect();
+
+    let tags: Vec<Value> = dish
+        .tags
+        .iter()
+        .map(|tag| json!({ "name": tag, "slug": slugify(tag) }))
+        .collect();
+
+    let mut nutrition = Map::new();
+    for nutrient in dish.nutrients.iter().flatten() {
+        if let Some((field, _, _)) = NUTRITION_FIELDS
+            .iter()
+            .find(|(_, n, _)| *n == nutrient.name)
+        {
+            nutrition.insert(
+                field.to_string(),
+                json!(format!("{} {}", nutrient.amount, nutrient.unit)),
+            );
+        }
+    }
+
+    json!({
+        "id": dish.id.to_string(),
+        "name": dish.name,
+        "slug": slugify(&dish.name),
+        "recipeServings": dish.servings,
+        "recipeYield": dish.servings.map(|s| format!("{} servings", s)),
+        "prepTime": dish.prep_time.map(|t| format!("{} minutes", t)),
+        "cookTime": dish.cook_time.map(|t| format!("{} minutes", t)),
+        "totalTime": dish.total_time().map(|t| format!("{} minutes", t)),
+        "recipeIngredient": ingredients,
+        "recipeInstructions": instructions,
+        "tags": tags,
+        "recipeCategory": [],
+        "orgURL": dish.source_url,
+        "nutrition": nutrition,
+        "dateAdded": dish.created_at.format("%Y-%m-%d").to_string(),
+    })
+}
+
+fn read_ingredient(value: &Value) -> Option<Ingredient> {
+    if let Some(text) = value.as_str() {
+        return parse_text(text);
+    }
+
+    let food = value.get("food").and_then(named);
+    let note = value
+        .get("note")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    let Some(food) = food else {
+        // Unparsed ingredients keep the whole line in the note or original text
+        let text = note.or_else(|| value.get("originalText").and_then(Value::as_str))?;
+        return parse_text(text);
+    };
+
+    let quantity = value.get("quantity").and_then(Value::as_f64).unwrap_or(0.0);
+    let unit = value.get("unit").and_then(named).unwrap_or_default();
+    let mut ingredient = Ingredient::new(food, quantity, unit);
+    ingredient.note = note.map(str::to_string);
+    Some(ingredient)
+}
+
+fn parse_text(text: &str) -> Option<Ingredient> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(parse_ingredient_line(text).unwrap_or_else(|_| Ingredient::new(text, 0.0, "")))
+}
+
+/// A `{"name": ...}` object or a plain string.
+fn named(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .or_else(|| value.get("name").and_then(Value::as_str))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn slugify(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Nutrient;
+
+    const RECIPE: &str = r#"{
+        "name": "Chicken Tacos",
+        "slug": "chicken-tacos",
+        "image": "Xb3k",
+        "recipeYield": "4 servings",
+        "recipeServings": 0,
+        "prepTime": "15 Minutes",
+        "performTime": "1 Hour 5 Minutes",
+        "orgURL": "https://example.com/tacos",
+        "recipeCategory": [{"name": "Dinner", "slug": "dinner"}],
+        "tags": [{"name": "Mexican", "slug": "mexican"}, {"name": "dinner"}],
+        "recipeIngredient": [
+            {"quantity": 1.5, "unit": {"name": "pound"}, "food": {"name": "chicken thighs"}, "note": "boneless"},
+            {"quantity": 8, "unit": null, "food": {"name": "tortillas"}, "note": ""},
+            {"quantity": 0, "unit": null, "food": null, "note": "2 cups shredded cabbage"},
+            "1 lime, cut into wedges"
+        ],
+        "recipeInstructions": [
+            {"title": "Chicken", "text": "Season the chicken.\nGrill until done."},
+            {"title": "", "text": "Serve in tortillas."}
+        ],
+        "nutrition": {"calories": "520", "proteinContent": "38 g", "sodiumContent": "640"}
+    }"#;
+
+    #[test]
+    fn test_recipe_to_dish() {
+        let value: Value = serde_json::from_str(RECIPE).unwrap();
+        let dish = recipe_to_dish(&value, "user1").unwrap();
+
+        assert_eq!(dish.name, "Chicken Tacos");
+        assert_eq!(dish.servings, Some(4));
+        assert_eq!(dish.prep_time, Some(15));
+        assert_eq!(dish.cook_time, Some(65));
+        assert_eq!(dish.tags, vec!["dinner", "mexican"]);
+        assert_eq!(
+            dish.source_url.as_deref(),
+            Some("https://example.com/tacos")
+        );
+        assert_eq!(dish.image_url, None);
+
+        assert_eq!(dish.ingredients.len(), 4);
+        assert_eq!(
+            dish.ingredients[0],
+            Ingredient::new("chicken thighs", 1.5, "pound").with_note("boneless")
+        );
+        assert_eq!(dish.ingredients[1], Ingredient::new("tortillas", 8.0, ""));
+        assert_eq!(dish.ingredients[2].quantity, 2.0);
+        assert_eq!(dish.ingredients[3].name, "lime");
+
+        assert_eq!(
+            dish.instructions,
+            "Chicken:\nSeason the chicken.\nGrill until done.\nServe in tortillas."
+        );
+
+        let nutrients = dish.nutrients.unwrap();
+        assert_eq!(nutrients[0], Nutrient::new("calories", 520.0, "kcal"));
+        assert_eq!(nutrients[1], Nutrient::new("protein", 38.0, "g"));
+        assert_eq!(nutrients[2], Nutrient::new("sodium", 640.0, "mg"));
+    }
+
+    #[test]
+    fn test_read_recipes_array_and_failures() {
+        let json = format!(r#"[{}, {{"slug": "nameless"}}]"#, RECIPE);
+        let collection = read_recipes(&json, "user1").unwrap();
+        assert_eq!(collection.dishes.len(), 1);
+        assert_eq!(collection.failed.len(), 1);
+        assert_eq!(collection.failed[0].0, "recipe 2");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let value: Value = serde_json::from_str(RECIPE).unwrap();
+        let original = recipe_to_dish(&value, "user1").unwrap();
+
+        let json = write_recipes(std::slice::from_ref(&original)).unwrap();
+        let collection = read_recipes(&json, "user1").unwrap();
+        let dish = &collection.dishes[0];
+
+        assert_eq!(dish.name, original.name);
+        assert_eq!(dish.ingredients, original.ingredients);
+        assert_eq!(dish.instructions, original.instructions);
+        assert_eq!(dish.servings, original.servings);
+        assert_eq!(dish.prep_time, original.prep_time);
+        assert_eq!(dish.cook_time, original.cook_time);
+        assert_eq!(dish.tags, original.tags);
+        assert_eq!(dish.source_url, original.source_url);
+        assert_eq!(dish.nutrients, original.nutrients);
+    }
+}
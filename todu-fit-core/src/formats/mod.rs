@@ -0,0 +1,123 @@
+//! Recipe import and export formats
+//!
+//! Converts between [`Dish`](crate::models::Dish) and external recipe formats:
+//! - `jsonld`: schema.org `Recipe` JSON-LD embedded in web pages
+//! - `cooklang`: plain-text Cooklang (`.cook`) recipes
+//! - `paprika`: Paprika `.paprikarecipes` archives
+//! - `mealie`: Mealie recipe JSON exports
+//! - `bundle`: todu-fit's own lossless JSON bundle of dishes
+//!
+//! `shopping_list` exports shopping lists (Markdown, plain text, CSV,
+//! todo.txt) and reads ticked-off Markdown checklists back. `ical` exports
+//! meal plans as iCalendar events and reads calendar files back.
+//!
+//! The collection formats carry many dishes at once; [`split_duplicates`]
+//! sorts an incoming collection into new dishes and ones that already exist.
+//!
+//! Remote sources are read through the [`Fetcher`] trait so the transport can
+//! be swapped out (e.g. for a local fixture server in tests).
+
+pub mod bundle;
+mod collection;
+pub mod cooklang;
+mod fetch;
+pub mod ical;
+pub mod jsonld;
+pub mod mealie;
+pub mod paprika;
+pub mod shopping_list;
+
+pub use collection::{split_duplicates, Collection, Duplicate, DuplicateCheck, DuplicateReason};
+pub use fetch::{Fetcher, HttpFetcher};
+
+use thiserror::Error;
+
+/// Errors that can occur when importing or exporting recipes
+#[derive(Error, Debug)]
+pub enum FormatError {
+    #[error("No schema.org Recipe found")]
+    NoRecipe,
+
+    #[error("Invalid JSON-LD: {0}")]
+    InvalidJson(String),
+
+    #[error("Recipe is missing required field '{0}'")]
+    MissingField(&'static str),
+
+    #[error("Invalid Cooklang: {0}")]
+    InvalidCooklang(String),
+
+    #[error("Failed to fetch {url}: {message}")]
+    Fetch { url: String, message: String },
+
+    #[error("Invalid archive: {0}")]
+    Archive(String),
+
+    #[error("Unsupported bundle: {0}")]
+    UnsupportedBundle(String),
+
+    #[error("CSV error: {0}")]
+    Csv(String),
+
+    #[error("Invalid iCalendar: {0}")]
+    InvalidCalendar(String),
+}
+
+/// Minutes from "25", "25 minutes", "1 hour 30 min", "1h30m" or "PT1H30M".
+pub(crate) fn parse_minutes(s: &str) -> Option<i32> {
+    if let Some(minutes) = jsonld::parse_iso8601_duration(s) {
+        return Some(minutes);
+    }
+
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut unit = String::new();
+    let mut found = false;
+
+    let mut flush = |number: &mut String, unit: &mut String, total: &mut f64| {
+        if let Ok(value) = number.parse::<f64>() {
+            *total += duration_to_minutes(value, unit);
+            found = true;
+        }
+        number.clear();
+        unit.clear();
+    };
+
+    for c in s.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            if !unit.is_empty() {
+                flush(&mut number, &mut unit, &mut total);
+            }
+            number.push(c);
+        } else if c.is_alphabetic() {
+            unit.push(c);
+        }
+    }
+    flush(&mut number, &mut unit, &mut total);
+
+    found.then_some(total.round() as i32)
+}
+
+/// Convert an amount of hours, minutes or seconds (by unit prefix) to minutes.
+pub(crate) fn duration_to_minutes(quantity: f64, unit: &str) -> f64 {
+    let unit = unit.to_lowercase();
+    if unit.starts_with('h') {
+        quantity * 60.0
+    } else if unit.starts_with('s') {
+        quantity / 60.0
+    } else if unit.starts_with('m') || unit.is_empty() {
+        quantity
+    } else {
+        0.0
+    }
+}
+
+/// The leading whole number of a string ("4 servings" -> 4), if positive.
+pub(crate) fn leading_integer(s: &str) -> Option<i32> {
+    let digits: String = s
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok().filter(|n| *n > 0)
+}
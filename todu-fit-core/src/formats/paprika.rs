@@ -0,0 +1,488 @@
+//! Paprika recipe archives
+//!
+//! A `.paprikarecipes` file is a zip archive with one entry per recipe. Each
+//! entry (`<name>.paprikarecipe`) is a gzip-compressed JSON object:
+//!
+//! | Paprika                  | Dish                                   |
+//! |--------------------------|----------------------------------------|
+//! | `uid`                    | `id` (when it is a UUID)               |
+//! | `ingredients`            | `ingredients` (one line each, parsed)  |
+//! | `directions`             | `instructions`                         |
+//! | `servings`               | `servings` ("4 servings" -> 4)         |
+//! | `prep_time`, `cook_time` | `prep_time`, `cook_time` (minutes)     |
+//! | `categories`             | `tags`                                 |
+//! | `nutritional_info`       | `nutrients` ("Calories: 250 kcal")     |
+//! | `source_url`, `image_url`| `source_url`, `image_url`              |
+
+use std::io::{Cursor, Read, Write};
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+
+use super::{leading_integer, parse_minutes, Collection, FormatError};
+use crate::ingredient_parser::parse_ingredient_line;
+use crate::models::{Dish, Ingredient, Nutrient};
+use crate::scaling::format_kitchen_quantity;
+
+const CREATED_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Nutrient names as written in Paprika's free-text nutrition field.
+const NUTRIENT_LABELS: &[(&str, &str)] = &[
+    ("calories", "calories"),
+    ("energy", "calories"),
+    ("protein", "protein"),
+    ("carbohydrates", "carbs"),
+    ("carbohydrate", "carbs"),
+    ("carbs", "carbs"),
+    ("total fat", "fat"),
+    ("fat", "fat"),
+    ("saturated fat", "saturated_fat"),
+    ("saturated_fat", "saturated_fat"),
+    ("fiber", "fiber"),
+    ("sugar", "sugar"),
+    ("sugars", "sugar"),
+    ("sodium", "sodium"),
+    ("cholesterol", "cholesterol"),
+];
+
+/// A single recipe as stored in a Paprika export.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PaprikaRecipe {
+    #[serde(default)]
+    pub uid: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub ingredients: Option<String>,
+    #[serde(default)]
+    pub directions: Option<String>,
+    #[serde(default)]
+    pub servings: Option<String>,
+    #[serde(default)]
+    pub prep_time: Option<String>,
+    #[serde(default)]
+    pub cook_time: Option<String>,
+    #[serde(default)]
+    pub total_time: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub image_url: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub nutritional_info: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+/// Read every recipe in a `.paprikarecipes` archive.
+///
+/// A single gzipped `.paprikarecipe` file is accepted too. Entries that
+/// can't be decoded are reported in [`Collection::failed`].
+pub fn read_archive(bytes: &[u8], created_by: &str) -> Result<Collection, FormatError> {
+    if !bytes.starts_with(b"PK") {
+        let recipe = decode_recipe(bytes)?;
+        return Ok(Collection {
+            dishes: vec![recipe_to_dish(recipe, created_by)?],
+            failed: Vec::new(),
+        });
+    }
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| FormatError::Archive(e.to_string()))?;
+    let mut collection = Collection::default();
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| FormatError::Archive(e.to_string()))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let entry_name = entry.name().to_string();
+
+        let mut data = Vec::new();
+        let result = entry
+            .read_to_end(&mut data)
+            .map_err(|e| FormatError::Archive(e.to_string()))
+            .and_then(|_| decode_recipe(&data))
+            .and_then(|recipe| recipe_to_dish(recipe, created_by));
+
+        match result {
+            Ok(dish) => collection.dishes.push(dish),
+            Err(e) => collection.failed.push((entry_name, e.to_string())),
+        }
+    }
+
+    Ok(collection)
+}
+
+/// Write dishes as a `.paprikarecipes` archive.
+pub fn write_archive(dishes: &[Dish]) -> Result<Vec<u8>, FormatError> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    // Entries are already gzipped, so store them without recompressing
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut used_names: Vec<String> = Vec::new();
+
+    for dish in dishes {
+        let recipe = dish_to_recipe(dish);
+        let json =
+            serde_json::to_vec(&recipe).map_err(|e| FormatError::InvalidJson(e.to_string()))?;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&json)
+            .map_err(|e| FormatError::Archive(e.to_string()))?;
+        let gzipped = encoder
+            .finish()
+            .map_err(|e| FormatError::Archive(e.to_string()))?;
+
+        let entry_name = unique_entry_name(&dish.name, &mut used_names);
+        zip.start_file(entry_name, options)
+            .map_err(|e| FormatError::Archive(e.to_string()))?;
+        zip.write_all(&gzipped)
+            .map_err(|e| FormatError::Archive(e.to_string()))?;
+    }
+
+    let cursor = zip
+        .finish()
+        .map_err(|e| FormatError::Archive(e.to_string()))?;
+    Ok(cursor.into_inner())
+}
+
+/// Build a Dish from a Paprika recipe.
+pub fn recipe_to_dish(recipe: PaprikaRecipe, created_by: &str) -> Result<Dish, FormatError> {
+    let name = recipe.name.trim();
+    if name.is_empty() {
+        return Err(FormatError::MissingField("name"));
+    }
+
+    let mut dish = Dish::new(name, created_by);
+
+    if let Some(id) = recipe
+        .uid
+        .as_deref()
+        .and_then(|uid| Uuid::parse_str(uid).ok())
+    {
+        dish.id = id;
+    }
+
+    if let Some(created) = recipe
+        .created
+        .as_deref()
+        .and_then(|c| NaiveDateTime::parse_from_str(c, CREATED_FORMAT).ok())
+    {
+        dish.created_at = Utc.from_utc_datetime(&created);
+        dish.updated_at = dish.created_at;
+    }
+
+    dish.ingredients = non_empty_lines(recipe.ingredients.as_deref())
+        .map(|line| parse_ingredient_line(line).unwrap_or_else(|_| Ingredient::new(line, 0.0, "")))
+        .collect();
+    dish.instructions = non_empty_lines(recipe.directions.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    dish.servings = recipe.servings.as_deref().and_then(leading_integer);
+
+    let prep = recipe.prep_time.as_deref().and_then(parse_minutes);
+    let cook = recipe.cook_time.as_deref().and_then(parse_minutes);
+    let total = recipe.total_time.as_deref().and_then(parse_minutes);
+    dish.prep_time = prep;
+    dish.cook_time = match (prep, cook, total) {
+        (_, Some(cook), _) => Some(cook),
+        (Some(prep), None, Some(total)) if total > prep => Some(total - prep),
+        (None, None, Some(total)) => Some(total),
+        _ => None,
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for category in &recipe.categories {
+        let tag = category.trim().to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    dish.tags = tags;
+
+    dish.source_url = recipe.source_url.filter(|url| !url.trim().is_empty());
+    dish.image_url = recipe.image_url.filter(|url| !url.trim().is_empty());
+
+    let nutrients = parse_nutritional_info(recipe.nutritional_info.as_deref().unwrap_or(""));
+    if !nutrients.is_empty() {
+        dish.nutrients = Some(nutrients);
+    }
+
+    Ok(dish)
+}
+
+/// Build a Paprika recipe from a Dish.
+pub fn dish_to_recipe(dish: &Dish) -> PaprikaRecipe {
+    let ingredients = dish
+        .ingredients
+        .iter()
+        .map(ingredient_line)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let nutritional_info = dish
+        .nutrients
+        .iter()
+        .flatten()
+        .map(|n| {
+            format!(
+                "{}: {} {}",
+                n.name,
+                format_kitchen_quantity(n.amount),
+                n.unit
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut recipe = PaprikaRecipe {
+        uid: Some(dish.id.to_string().to_uppercase()),
+        name: dish.name.clone(),
+        ingredients: Some(ingredients),
+        directions: Some(dish.instructions.clone()),
+        servings: dish.servings.map(|s| s.to_string()),
+        prep_time: dish.prep_time.map(|t| format!("{} mins", t)),
+        cook_time: dish.cook_time.map(|t| format!("{} mins", t)),
+        total_time: dish.total_time().map(|t| format!("{} mins", t)),
+        source: None,
+        source_url: dish.source_url.clone(),
+        image_url: dish.image_url.clone(),
+        categories: dish.tags.clone(),
+        nutritional_info: Some(nutritional_info),
+        notes: None,
+        created: Some(dish.created_at.format(CREATED_FORMAT).to_string()),
+        hash: None,
+    };
+    recipe.hash = Some(recipe_hash(&recipe));
+    recipe
+}
+
+/// Parse "Calories: 250 kcal" style lines (also separated by `;` or `,`).
+fn parse_nutritional_info(text: &str) -> Vec<Nutrient> {
+    let mut nutrients: Vec<Nutrient> = Vec::new();
+
+    for part in text.split(['\n', ';', ',']) {
+        let Some((label, value)) = part.split_once(':') else {
+            continue;
+        };
+        let label = label.trim().to_lowercase();
+        let Some((_, name)) = NUTRIENT_LABELS.iter().find(|(l, _)| *l == label) else {
+            continue;
+        };
+        let Some(amount) = super::jsonld::leading_number(value) else {
+            continue;
+        };
+
+        let unit = match (*name, super::jsonld::unit_suffix(value.trim())) {
+            ("calories", _) => "kcal".to_string(),
+            (_, Some(unit)) => unit,
+            ("sodium" | "cholesterol", None) => "mg".to_string(),
+            (_, None) => "g".to_string(),
+        };
+
+        if !nutrients.iter().any(|n| n.name == *name) {
+            nutrients.push(Nutrient::new(*name, amount, unit));
+        }
+    }
+
+    nutrients
+}
+
+fn decode_recipe(bytes: &[u8]) -> Result<PaprikaRecipe, FormatError> {
+    let mut json = Vec::new();
+    // Paprika gzips every entry, but accept plain JSON as well
+    if GzDecoder::new(bytes).read_to_end(&mut json).is_err() {
+        json = bytes.to_vec();
+    }
+    serde_json::from_slice(&json).map_err(|e| FormatError::InvalidJson(e.to_string()))
+}
+
+fn ingredient_line(ingredient: &Ingredient) -> String {
+    let mut line = match (ingredient.quantity == 0.0, ingredient.unit.is_empty()) {
+        (true, _) => ingredient.name.clone(),
+        (false, true) => format!(
+            "{} {}",
+            format_kitchen_quantity(ingredient.quantity),
+            ingredient.name
+        ),
+        (false, false) => format!(
+            "{} {} {}",
+            format_kitchen_quantity(ingredient.quantity),
+            ingredient.unit,
+            ingredient.name
+        ),
+    };
+    if let Some(note) = &ingredient.note {
+        line.push_str(", ");
+        line.push_str(note);
+    }
+    line
+}
+
+fn non_empty_lines(text: Option<&str>) -> impl Iterator<Item = &str> {
+    text.unwrap_or("")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+/// Paprika identifies recipe revisions by a SHA-256 of their content.
+fn recipe_hash(recipe: &PaprikaRecipe) -> String {
+    let json = serde_json::to_vec(recipe).unwrap_or_default();
+    Sha256::digest(&json)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+fn unique_entry_name(name: &str, used: &mut Vec<String>) -> String {
+    let base: String = name
+        .chars()
+        .map(|c| if "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+
+    let mut candidate = format!("{}.paprikarecipe", base);
+    let mut n = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{} ({}).paprikarecipe", base, n);
+        n += 1;
+    }
+    used.push(candidate.clone());
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_dish() -> Dish {
+        let mut dish = Dish::new("Pancakes", "user1")
+            .with_servings(4)
+            .with_prep_time(10)
+            .with_cook_time(15)
+            .with_ingredients(vec![
+                Ingredient::new("flour", 1.5, "cups").with_note("sifted"),
+                Ingredient::new("eggs", 2.0, ""),
+                Ingredient::new("salt", 0.0, ""),
+            ])
+            .with_instructions("Mix.\nCook on a hot griddle.")
+            .with_nutrients(vec![
+                Nutrient::new("calories", 250.0, "kcal"),
+                Nutrient::new("protein", 8.0, "g"),
+            ])
+            .with_tags(vec!["breakfast".to_string()]);
+        dish.source_url = Some("https://example.com/pancakes".to_string());
+        dish
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let original = sample_dish();
+        let bytes = write_archive(std::slice::from_ref(&original)).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+
+        let collection = read_archive(&bytes, "user2").unwrap();
+        assert!(collection.failed.is_empty());
+        let dish = &collection.dishes[0];
+
+        assert_eq!(dish.id, original.id);
+        assert_eq!(dish.name, "Pancakes");
+        assert_eq!(dish.servings, Some(4));
+        assert_eq!(dish.prep_time, Some(10));
+        assert_eq!(dish.cook_time, Some(15));
+        assert_eq!(dish.ingredients, original.ingredients);
+        assert_eq!(dish.instructions, original.instructions);
+        assert_eq!(dish.nutrients, original.nutrients);
+        assert_eq!(dish.tags, original.tags);
+        assert_eq!(dish.source_url, original.source_url);
+        assert_eq!(dish.created_by, "user2");
+    }
+
+    #[test]
+    fn test_archive_with_duplicate_names() {
+        let dishes = vec![Dish::new("Soup", "user1"), Dish::new("Soup", "user1")];
+        let bytes = write_archive(&dishes).unwrap();
+        let collection = read_archive(&bytes, "user1").unwrap();
+        assert_eq!(collection.dishes.len(), 2);
+    }
+
+    #[test]
+    fn test_read_single_gzipped_recipe() {
+        let json = r#"{
+            "uid": "not-a-uuid",
+            "name": "Chili",
+            "ingredients": "1 lb ground beef\n2 cans beans\n\nsalt to taste",
+            "directions": "Brown the beef.\n\nSimmer everything.",
+            "servings": "6 servings",
+            "prep_time": "15 min",
+            "total_time": "1 hr 15 min",
+            "categories": ["Dinner", "dinner", "Comfort Food"],
+            "nutritional_info": "Calories: 420\nProtein: 30 g\nSodium: 800",
+            "created": "2024-02-03 18:30:00",
+            "source_url": ""
+        }"#;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(json.as_bytes()).unwrap();
+        let bytes = encoder.finish().unwrap();
+
+        let collection = read_archive(&bytes, "user1").unwrap();
+        let dish = &collection.dishes[0];
+
+        assert_eq!(dish.name, "Chili");
+        assert_eq!(dish.ingredients.len(), 3);
+        assert_eq!(dish.ingredients[0].unit, "lb");
+        assert_eq!(dish.instructions, "Brown the beef.\nSimmer everything.");
+        assert_eq!(dish.servings, Some(6));
+        assert_eq!(dish.prep_time, Some(15));
+        assert_eq!(dish.cook_time, Some(60));
+        assert_eq!(dish.tags, vec!["dinner", "comfort food"]);
+        assert_eq!(dish.source_url, None);
+        assert_eq!(dish.created_at.format("%Y-%m-%d").to_string(), "2024-02-03");
+
+        let nutrients = dish.nutrients.as_ref().unwrap();
+        assert_eq!(nutrients[0], Nutrient::new("calories", 420.0, "kcal"));
+        assert_eq!(nutrients[1], Nutrient::new("protein", 30.0, "g"));
+        assert_eq!(nutrients[2], Nutrient::new("sodium", 800.0, "mg"));
+    }
+
+    #[test]
+    fn test_read_archive_reports_bad_entries() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("Broken.paprikarecipe", options).unwrap();
+        zip.write_all(b"not json").unwrap();
+        zip.start_file("Toast.paprikarecipe", options).unwrap();
+        zip.write_all(br#"{"name": "Toast"}"#).unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let collection = read_archive(&bytes, "user1").unwrap();
+        assert_eq!(collection.dishes.len(), 1);
+        assert_eq!(collection.dishes[0].name, "Toast");
+        assert_eq!(collection.failed.len(), 1);
+        assert_eq!(collection.failed[0].0, "Broken.paprikarecipe");
+    }
+
+    #[test]
+    fn test_recipe_hash_is_stable() {
+        let dish = sample_dish();
+        assert_eq!(dish_to_recipe(&dish).hash, dish_to_recipe(&dish).hash);
+        assert_eq!(dish_to_recipe(&dish).hash.unwrap().len(), 64);
+    }
+}
@@ -0,0 +1,299 @@
+//! Shopping list export and checklist import
+//!
+//! A [`ShoppingList`] can be written out as:
+//! - Markdown: a `- [ ]` / `- [x]` checklist, with a heading per store section
+//! - plain text: only what is left to buy, for pasting into a message
+//! - CSV: one row per item with its quantity, unit, state and section
+//! - todo.txt: one task per item, tagged `+shopping` and `@section`
+//!
+//! [`parse_markdown_checklist`] reads a Markdown checklist back (e.g. after
+//! ticking items off in a notes app) so the checked state can be applied to
+//! the cart. Lines are matched to items by name, ignoring the quantity in
+//! front of it.
+
+use super::FormatError;
+use crate::models::ShoppingItem;
+use crate::scaling::format_kitchen_quantity;
+use crate::text::capitalize;
+
+/// A shopping list ready to export.
+#[derive(Debug, Clone, Default)]
+pub struct ShoppingList {
+    /// Heading (e.g. "Shopping list - Week of Oct 18, 2026")
+    pub title: String,
+    /// Items in display order
+    pub items: Vec<ShoppingItem>,
+    /// Store section of each item, parallel to `items`.
+    /// Empty when the list isn't grouped by store.
+    pub categories: Vec<String>,
+}
+
+impl ShoppingList {
+    /// Items with their store section, if the list is grouped.
+    fn entries(&self) -> impl Iterator<Item = (&ShoppingItem, Option<&str>)> {
+        let categories = self.categories.iter().map(|c| Some(c.as_str()));
+        self.items
+            .iter()
+            .zip(categories.chain(std::iter::repeat(None)))
+    }
+
+    /// Markdown checklist, keeping each item's checked state.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title);
+        let mut section: Option<&str> = None;
+        let mut first = true;
+
+        for (item, category) in self.entries() {
+            if first || category != section {
+                out.push('\n');
+                if let Some(category) = category {
+                    out.push_str(&format!("## {}\n\n", capitalize(category)));
+                }
+                section = category;
+                first = false;
+            }
+            let mark = if item.checked { "x" } else { " " };
+            out.push_str(&format!("- [{}] {}\n", mark, item_text(item)));
+        }
+        out
+    }
+
+    /// Plain text of the items still to buy, for messaging apps.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{}\n", self.title);
+        let mut section: Option<&str> = None;
+        let mut any = false;
+
+        for (item, category) in self.entries().filter(|(item, _)| !item.checked) {
+            if !any || category != section {
+                out.push('\n');
+                if let Some(category) = category {
+                    out.push_str(&format!("{}:\n", capitalize(category)));
+                }
+                section = category;
+                any = true;
+            }
+            out.push_str(&format!("- {}\n", item_text(item)));
+        }
+        if !any {
+            out.push_str("\nNothing left to buy.\n");
+        }
+        out
+    }
+
+    /// CSV with a header row: name, quantity, unit, checked, category, manual.
+    pub fn to_csv(&self) -> Result<String, FormatError> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let csv_error = |e: csv::Error| FormatError::Csv(e.to_string());
+
+        writer
+            .write_record(["name", "quantity", "unit", "checked", "category", "manual"])
+            .map_err(csv_error)?;
+        for (item, category) in self.entries() {
+            writer
+                .write_record([
+                    item.name.as_str(),
+                    &item.quantity.to_string(),
+                    &item.unit,
+                    &item.checked.to_string(),
+                    category.unwrap_or(""),
+                    &item.is_manual.to_string(),
+                ])
+                .map_err(csv_error)?;
+        }
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| FormatError::Csv(e.to_string()))?;
+        String::from_utf8(bytes).map_err(|e| FormatError::Csv(e.to_string()))
+    }
+
+    /// todo.txt tasks, one per item. Checked items are marked complete.
+    pub fn to_todo_txt(&self) -> String {
+        let mut out = String::new();
+        for (item, category) in self.entries() {
+            if item.checked {
+                out.push_str("x ");
+            }
+            out.push_str(&item_text(item));
+            out.push_str(" +shopping");
+            if let Some(category) = category {
+                // Contexts can't contain spaces
+                out.push_str(&format!(" @{}", category.replace(char::is_whitespace, "-")));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// One `- [ ]` / `- [x]` line of a Markdown checklist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecklistEntry {
+    /// Text after the checkbox (e.g. "2 cups flour")
+    pub text: String,
+    /// Whether the box is ticked
+    pub checked: bool,
+}
+
+impl ChecklistEntry {
+    /// Find the item this line refers to.
+    ///
+    /// The line matches an item when it is the item's name, or ends with it
+    /// after a quantity ("9 cups flour"). The longest matching name wins, so
+    /// "2 peanut butter" is not taken for "butter".
+    pub fn find_item<'a>(&self, items: &'a [ShoppingItem]) -> Option<&'a ShoppingItem> {
+        let text = self.text.trim().to_lowercase();
+        items
+            .iter()
+            .filter(|item| {
+                let name = item.name.to_lowercase();
+                text == name || text.ends_with(&format!(" {}", name))
+            })
+            .max_by_key(|item| item.name.len())
+    }
+}
+
+/// Read the checklist lines of a Markdown document.
+///
+/// Accepts `-`, `*` and `+` bullets with `[ ]`, `[x]` or `[X]` boxes; any
+/// other line (headings, notes, plain bullets) is ignored.
+pub fn parse_markdown_checklist(markdown: &str) -> Vec<ChecklistEntry> {
+    markdown
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim_start().strip_prefix(['-', '*', '+'])?;
+            let rest = rest.trim_start();
+            let checked = match rest.get(..3)? {
+                "[ ]" => false,
+                "[x]" | "[X]" => true,
+                _ => return None,
+            };
+            let text = rest[3..].trim();
+            (!text.is_empty()).then(|| ChecklistEntry {
+                text: text.to_string(),
+                checked,
+            })
+        })
+        .collect()
+}
+
+/// "9 cups flour", or just the name when there is no quantity.
+fn item_text(item: &ShoppingItem) -> String {
+    let quantity = format_kitchen_quantity(item.quantity);
+    match (item.quantity > 0.0, item.unit.is_empty()) {
+        (false, _) => item.name.clone(),
+        (true, true) => format!("{} {}", quantity, item.name),
+        (true, false) => format!("{} {} {}", quantity, item.unit, item.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Ingredient, ManualItem};
+
+    fn list(grouped: bool) -> ShoppingList {
+        ShoppingList {
+            title: "Shopping list - Week of Oct 18, 2026".to_string(),
+            items: vec![
+                ShoppingItem::from_ingredient(&Ingredient::new("garlic", 3.0, "cloves"), false),
+                ShoppingItem::from_ingredient(&Ingredient::new("eggs", 6.0, ""), true),
+                ShoppingItem::from_ingredient(&Ingredient::new("flour", 1.5, "cups"), false),
+                ShoppingItem::from_manual(&ManualItem::new("Paper Towels"), false),
+            ],
+            categories: if grouped {
+                ["produce", "dairy", "pantry", "household"]
+                    .map(String::from)
+                    .to_vec()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    #[test]
+    fn test_markdown_roundtrip() {
+        let markdown = list(true).to_markdown();
+        assert!(markdown.starts_with("# Shopping list - Week of Oct 18, 2026\n\n## Produce\n"));
+        assert!(markdown.contains("- [ ] 3 cloves garlic\n"));
+        assert!(markdown.contains("- [x] 6 eggs\n"));
+        assert!(markdown.contains("- [ ] 1 1/2 cups flour\n"));
+
+        // Tick flour in a notes app
+        let edited = markdown.replace("- [ ] 1 1/2 cups flour", "* [X] 1 1/2 cups flour");
+        let entries = parse_markdown_checklist(&edited);
+        assert_eq!(entries.len(), 4);
+
+        let items = list(true).items;
+        let flour = &entries[2];
+        assert!(flour.checked);
+        assert_eq!(flour.find_item(&items).unwrap().name, "flour");
+        assert_eq!(entries[3].find_item(&items).unwrap().name, "Paper Towels");
+    }
+
+    #[test]
+    fn test_parse_markdown_checklist_ignores_other_lines() {
+        let entries = parse_markdown_checklist(
+            "# Groceries\n- milk\n- [ ] \n  - [x] Bread\n+ [ ] 2 peanut butter\nnotes [x]\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                ChecklistEntry {
+                    text: "Bread".to_string(),
+                    checked: true
+                },
+                ChecklistEntry {
+                    text: "2 peanut butter".to_string(),
+                    checked: false
+                },
+            ]
+        );
+
+        let items = vec![
+            ShoppingItem::from_ingredient(&Ingredient::new("butter", 1.0, ""), false),
+            ShoppingItem::from_ingredient(&Ingredient::new("peanut butter", 1.0, ""), false),
+        ];
+        assert_eq!(entries[1].find_item(&items).unwrap().name, "peanut butter");
+        assert!(entries[0].find_item(&items).is_none());
+    }
+
+    #[test]
+    fn test_text_lists_only_unchecked_items() {
+        let text = list(false).to_text();
+        assert_eq!(
+            text,
+            "Shopping list - Week of Oct 18, 2026\n\n\
+             - 3 cloves garlic\n- 1 1/2 cups flour\n- 1 Paper Towels\n"
+        );
+
+        let grouped = list(true).to_text();
+        assert!(grouped.contains("\nProduce:\n- 3 cloves garlic\n\nPantry:\n"));
+        assert!(!grouped.contains("Dairy"));
+    }
+
+    #[test]
+    fn test_csv_and_todo_txt() {
+        let csv = list(true).to_csv().unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("name,quantity,unit,checked,category,manual")
+        );
+        assert_eq!(lines.next(), Some("garlic,3,cloves,false,produce,false"));
+        assert_eq!(lines.nth(2), Some("Paper Towels,1,,false,household,true"));
+
+        let todo = list(true).to_todo_txt();
+        assert_eq!(
+            todo.lines().collect::<Vec<_>>(),
+            vec![
+                "3 cloves garlic +shopping @produce",
+                "x 6 eggs +shopping @dairy",
+                "1 1/2 cups flour +shopping @pantry",
+                "1 Paper Towels +shopping @household",
+            ]
+        );
+        assert!(!list(false).to_todo_txt().contains('@'));
+    }
+}
@@ -0,0 +1,243 @@
+//! Daily nutrition goals
+//!
+//! Goals live in the personal [`IdentityDocument`](crate::IdentityDocument)
+//! so they follow the user across devices. Each goal is a daily amount for
+//! one nutrient: a target to land near, a minimum to reach, or a maximum to
+//! stay under.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How close (as a fraction of the goal) a target goal counts as met.
+pub const TARGET_TOLERANCE: f64 = 0.10;
+
+/// What kind of limit a goal sets
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalKind {
+    /// Aim for the amount, within [`TARGET_TOLERANCE`]
+    #[default]
+    Target,
+    /// Eat at least the amount
+    Min,
+    /// Eat no more than the amount
+    Max,
+}
+
+impl fmt::Display for GoalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalKind::Target => write!(f, "target"),
+            GoalKind::Min => write!(f, "min"),
+            GoalKind::Max => write!(f, "max"),
+        }
+    }
+}
+
+impl FromStr for GoalKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "target" => Ok(GoalKind::Target),
+            "min" | "minimum" | "at-least" => Ok(GoalKind::Min),
+            "max" | "maximum" | "at-most" => Ok(GoalKind::Max),
+            _ => Err(format!(
+                "Invalid goal kind '{}'. Valid kinds: target, min, max",
+                s
+            )),
+        }
+    }
+}
+
+/// A daily amount for one nutrient.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NutritionGoal {
+    pub nutrient: String,
+    pub amount: f64,
+    pub unit: String,
+    #[serde(default)]
+    pub kind: GoalKind,
+}
+
+impl NutritionGoal {
+    /// Create a target goal. Nutrient names are stored lowercase.
+    pub fn new(nutrient: impl Into<String>, amount: f64, unit: impl Into<String>) -> Self {
+        Self {
+            nutrient: nutrient.into().trim().to_lowercase(),
+            amount,
+            unit: unit.into(),
+            kind: GoalKind::Target,
+        }
+    }
+
+    pub fn with_kind(mut self, kind: GoalKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// The unit nutrients of this name are stored in (kcal, mg or g).
+    pub fn default_unit(nutrient: &str) -> &'static str {
+        match nutrient.to_lowercase().as_str() {
+            "calories" => "kcal",
+            "sodium" | "cholesterol" | "potassium" | "calcium" | "iron" => "mg",
+            _ => "g",
+        }
+    }
+
+    /// Compare a day's total against this goal.
+    pub fn evaluate(&self, actual: f64) -> GoalProgress {
+        let status = match self.kind {
+            GoalKind::Target => {
+                let tolerance = self.amount * TARGET_TOLERANCE;
+                if actual < self.amount - tolerance {
+                    GoalStatus::Under
+                } else if actual > self.amount + tolerance {
+                    GoalStatus::Over
+                } else {
+                    GoalStatus::Met
+                }
+            }
+            GoalKind::Min if actual < self.amount => GoalStatus::Under,
+            GoalKind::Max if actual > self.amount => GoalStatus::Over,
+            _ => GoalStatus::Met,
+        };
+
+        let percent = if self.amount > 0.0 {
+            actual / self.amount * 100.0
+        } else {
+            0.0
+        };
+
+        GoalProgress {
+            nutrient: self.nutrient.clone(),
+            unit: self.unit.clone(),
+            kind: self.kind,
+            goal: self.amount,
+            actual,
+            remaining: self.amount - actual,
+            percent,
+            status,
+        }
+    }
+}
+
+/// Where a day's total stands against a goal
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Under,
+    Met,
+    Over,
+}
+
+impl fmt::Display for GoalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalStatus::Under => write!(f, "under"),
+            GoalStatus::Met => write!(f, "met"),
+            GoalStatus::Over => write!(f, "over"),
+        }
+    }
+}
+
+/// Progress towards one goal for one day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GoalProgress {
+    pub nutrient: String,
+    pub unit: String,
+    pub kind: GoalKind,
+    pub goal: f64,
+    pub actual: f64,
+    /// Amount left to reach the goal (negative once past it)
+    pub remaining: f64,
+    /// `actual` as a percentage of `goal`
+    pub percent: f64,
+    pub status: GoalStatus,
+}
+
+/// Evaluate every goal against a day's nutrient totals.
+///
+/// Nutrients missing from `totals` count as zero.
+pub fn evaluate_goals(goals: &[NutritionGoal], totals: &HashMap<String, f64>) -> Vec<GoalProgress> {
+    goals
+        .iter()
+        .map(|goal| {
+            let actual = totals
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&goal.nutrient))
+                .map(|(_, amount)| *amount)
+                .unwrap_or(0.0);
+            goal.evaluate(actual)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_goal() {
+        let goal = NutritionGoal::new("Calories", 2000.0, "kcal");
+        assert_eq!(goal.nutrient, "calories");
+
+        assert_eq!(goal.evaluate(1500.0).status, GoalStatus::Under);
+        assert_eq!(goal.evaluate(1900.0).status, GoalStatus::Met);
+        assert_eq!(goal.evaluate(2150.0).status, GoalStatus::Met);
+        assert_eq!(goal.evaluate(2300.0).status, GoalStatus::Over);
+    }
+
+    #[test]
+    fn test_min_and_max_goals() {
+        let protein = NutritionGoal::new("protein", 120.0, "g").with_kind(GoalKind::Min);
+        assert_eq!(protein.evaluate(100.0).status, GoalStatus::Under);
+        assert_eq!(protein.evaluate(150.0).status, GoalStatus::Met);
+
+        let sodium = NutritionGoal::new("sodium", 2300.0, "mg").with_kind(GoalKind::Max);
+        assert_eq!(sodium.evaluate(1000.0).status, GoalStatus::Met);
+        assert_eq!(sodium.evaluate(2500.0).status, GoalStatus::Over);
+    }
+
+    #[test]
+    fn test_progress_amounts() {
+        let progress = NutritionGoal::new("calories", 2000.0, "kcal").evaluate(1500.0);
+        assert_eq!(progress.remaining, 500.0);
+        assert_eq!(progress.percent, 75.0);
+
+        let over = NutritionGoal::new("fat", 50.0, "g").evaluate(60.0);
+        assert_eq!(over.remaining, -10.0);
+        assert_eq!(over.percent, 120.0);
+    }
+
+    #[test]
+    fn test_evaluate_goals_missing_nutrient() {
+        let goals = vec![
+            NutritionGoal::new("calories", 2000.0, "kcal"),
+            NutritionGoal::new("fiber", 30.0, "g").with_kind(GoalKind::Min),
+        ];
+        let totals = HashMap::from([("calories".to_string(), 1950.0)]);
+
+        let progress = evaluate_goals(&goals, &totals);
+        assert_eq!(progress[0].status, GoalStatus::Met);
+        assert_eq!(progress[1].actual, 0.0);
+        assert_eq!(progress[1].status, GoalStatus::Under);
+    }
+
+    #[test]
+    fn test_goal_kind_parse() {
+        assert_eq!("MAX".parse::<GoalKind>(), Ok(GoalKind::Max));
+        assert_eq!("at-least".parse::<GoalKind>(), Ok(GoalKind::Min));
+        assert!("sometimes".parse::<GoalKind>().is_err());
+    }
+
+    #[test]
+    fn test_goal_json_defaults_to_target() {
+        let goal: NutritionGoal =
+            serde_json::from_str(r#"{"nutrient": "protein", "amount": 100, "unit": "g"}"#).unwrap();
+        assert_eq!(goal.kind, GoalKind::Target);
+    }
+}
@@ -310,6 +310,30 @@ impl Identity {
         self.deserialize_group_document(&bytes)
     }
 
+    /// Save a group document.
+    ///
+    /// The change is made on top of the stored document's history, so it
+    /// replaces the current contents on sync instead of conflicting with
+    /// them as an unrelated write would.
+    pub fn save_group(
+        &self,
+        group_doc_id: &DocumentId,
+        doc: &GroupDocument,
+    ) -> Result<(), IdentityError> {
+        let existing = self
+            .storage
+            .load(group_doc_id)
+            .map_err(IdentityError::StorageError)?;
+        let bytes = match existing {
+            Some(bytes) => self.update_group_document(&bytes, doc)?,
+            None => self.serialize_group_document(doc)?,
+        };
+        self.storage
+            .save(group_doc_id, &bytes)
+            .map_err(IdentityError::StorageError)?;
+        Ok(())
+    }
+
     /// Get the meallogs document ID for the current identity.
     pub fn meallogs_doc_id(&self) -> Result<DocumentId, IdentityError> {
         let identity = self.load_identity()?;
@@ -366,6 +390,28 @@ impl Identity {
         Ok(am_doc.save())
     }
 
+    fn update_group_document(
+        &self,
+        bytes: &[u8],
+        doc: &GroupDocument,
+    ) -> Result<Vec<u8>, IdentityError> {
+        let json = serde_json::to_string(doc).map_err(IdentityError::SerializationError)?;
+
+        let mut am_doc =
+            AutoCommit::load(bytes).map_err(|e| IdentityError::AutomergeError(e.to_string()))?;
+        let current: Option<String> = am_doc
+            .get(automerge::ROOT, "data")
+            .map_err(|e| IdentityError::AutomergeError(e.to_string()))?
+            .and_then(|(val, _)| val.into_string().ok());
+        if current.as_deref() != Some(json.as_str()) {
+            am_doc
+                .put(automerge::ROOT, "data", json)
+                .map_err(|e| IdentityError::AutomergeError(e.to_string()))?;
+        }
+
+        Ok(am_doc.save())
+    }
+
     fn deserialize_group_document(&self, bytes: &[u8]) -> Result<GroupDocument, IdentityError> {
         let am_doc =
             AutoCommit::load(bytes).map_err(|e| IdentityError::AutomergeError(e.to_string()))?;
@@ -565,6 +611,49 @@ mod tests {
         assert!(matches!(result, Err(IdentityError::NotInitialized)));
     }
 
+    #[test]
+    fn test_save_group() {
+        let (identity, _temp) = test_identity();
+        identity.initialize_new().unwrap();
+        let group_id = identity.create_group("Family").unwrap();
+
+        let mut group_doc = identity.load_group(&group_id).unwrap();
+        group_doc
+            .add_meal_slot("brunch".parse().unwrap(), None, None)
+            .unwrap();
+        identity.save_group(&group_id, &group_doc).unwrap();
+
+        let loaded = identity.load_group(&group_id).unwrap();
+        assert_eq!(loaded.meal_slots().len(), 5);
+        assert_eq!(loaded.dishes_doc_id, group_doc.dishes_doc_id);
+    }
+
+    #[test]
+    fn test_save_group_builds_on_history() {
+        let (identity, _temp) = test_identity();
+        identity.initialize_new().unwrap();
+        let group_id = identity.create_group("Family").unwrap();
+
+        // Another device holds the group as it was before the edit
+        let before = identity.storage.load(&group_id).unwrap().unwrap();
+        let mut replica = AutoCommit::load(&before).unwrap();
+
+        let mut group_doc = identity.load_group(&group_id).unwrap();
+        group_doc.week_start = chrono::Weekday::Mon;
+        identity.save_group(&group_id, &group_doc).unwrap();
+
+        // Syncing the edit replaces the old contents rather than conflicting
+        let after = identity.storage.load(&group_id).unwrap().unwrap();
+        replica
+            .merge(&mut AutoCommit::load(&after).unwrap())
+            .unwrap();
+        assert_eq!(replica.get_all(automerge::ROOT, "data").unwrap().len(), 1);
+        let merged = identity
+            .deserialize_group_document(&replica.save())
+            .unwrap();
+        assert_eq!(merged.week_start, chrono::Weekday::Mon);
+    }
+
     #[test]
     fn test_create_multiple_groups() {
         let (identity, _temp) = test_identity();
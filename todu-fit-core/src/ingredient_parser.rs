@@ -0,0 +1,405 @@
+//! Free-text ingredient line parsing
+//!
+//! Turns recipe lines such as "1 1/2 cups flour, sifted", "2-3 cloves garlic"
+//! or "salt to taste" into [`Ingredient`] values.
+//!
+//! - Quantities may be whole numbers, decimals, fractions ("1/2"), mixed
+//!   numbers ("1 1/2") or unicode fractions ("1½").
+//! - Ranges ("2-3", "2 to 3") use the upper bound, so shopping lists buy enough.
+//! - Text after the first comma, and trailing parentheticals, become the note.
+//! - Lines without a quantity ("salt to taste") get a quantity of 0.
+
+use thiserror::Error;
+
+use crate::models::Ingredient;
+use crate::units::Unit;
+
+/// Errors that can occur when parsing an ingredient line
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum IngredientParseError {
+    #[error("Ingredient line is empty")]
+    Empty,
+
+    #[error("No ingredient name found in '{0}'")]
+    MissingName(String),
+}
+
+/// Units that aren't convertible but are common in recipes.
+const COUNT_UNITS: &[&str] = &[
+    "bag", "bags", "bottle", "bottles", "box", "boxes", "bunch", "bunches", "can", "cans", "clove",
+    "cloves", "dash", "dashes", "handful", "handfuls", "head", "heads", "jar", "jars", "leaf",
+    "leaves", "package", "packages", "pkg", "pinch", "pinches", "slice", "slices", "sprig",
+    "sprigs", "stalk", "stalks", "stick", "sticks",
+];
+
+/// Phrases that mark an unmeasured ingredient when they end a line.
+const UNMEASURED_PHRASES: &[&str] = &["to taste", "as needed", "for garnish", "for serving"];
+
+/// Parse a single ingredient line.
+pub fn parse_ingredient_line(line: &str) -> Result<Ingredient, IngredientParseError> {
+    let normalized = normalize(line);
+    let text = normalized.trim().trim_start_matches(['-', '*', '•']).trim();
+
+    if text.is_empty() {
+        return Err(IngredientParseError::Empty);
+    }
+
+    // Split off the preparation note after the first comma
+    let (body, mut notes) = match text.split_once(',') {
+        Some((body, note)) => (body.trim().to_string(), vec![note.trim().to_string()]),
+        None => (text.to_string(), Vec::new()),
+    };
+
+    // "salt to taste", "parsley for garnish"
+    let mut body = body;
+    for phrase in UNMEASURED_PHRASES {
+        // Compare by characters, since lowercasing can change byte lengths
+        let cut = body
+            .char_indices()
+            .nth_back(phrase.chars().count() - 1)
+            .map(|(i, _)| i);
+        if let Some(cut) = cut.filter(|&cut| body[cut..].to_lowercase() == *phrase) {
+            notes.insert(0, body[cut..].to_string());
+            body = body[..cut].trim().to_string();
+            break;
+        }
+    }
+
+    let tokens: Vec<&str> = body.split_whitespace().collect();
+    let (quantity, mut rest) = parse_quantity(&tokens);
+
+    // A parenthetical right after the quantity describes the package: "1 (14 oz) can"
+    if rest.first().is_some_and(|t| t.starts_with('(')) {
+        let end = rest.iter().position(|t| t.ends_with(')')).unwrap_or(0);
+        let paren = rest[..=end].join(" ");
+        notes.push(paren.trim_matches(['(', ')']).to_string());
+        rest = &rest[end + 1..];
+    }
+
+    let (unit, rest) = if quantity.is_some() {
+        parse_unit(rest)
+    } else {
+        (String::new(), rest)
+    };
+
+    let rest = match rest.first() {
+        Some(t) if t.eq_ignore_ascii_case("of") => &rest[1..],
+        _ => rest,
+    };
+
+    // Trailing parenthetical on the name: "butter (softened)"
+    let mut name = rest.join(" ");
+    if name.ends_with(')') {
+        if let Some(start) = name.rfind('(') {
+            notes.push(name[start + 1..name.len() - 1].trim().to_string());
+            name = name[..start].trim().to_string();
+        }
+    }
+
+    if name.is_empty() {
+        return Err(IngredientParseError::MissingName(line.trim().to_string()));
+    }
+
+    let mut ingredient = Ingredient::new(name, quantity.unwrap_or(0.0), unit);
+    notes.retain(|n| !n.is_empty());
+    if !notes.is_empty() {
+        ingredient = ingredient.with_note(notes.join(", "));
+    }
+
+    Ok(ingredient)
+}
+
+/// Parse many ingredient lines, skipping blank lines and `#` comments.
+///
+/// Each result is paired with its 1-based line number.
+pub fn parse_ingredient_lines(
+    text: &str,
+) -> Vec<(usize, Result<Ingredient, IngredientParseError>)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.starts_with('#')
+        })
+        .map(|(i, line)| (i + 1, parse_ingredient_line(line)))
+        .collect()
+}
+
+/// Parse a standalone quantity such as "2", "1.5", "1 1/2", "½" or "2-3".
+///
+/// Returns `None` unless the whole text is a quantity.
+pub fn parse_quantity_text(text: &str) -> Option<f64> {
+    let normalized = normalize(text);
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    match parse_quantity(&tokens) {
+        (Some(quantity), []) => Some(quantity),
+        _ => None,
+    }
+}
+
+/// Replace unicode fractions and dashes with their ASCII forms.
+fn normalize(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    for c in line.chars() {
+        let replacement = match c {
+            '½' => " 1/2",
+            '⅓' => " 1/3",
+            '⅔' => " 2/3",
+            '¼' => " 1/4",
+            '¾' => " 3/4",
+            '⅕' => " 1/5",
+            '⅖' => " 2/5",
+            '⅗' => " 3/5",
+            '⅘' => " 4/5",
+            '⅙' => " 1/6",
+            '⅚' => " 5/6",
+            '⅛' => " 1/8",
+            '⅜' => " 3/8",
+            '⅝' => " 5/8",
+            '⅞' => " 7/8",
+            '⁄' => "/",
+            '–' | '—' => "-",
+            _ => {
+                out.push(c);
+                continue;
+            }
+        };
+        out.push_str(replacement);
+    }
+    out
+}
+
+/// Parse a single number: "2", "1.5", "1/2".
+fn parse_number(token: &str) -> Option<f64> {
+    if let Some((num, den)) = token.split_once('/') {
+        let num: f64 = num.parse().ok()?;
+        let den: f64 = den.parse().ok()?;
+        if den == 0.0 {
+            return None;
+        }
+        return Some(num / den);
+    }
+    token.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+/// Parse a number that may be followed by a fraction ("1 1/2").
+fn parse_amount<'a>(tokens: &'a [&'a str]) -> Option<(f64, &'a [&'a str])> {
+    let first = tokens.first()?;
+    let mut value = parse_number(first)?;
+    let mut rest = &tokens[1..];
+
+    if !first.contains('/') {
+        if let Some(frac) = rest.first().filter(|t| t.contains('/')) {
+            if let Some(f) = parse_number(frac) {
+                value += f;
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    Some((value, rest))
+}
+
+/// Parse a leading quantity, including ranges, returning the remaining tokens.
+fn parse_quantity<'a>(tokens: &'a [&'a str]) -> (Option<f64>, &'a [&'a str]) {
+    let Some(first) = tokens.first() else {
+        return (None, tokens);
+    };
+
+    if first.eq_ignore_ascii_case("a") || first.eq_ignore_ascii_case("an") {
+        return (Some(1.0), &tokens[1..]);
+    }
+
+    // Range written without spaces: "2-3"
+    if let Some((low, high)) = first.split_once('-') {
+        if let (Some(_), Some(high)) = (parse_number(low), parse_number(high)) {
+            return (Some(high), &tokens[1..]);
+        }
+    }
+
+    let Some((value, rest)) = parse_amount(tokens) else {
+        return (None, tokens);
+    };
+
+    // Range with separators: "2 - 3", "2 to 3"
+    if let Some(sep) = rest.first() {
+        if *sep == "-" || sep.eq_ignore_ascii_case("to") {
+            if let Some((high, after)) = parse_amount(&rest[1..]) {
+                return (Some(high), after);
+            }
+        }
+    }
+
+    (Some(value), rest)
+}
+
+/// Parse a leading unit (one or two words), returning the remaining tokens.
+fn parse_unit<'a>(tokens: &'a [&'a str]) -> (String, &'a [&'a str]) {
+    // Leave at least one token for the name
+    if tokens.len() >= 3 {
+        let two = format!("{} {}", tokens[0], tokens[1]);
+        if Unit::parse(&two).is_some() {
+            return (two.trim_end_matches('.').to_string(), &tokens[2..]);
+        }
+    }
+
+    if tokens.len() >= 2 {
+        let word = tokens[0].trim_end_matches('.');
+        let is_unit = !word.is_empty()
+            && (Unit::parse(word).is_some_and(|u| u != Unit::Each)
+                || COUNT_UNITS.contains(&word.to_lowercase().as_str()));
+        if is_unit {
+            return (word.to_string(), &tokens[1..]);
+        }
+    }
+
+    (String::new(), tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple() {
+        let ing = parse_ingredient_line("2 cups milk").unwrap();
+        assert_eq!(ing, Ingredient::new("milk", 2.0, "cups"));
+    }
+
+    #[test]
+    fn test_parse_mixed_number_with_note() {
+        let ing = parse_ingredient_line("1 1/2 cups flour, sifted").unwrap();
+        assert_eq!(ing.name, "flour");
+        assert_eq!(ing.quantity, 1.5);
+        assert_eq!(ing.unit, "cups");
+        assert_eq!(ing.note.as_deref(), Some("sifted"));
+    }
+
+    #[test]
+    fn test_parse_unicode_fraction() {
+        let ing = parse_ingredient_line("1½ tsp baking soda").unwrap();
+        assert_eq!(ing.quantity, 1.5);
+        assert_eq!(ing.unit, "tsp");
+        assert_eq!(ing.name, "baking soda");
+
+        let ing = parse_ingredient_line("¾ cup sugar").unwrap();
+        assert_eq!(ing.quantity, 0.75);
+    }
+
+    #[test]
+    fn test_parse_range_uses_upper_bound() {
+        let ing = parse_ingredient_line("2-3 cloves garlic").unwrap();
+        assert_eq!(ing.quantity, 3.0);
+        assert_eq!(ing.unit, "cloves");
+        assert_eq!(ing.name, "garlic");
+
+        let ing = parse_ingredient_line("1 to 2 tbsp olive oil").unwrap();
+        assert_eq!(ing.quantity, 2.0);
+        assert_eq!(ing.unit, "tbsp");
+
+        let ing = parse_ingredient_line("4 – 6 chicken thighs").unwrap();
+        assert_eq!(ing.quantity, 6.0);
+        assert_eq!(ing.unit, "");
+        assert_eq!(ing.name, "chicken thighs");
+    }
+
+    #[test]
+    fn test_parse_to_taste() {
+        let ing = parse_ingredient_line("salt to taste").unwrap();
+        assert_eq!(ing.name, "salt");
+        assert_eq!(ing.quantity, 0.0);
+        assert_eq!(ing.note.as_deref(), Some("to taste"));
+
+        let ing = parse_ingredient_line("Black pepper, to taste").unwrap();
+        assert_eq!(ing.name, "Black pepper");
+        assert_eq!(ing.note.as_deref(), Some("to taste"));
+
+        // Lowercasing "İ" adds a byte; the note still starts in the right place
+        let ing = parse_ingredient_line("İSOT PEPPER TO TASTE").unwrap();
+        assert_eq!(ing.name, "İSOT PEPPER");
+        assert_eq!(ing.note.as_deref(), Some("TO TASTE"));
+    }
+
+    #[test]
+    fn test_parse_no_unit() {
+        let ing = parse_ingredient_line("3 eggs").unwrap();
+        assert_eq!(ing, Ingredient::new("eggs", 3.0, ""));
+    }
+
+    #[test]
+    fn test_parse_article_and_of() {
+        let ing = parse_ingredient_line("a pinch of salt").unwrap();
+        assert_eq!(ing.quantity, 1.0);
+        assert_eq!(ing.unit, "pinch");
+        assert_eq!(ing.name, "salt");
+    }
+
+    #[test]
+    fn test_parse_two_word_unit() {
+        let ing = parse_ingredient_line("8 fl oz cream").unwrap();
+        assert_eq!(ing.unit, "fl oz");
+        assert_eq!(ing.name, "cream");
+    }
+
+    #[test]
+    fn test_parse_package_size() {
+        let ing = parse_ingredient_line("1 (14 oz) can diced tomatoes").unwrap();
+        assert_eq!(ing.quantity, 1.0);
+        assert_eq!(ing.unit, "can");
+        assert_eq!(ing.name, "diced tomatoes");
+        assert_eq!(ing.note.as_deref(), Some("14 oz"));
+    }
+
+    #[test]
+    fn test_parse_trailing_parenthetical() {
+        let ing = parse_ingredient_line("- 4 tbsp butter (softened)").unwrap();
+        assert_eq!(ing.name, "butter");
+        assert_eq!(ing.note.as_deref(), Some("softened"));
+    }
+
+    #[test]
+    fn test_parse_unit_word_as_name() {
+        // A lone unit-like word is the name, not the unit
+        let ing = parse_ingredient_line("2 cloves").unwrap();
+        assert_eq!(ing.name, "cloves");
+        assert_eq!(ing.unit, "");
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        let ing = parse_ingredient_line("0.5 kg potatoes").unwrap();
+        assert_eq!(ing.quantity, 0.5);
+        assert_eq!(ing.unit, "kg");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_ingredient_line("   "),
+            Err(IngredientParseError::Empty)
+        );
+        assert!(matches!(
+            parse_ingredient_line("2"),
+            Err(IngredientParseError::MissingName(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_quantity_text() {
+        assert_eq!(parse_quantity_text("1 1/2"), Some(1.5));
+        assert_eq!(parse_quantity_text("½"), Some(0.5));
+        assert_eq!(parse_quantity_text("2-3"), Some(3.0));
+        assert_eq!(parse_quantity_text("some"), None);
+        assert_eq!(parse_quantity_text("2 cups"), None);
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let text = "# Dough\n2 cups flour\n\n1 tsp salt\n";
+        let results = parse_ingredient_lines(text);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, 2);
+        assert_eq!(results[1].0, 4);
+        assert_eq!(results[1].1.as_ref().unwrap().name, "salt");
+    }
+}
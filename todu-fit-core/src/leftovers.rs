@@ -0,0 +1,101 @@
+//! Leftover meal plans
+//!
+//! A meal plan can eat the leftovers of another plan instead of cooking.
+//! The source plan's batch is bought and cooked once, and the servings its
+//! leftover plans eat are counted against it.
+
+use crate::models::{Dish, MealPlan};
+
+/// How much of a plan's cooking is eaten as leftovers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeftoverServings {
+    /// Servings the source plan cooks, if known
+    pub cooked: Option<i32>,
+    /// Servings eaten by leftover plans
+    pub eaten: i32,
+    /// Number of leftover plans
+    pub plans: usize,
+}
+
+impl LeftoverServings {
+    /// Cooked servings not eaten as leftovers, which includes those eaten
+    /// at the source meal itself. Negative when leftover plans claim more
+    /// than was cooked.
+    pub fn remaining(&self) -> Option<i32> {
+        self.cooked.map(|cooked| cooked - self.eaten)
+    }
+}
+
+impl MealPlan {
+    /// Servings this plan cooks: its own servings, or else those of the
+    /// first planned dish that has them.
+    pub fn cooked_servings(&self, dishes: &[Dish]) -> Option<i32> {
+        self.servings.or_else(|| {
+            self.dish_ids
+                .iter()
+                .filter_map(|id| dishes.iter().find(|d| d.id == *id))
+                .find_map(|d| d.servings)
+        })
+    }
+
+    /// Servings of this plan's cooking eaten by the leftover plans among
+    /// `plans`. A leftover plan without servings eats one.
+    pub fn leftover_servings(&self, plans: &[MealPlan], dishes: &[Dish]) -> LeftoverServings {
+        let leftovers: Vec<&MealPlan> = self.leftover_plans(plans).collect();
+        LeftoverServings {
+            cooked: self.cooked_servings(dishes),
+            eaten: leftovers.iter().map(|p| p.servings.unwrap_or(1)).sum(),
+            plans: leftovers.len(),
+        }
+    }
+
+    /// The plans among `plans` that eat this plan's leftovers.
+    pub fn leftover_plans<'a>(
+        &self,
+        plans: &'a [MealPlan],
+    ) -> impl Iterator<Item = &'a MealPlan> + 'a {
+        let id = self.id;
+        plans.iter().filter(move |p| p.leftovers_of == Some(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Ingredient, MealType};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_leftovers_share_the_source_batch() {
+        let chili = Dish::new("Chili", "user1")
+            .with_ingredients(vec![Ingredient::new("beans", 400.0, "g")])
+            .with_servings(4);
+        let date = NaiveDate::from_ymd_opt(2026, 1, 11).unwrap();
+        let dinner = MealPlan::new(date, MealType::Dinner, "Dinner", "user1")
+            .with_dish_ids(vec![chili.id])
+            .with_servings(6);
+        let lunch = MealPlan::new(date.succ_opt().unwrap(), MealType::Lunch, "Lunch", "user1")
+            .with_dish_ids(vec![chili.id])
+            .with_servings(2)
+            .with_leftovers_of(dinner.id);
+        let snack = MealPlan::new(date.succ_opt().unwrap(), MealType::Snack, "Snack", "user1")
+            .with_leftovers_of(dinner.id);
+        let dishes = vec![chili];
+
+        // The batch is bought for once, at the source plan's servings
+        assert_eq!(dinner.ingredients(&dishes).unwrap()[0].quantity, 600.0);
+        assert!(lunch.ingredients(&dishes).unwrap().is_empty());
+
+        let plans = vec![dinner.clone(), lunch, snack];
+        assert_eq!(dinner.leftover_plans(&plans).count(), 2);
+        let usage = dinner.leftover_servings(&plans, &dishes);
+        assert_eq!(usage.cooked, Some(6));
+        assert_eq!((usage.eaten, usage.plans), (3, 2));
+        assert_eq!(usage.remaining(), Some(3));
+
+        // Without plan servings, the dish's own servings count
+        let mut plain = dinner;
+        plain.servings = None;
+        assert_eq!(plain.cooked_servings(&dishes), Some(4));
+    }
+}
@@ -3,24 +3,52 @@
 //! Shared types and logic for Todu Fit applications.
 
 pub mod automerge;
+pub mod categories;
+pub mod composition;
 pub mod document_id;
 pub mod documents;
+pub mod formats;
+pub mod goals;
 pub mod identity;
+pub mod ingredient_parser;
+pub mod leftovers;
 pub mod models;
+pub mod nutrition_db;
+pub mod plan_generator;
+pub mod plan_ops;
+pub mod report;
+pub mod scaling;
 pub mod sync;
+pub mod text;
+pub mod units;
 
 pub use automerge::{
-    delete_dish, delete_meallog, delete_mealplan, delete_shopping_cart, write_dish, write_meallog,
-    write_mealplan, write_shopping_cart, DocType, DocumentStorage, MultiDocStorage,
+    commit_with_message, delete_dish, delete_meallog, delete_mealplan, delete_pantry_item,
+    delete_shopping_cart, pantry_item_stock, write_dish, write_meallog, write_mealplan,
+    write_pantry_item, write_shopping_cart, DocType, DocumentStorage, MultiDocStorage,
     MultiStorageError, StorageError,
 };
+pub use automerge::{HistoryError, Revision};
+pub use categories::StoreLayout;
+pub use composition::CompositionError;
 pub use document_id::{DocumentId, DocumentIdError};
 pub use documents::{GroupDocument, GroupRef, IdentityDocument};
+pub use goals::{evaluate_goals, GoalKind, GoalProgress, GoalStatus, NutritionGoal};
 pub use identity::{Identity, IdentityError, IdentityState};
+pub use ingredient_parser::{parse_ingredient_line, IngredientParseError};
+pub use leftovers::LeftoverServings;
 pub use models::{
-    Dish, Ingredient, ManualItem, MealLog, MealPlan, MealType, Nutrient, ShoppingCart, ShoppingItem,
+    CartPeriod, Dish, DishComponent, FoodEntry, Ingredient, ManualItem, MealLog, MealPlan,
+    MealSlot, MealType, Nutrient, Pantry, PantryItem, PlanTemplate, Recurrence, RecurringPlan,
+    ShoppingCart, ShoppingItem, TemplateEntry,
 };
+pub use nutrition_db::{Food, FoodDatabase, FoodDbError, NutritionCalculation};
+pub use plan_generator::{GeneratorOptions, Proposal, ProposedMeal, SlotRule};
+pub use plan_ops::{ConflictStrategy, PlanChanges, PlanConflict, PlanOpError};
+pub use report::{nutrition_report, GroupBy, NutritionReport};
+pub use scaling::ScaleError;
 pub use sync::{check_server, SyncClient, SyncError, SyncResult};
+pub use units::{Dimension, Unit, UnitError};
 
 pub fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -6,13 +6,34 @@ use uuid::Uuid;
 use super::ingredient::Ingredient;
 use super::nutrient::Nutrient;
 
+/// Another dish used inside a dish, such as a pizza dough or a curry base.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DishComponent {
+    pub dish_id: Uuid,
+    /// Batches of the component recipe used in one batch of this dish
+    pub scale: f64,
+}
+
+impl DishComponent {
+    pub fn new(dish_id: Uuid, scale: f64) -> Self {
+        Self { dish_id, scale }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Dish {
     pub id: Uuid,
     pub name: String,
     pub ingredients: Vec<Ingredient>,
+    /// Sub-recipes, expanded by [`Dish::flatten`]
+    #[serde(default)]
+    pub components: Vec<DishComponent>,
     pub instructions: String,
     pub nutrients: Option<Vec<Nutrient>>,
+    /// True when `nutrients` were calculated from the food database rather
+    /// than entered by hand, so they may be recalculated on edits
+    #[serde(default)]
+    pub nutrients_calculated: bool,
     pub prep_time: Option<i32>, // minutes
     pub cook_time: Option<i32>, // minutes
     pub servings: Option<i32>,
@@ -31,8 +52,10 @@ impl Dish {
             id: Uuid::new_v4(),
             name: name.into(),
             ingredients: Vec::new(),
+            components: Vec::new(),
             instructions: String::new(),
             nutrients: None,
+            nutrients_calculated: false,
             prep_time: None,
             cook_time: None,
             servings: None,
@@ -50,6 +73,11 @@ impl Dish {
         self
     }
 
+    pub fn with_components(mut self, components: Vec<DishComponent>) -> Self {
+        self.components = components;
+        self
+    }
+
     pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
         self.instructions = instructions.into();
         self
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::nutrient::Nutrient;
+
+/// An ad-hoc food eaten in a meal, such as a banana or a protein bar.
+///
+/// Food entries live only in the personal meal log, so one-off foods don't
+/// need a dish in the shared group collection. Nutrients are for the whole
+/// amount eaten.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FoodEntry {
+    pub name: String,
+    pub amount: f64,
+    pub unit: String,
+    #[serde(default)]
+    pub nutrients: Vec<Nutrient>,
+}
+
+impl FoodEntry {
+    pub fn new(name: impl Into<String>, amount: f64, unit: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            amount,
+            unit: unit.into(),
+            nutrients: Vec::new(),
+        }
+    }
+
+    pub fn with_nutrients(mut self, nutrients: Vec<Nutrient>) -> Self {
+        self.nutrients = nutrients;
+        self
+    }
+}
+
+impl fmt::Display for FoodEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.amount == 1.0 && self.unit.is_empty() {
+            write!(f, "{}", self.name)
+        } else if self.unit.is_empty() {
+            write!(f, "{} {}", self.amount, self.name)
+        } else {
+            write!(f, "{} {} {}", self.amount, self.unit, self.name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_food_entry_display() {
+        assert_eq!(format!("{}", FoodEntry::new("banana", 1.0, "")), "banana");
+        assert_eq!(format!("{}", FoodEntry::new("eggs", 2.0, "")), "2 eggs");
+        assert_eq!(
+            format!("{}", FoodEntry::new("almonds", 30.0, "g")),
+            "30 g almonds"
+        );
+    }
+
+    #[test]
+    fn test_food_entry_json_defaults_nutrients() {
+        let entry: FoodEntry =
+            serde_json::from_str(r#"{"name": "apple", "amount": 1, "unit": ""}"#).unwrap();
+        assert!(entry.nutrients.is_empty());
+    }
+}
@@ -6,6 +6,9 @@ pub struct Ingredient {
     pub name: String,
     pub quantity: f64,
     pub unit: String,
+    /// Preparation note, e.g. "sifted" or "finely chopped"
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 impl Ingredient {
@@ -14,17 +17,31 @@ impl Ingredient {
             name: name.into(),
             quantity,
             unit: unit.into(),
+            note: None,
         }
     }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
 }
 
 impl fmt::Display for Ingredient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.unit.is_empty() {
-            write!(f, "{} {}", self.quantity, self.name)
+        if self.quantity == 0.0 {
+            write!(f, "{}", self.name)?;
+        } else if self.unit.is_empty() {
+            write!(f, "{} {}", self.quantity, self.name)?;
         } else {
-            write!(f, "{} {} {}", self.quantity, self.unit, self.name)
+            write!(f, "{} {} {}", self.quantity, self.unit, self.name)?;
+        }
+
+        if let Some(note) = &self.note {
+            write!(f, ", {}", note)?;
         }
+
+        Ok(())
     }
 }
 
@@ -52,6 +69,25 @@ mod tests {
         assert_eq!(format!("{}", ingredient), "3 eggs");
     }
 
+    #[test]
+    fn test_ingredient_display_with_note() {
+        let ingredient = Ingredient::new("flour", 1.5, "cups").with_note("sifted");
+        assert_eq!(format!("{}", ingredient), "1.5 cups flour, sifted");
+    }
+
+    #[test]
+    fn test_ingredient_display_unmeasured() {
+        let ingredient = Ingredient::new("salt", 0.0, "").with_note("to taste");
+        assert_eq!(format!("{}", ingredient), "salt, to taste");
+    }
+
+    #[test]
+    fn test_ingredient_json_without_note() {
+        let parsed: Ingredient =
+            serde_json::from_str(r#"{"name":"sugar","quantity":1.0,"unit":"tbsp"}"#).unwrap();
+        assert_eq!(parsed.note, None);
+    }
+
     #[test]
     fn test_ingredient_json_roundtrip() {
         let ingredient = Ingredient::new("sugar", 1.0, "tbsp");
@@ -1,9 +1,11 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use uuid::Uuid;
 
 use super::dish::Dish;
+use super::food_entry::FoodEntry;
 use super::meal_type::MealType;
 
 /// A meal log represents what was actually eaten (vs MealPlan which is planned).
@@ -21,6 +23,13 @@ pub struct MealLog {
     /// Dish snapshots - full copies of dishes as they were when logged.
     /// These are not references; modifying the original dish won't affect logs.
     pub dishes: Vec<Dish>,
+    /// Servings eaten of each dish snapshot, keyed by dish id. Dishes without
+    /// an entry count as one serving.
+    #[serde(default)]
+    pub portions: HashMap<Uuid, f64>,
+    /// Ad-hoc foods eaten alongside (or instead of) dishes
+    #[serde(default)]
+    pub foods: Vec<FoodEntry>,
     pub notes: Option<String>,
     pub created_by: String,
     pub created_at: DateTime<Utc>,
@@ -34,6 +43,8 @@ impl MealLog {
             meal_type,
             mealplan_id: None,
             dishes: Vec::new(),
+            portions: HashMap::new(),
+            foods: Vec::new(),
             notes: None,
             created_by: created_by.into(),
             created_at: Utc::now(),
@@ -54,6 +65,70 @@ impl MealLog {
         self.notes = Some(notes.into());
         self
     }
+
+    pub fn with_foods(mut self, foods: Vec<FoodEntry>) -> Self {
+        self.foods = foods;
+        self
+    }
+
+    /// Set the servings eaten of a logged dish. A portion of 1 is the default
+    /// and isn't stored.
+    pub fn with_portion(mut self, dish_id: Uuid, portion: f64) -> Self {
+        self.set_portion(dish_id, portion);
+        self
+    }
+
+    pub fn set_portion(&mut self, dish_id: Uuid, portion: f64) {
+        if portion == 1.0 {
+            self.portions.remove(&dish_id);
+        } else {
+            self.portions.insert(dish_id, portion);
+        }
+    }
+
+    /// Servings eaten of a logged dish (1 unless set otherwise).
+    pub fn portion_of(&self, dish_id: Uuid) -> f64 {
+        self.portions.get(&dish_id).copied().unwrap_or(1.0)
+    }
+
+    /// Add a dish snapshot eaten in `portion` servings. Adding a dish that is
+    /// already logged adds to its portion instead of duplicating it.
+    pub fn add_dish(&mut self, dish: Dish, portion: f64) {
+        if self.dishes.iter().any(|d| d.id == dish.id) {
+            let total = self.portion_of(dish.id) + portion;
+            self.set_portion(dish.id, total);
+        } else {
+            self.set_portion(dish.id, portion);
+            self.dishes.push(dish);
+        }
+    }
+
+    /// Remove a dish snapshot and its portion. Returns false if the dish
+    /// wasn't logged.
+    pub fn remove_dish(&mut self, dish_id: Uuid) -> bool {
+        let before = self.dishes.len();
+        self.dishes.retain(|d| d.id != dish_id);
+        self.portions.remove(&dish_id);
+        self.dishes.len() != before
+    }
+
+    /// Total nutrients eaten in this meal: the dish snapshots weighted by
+    /// each dish's portion, plus any food entries.
+    pub fn nutrient_totals(&self) -> HashMap<String, f64> {
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        for dish in &self.dishes {
+            let portion = self.portion_of(dish.id);
+            for nutrient in dish.nutrients.iter().flatten() {
+                *totals.entry(nutrient.name.clone()).or_insert(0.0) += nutrient.amount * portion;
+            }
+        }
+        for food in &self.foods {
+            for nutrient in &food.nutrients {
+                *totals.entry(nutrient.name.clone()).or_insert(0.0) += nutrient.amount;
+            }
+        }
+        totals
+    }
 }
 
 impl fmt::Display for MealLog {
@@ -64,7 +139,17 @@ impl fmt::Display for MealLog {
         if !self.dishes.is_empty() {
             writeln!(f, "Dishes:")?;
             for dish in &self.dishes {
-                writeln!(f, "  - {}", dish.name)?;
+                match self.portions.get(&dish.id) {
+                    Some(portion) => writeln!(f, "  - {} x{}", dish.name, portion)?,
+                    None => writeln!(f, "  - {}", dish.name)?,
+                }
+            }
+        }
+
+        if !self.foods.is_empty() {
+            writeln!(f, "Foods:")?;
+            for food in &self.foods {
+                writeln!(f, "  - {}", food)?;
             }
         }
 
@@ -138,4 +223,101 @@ mod tests {
         assert_eq!(parsed.meal_type, log.meal_type);
         assert_eq!(parsed.notes, log.notes);
     }
+
+    #[test]
+    fn test_meal_log_nutrient_totals() {
+        use crate::models::Nutrient;
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let soup = Dish::new("Soup", "user1").with_nutrients(vec![
+            Nutrient::new("calories", 200.0, "kcal"),
+            Nutrient::new("protein", 10.0, "g"),
+        ]);
+        let bread = Dish::new("Bread", "user1")
+            .with_nutrients(vec![Nutrient::new("calories", 150.0, "kcal")]);
+        let water = Dish::new("Water", "user1");
+        let log =
+            MealLog::new(date, MealType::Lunch, "user1").with_dishes(vec![soup, bread, water]);
+
+        let totals = log.nutrient_totals();
+        assert_eq!(totals["calories"], 350.0);
+        assert_eq!(totals["protein"], 10.0);
+    }
+
+    #[test]
+    fn test_meal_log_portions() {
+        use crate::models::Nutrient;
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let lasagna = Dish::new("Lasagna", "user1")
+            .with_nutrients(vec![Nutrient::new("calories", 600.0, "kcal")]);
+        let rice = Dish::new("Rice", "user1")
+            .with_nutrients(vec![Nutrient::new("calories", 200.0, "kcal")]);
+        let (lasagna_id, rice_id) = (lasagna.id, rice.id);
+        let log = MealLog::new(date, MealType::Dinner, "user1")
+            .with_dishes(vec![lasagna, rice])
+            .with_portion(lasagna_id, 0.5)
+            .with_portion(rice_id, 3.0);
+
+        assert_eq!(log.portion_of(lasagna_id), 0.5);
+        assert_eq!(log.nutrient_totals()["calories"], 900.0);
+        assert!(format!("{}", log).contains("Lasagna x0.5"));
+
+        let log = log.with_portion(rice_id, 1.0);
+        assert!(!log.portions.contains_key(&rice_id));
+        assert_eq!(log.portion_of(rice_id), 1.0);
+    }
+
+    #[test]
+    fn test_meal_log_json_without_portions() {
+        let json = r#"{
+            "id": "00000000-0000-0000-0000-000000000001",
+            "date": "2025-01-01",
+            "meal_type": "lunch",
+            "mealplan_id": null,
+            "dishes": [],
+            "notes": null,
+            "created_by": "user1",
+            "created_at": "2025-01-01T12:00:00Z"
+        }"#;
+        let log: MealLog = serde_json::from_str(json).unwrap();
+        assert!(log.portions.is_empty());
+        assert!(log.foods.is_empty());
+    }
+
+    #[test]
+    fn test_meal_log_food_entries() {
+        use crate::models::Nutrient;
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let toast = Dish::new("Toast", "user1")
+            .with_nutrients(vec![Nutrient::new("calories", 150.0, "kcal")]);
+        let banana = FoodEntry::new("banana", 1.0, "")
+            .with_nutrients(vec![Nutrient::new("calories", 105.0, "kcal")]);
+        let log = MealLog::new(date, MealType::Breakfast, "user1")
+            .with_dishes(vec![toast])
+            .with_foods(vec![banana]);
+
+        assert_eq!(log.nutrient_totals()["calories"], 255.0);
+        assert!(format!("{}", log).contains("Foods:\n  - banana"));
+    }
+
+    #[test]
+    fn test_meal_log_add_and_remove_dish() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let rice = Dish::new("Rice", "user1");
+        let curry = Dish::new("Curry", "user1");
+        let mut log = MealLog::new(date, MealType::Dinner, "user1");
+
+        log.add_dish(rice.clone(), 1.0);
+        log.add_dish(curry.clone(), 1.0);
+        log.add_dish(rice.clone(), 2.0);
+        assert_eq!(log.dishes.len(), 2);
+        assert_eq!(log.portion_of(rice.id), 3.0);
+
+        assert!(log.remove_dish(rice.id));
+        assert!(!log.remove_dish(rice.id));
+        assert!(log.portions.is_empty());
+        assert_eq!(log.dishes[0].name, "Curry");
+    }
 }
@@ -19,6 +19,13 @@ pub struct MealPlan {
     pub cook: String,
     /// References to dishes by UUID (resolved at display time)
     pub dish_ids: Vec<Uuid>,
+    /// Servings being cooked (overrides each dish's own servings when set)
+    #[serde(default)]
+    pub servings: Option<i32>,
+    /// The plan whose cooking this meal eats the leftovers of. A leftover
+    /// plan's servings are what it eats from that batch.
+    #[serde(default)]
+    pub leftovers_of: Option<Uuid>,
     pub created_by: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -39,6 +46,8 @@ impl MealPlan {
             title: title.into(),
             cook: "Unknown".to_string(),
             dish_ids: Vec::new(),
+            servings: None,
+            leftovers_of: None,
             created_by: created_by.into(),
             created_at: now,
             updated_at: now,
@@ -56,6 +65,23 @@ impl MealPlan {
         self
     }
 
+    /// Set how many servings are being cooked for this meal.
+    pub fn with_servings(mut self, servings: i32) -> Self {
+        self.servings = Some(servings);
+        self
+    }
+
+    /// Mark this meal as leftovers of another plan.
+    pub fn with_leftovers_of(mut self, plan_id: Uuid) -> Self {
+        self.leftovers_of = Some(plan_id);
+        self
+    }
+
+    /// Whether this meal is eaten from another plan's cooking.
+    pub fn is_leftovers(&self) -> bool {
+        self.leftovers_of.is_some()
+    }
+
     /// Add a dish to this meal plan by ID.
     pub fn add_dish(&mut self, dish_id: Uuid) {
         if !self.dish_ids.contains(&dish_id) {
@@ -85,6 +111,14 @@ impl fmt::Display for MealPlan {
         writeln!(f, "Meal: {}", self.meal_type)?;
         writeln!(f, "Cook: {}", self.cook)?;
 
+        if let Some(servings) = self.servings {
+            writeln!(f, "Servings: {}", servings)?;
+        }
+
+        if let Some(source) = self.leftovers_of {
+            writeln!(f, "Leftovers of: {}", source)?;
+        }
+
         if !self.dish_ids.is_empty() {
             writeln!(f, "\nDishes: {} dish(es)", self.dish_ids.len())?;
         }
@@ -128,6 +162,41 @@ mod tests {
         assert_eq!(plan.dish_ids[0], dish_id);
     }
 
+    #[test]
+    fn test_meal_plan_with_servings() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let plan = MealPlan::new(date, MealType::Dinner, "Dinner", "user1").with_servings(6);
+
+        assert_eq!(plan.servings, Some(6));
+        assert!(format!("{}", plan).contains("Servings: 6"));
+    }
+
+    #[test]
+    fn test_meal_plan_json_without_servings() {
+        // Plans serialized before servings existed still deserialize
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let plan = MealPlan::new(date, MealType::Dinner, "Dinner", "user1");
+        let mut value = serde_json::to_value(&plan).unwrap();
+        value.as_object_mut().unwrap().remove("servings");
+        value.as_object_mut().unwrap().remove("leftovers_of");
+
+        let parsed: MealPlan = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.servings, None);
+        assert_eq!(parsed.leftovers_of, None);
+    }
+
+    #[test]
+    fn test_meal_plan_with_leftovers_of() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let dinner = MealPlan::new(date, MealType::Dinner, "Dinner", "user1");
+        let lunch = MealPlan::new(date.succ_opt().unwrap(), MealType::Lunch, "Lunch", "user1")
+            .with_leftovers_of(dinner.id);
+
+        assert!(!dinner.is_leftovers());
+        assert!(lunch.is_leftovers());
+        assert_eq!(lunch.leftovers_of, Some(dinner.id));
+    }
+
     #[test]
     fn test_meal_plan_add_dish() {
         let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
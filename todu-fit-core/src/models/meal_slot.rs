@@ -0,0 +1,107 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use super::meal_type::MealType;
+
+/// A meal slot defined by a group: a meal type with a display order and an
+/// optional default time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MealSlot {
+    pub meal_type: MealType,
+    /// Position in the day, lowest first
+    pub order: u32,
+    /// Usual time for the meal, used as a default by calendar exports
+    #[serde(default)]
+    pub default_time: Option<NaiveTime>,
+}
+
+impl MealSlot {
+    pub fn new(meal_type: MealType, order: u32) -> Self {
+        Self {
+            meal_type,
+            order,
+            default_time: None,
+        }
+    }
+
+    pub fn with_default_time(mut self, time: NaiveTime) -> Self {
+        self.default_time = Some(time);
+        self
+    }
+
+    /// Parse a meal type name and check it is one of `slots`.
+    pub fn resolve(slots: &[MealSlot], name: &str) -> Result<MealType, String> {
+        let meal_type: MealType = name.parse()?;
+        if slots.iter().any(|s| s.meal_type == meal_type) {
+            return Ok(meal_type);
+        }
+        let names: Vec<&str> = slots.iter().map(|s| s.meal_type.as_str()).collect();
+        Err(format!(
+            "Unknown meal type '{}'. Valid options: {}",
+            name,
+            names.join(", ")
+        ))
+    }
+
+    /// Display position of `meal_type` among `slots`; unknown types sort last.
+    pub fn rank(slots: &[MealSlot], meal_type: &MealType) -> usize {
+        slots
+            .iter()
+            .position(|s| &s.meal_type == meal_type)
+            .unwrap_or(slots.len())
+    }
+
+    /// Slots for the four built-in meal types, used by groups that haven't
+    /// defined their own.
+    pub fn builtin() -> Vec<MealSlot> {
+        let times = [(8, 0), (12, 0), (18, 0), (15, 0)];
+        MealType::BUILTIN
+            .into_iter()
+            .zip(times)
+            .enumerate()
+            .map(|(order, (meal_type, (hour, minute)))| {
+                let slot = MealSlot::new(meal_type, order as u32);
+                match NaiveTime::from_hms_opt(hour, minute, 0) {
+                    Some(time) => slot.with_default_time(time),
+                    None => slot,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_slots() {
+        let slots = MealSlot::builtin();
+        assert_eq!(slots.len(), 4);
+        assert_eq!(slots[0].meal_type, MealType::Breakfast);
+        assert_eq!(slots[3].meal_type, MealType::Snack);
+        assert_eq!(slots[3].order, 3);
+        assert_eq!(slots[2].default_time, NaiveTime::from_hms_opt(18, 0, 0));
+    }
+
+    #[test]
+    fn test_resolve_meal_type() {
+        let slots = MealSlot::builtin();
+        assert_eq!(MealSlot::resolve(&slots, "LUNCH"), Ok(MealType::Lunch));
+
+        let err = MealSlot::resolve(&slots, "brunch").unwrap_err();
+        assert!(err.contains("breakfast, lunch, dinner, snack"));
+    }
+
+    #[test]
+    fn test_meal_slot_json_roundtrip() {
+        let slot = MealSlot::new("Pre-Workout".parse().unwrap(), 2)
+            .with_default_time(NaiveTime::from_hms_opt(6, 30, 0).unwrap());
+
+        let json = serde_json::to_string(&slot).unwrap();
+        assert!(json.contains("\"pre-workout\""));
+
+        let parsed: MealSlot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, slot);
+    }
+}
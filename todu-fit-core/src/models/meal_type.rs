@@ -1,44 +1,85 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+/// The meal a plan or log belongs to.
+///
+/// The four built-in types are always understood. Groups can define their
+/// own meal slots ("brunch", "pre-workout"), which parse as [`MealType::Custom`].
+/// Serialized as the lowercase name in every case.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MealType {
     Breakfast,
     Lunch,
     Dinner,
     Snack,
+    /// A group-defined meal slot; the name is lowercase with single spaces
+    Custom(String),
 }
 
-impl fmt::Display for MealType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl MealType {
+    /// The built-in meal types, in their default display order.
+    pub const BUILTIN: [MealType; 4] = [
+        MealType::Breakfast,
+        MealType::Lunch,
+        MealType::Dinner,
+        MealType::Snack,
+    ];
+
+    pub fn as_str(&self) -> &str {
         match self {
-            MealType::Breakfast => write!(f, "breakfast"),
-            MealType::Lunch => write!(f, "lunch"),
-            MealType::Dinner => write!(f, "dinner"),
-            MealType::Snack => write!(f, "snack"),
+            MealType::Breakfast => "breakfast",
+            MealType::Lunch => "lunch",
+            MealType::Dinner => "dinner",
+            MealType::Snack => "snack",
+            MealType::Custom(name) => name,
         }
     }
+
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, MealType::Custom(_))
+    }
+}
+
+impl fmt::Display for MealType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
 }
 
 impl FromStr for MealType {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
+        let name = s
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        match name.as_str() {
+            "" => Err("Meal type cannot be empty".to_string()),
             "breakfast" => Ok(MealType::Breakfast),
             "lunch" => Ok(MealType::Lunch),
             "dinner" => Ok(MealType::Dinner),
             "snack" => Ok(MealType::Snack),
-            _ => Err(format!(
-                "Invalid meal type '{}'. Valid options: breakfast, lunch, dinner, snack",
-                s
-            )),
+            _ => Ok(MealType::Custom(name)),
         }
     }
 }
 
+impl Serialize for MealType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MealType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,10 +103,24 @@ mod tests {
         assert_eq!(MealType::from_str("snack").unwrap(), MealType::Snack);
     }
 
+    #[test]
+    fn test_meal_type_from_str_custom() {
+        assert_eq!(
+            MealType::from_str("brunch").unwrap(),
+            MealType::Custom("brunch".to_string())
+        );
+        assert_eq!(
+            MealType::from_str("  Second   Breakfast ").unwrap(),
+            MealType::Custom("second breakfast".to_string())
+        );
+        assert!(!MealType::from_str("pre-workout").unwrap().is_builtin());
+        assert!(MealType::from_str("Snack").unwrap().is_builtin());
+    }
+
     #[test]
     fn test_meal_type_from_str_invalid() {
-        assert!(MealType::from_str("brunch").is_err());
         assert!(MealType::from_str("").is_err());
+        assert!(MealType::from_str("   ").is_err());
     }
 
     #[test]
@@ -77,4 +132,15 @@ mod tests {
         let parsed: MealType = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed, meal_type);
     }
+
+    #[test]
+    fn test_custom_meal_type_json_roundtrip() {
+        let meal_type = MealType::Custom("second breakfast".to_string());
+        let json = serde_json::to_string(&meal_type).unwrap();
+        assert_eq!(json, "\"second breakfast\"");
+
+        let parsed: MealType = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, meal_type);
+        assert!(serde_json::from_str::<MealType>("\"\"").is_err());
+    }
 }
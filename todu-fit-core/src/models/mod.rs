@@ -1,15 +1,25 @@
 mod dish;
+mod food_entry;
 mod ingredient;
 mod meal_log;
 mod meal_plan;
+mod meal_slot;
 mod meal_type;
 mod nutrient;
+mod pantry;
+mod plan_template;
+mod recurring_plan;
 mod shopping_cart;
 
-pub use dish::Dish;
+pub use dish::{Dish, DishComponent};
+pub use food_entry::FoodEntry;
 pub use ingredient::Ingredient;
 pub use meal_log::MealLog;
 pub use meal_plan::MealPlan;
+pub use meal_slot::MealSlot;
 pub use meal_type::MealType;
 pub use nutrient::Nutrient;
-pub use shopping_cart::{ManualItem, ShoppingCart, ShoppingItem};
+pub use pantry::{Pantry, PantryItem};
+pub use plan_template::{default_title, PlanTemplate, TemplateEntry};
+pub use recurring_plan::{expand_recurring, Frequency, Recurrence, RecurringPlan};
+pub use shopping_cart::{CartPeriod, ManualItem, ShoppingCart, ShoppingItem};
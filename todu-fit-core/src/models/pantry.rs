@@ -0,0 +1,244 @@
+//! Pantry inventory shared by a group.
+//!
+//! The pantry tracks ingredients on hand so the shopping list only asks for
+//! what is missing. Staples (salt, oil, ...) are assumed to always be in
+//! stock and never appear on the shopping list.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::Ingredient;
+use crate::scaling::format_kitchen_quantity;
+use crate::units;
+
+/// Quantities at or below this are treated as used up.
+const EPSILON: f64 = 1e-6;
+
+/// An ingredient kept in the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PantryItem {
+    /// Ingredient name as entered
+    pub name: String,
+    /// Amount on hand
+    pub quantity: f64,
+    /// Unit of the quantity (may be empty for counted items)
+    pub unit: String,
+    /// Always in stock; excluded from shopping lists and never used up
+    #[serde(default)]
+    pub staple: bool,
+}
+
+impl PantryItem {
+    /// Create a pantry item with a stock amount.
+    pub fn new(name: impl Into<String>, quantity: f64, unit: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            quantity,
+            unit: unit.into(),
+            staple: false,
+        }
+    }
+
+    /// Create a staple item.
+    pub fn staple(name: impl Into<String>) -> Self {
+        Self {
+            staple: true,
+            ..Self::new(name, 0.0, "")
+        }
+    }
+
+    /// Key used to store the item, the lowercased name.
+    pub fn key(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    /// Convert a quantity of this ingredient into the item's unit.
+    fn to_own_unit(&self, quantity: f64, unit: &str) -> Option<f64> {
+        if self.unit.eq_ignore_ascii_case(unit) {
+            return Some(quantity);
+        }
+        units::convert_ingredient(&self.name, quantity, unit, &self.unit).ok()
+    }
+}
+
+impl fmt::Display for PantryItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.staple {
+            return write!(f, "{} (staple)", self.name);
+        }
+        let quantity = format_kitchen_quantity(self.quantity);
+        if self.unit.is_empty() {
+            write!(f, "{} {}", quantity, self.name)
+        } else {
+            write!(f, "{} {} {}", quantity, self.unit, self.name)
+        }
+    }
+}
+
+/// The group's pantry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Pantry {
+    pub items: Vec<PantryItem>,
+}
+
+impl Pantry {
+    /// Create a pantry from its items.
+    pub fn new(items: Vec<PantryItem>) -> Self {
+        Self { items }
+    }
+
+    /// Find an item by name (case-insensitive).
+    pub fn find(&self, name: &str) -> Option<&PantryItem> {
+        let key = name.to_lowercase();
+        self.items.iter().find(|i| i.key() == key)
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut PantryItem> {
+        let key = name.to_lowercase();
+        self.items.iter_mut().find(|i| i.key() == key)
+    }
+
+    /// Whether an ingredient is a staple.
+    pub fn is_staple(&self, name: &str) -> bool {
+        self.find(name).is_some_and(|i| i.staple)
+    }
+
+    /// Add stock, converting into the unit already stored for the item.
+    ///
+    /// An item that is out of stock takes the new unit. Returns the updated
+    /// item, or `None` if the units can't be converted.
+    pub fn add(&mut self, name: &str, quantity: f64, unit: &str) -> Option<&PantryItem> {
+        if self.find(name).is_none() {
+            self.items.push(PantryItem::new(name, quantity, unit));
+            return self.find(name);
+        }
+
+        let item = self.find_mut(name)?;
+        if item.quantity <= EPSILON && !item.unit.eq_ignore_ascii_case(unit) {
+            item.quantity = quantity;
+            item.unit = unit.to_string();
+        } else {
+            item.quantity += item.to_own_unit(quantity, unit)?;
+        }
+        Some(item)
+    }
+
+    /// Use up stock, never going below zero. Staples are left untouched.
+    ///
+    /// Returns the updated item, or `None` if the item isn't stocked or the
+    /// units can't be converted.
+    pub fn consume(&mut self, name: &str, quantity: f64, unit: &str) -> Option<&PantryItem> {
+        let item = self.find_mut(name)?;
+        if !item.staple {
+            let used = item.to_own_unit(quantity, unit)?;
+            item.quantity = (item.quantity - used).max(0.0);
+        }
+        Some(item)
+    }
+
+    /// Remove an item. Returns true if it was in the pantry.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let key = name.to_lowercase();
+        let before = self.items.len();
+        self.items.retain(|i| i.key() != key);
+        self.items.len() != before
+    }
+
+    /// What still needs buying for `ingredients` after using pantry stock.
+    ///
+    /// Staples are dropped, stocked items are reduced by the amount on hand
+    /// and dropped when fully covered. Lines with the same name draw on the
+    /// same stock, so it is only counted once. Stock in a unit that can't
+    /// be converted is ignored.
+    pub fn subtract(&self, ingredients: &[Ingredient]) -> Vec<Ingredient> {
+        let mut stock = self.clone();
+        ingredients
+            .iter()
+            .filter_map(|ing| {
+                let Some(item) = stock.find_mut(&ing.name) else {
+                    return Some(ing.clone());
+                };
+                if item.staple {
+                    return None;
+                }
+                let needed = match item.to_own_unit(ing.quantity, &ing.unit) {
+                    Some(needed) => needed,
+                    None => return Some(ing.clone()),
+                };
+                let missing = needed - item.quantity;
+                item.quantity = (-missing).max(0.0);
+                if missing <= EPSILON {
+                    return None;
+                }
+                // Keep the ingredient's own unit for the shopping list
+                let mut remaining = ing.clone();
+                remaining.quantity = ing.quantity * missing / needed;
+                Some(remaining)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_consume_converts_units() {
+        let mut pantry = Pantry::default();
+        pantry.add("Olive Oil", 500.0, "ml");
+        let item = pantry.add("olive oil", 1.0, "l").unwrap();
+        assert_eq!(item.quantity, 1500.0);
+
+        let item = pantry.consume("OLIVE OIL", 2.0, "l").unwrap();
+        assert_eq!(item.quantity, 0.0);
+
+        // Out of stock items take the next unit added
+        let item = pantry.add("olive oil", 2.0, "cup").unwrap();
+        assert_eq!(item.unit, "cup");
+        assert!(pantry.add("olive oil", 3.0, "cloves").is_none());
+    }
+
+    #[test]
+    fn test_subtract_reduces_shopping_list() {
+        let mut pantry = Pantry::new(vec![
+            PantryItem::new("eggs", 4.0, ""),
+            PantryItem::new("rice", 1.0, "kg"),
+            PantryItem::staple("salt"),
+        ]);
+        pantry.add("garlic", 2.0, "cloves");
+
+        let needed = pantry.subtract(&[
+            Ingredient::new("Eggs", 6.0, ""),
+            Ingredient::new("rice", 500.0, "g"),
+            Ingredient::new("salt", 1.0, "tsp"),
+            Ingredient::new("garlic", 1.0, "tbsp"),
+            Ingredient::new("milk", 1.0, "cup"),
+        ]);
+
+        let names: Vec<_> = needed.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["Eggs", "garlic", "milk"]);
+        assert_eq!(needed[0].quantity, 2.0);
+        assert_eq!(needed[1].unit, "tbsp");
+
+        // Two recipes asking for rice share the kilo on hand
+        let needed = pantry.subtract(&[
+            Ingredient::new("rice", 600.0, "g"),
+            Ingredient::new("Rice", 600.0, "g"),
+        ]);
+        assert_eq!(needed.len(), 1);
+        assert!((needed[0].quantity - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_staples_are_never_used_up() {
+        let mut pantry = Pantry::new(vec![PantryItem::staple("Salt")]);
+        assert!(pantry.is_staple("salt"));
+        let item = pantry.consume("salt", 1.0, "tbsp").unwrap();
+        assert!(item.staple);
+        assert_eq!(item.to_string(), "Salt (staple)");
+
+        assert!(pantry.remove("SALT"));
+        assert!(!pantry.is_staple("salt"));
+    }
+}
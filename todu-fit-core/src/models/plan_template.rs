@@ -0,0 +1,168 @@
+//! Weekly meal plan templates.
+//!
+//! A template captures a week of meal plans by weekday and meal slot so a
+//! household can re-create a rotating week of meals in one step.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{MealPlan, MealType};
+use crate::text::capitalize;
+
+/// One planned meal in a template.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateEntry {
+    /// Day of the week the meal is on
+    pub weekday: Weekday,
+    /// Meal slot
+    pub meal_type: MealType,
+    /// Custom title; `None` uses the default "<Meal> on <date>"
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Who cooks
+    pub cook: String,
+    /// Dishes by ID
+    pub dish_ids: Vec<Uuid>,
+    /// Servings being cooked
+    #[serde(default)]
+    pub servings: Option<i32>,
+}
+
+/// A named week of meal plans, stored in the group document.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanTemplate {
+    /// Template name (e.g. "week-a")
+    pub name: String,
+    /// Meals, ordered by day of the week starting on the week's first day
+    pub entries: Vec<TemplateEntry>,
+}
+
+impl PlanTemplate {
+    /// Capture the plans of the week starting on `week_start`.
+    ///
+    /// Plans outside the seven days are ignored. Default titles are not
+    /// kept, since they name the original date.
+    pub fn from_week(name: impl Into<String>, week_start: NaiveDate, plans: &[MealPlan]) -> Self {
+        let week_end = week_start + Duration::days(6);
+        let mut plans: Vec<&MealPlan> = plans
+            .iter()
+            .filter(|p| p.date >= week_start && p.date <= week_end)
+            .collect();
+        plans.sort_by_key(|p| p.date);
+
+        let entries = plans
+            .into_iter()
+            .map(|plan| TemplateEntry {
+                weekday: plan.date.weekday(),
+                meal_type: plan.meal_type.clone(),
+                title: (plan.title != default_title(&plan.meal_type, plan.date))
+                    .then(|| plan.title.clone()),
+                cook: plan.cook.clone(),
+                dish_ids: plan.dish_ids.clone(),
+                servings: plan.servings,
+            })
+            .collect();
+
+        Self {
+            name: name.into(),
+            entries,
+        }
+    }
+
+    /// New meal plans for the week starting on `week_start`.
+    ///
+    /// Each entry lands on the day of that week with its weekday, so a
+    /// template can be applied to weeks starting on any day.
+    pub fn plans_for_week(&self, week_start: NaiveDate, created_by: &str) -> Vec<MealPlan> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let offset = entry.weekday.days_since(week_start.weekday());
+                let date = week_start + Duration::days(offset as i64);
+                let title = entry
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| default_title(&entry.meal_type, date));
+
+                let mut plan = MealPlan::new(date, entry.meal_type.clone(), title, created_by)
+                    .with_cook(&entry.cook)
+                    .with_dish_ids(entry.dish_ids.clone());
+                plan.servings = entry.servings;
+                plan
+            })
+            .collect()
+    }
+}
+
+/// The title a plan gets when none is given ("Dinner on 2026-01-11").
+pub fn default_title(meal_type: &MealType, date: NaiveDate) -> String {
+    format!("{} on {}", capitalize(meal_type.as_str()), date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_from_week_and_apply() {
+        let dish = Uuid::new_v4();
+        let monday = date("2026-01-12");
+        let plans = vec![
+            MealPlan::new(
+                monday,
+                MealType::Dinner,
+                default_title(&MealType::Dinner, monday),
+                "alice",
+            )
+            .with_cook("Bob")
+            .with_dish_ids(vec![dish])
+            .with_servings(4),
+            MealPlan::new(date("2026-01-11"), MealType::Lunch, "Picnic", "alice"),
+            // Next week, not captured
+            MealPlan::new(date("2026-01-18"), MealType::Lunch, "Later", "alice"),
+        ];
+
+        let template = PlanTemplate::from_week("week-a", date("2026-01-11"), &plans);
+        assert_eq!(template.entries.len(), 2);
+        assert_eq!(template.entries[0].weekday, Weekday::Sun);
+        assert_eq!(template.entries[0].title.as_deref(), Some("Picnic"));
+        assert_eq!(template.entries[1].title, None);
+
+        // Applied to a week starting on a Thursday, Monday is the fifth day
+        let applied = template.plans_for_week(date("2026-02-05"), "carol");
+        assert_eq!(applied[0].date, date("2026-02-08"));
+        assert_eq!(applied[0].title, "Picnic");
+        let dinner = &applied[1];
+        assert_eq!(dinner.date, date("2026-02-09"));
+        assert_eq!(dinner.title, "Dinner on 2026-02-09");
+        assert_eq!(dinner.cook, "Bob");
+        assert_eq!(dinner.dish_ids, vec![dish]);
+        assert_eq!(dinner.servings, Some(4));
+        assert_eq!(dinner.created_by, "carol");
+        assert_ne!(dinner.id, plans[0].id);
+    }
+
+    #[test]
+    fn test_template_json_roundtrip() {
+        let template = PlanTemplate {
+            name: "week-b".to_string(),
+            entries: vec![TemplateEntry {
+                weekday: Weekday::Fri,
+                meal_type: "brunch".parse().unwrap(),
+                title: None,
+                cook: "Alice".to_string(),
+                dish_ids: vec![Uuid::new_v4()],
+                servings: None,
+            }],
+        };
+
+        let json = serde_json::to_string(&template).unwrap();
+        let parsed: PlanTemplate = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, template);
+    }
+}
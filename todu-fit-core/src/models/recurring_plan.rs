@@ -0,0 +1,540 @@
+//! Recurring meal plans.
+//!
+//! A recurring plan ("taco Tuesday", "pizza every other Friday") is stored
+//! once in the group document and expanded into concrete [`MealPlan`]
+//! occurrences when a date range is listed.
+//!
+//! Each occurrence has an ID derived from the series ID and its date, so the
+//! same occurrence always gets the same ID. Saving a meal plan with that ID
+//! overrides the occurrence; adding its date to [`RecurringPlan::skipped`]
+//! drops it. Neither changes the rest of the series.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::{Builder, Uuid};
+
+use super::{default_title, MealPlan, MealType};
+
+/// Which days a recurrence falls on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "freq", rename_all = "lowercase")]
+pub enum Frequency {
+    /// On each of `weekdays`; the start date's weekday when empty
+    Weekly { weekdays: Vec<Weekday> },
+    /// The `nth` `weekday` of the month; negative counts from the end,
+    /// so -1 is the last
+    Monthly { nth: i8, weekday: Weekday },
+}
+
+/// A recurrence rule, a subset of iCalendar RRULE.
+///
+/// Written as `FREQ=WEEKLY;INTERVAL=2;BYDAY=FR` or
+/// `FREQ=MONTHLY;BYDAY=-1SU;UNTIL=20261231`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Recurrence {
+    #[serde(flatten)]
+    pub frequency: Frequency,
+    /// Repeat every `interval` weeks or months
+    pub interval: u32,
+    /// Last date an occurrence can fall on
+    #[serde(default)]
+    pub until: Option<NaiveDate>,
+}
+
+impl Recurrence {
+    /// Every week on `weekdays`.
+    pub fn weekly(weekdays: Vec<Weekday>) -> Self {
+        Self {
+            frequency: Frequency::Weekly { weekdays },
+            interval: 1,
+            until: None,
+        }
+    }
+
+    /// The `nth` `weekday` of every month.
+    pub fn monthly(nth: i8, weekday: Weekday) -> Self {
+        Self {
+            frequency: Frequency::Monthly { nth, weekday },
+            interval: 1,
+            until: None,
+        }
+    }
+
+    /// Repeat every `interval` weeks or months instead of every one.
+    pub fn every(mut self, interval: u32) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Stop after `until`.
+    pub fn until(mut self, until: NaiveDate) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Occurrence dates from `start` (the series' first day) that fall in
+    /// `from..=to`, in order.
+    pub fn dates(&self, start: NaiveDate, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let from = from.max(start);
+        let to = self.until.map_or(to, |until| to.min(until));
+        if from > to {
+            return Vec::new();
+        }
+        let interval = self.interval.max(1);
+
+        match &self.frequency {
+            Frequency::Weekly { weekdays } => {
+                let weekdays = if weekdays.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    weekdays.clone()
+                };
+                // Count weeks from the Monday of the start date's week
+                let first_monday =
+                    start - Duration::days(start.weekday().num_days_from_monday() as i64);
+                from.iter_days()
+                    .take_while(|d| *d <= to)
+                    .filter(|d| weekdays.contains(&d.weekday()))
+                    .filter(|d| ((*d - first_monday).num_days() / 7) % interval as i64 == 0)
+                    .collect()
+            }
+            Frequency::Monthly { nth, weekday } => {
+                let month_index = |d: NaiveDate| d.year() * 12 + d.month0() as i32;
+                let first = start.with_day(1).expect("day 1 exists");
+                let mut dates = Vec::new();
+                let mut offset = 0;
+                while let Some(month) = first.checked_add_months(Months::new(offset)) {
+                    if month_index(month) > month_index(to) {
+                        break;
+                    }
+                    if let Some(date) = nth_weekday(month, *nth, *weekday) {
+                        if date >= from && date <= to {
+                            dates.push(date);
+                        }
+                    }
+                    offset += interval;
+                }
+                dates
+            }
+        }
+    }
+
+    /// Plain-English description, e.g. "every 2 weeks on Fri".
+    pub fn describe(&self) -> String {
+        let mut text = match (&self.frequency, self.interval) {
+            (Frequency::Weekly { weekdays }, interval) => {
+                let days: Vec<String> = weekdays.iter().map(|d| d.to_string()).collect();
+                let every = if interval <= 1 {
+                    "every week".to_string()
+                } else {
+                    format!("every {} weeks", interval)
+                };
+                if days.is_empty() {
+                    every
+                } else {
+                    format!("{} on {}", every, days.join(", "))
+                }
+            }
+            (Frequency::Monthly { nth, weekday }, interval) => {
+                let every = if interval <= 1 {
+                    "every month".to_string()
+                } else {
+                    format!("every {} months", interval)
+                };
+                format!("the {} {} of {}", ordinal(*nth), weekday, every)
+            }
+        };
+        if let Some(until) = self.until {
+            text.push_str(&format!(" until {}", until));
+        }
+        text
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.frequency {
+            Frequency::Weekly { weekdays } => {
+                write!(f, "FREQ=WEEKLY")?;
+                if self.interval > 1 {
+                    write!(f, ";INTERVAL={}", self.interval)?;
+                }
+                if !weekdays.is_empty() {
+                    let days: Vec<&str> = weekdays.iter().map(|d| rrule_day(*d)).collect();
+                    write!(f, ";BYDAY={}", days.join(","))?;
+                }
+            }
+            Frequency::Monthly { nth, weekday } => {
+                write!(f, "FREQ=MONTHLY")?;
+                if self.interval > 1 {
+                    write!(f, ";INTERVAL={}", self.interval)?;
+                }
+                write!(f, ";BYDAY={}{}", nth, rrule_day(*weekday))?;
+            }
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("RRULE:")
+            .or_else(|| s.strip_prefix("rrule:"))
+            .unwrap_or(s);
+
+        let (mut freq, mut interval, mut until, mut byday) = (None, 1, None, None);
+        for part in s.split(';').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid rule part '{}'", part))?;
+            let value = value.trim().to_uppercase();
+            match key.trim().to_uppercase().as_str() {
+                "FREQ" => freq = Some(value),
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("Invalid INTERVAL '{}'", value))?
+                }
+                "UNTIL" => {
+                    // Date or date-time; only the date is kept
+                    let date = value.get(..8).unwrap_or(&value);
+                    until = Some(
+                        NaiveDate::parse_from_str(date, "%Y%m%d")
+                            .map_err(|_| format!("Invalid UNTIL '{}'", value))?,
+                    );
+                }
+                "BYDAY" => byday = Some(value),
+                other => return Err(format!("Unsupported rule part '{}'", other)),
+            }
+        }
+
+        let frequency = match freq.as_deref() {
+            Some("WEEKLY") => {
+                let weekdays = match &byday {
+                    Some(days) => days
+                        .split(',')
+                        .map(|d| parse_rrule_day(d.trim()))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => Vec::new(),
+                };
+                Frequency::Weekly { weekdays }
+            }
+            Some("MONTHLY") => {
+                let byday = byday.ok_or("FREQ=MONTHLY needs BYDAY, e.g. BYDAY=2FR")?;
+                let invalid = || format!("Invalid BYDAY '{}'; use e.g. 2FR or -1SU", byday);
+                // The day is the last two characters, which may not be ASCII
+                let split = byday
+                    .char_indices()
+                    .nth_back(1)
+                    .map(|(i, _)| i)
+                    .ok_or_else(invalid)?;
+                let (nth, day) = byday.split_at(split);
+                let nth: i8 = nth
+                    .trim_start_matches('+')
+                    .parse()
+                    .ok()
+                    .filter(|n: &i8| *n != 0 && n.abs() <= 5)
+                    .ok_or_else(invalid)?;
+                Frequency::Monthly {
+                    nth,
+                    weekday: parse_rrule_day(day)?,
+                }
+            }
+            Some(other) => return Err(format!("Unsupported FREQ '{}'", other)),
+            None => return Err("Rule needs FREQ=WEEKLY or FREQ=MONTHLY".to_string()),
+        };
+
+        Ok(Self {
+            frequency,
+            interval,
+            until,
+        })
+    }
+}
+
+/// A meal plan that repeats on a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecurringPlan {
+    pub id: Uuid,
+    pub meal_type: MealType,
+    /// Title of each occurrence; empty uses the default "<Meal> on <date>"
+    pub title: String,
+    pub cook: String,
+    /// Dishes by ID
+    pub dish_ids: Vec<Uuid>,
+    /// Servings being cooked
+    #[serde(default)]
+    pub servings: Option<i32>,
+    pub rule: Recurrence,
+    /// First day of the series
+    pub start: NaiveDate,
+    /// Dates of occurrences that were skipped
+    #[serde(default)]
+    pub skipped: Vec<NaiveDate>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl RecurringPlan {
+    pub fn new(
+        meal_type: MealType,
+        title: impl Into<String>,
+        rule: Recurrence,
+        start: NaiveDate,
+        created_by: impl Into<String>,
+    ) -> Self {
+        let created_by = created_by.into();
+        Self {
+            id: Uuid::new_v4(),
+            meal_type,
+            title: title.into(),
+            cook: created_by.clone(),
+            dish_ids: Vec::new(),
+            servings: None,
+            rule,
+            start,
+            skipped: Vec::new(),
+            created_by,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// ID of the occurrence on `date`, the same every time it is expanded.
+    pub fn occurrence_id(&self, date: NaiveDate) -> Uuid {
+        let mut hasher = Sha256::new();
+        hasher.update(self.id.as_bytes());
+        hasher.update(date.to_string().as_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        Builder::from_custom_bytes(bytes).into_uuid()
+    }
+
+    /// Occurrences in `from..=to`, leaving out skipped dates.
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<MealPlan> {
+        self.rule
+            .dates(self.start, from, to)
+            .into_iter()
+            .filter(|d| !self.skipped.contains(d))
+            .map(|date| self.occurrence(date))
+            .collect()
+    }
+
+    /// The occurrence with `id`, searching dates up to `to`. Skipped
+    /// occurrences are found too.
+    pub fn find_occurrence(&self, id: Uuid, to: NaiveDate) -> Option<MealPlan> {
+        self.rule
+            .dates(self.start, self.start, to)
+            .into_iter()
+            .find(|d| self.occurrence_id(*d) == id)
+            .map(|date| self.occurrence(date))
+    }
+
+    fn occurrence(&self, date: NaiveDate) -> MealPlan {
+        let title = if self.title.is_empty() {
+            default_title(&self.meal_type, date)
+        } else {
+            self.title.clone()
+        };
+        MealPlan {
+            id: self.occurrence_id(date),
+            date,
+            meal_type: self.meal_type.clone(),
+            title,
+            cook: self.cook.clone(),
+            dish_ids: self.dish_ids.clone(),
+            servings: self.servings,
+            leftovers_of: None,
+            created_by: self.created_by.clone(),
+            created_at: self.created_at,
+            updated_at: self.created_at,
+        }
+    }
+}
+
+/// Concrete occurrences of `recurring` in `from..=to`.
+///
+/// Occurrences overridden by a plan in `plans` (same ID) are left out; the
+/// override is listed in their place wherever its date now is.
+pub fn expand_recurring(
+    recurring: &[RecurringPlan],
+    plans: &[MealPlan],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<MealPlan> {
+    recurring
+        .iter()
+        .flat_map(|series| series.occurrences(from, to))
+        .filter(|occurrence| plans.iter().all(|p| p.id != occurrence.id))
+        .collect()
+}
+
+/// The `nth` `weekday` of the month starting on `first`.
+fn nth_weekday(first: NaiveDate, nth: i8, weekday: Weekday) -> Option<NaiveDate> {
+    if nth > 0 {
+        NaiveDate::from_weekday_of_month_opt(first.year(), first.month(), weekday, nth as u8)
+    } else {
+        let next_month = first.checked_add_months(Months::new(1))?;
+        let last = next_month.pred_opt()?;
+        let back = last.weekday().days_since(weekday) as i64 + 7 * (-nth as i64 - 1);
+        let date = last - Duration::days(back);
+        (date.month() == first.month()).then_some(date)
+    }
+}
+
+fn ordinal(nth: i8) -> String {
+    match nth {
+        -1 => "last".to_string(),
+        n if n < 0 => format!("{} from last", ordinal(-n)),
+        1 => "1st".to_string(),
+        2 => "2nd".to_string(),
+        3 => "3rd".to_string(),
+        n => format!("{}th", n),
+    }
+}
+
+fn rrule_day(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_rrule_day(day: &str) -> Result<Weekday, String> {
+    match day {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(format!(
+            "Invalid day '{}'; use MO, TU, WE, TH, FR, SA or SU",
+            day
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_weekly_every_other_week() {
+        // Starts on a Wednesday; every other week's Friday, from that week
+        let rule: Recurrence = "FREQ=WEEKLY;INTERVAL=2;BYDAY=FR".parse().unwrap();
+        let dates = rule.dates(date("2026-01-07"), date("2026-01-01"), date("2026-02-15"));
+        assert_eq!(
+            dates,
+            vec![date("2026-01-09"), date("2026-01-23"), date("2026-02-06")]
+        );
+        assert_eq!(rule.to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=FR");
+        assert_eq!(rule.describe(), "every 2 weeks on Fri");
+
+        // No BYDAY repeats on the start date's weekday
+        let rule = Recurrence::weekly(Vec::new()).until(date("2026-01-20"));
+        let dates = rule.dates(date("2026-01-06"), date("2026-01-01"), date("2026-12-31"));
+        assert_eq!(
+            dates,
+            vec![date("2026-01-06"), date("2026-01-13"), date("2026-01-20")]
+        );
+    }
+
+    #[test]
+    fn test_monthly_nth_weekday() {
+        let rule: Recurrence = "RRULE:FREQ=MONTHLY;BYDAY=-1SU;UNTIL=20260430T000000Z"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.until, Some(date("2026-04-30")));
+        let dates = rule.dates(date("2026-01-01"), date("2026-01-01"), date("2026-12-31"));
+        assert_eq!(
+            dates,
+            vec![
+                date("2026-01-25"),
+                date("2026-02-22"),
+                date("2026-03-29"),
+                date("2026-04-26")
+            ]
+        );
+        assert_eq!(
+            rule.describe(),
+            "the last Sun of every month until 2026-04-30"
+        );
+
+        // Months without a 5th Friday are passed over
+        let fifth = Recurrence::monthly(5, Weekday::Fri).every(1);
+        let dates = fifth.dates(date("2026-01-01"), date("2026-01-01"), date("2026-06-30"));
+        assert_eq!(dates, vec![date("2026-01-30"), date("2026-05-29")]);
+        assert_eq!(fifth.to_string(), "FREQ=MONTHLY;BYDAY=5FR");
+
+        assert!("FREQ=MONTHLY".parse::<Recurrence>().is_err());
+        assert!("FREQ=MONTHLY;BYDAY=0FR".parse::<Recurrence>().is_err());
+        assert!("FREQ=MONTHLY;BYDAY=1É".parse::<Recurrence>().is_err());
+        assert!("FREQ=MONTHLY;BYDAY=É".parse::<Recurrence>().is_err());
+        assert!("FREQ=DAILY".parse::<Recurrence>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=XX".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn test_occurrences_skip_and_override() {
+        let mut tacos = RecurringPlan::new(
+            MealType::Dinner,
+            "Taco Tuesday",
+            Recurrence::weekly(vec![Weekday::Tue]),
+            date("2026-01-01"),
+            "alice",
+        );
+        let dinners = tacos.occurrences(date("2026-01-01"), date("2026-01-31"));
+        assert_eq!(dinners.len(), 4);
+        assert_eq!(dinners[0].date, date("2026-01-06"));
+        assert_eq!(dinners[0].title, "Taco Tuesday");
+        assert_eq!(dinners[0].id, tacos.occurrence_id(date("2026-01-06")));
+        assert_ne!(dinners[0].id, dinners[1].id);
+
+        // Skip one, override another by saving a plan with its ID
+        tacos.skipped.push(date("2026-01-13"));
+        let mut moved = dinners[2].clone();
+        moved.date = date("2026-01-21");
+        let plans = vec![moved.clone()];
+        let expanded = expand_recurring(
+            std::slice::from_ref(&tacos),
+            &plans,
+            date("2026-01-01"),
+            date("2026-01-31"),
+        );
+        let dates: Vec<NaiveDate> = expanded.iter().map(|p| p.date).collect();
+        assert_eq!(dates, vec![date("2026-01-06"), date("2026-01-27")]);
+
+        let found = tacos.find_occurrence(moved.id, date("2026-12-31")).unwrap();
+        assert_eq!(found.date, date("2026-01-20"));
+        assert!(tacos
+            .find_occurrence(tacos.occurrence_id(date("2026-01-13")), date("2026-12-31"))
+            .is_some());
+        assert!(tacos
+            .find_occurrence(Uuid::new_v4(), date("2026-12-31"))
+            .is_none());
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! Shopping carts aggregate ingredients from meal plans for a week
 //! and allow manual items to be added. Items can be checked off
-//! as they are purchased.
+//! as they are purchased. Besides weeks, a cart can cover a shopping
+//! trip over any range of dates.
 
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -57,21 +59,102 @@ impl fmt::Display for ManualItem {
     }
 }
 
-/// A shopping cart for a specific week.
+/// The dates a shopping cart covers.
 ///
-/// The cart is keyed by the week's start date (Sunday).
+/// Carts are stored under a key made from their period: a week is keyed by
+/// its start date ("2026-01-11") and a trip by its first and last dates
+/// ("2026-01-15..2026-01-18").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CartPeriod {
+    /// Seven days from the start date
+    Week(NaiveDate),
+    /// A shopping trip from the first to the last date (inclusive)
+    Trip(NaiveDate, NaiveDate),
+}
+
+impl CartPeriod {
+    /// The week containing `date`, for weeks starting on `first_day`.
+    pub fn week_containing(date: NaiveDate, first_day: Weekday) -> Self {
+        let days_since_start = date.weekday().days_since(first_day);
+        CartPeriod::Week(date - Duration::days(days_since_start as i64))
+    }
+
+    /// The week starting on `first_day` that overlaps this one the most,
+    /// used to find carts stored under an earlier week start. Trips are
+    /// returned unchanged.
+    pub fn realigned(&self, first_day: Weekday) -> Self {
+        match *self {
+            CartPeriod::Week(start) => {
+                let days_since_start = start.weekday().days_since(first_day) as i64;
+                if days_since_start <= 3 {
+                    CartPeriod::Week(start - Duration::days(days_since_start))
+                } else {
+                    CartPeriod::Week(start + Duration::days(7 - days_since_start))
+                }
+            }
+            trip => trip,
+        }
+    }
+
+    /// Parse a cart key. Returns `None` for keys that aren't a date or a
+    /// date range in order.
+    pub fn parse(key: &str) -> Option<Self> {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+        match key.split_once("..") {
+            Some((from, to)) => {
+                let (from, to) = (date(from)?, date(to)?);
+                (from <= to).then_some(CartPeriod::Trip(from, to))
+            }
+            None => date(key).map(CartPeriod::Week),
+        }
+    }
+
+    /// The key the cart is stored under.
+    pub fn key(&self) -> String {
+        match self {
+            CartPeriod::Week(start) => start.to_string(),
+            CartPeriod::Trip(from, to) => format!("{}..{}", from, to),
+        }
+    }
+
+    /// First day covered.
+    pub fn start(&self) -> NaiveDate {
+        match self {
+            CartPeriod::Week(start) | CartPeriod::Trip(start, _) => *start,
+        }
+    }
+
+    /// Last day covered (inclusive).
+    pub fn end(&self) -> NaiveDate {
+        match self {
+            CartPeriod::Week(start) => *start + Duration::days(6),
+            CartPeriod::Trip(_, end) => *end,
+        }
+    }
+}
+
+/// A shopping cart for a specific week or shopping trip.
+///
+/// The cart is keyed by its [`CartPeriod`]: the week's start date, or the
+/// trip's date range. Carts from before week starts were configurable are
+/// keyed by a Sunday and read as weeks starting that day.
 /// It contains:
 /// - Auto-generated ingredients from meal plans (computed, not stored)
 /// - Manual items added by the user
 /// - Set of checked (purchased) item names
+/// - Amounts added to the pantry when items were checked
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShoppingCart {
-    /// Week start date as string (YYYY-MM-DD, always a Sunday)
+    /// Cart key: week start date (YYYY-MM-DD) or trip range
+    /// (YYYY-MM-DD..YYYY-MM-DD), see [`CartPeriod`]
     pub week: String,
     /// Names of items that have been checked off (case-normalized)
     pub checked: Vec<String>,
     /// Manual items added to the cart
     pub manual_items: Vec<ManualItem>,
+    /// Stock added to the pantry by checking items, so unchecking can undo it
+    #[serde(default)]
+    pub stocked: Vec<Ingredient>,
 }
 
 impl ShoppingCart {
@@ -81,9 +164,20 @@ impl ShoppingCart {
             week: week.into(),
             checked: Vec::new(),
             manual_items: Vec::new(),
+            stocked: Vec::new(),
         }
     }
 
+    /// Create a new empty shopping cart for a week or trip.
+    pub fn for_period(period: CartPeriod) -> Self {
+        Self::new(period.key())
+    }
+
+    /// The dates this cart covers, if its key is valid.
+    pub fn period(&self) -> Option<CartPeriod> {
+        CartPeriod::parse(&self.week)
+    }
+
     /// Check if an item is checked (case-insensitive).
     pub fn is_checked(&self, name: &str) -> bool {
         let name_lower = name.to_lowercase();
@@ -104,9 +198,34 @@ impl ShoppingCart {
         self.checked.retain(|c| c.to_lowercase() != name_lower);
     }
 
-    /// Clear all checked items.
+    /// Clear all checked items. Stock they added stays in the pantry.
     pub fn clear_checked(&mut self) {
         self.checked.clear();
+        self.stocked.clear();
+    }
+
+    /// Record the amount an item added to the pantry when it was checked.
+    pub fn record_stocked(&mut self, ingredient: Ingredient) {
+        self.take_stocked(&ingredient.name);
+        self.stocked.push(ingredient);
+    }
+
+    /// The amount an item added to the pantry (case-insensitive).
+    pub fn stocked_for(&self, name: &str) -> Option<&Ingredient> {
+        let name_lower = name.to_lowercase();
+        self.stocked
+            .iter()
+            .find(|i| i.name.to_lowercase() == name_lower)
+    }
+
+    /// Remove and return the amount an item added to the pantry.
+    pub fn take_stocked(&mut self, name: &str) -> Option<Ingredient> {
+        let name_lower = name.to_lowercase();
+        let index = self
+            .stocked
+            .iter()
+            .position(|i| i.name.to_lowercase() == name_lower)?;
+        Some(self.stocked.remove(index))
     }
 
     /// Add a manual item.
@@ -236,6 +355,63 @@ mod tests {
         assert!(cart.manual_items.is_empty());
     }
 
+    #[test]
+    fn test_cart_period_keys() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        // Sunday-keyed carts keep working as weeks
+        let week = ShoppingCart::new("2026-01-11").period().unwrap();
+        assert_eq!(week, CartPeriod::Week(date("2026-01-11")));
+        assert_eq!(week.end(), date("2026-01-17"));
+
+        let trip = CartPeriod::Trip(date("2026-01-15"), date("2026-01-18"));
+        let cart = ShoppingCart::for_period(trip);
+        assert_eq!(cart.week, "2026-01-15..2026-01-18");
+        assert_eq!(cart.period(), Some(trip));
+        assert_eq!(trip.end(), date("2026-01-18"));
+
+        assert_eq!(CartPeriod::parse("2026-01-18..2026-01-15"), None);
+        assert_eq!(CartPeriod::parse("shopping"), None);
+    }
+
+    #[test]
+    fn test_week_containing() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let wednesday = date("2026-01-14");
+
+        assert_eq!(
+            CartPeriod::week_containing(wednesday, Weekday::Sun),
+            CartPeriod::Week(date("2026-01-11"))
+        );
+        assert_eq!(
+            CartPeriod::week_containing(wednesday, Weekday::Thu),
+            CartPeriod::Week(date("2026-01-08"))
+        );
+        assert_eq!(
+            CartPeriod::week_containing(date("2026-01-15"), Weekday::Thu),
+            CartPeriod::Week(date("2026-01-15"))
+        );
+    }
+
+    #[test]
+    fn test_realigned() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let sunday_week = CartPeriod::Week(date("2026-01-11"));
+
+        assert_eq!(
+            sunday_week.realigned(Weekday::Thu),
+            CartPeriod::Week(date("2026-01-08"))
+        );
+        assert_eq!(
+            sunday_week.realigned(Weekday::Mon),
+            CartPeriod::Week(date("2026-01-12"))
+        );
+        assert_eq!(sunday_week.realigned(Weekday::Sun), sunday_week);
+
+        let trip = CartPeriod::Trip(date("2026-01-13"), date("2026-01-15"));
+        assert_eq!(trip.realigned(Weekday::Thu), trip);
+    }
+
     #[test]
     fn test_shopping_cart_check_uncheck() {
         let mut cart = ShoppingCart::new("2026-01-11");
@@ -261,6 +437,23 @@ mod tests {
         assert!(cart.checked.is_empty());
     }
 
+    #[test]
+    fn test_shopping_cart_stocked() {
+        let mut cart = ShoppingCart::new("2026-01-11");
+        cart.check("eggs");
+        cart.record_stocked(Ingredient::new("Eggs", 6.0, ""));
+        cart.record_stocked(Ingredient::new("eggs", 12.0, ""));
+        assert_eq!(cart.stocked.len(), 1);
+        assert_eq!(cart.stocked_for("EGGS").unwrap().quantity, 12.0);
+
+        assert_eq!(cart.take_stocked("eggs").unwrap().quantity, 12.0);
+        assert!(cart.take_stocked("eggs").is_none());
+
+        cart.record_stocked(Ingredient::new("milk", 1.0, "l"));
+        cart.clear_checked();
+        assert!(cart.stocked.is_empty());
+    }
+
     #[test]
     fn test_shopping_cart_manual_items() {
         let mut cart = ShoppingCart::new("2026-01-11");
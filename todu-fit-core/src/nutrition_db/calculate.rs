@@ -0,0 +1,392 @@
+//! Dish nutrient calculation from the food database
+
+use std::fmt;
+
+use super::{normalize_name, Food, FoodDatabase};
+use crate::models::{Dish, Ingredient, Nutrient};
+use crate::units::{convert, convert_ingredient, Dimension, Unit};
+
+/// Portion names used for counted ingredients ("2 eggs"), most typical first.
+const COUNT_PORTIONS: &[&str] = &[
+    "medium",
+    "large",
+    "whole",
+    "each",
+    "piece",
+    "item",
+    "small",
+    "extra large",
+];
+
+/// An ingredient matched to a food, with its weight and nutrients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientMatch {
+    pub ingredient: Ingredient,
+    pub fdc_id: i64,
+    pub food_name: String,
+    pub grams: f64,
+    pub nutrients: Vec<Nutrient>,
+}
+
+/// Why an ingredient couldn't be included in the calculation
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnmatchedReason {
+    /// No food in the database matches the ingredient name
+    NoFood,
+    /// The ingredient has no quantity ("salt to taste")
+    Unmeasured,
+    /// The unit isn't a known mass, volume or count unit, and no portion uses it
+    UnknownUnit(String),
+    /// The food has no weight for this unit (e.g. no "cup" portion or density)
+    NoWeight(String),
+}
+
+impl fmt::Display for UnmatchedReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnmatchedReason::NoFood => write!(f, "no matching food"),
+            UnmatchedReason::Unmeasured => write!(f, "no quantity"),
+            UnmatchedReason::UnknownUnit(unit) => write!(f, "unknown unit '{}'", unit),
+            UnmatchedReason::NoWeight(unit) => write!(f, "no gram weight for '{}'", unit),
+        }
+    }
+}
+
+/// An ingredient left out of the calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmatchedIngredient {
+    pub ingredient: Ingredient,
+    /// The food it matched, when the problem was converting the quantity
+    pub food_name: Option<String>,
+    pub reason: UnmatchedReason,
+}
+
+/// Result of [`FoodDatabase::calculate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NutritionCalculation {
+    /// Servings the total is divided by (1 when the dish has none set)
+    pub servings: i32,
+    pub total: Vec<Nutrient>,
+    pub per_serving: Vec<Nutrient>,
+    pub matched: Vec<IngredientMatch>,
+    pub unmatched: Vec<UnmatchedIngredient>,
+}
+
+impl NutritionCalculation {
+    /// True when every measured ingredient was matched.
+    pub fn is_complete(&self) -> bool {
+        self.unmatched
+            .iter()
+            .all(|u| u.reason == UnmatchedReason::Unmeasured)
+    }
+}
+
+impl FoodDatabase {
+    /// Recalculate a dish's stored nutrients after an edit. Hand-entered
+    /// nutrients are left alone, as is everything when the database is
+    /// empty. Returns the calculation when the dish was recalculated.
+    pub fn update_nutrients(&self, dish: &mut Dish) -> Option<NutritionCalculation> {
+        if self.foods.is_empty() || (dish.nutrients.is_some() && !dish.nutrients_calculated) {
+            return None;
+        }
+        let calc = self.calculate(dish);
+        dish.nutrients = (!calc.matched.is_empty()).then(|| calc.per_serving.clone());
+        dish.nutrients_calculated = dish.nutrients.is_some();
+        Some(calc)
+    }
+
+    /// Calculate a dish's total and per-serving nutrients.
+    pub fn calculate(&self, dish: &Dish) -> NutritionCalculation {
+        let mut matched = Vec::new();
+        let mut unmatched = Vec::new();
+
+        for ingredient in &dish.ingredients {
+            match self.match_ingredient(ingredient) {
+                Ok(m) => matched.push(m),
+                Err((food_name, reason)) => unmatched.push(UnmatchedIngredient {
+                    ingredient: ingredient.clone(),
+                    food_name,
+                    reason,
+                }),
+            }
+        }
+
+        let mut total: Vec<Nutrient> = Vec::new();
+        for nutrient in matched.iter().flat_map(|m| &m.nutrients) {
+            match total.iter_mut().find(|n| n.name == nutrient.name) {
+                Some(existing) => existing.amount += nutrient.amount,
+                None => total.push(nutrient.clone()),
+            }
+        }
+
+        let servings = dish.servings.filter(|s| *s > 0).unwrap_or(1);
+        let per_serving = total
+            .iter()
+            .map(|n| Nutrient::new(&n.name, round1(n.amount / servings as f64), &n.unit))
+            .collect();
+        for nutrient in &mut total {
+            nutrient.amount = round1(nutrient.amount);
+        }
+
+        NutritionCalculation {
+            servings,
+            total,
+            per_serving,
+            matched,
+            unmatched,
+        }
+    }
+
+    /// Match one ingredient to a food and work out its nutrients.
+    ///
+    /// On failure, returns the matched food's name (if any) and the reason.
+    fn match_ingredient(
+        &self,
+        ingredient: &Ingredient,
+    ) -> Result<IngredientMatch, (Option<String>, UnmatchedReason)> {
+        let food = self
+            .find(&ingredient.name)
+            .ok_or((None, UnmatchedReason::NoFood))?;
+
+        if ingredient.quantity <= 0.0 {
+            return Err((Some(food.name.clone()), UnmatchedReason::Unmeasured));
+        }
+
+        let grams =
+            grams_for(food, ingredient).map_err(|reason| (Some(food.name.clone()), reason))?;
+        let nutrients = food
+            .nutrients
+            .iter()
+            .map(|n| Nutrient::new(&n.name, n.amount * grams / 100.0, &n.unit))
+            .collect();
+
+        Ok(IngredientMatch {
+            ingredient: ingredient.clone(),
+            fdc_id: food.fdc_id,
+            food_name: food.name.clone(),
+            grams,
+            nutrients,
+        })
+    }
+}
+
+/// Weight in grams of an ingredient quantity of `food`.
+///
+/// Mass units convert directly. Volumes use the food's volume portions, then
+/// the approximate densities in [`crate::units`]. Counts use portions such
+/// as "large" or "medium", and other units need a portion of the same name.
+fn grams_for(food: &Food, ingredient: &Ingredient) -> Result<f64, UnmatchedReason> {
+    let quantity = ingredient.quantity;
+    let unit_name = ingredient.unit.trim();
+    let no_weight = || {
+        UnmatchedReason::NoWeight(if unit_name.is_empty() {
+            "each".to_string()
+        } else {
+            unit_name.to_string()
+        })
+    };
+
+    // Portions with no amount can't be scaled from
+    let portions = || food.portions.iter().filter(|p| p.amount > 0.0);
+
+    let Some(unit) = Unit::parse(unit_name) else {
+        let wanted = normalize_name(unit_name);
+        return portions()
+            .find(|p| normalize_name(&p.unit) == wanted)
+            .map(|p| quantity / p.amount * p.grams)
+            .ok_or_else(|| UnmatchedReason::UnknownUnit(unit_name.to_string()));
+    };
+
+    match unit.dimension() {
+        Dimension::Mass => convert(quantity, unit_name, "g").map_err(|_| no_weight()),
+        Dimension::Volume => {
+            let portion = portions().find_map(|p| {
+                let portion_unit = Unit::parse(&p.unit)?;
+                (portion_unit.dimension() == Dimension::Volume).then_some(p)
+            });
+            if let Some(portion) = portion {
+                let in_portion_unit =
+                    convert(quantity, unit_name, &portion.unit).map_err(|_| no_weight())?;
+                return Ok(in_portion_unit / portion.amount * portion.grams);
+            }
+            convert_ingredient(&ingredient.name, quantity, unit_name, "g")
+                .or_else(|_| convert_ingredient(&food.name, quantity, unit_name, "g"))
+                .map_err(|_| no_weight())
+        }
+        Dimension::Count => {
+            let count = quantity * unit.base_factor();
+            COUNT_PORTIONS
+                .iter()
+                .find_map(|label| portions().find(|p| p.unit == *label))
+                .map(|p| count / p.amount * p.grams)
+                .ok_or_else(no_weight)
+        }
+    }
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::sample_db;
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.05,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_grams_for_units() {
+        let db = sample_db();
+        let flour = db.get(1).unwrap();
+        let eggs = db.get(2).unwrap();
+        let milk = db.get(3).unwrap();
+
+        assert_close(
+            grams_for(flour, &Ingredient::new("flour", 200.0, "g")).unwrap(),
+            200.0,
+        );
+        assert_close(
+            grams_for(flour, &Ingredient::new("flour", 1.0, "lb")).unwrap(),
+            453.6,
+        );
+        // Volume through the food's cup portion
+        assert_close(
+            grams_for(flour, &Ingredient::new("flour", 2.0, "cups")).unwrap(),
+            250.0,
+        );
+        assert_close(
+            grams_for(flour, &Ingredient::new("flour", 4.0, "tbsp")).unwrap(),
+            31.25,
+        );
+        // Volume through the density table
+        assert_close(
+            grams_for(milk, &Ingredient::new("milk", 250.0, "ml")).unwrap(),
+            257.5,
+        );
+        // Counts through the "large" portion
+        assert_close(
+            grams_for(eggs, &Ingredient::new("eggs", 2.0, "")).unwrap(),
+            100.0,
+        );
+        assert_close(
+            grams_for(eggs, &Ingredient::new("eggs", 1.0, "dozen")).unwrap(),
+            600.0,
+        );
+    }
+
+    #[test]
+    fn test_grams_for_failures() {
+        let db = sample_db();
+        let milk = db.get(3).unwrap();
+        assert_eq!(
+            grams_for(milk, &Ingredient::new("milk", 1.0, "")),
+            Err(UnmatchedReason::NoWeight("each".to_string()))
+        );
+        assert_eq!(
+            grams_for(milk, &Ingredient::new("milk", 1.0, "splash")),
+            Err(UnmatchedReason::UnknownUnit("splash".to_string()))
+        );
+
+        // A portion with no amount is skipped rather than divided by
+        let mut eggs = db.get(2).unwrap().clone();
+        for portion in &mut eggs.portions {
+            portion.amount = 0.0;
+        }
+        assert_eq!(
+            grams_for(&eggs, &Ingredient::new("eggs", 2.0, "")),
+            Err(UnmatchedReason::NoWeight("each".to_string()))
+        );
+        assert!(grams_for(&eggs, &Ingredient::new("eggs", 1.0, "large")).is_err());
+    }
+
+    #[test]
+    fn test_calculate_dish() {
+        let db = sample_db();
+        let dish = Dish::new("Pancakes", "user1")
+            .with_servings(2)
+            .with_ingredients(vec![
+                Ingredient::new("all-purpose flour", 1.0, "cup"),
+                Ingredient::new("eggs", 2.0, ""),
+                Ingredient::new("saffron", 1.0, "pinch"),
+                Ingredient::new("salt", 0.0, ""),
+            ]);
+
+        let calc = db.calculate(&dish);
+
+        assert_eq!(calc.matched.len(), 2);
+        assert_eq!(calc.matched[0].food_name, db.get(1).unwrap().name);
+        assert_eq!(calc.unmatched.len(), 2);
+        assert_eq!(calc.unmatched[0].reason, UnmatchedReason::NoFood);
+        assert!(!calc.is_complete());
+
+        // 125 g flour (455 kcal) + 100 g egg (143 kcal)
+        assert_eq!(calc.total[0], Nutrient::new("calories", 598.0, "kcal"));
+        assert_eq!(
+            calc.per_serving[0],
+            Nutrient::new("calories", 299.0, "kcal")
+        );
+        assert_close(calc.per_serving[1].amount, (10.3 * 1.25 + 12.6) / 2.0);
+    }
+
+    #[test]
+    fn test_calculate_without_servings() {
+        let db = sample_db();
+        let dish = Dish::new("Milk", "user1").with_ingredients(vec![Ingredient::new(
+            "whole milk",
+            100.0,
+            "g",
+        )]);
+
+        let calc = db.calculate(&dish);
+        assert!(calc.is_complete());
+        assert_eq!(calc.servings, 1);
+        assert_eq!(calc.per_serving, calc.total);
+        assert_eq!(calc.total[0], Nutrient::new("calories", 61.0, "kcal"));
+    }
+
+    #[test]
+    fn test_update_nutrients() {
+        let db = sample_db();
+        let mut dish = Dish::new("Milk", "user1").with_ingredients(vec![Ingredient::new(
+            "whole milk",
+            100.0,
+            "g",
+        )]);
+
+        let calc = db.update_nutrients(&mut dish).unwrap();
+        assert!(dish.nutrients_calculated);
+        assert_eq!(dish.nutrients, Some(calc.per_serving));
+
+        // Calculated nutrients follow the ingredients
+        dish.ingredients[0].quantity = 200.0;
+        db.update_nutrients(&mut dish).unwrap();
+        assert_eq!(
+            dish.nutrients.as_ref().unwrap()[0],
+            Nutrient::new("calories", 122.0, "kcal")
+        );
+
+        // Nothing matched: no nutrients rather than zeros
+        dish.ingredients = vec![Ingredient::new("saffron", 1.0, "pinch")];
+        db.update_nutrients(&mut dish).unwrap();
+        assert_eq!(dish.nutrients, None);
+        assert!(!dish.nutrients_calculated);
+
+        // Hand-entered nutrients are kept
+        let mut manual = Dish::new("Milk", "user1")
+            .with_ingredients(vec![Ingredient::new("whole milk", 100.0, "g")])
+            .with_nutrients(vec![Nutrient::new("calories", 50.0, "kcal")]);
+        assert!(db.update_nutrients(&mut manual).is_none());
+        assert_eq!(manual.nutrients.unwrap()[0].amount, 50.0);
+
+        // No database, no calculation
+        assert!(FoodDatabase::default()
+            .update_nutrients(&mut dish)
+            .is_none());
+    }
+}
@@ -0,0 +1,418 @@
+//! USDA FoodData Central CSV import
+//!
+//! Reads the CSV export from <https://fdc.nal.usda.gov/download-datasets>:
+//!
+//! - `food.csv`: `fdc_id`, `data_type`, `description`
+//! - `food_nutrient.csv`: `fdc_id`, `nutrient_id`, `amount` (per 100 g)
+//! - `food_portion.csv` (optional): household measures and gram weights
+//! - `measure_unit.csv` (optional): names for `food_portion.measure_unit_id`
+//!
+//! Branded foods make up most of the full export, so only the generic data
+//! types are imported by default.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use csv::StringRecord;
+
+use super::{Food, FoodDbError, FoodPortion};
+use crate::models::Nutrient;
+
+/// Data types imported when none are specified.
+pub const DEFAULT_DATA_TYPES: &[&str] = &["foundation_food", "sr_legacy_food", "survey_fndds_food"];
+
+/// FDC nutrient ids mapped to nutrient names and units.
+///
+/// When several ids map to the same name, the earlier one wins.
+const NUTRIENT_IDS: &[(i64, &str, &str)] = &[
+    (1008, "calories", "kcal"),
+    (2047, "calories", "kcal"),
+    (2048, "calories", "kcal"),
+    (1003, "protein", "g"),
+    (1005, "carbs", "g"),
+    (1050, "carbs", "g"),
+    (1004, "fat", "g"),
+    (1085, "fat", "g"),
+    (1258, "saturated_fat", "g"),
+    (1079, "fiber", "g"),
+    (2000, "sugar", "g"),
+    (1063, "sugar", "g"),
+    (1093, "sodium", "mg"),
+    (1253, "cholesterol", "mg"),
+];
+
+/// The CSV files of a FoodData Central export.
+pub struct FdcSources<R: Read> {
+    pub food: R,
+    pub food_nutrient: R,
+    pub food_portion: Option<R>,
+    pub measure_unit: Option<R>,
+}
+
+/// Import foods from an extracted FoodData Central CSV directory.
+///
+/// `data_types` filters `food.data_type`; pass an empty slice for
+/// [`DEFAULT_DATA_TYPES`].
+pub fn import_dir(dir: &Path, data_types: &[String]) -> Result<Vec<Food>, FoodDbError> {
+    let open = |name: &str| -> Result<File, FoodDbError> {
+        File::open(dir.join(name)).map_err(|_| FoodDbError::MissingFile(name.to_string()))
+    };
+    let open_optional = |name: &str| File::open(dir.join(name)).ok();
+
+    import(
+        FdcSources {
+            food: open("food.csv")?,
+            food_nutrient: open("food_nutrient.csv")?,
+            food_portion: open_optional("food_portion.csv"),
+            measure_unit: open_optional("measure_unit.csv"),
+        },
+        data_types,
+    )
+}
+
+/// Import foods from FoodData Central CSV readers.
+pub fn import<R: Read>(
+    sources: FdcSources<R>,
+    data_types: &[String],
+) -> Result<Vec<Food>, FoodDbError> {
+    let data_types: Vec<String> = if data_types.is_empty() {
+        DEFAULT_DATA_TYPES.iter().map(|t| t.to_string()).collect()
+    } else {
+        data_types.iter().map(|t| t.to_lowercase()).collect()
+    };
+
+    let mut foods: Vec<Food> = Vec::new();
+    let mut index: HashMap<i64, usize> = HashMap::new();
+
+    read_table(
+        "food.csv",
+        sources.food,
+        &["fdc_id", "data_type", "description"],
+        |row| {
+            let data_type = row[1].to_lowercase();
+            if !data_types.contains(&data_type) {
+                return;
+            }
+            if let Ok(fdc_id) = row[0].parse() {
+                index.insert(fdc_id, foods.len());
+                foods.push(Food {
+                    fdc_id,
+                    name: row[2].trim().to_string(),
+                    nutrients: Vec::new(),
+                    portions: Vec::new(),
+                });
+            }
+        },
+    )?;
+
+    // Position in NUTRIENT_IDS of the id each stored nutrient came from
+    let mut priorities: HashMap<(usize, &str), usize> = HashMap::new();
+
+    read_table(
+        "food_nutrient.csv",
+        sources.food_nutrient,
+        &["fdc_id", "nutrient_id", "amount"],
+        |row| {
+            let (Ok(fdc_id), Ok(nutrient_id), Ok(amount)) = (
+                row[0].parse::<i64>(),
+                row[1].parse::<i64>(),
+                row[2].parse::<f64>(),
+            ) else {
+                return;
+            };
+            let Some(&food_index) = index.get(&fdc_id) else {
+                return;
+            };
+            let Some(priority) = NUTRIENT_IDS
+                .iter()
+                .position(|(id, _, _)| *id == nutrient_id)
+            else {
+                return;
+            };
+
+            let (_, name, unit) = NUTRIENT_IDS[priority];
+            let nutrients = &mut foods[food_index].nutrients;
+            match priorities.get(&(food_index, name)) {
+                Some(&existing) if existing <= priority => {}
+                Some(_) => {
+                    if let Some(n) = nutrients.iter_mut().find(|n| n.name == name) {
+                        n.amount = amount;
+                    }
+                    priorities.insert((food_index, name), priority);
+                }
+                None => {
+                    nutrients.push(Nutrient::new(name, amount, unit));
+                    priorities.insert((food_index, name), priority);
+                }
+            }
+        },
+    )?;
+
+    let mut measure_units: HashMap<String, String> = HashMap::new();
+    if let Some(reader) = sources.measure_unit {
+        read_table("measure_unit.csv", reader, &["id", "name"], |row| {
+            measure_units.insert(row[0].to_string(), row[1].trim().to_lowercase());
+        })?;
+    }
+
+    if let Some(reader) = sources.food_portion {
+        read_table(
+            "food_portion.csv",
+            reader,
+            &[
+                "fdc_id",
+                "amount",
+                "measure_unit_id",
+                "portion_description",
+                "modifier",
+                "gram_weight",
+            ],
+            |row| {
+                let (Ok(fdc_id), Ok(grams)) = (row[0].parse::<i64>(), row[5].parse::<f64>()) else {
+                    return;
+                };
+                let Some(&food_index) = index.get(&fdc_id) else {
+                    return;
+                };
+                if let Some(portion) = parse_portion(&row, &measure_units, grams) {
+                    foods[food_index].portions.push(portion);
+                }
+            },
+        )?;
+    }
+
+    foods.retain(|food| !food.nutrients.is_empty());
+    Ok(foods)
+}
+
+/// Work out the measure a portion row refers to.
+///
+/// Foundation foods name it through `measure_unit_id`; SR Legacy puts it in
+/// `modifier` ("cup, sifted"); FNDDS only has `portion_description`
+/// ("1 cup, chopped").
+fn parse_portion(
+    row: &[&str],
+    measure_units: &HashMap<String, String>,
+    grams: f64,
+) -> Option<FoodPortion> {
+    if grams <= 0.0 {
+        return None;
+    }
+
+    let mut amount: Option<f64> = row[1].parse().ok().filter(|a: &f64| *a > 0.0);
+    let measure = measure_units
+        .get(row[2])
+        .filter(|name| name.as_str() != "undetermined")
+        .cloned();
+
+    let unit = match measure {
+        Some(unit) => unit,
+        None if !row[4].trim().is_empty() && row[4].parse::<f64>().is_err() => first_clause(row[4]),
+        None => {
+            let description = row[3].trim();
+            let number: String = description
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            if amount.is_none() {
+                amount = number.parse().ok();
+            }
+            first_clause(&description[number.len()..])
+        }
+    };
+
+    if unit.is_empty() {
+        return None;
+    }
+
+    Some(FoodPortion {
+        unit,
+        amount: amount.unwrap_or(1.0),
+        grams,
+    })
+}
+
+fn first_clause(s: &str) -> String {
+    s.split([',', '('])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase()
+}
+
+/// Stream a CSV file, calling `row` with the `columns` of each record in order.
+fn read_table<R: Read>(
+    file: &str,
+    reader: R,
+    columns: &[&str],
+    mut row: impl FnMut(Vec<&str>),
+) -> Result<(), FoodDbError> {
+    let csv_error = |message: String| FoodDbError::Csv {
+        file: file.to_string(),
+        message,
+    };
+
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers = reader
+        .headers()
+        .map_err(|e| csv_error(e.to_string()))?
+        .clone();
+    let positions: Vec<usize> = columns
+        .iter()
+        .map(|column| {
+            headers
+                .iter()
+                .position(|h| h.trim() == *column)
+                .ok_or_else(|| csv_error(format!("missing column '{}'", column)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut record = StringRecord::new();
+    while reader
+        .read_record(&mut record)
+        .map_err(|e| csv_error(e.to_string()))?
+    {
+        let values = positions
+            .iter()
+            .map(|&i| record.get(i).unwrap_or(""))
+            .collect();
+        row(values);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOOD: &str = "\"fdc_id\",\"data_type\",\"description\",\"food_category_id\",\"publication_date\"
+\"1001\",\"sr_legacy_food\",\"Wheat flour, white, all-purpose, enriched, bleached\",\"20\",\"2019-04-01\"
+\"1002\",\"foundation_food\",\"Eggs, Grade A, Large, egg whole\",\"1\",\"2019-04-01\"
+\"1003\",\"branded_food\",\"ACME PANCAKE MIX\",\"\",\"2020-01-01\"
+\"1004\",\"survey_fndds_food\",\"Milk, whole\",\"\",\"2020-01-01\"
+";
+
+    const FOOD_NUTRIENT: &str = "\"id\",\"fdc_id\",\"nutrient_id\",\"amount\"
+\"1\",\"1001\",\"1008\",\"364\"
+\"2\",\"1001\",\"1003\",\"10.33\"
+\"3\",\"1001\",\"1093\",\"2\"
+\"4\",\"1002\",\"2047\",\"148\"
+\"5\",\"1002\",\"1008\",\"143\"
+\"6\",\"1002\",\"1003\",\"12.4\"
+\"7\",\"1003\",\"1008\",\"400\"
+\"8\",\"1004\",\"1008\",\"61\"
+\"9\",\"1004\",\"1162\",\"0\"
+";
+
+    const FOOD_PORTION: &str = "\"id\",\"fdc_id\",\"seq_num\",\"amount\",\"measure_unit_id\",\"portion_description\",\"modifier\",\"gram_weight\"
+\"1\",\"1001\",\"1\",\"1\",\"9999\",\"\",\"cup, sifted\",\"125\"
+\"2\",\"1002\",\"1\",\"1\",\"1124\",\"\",\"\",\"50.3\"
+\"3\",\"1004\",\"1\",\"\",\"9999\",\"1 cup\",\"\",\"244\"
+\"4\",\"1004\",\"2\",\"\",\"9999\",\"Quantity not specified\",\"\",\"0\"
+";
+
+    const MEASURE_UNIT: &str = "\"id\",\"name\"
+\"1000\",\"cup\"
+\"1124\",\"large\"
+\"9999\",\"undetermined\"
+";
+
+    fn import_sample(data_types: &[String]) -> Vec<Food> {
+        import(
+            FdcSources {
+                food: FOOD.as_bytes(),
+                food_nutrient: FOOD_NUTRIENT.as_bytes(),
+                food_portion: Some(FOOD_PORTION.as_bytes()),
+                measure_unit: Some(MEASURE_UNIT.as_bytes()),
+            },
+            data_types,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_import_default_data_types() {
+        let foods = import_sample(&[]);
+        let ids: Vec<i64> = foods.iter().map(|f| f.fdc_id).collect();
+        assert_eq!(ids, vec![1001, 1002, 1004]);
+
+        let flour = &foods[0];
+        assert_eq!(
+            flour.name,
+            "Wheat flour, white, all-purpose, enriched, bleached"
+        );
+        assert_eq!(
+            flour.nutrients,
+            vec![
+                Nutrient::new("calories", 364.0, "kcal"),
+                Nutrient::new("protein", 10.33, "g"),
+                Nutrient::new("sodium", 2.0, "mg"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_prefers_energy_id_1008() {
+        let foods = import_sample(&[]);
+        let eggs = &foods[1];
+        assert_eq!(eggs.nutrients[0], Nutrient::new("calories", 143.0, "kcal"));
+        assert_eq!(eggs.nutrients.len(), 2);
+    }
+
+    #[test]
+    fn test_import_portions() {
+        let foods = import_sample(&[]);
+        assert_eq!(
+            foods[0].portions,
+            vec![FoodPortion {
+                unit: "cup".to_string(),
+                amount: 1.0,
+                grams: 125.0
+            }]
+        );
+        assert_eq!(foods[1].portions[0].unit, "large");
+        assert_eq!(foods[1].portions[0].grams, 50.3);
+        // FNDDS portion amount comes from the description; zero weights are dropped
+        assert_eq!(
+            foods[2].portions,
+            vec![FoodPortion {
+                unit: "cup".to_string(),
+                amount: 1.0,
+                grams: 244.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_import_selected_data_types() {
+        let foods = import_sample(&["branded_food".to_string()]);
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].name, "ACME PANCAKE MIX");
+    }
+
+    #[test]
+    fn test_import_missing_column() {
+        let result = import(
+            FdcSources {
+                food: "\"id\",\"description\"\n".as_bytes(),
+                food_nutrient: FOOD_NUTRIENT.as_bytes(),
+                food_portion: None,
+                measure_unit: None,
+            },
+            &[],
+        );
+        assert!(matches!(result, Err(FoodDbError::Csv { .. })));
+    }
+
+    #[test]
+    fn test_import_dir_missing_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(matches!(
+            import_dir(temp_dir.path(), &[]),
+            Err(FoodDbError::MissingFile(f)) if f == "food.csv"
+        ));
+    }
+}
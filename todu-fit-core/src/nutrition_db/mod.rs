@@ -0,0 +1,356 @@
+//! Offline food composition database
+//!
+//! Foods are imported from the USDA FoodData Central CSV export (see
+//! [`fdc`]) and stored as JSON under the data directory. Nutrients are kept
+//! per 100 g, along with household portion weights ("1 cup = 128 g") used to
+//! turn volume and count quantities into grams.
+//!
+//! [`FoodDatabase::calculate`] maps a dish's ingredients onto foods and sums
+//! their nutrients, reporting any ingredient it couldn't match.
+
+mod calculate;
+pub mod fdc;
+
+pub use calculate::{IngredientMatch, NutritionCalculation, UnmatchedIngredient, UnmatchedReason};
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::models::Nutrient;
+
+/// File name of the food database inside the data directory.
+pub const FOOD_DB_FILE: &str = "foods.json";
+
+/// Current on-disk format version.
+const FOOD_DB_VERSION: u32 = 1;
+
+/// Errors that can occur when loading or importing the food database
+#[derive(Error, Debug)]
+pub enum FoodDbError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid food database: {0}")]
+    InvalidJson(String),
+
+    #[error("Invalid CSV in {file}: {message}")]
+    Csv { file: String, message: String },
+
+    #[error("Missing FoodData Central file: {0}")]
+    MissingFile(String),
+
+    #[error("Food not found: {0}")]
+    FoodNotFound(i64),
+}
+
+/// A household measure and its weight, e.g. 1 cup = 128 g.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FoodPortion {
+    /// Measure name as given by the source ("cup", "large", "slice")
+    pub unit: String,
+    /// Number of `unit`s the weight refers to
+    pub amount: f64,
+    pub grams: f64,
+}
+
+/// A food with nutrients per 100 g.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Food {
+    /// FoodData Central id
+    pub fdc_id: i64,
+    pub name: String,
+    pub nutrients: Vec<Nutrient>,
+    #[serde(default)]
+    pub portions: Vec<FoodPortion>,
+}
+
+/// The local food database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FoodDatabase {
+    pub version: u32,
+    pub foods: Vec<Food>,
+    /// Ingredient name (normalized) -> FDC id, overriding automatic matching
+    #[serde(default)]
+    pub aliases: BTreeMap<String, i64>,
+}
+
+impl Default for FoodDatabase {
+    fn default() -> Self {
+        Self {
+            version: FOOD_DB_VERSION,
+            foods: Vec::new(),
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl FoodDatabase {
+    /// Path of the database file inside a data directory.
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(FOOD_DB_FILE)
+    }
+
+    /// Load the database, or return an empty one if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self, FoodDbError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| FoodDbError::InvalidJson(e.to_string()))
+    }
+
+    /// Write the database to disk, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> Result<(), FoodDbError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json =
+            serde_json::to_string(self).map_err(|e| FoodDbError::InvalidJson(e.to_string()))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Add foods, replacing any existing food with the same FDC id.
+    ///
+    /// Returns the number of foods that were new.
+    pub fn merge(&mut self, foods: Vec<Food>) -> usize {
+        let mut added = 0;
+        for food in foods {
+            match self.foods.iter_mut().find(|f| f.fdc_id == food.fdc_id) {
+                Some(existing) => *existing = food,
+                None => {
+                    self.foods.push(food);
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+
+    /// Get a food by FDC id.
+    pub fn get(&self, fdc_id: i64) -> Option<&Food> {
+        self.foods.iter().find(|f| f.fdc_id == fdc_id)
+    }
+
+    /// Always match `ingredient` to the given food.
+    pub fn set_alias(&mut self, ingredient: &str, fdc_id: i64) -> Result<(), FoodDbError> {
+        if self.get(fdc_id).is_none() {
+            return Err(FoodDbError::FoodNotFound(fdc_id));
+        }
+        self.aliases.insert(normalize_name(ingredient), fdc_id);
+        Ok(())
+    }
+
+    /// Remove an alias. Returns true if one existed.
+    pub fn remove_alias(&mut self, ingredient: &str) -> bool {
+        self.aliases.remove(&normalize_name(ingredient)).is_some()
+    }
+
+    /// Foods whose name contains every word of `query`, best matches first.
+    pub fn search(&self, query: &str) -> Vec<&Food> {
+        let query = tokens(query);
+        let mut scored: Vec<(i32, &Food)> = self
+            .foods
+            .iter()
+            .filter_map(|food| match_score(&query, &food.name).map(|score| (score, food)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, food)| food).collect()
+    }
+
+    /// The food an ingredient name maps to: its alias, or the best search match.
+    pub fn find(&self, ingredient: &str) -> Option<&Food> {
+        if let Some(id) = self.aliases.get(&normalize_name(ingredient)) {
+            if let Some(food) = self.get(*id) {
+                return Some(food);
+            }
+        }
+        self.search(ingredient).into_iter().next()
+    }
+}
+
+/// Lowercased words with simple plurals removed ("Large Eggs" -> "large egg").
+fn normalize_name(name: &str) -> String {
+    tokens(name).join(" ")
+}
+
+fn tokens(s: &str) -> Vec<String> {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(singular)
+        .collect()
+}
+
+fn singular(word: &str) -> String {
+    if word.len() > 4 && word.ends_with("oes") {
+        word[..word.len() - 2].to_string()
+    } else if word.len() > 4 && word.ends_with("ies") {
+        format!("{}y", &word[..word.len() - 3])
+    } else if word.len() > 3
+        && word.ends_with('s')
+        && !word.ends_with("ss")
+        && !word.ends_with("us")
+    {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+/// Score how well a food description matches the query words.
+///
+/// Every query word must appear in the description. Descriptions whose
+/// leading segment ("Wheat flour" in "Wheat flour, white, all-purpose")
+/// names the query's last word rank higher, as do shorter descriptions and
+/// raw foods.
+fn match_score(query: &[String], description: &str) -> Option<i32> {
+    if query.is_empty() {
+        return None;
+    }
+
+    let words: HashSet<String> = tokens(description).into_iter().collect();
+    if !query.iter().all(|w| words.contains(w)) {
+        return None;
+    }
+
+    let lead = tokens(description.split(',').next().unwrap_or(""));
+    let head = query.last()?;
+
+    let mut score = 0;
+    if lead.contains(head) {
+        score += 20;
+    }
+    if lead.len() == query.len() && query.iter().all(|w| lead.contains(w)) {
+        score += 10;
+    }
+    if words.contains("raw") {
+        score += 3;
+    }
+    score -= words.len() as i32;
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    pub(super) fn sample_db() -> FoodDatabase {
+        let mut db = FoodDatabase::default();
+        db.merge(vec![
+            Food {
+                fdc_id: 1,
+                name: "Wheat flour, white, all-purpose, enriched, bleached".to_string(),
+                nutrients: vec![
+                    Nutrient::new("calories", 364.0, "kcal"),
+                    Nutrient::new("protein", 10.3, "g"),
+                ],
+                portions: vec![FoodPortion {
+                    unit: "cup".to_string(),
+                    amount: 1.0,
+                    grams: 125.0,
+                }],
+            },
+            Food {
+                fdc_id: 2,
+                name: "Egg, whole, raw, fresh".to_string(),
+                nutrients: vec![
+                    Nutrient::new("calories", 143.0, "kcal"),
+                    Nutrient::new("protein", 12.6, "g"),
+                ],
+                portions: vec![
+                    FoodPortion {
+                        unit: "large".to_string(),
+                        amount: 1.0,
+                        grams: 50.0,
+                    },
+                    FoodPortion {
+                        unit: "cup".to_string(),
+                        amount: 1.0,
+                        grams: 243.0,
+                    },
+                ],
+            },
+            Food {
+                fdc_id: 3,
+                name: "Milk, whole, 3.25% milkfat, with added vitamin D".to_string(),
+                nutrients: vec![
+                    Nutrient::new("calories", 61.0, "kcal"),
+                    Nutrient::new("protein", 3.15, "g"),
+                ],
+                portions: Vec::new(),
+            },
+            Food {
+                fdc_id: 4,
+                name: "Cookies, chocolate chip, made with egg".to_string(),
+                nutrients: vec![Nutrient::new("calories", 488.0, "kcal")],
+                portions: Vec::new(),
+            },
+        ]);
+        db
+    }
+
+    #[test]
+    fn test_find_prefers_leading_segment() {
+        let db = sample_db();
+        assert_eq!(db.find("eggs").unwrap().fdc_id, 2);
+        assert_eq!(db.find("all-purpose flour").unwrap().fdc_id, 1);
+        assert_eq!(db.find("whole milk").unwrap().fdc_id, 3);
+        assert!(db.find("saffron").is_none());
+    }
+
+    #[test]
+    fn test_alias_overrides_matching() {
+        let mut db = sample_db();
+        assert!(db.find("AP flour").is_none());
+
+        db.set_alias("AP Flour", 1).unwrap();
+        assert_eq!(db.find("ap flour").unwrap().fdc_id, 1);
+
+        assert!(matches!(
+            db.set_alias("unknown", 99),
+            Err(FoodDbError::FoodNotFound(99))
+        ));
+        assert!(db.remove_alias("ap flour"));
+        assert!(db.find("AP flour").is_none());
+    }
+
+    #[test]
+    fn test_merge_replaces_by_id() {
+        let mut db = sample_db();
+        let mut milk = db.get(3).unwrap().clone();
+        milk.name = "Milk, whole".to_string();
+
+        assert_eq!(db.merge(vec![milk]), 0);
+        assert_eq!(db.foods.len(), 4);
+        assert_eq!(db.get(3).unwrap().name, "Milk, whole");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = FoodDatabase::path(temp_dir.path());
+
+        assert_eq!(FoodDatabase::load(&path).unwrap(), FoodDatabase::default());
+
+        let mut db = sample_db();
+        db.set_alias("ap flour", 1).unwrap();
+        db.save(&path).unwrap();
+
+        assert_eq!(FoodDatabase::load(&path).unwrap(), db);
+    }
+
+    #[test]
+    fn test_singular() {
+        assert_eq!(normalize_name("Large Eggs"), "large egg");
+        assert_eq!(normalize_name("tomatoes"), "tomato");
+        assert_eq!(normalize_name("berries"), "berry");
+        assert_eq!(normalize_name("hummus"), "hummus");
+        assert_eq!(normalize_name("Swiss"), "swiss");
+    }
+}
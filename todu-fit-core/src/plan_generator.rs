@@ -0,0 +1,405 @@
+//! Weekly meal plan generator
+//!
+//! Fills the empty meal slots of a week with the group's dishes, picked at
+//! random within constraints:
+//! - tags: each slot can be limited to dishes with one of a set of tags
+//! - time: a cap on the total prep and cook time of a weeknight's dishes
+//! - variety: a dish isn't used again within a number of days of being
+//!   planned or eaten
+//! - nutrition: with daily goals, dishes closest to each meal's share of the
+//!   goals are preferred
+//!
+//! The generator draws from the random number generator it is given, so the
+//! same seed and inputs always produce the same proposal.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+use crate::goals::{GoalKind, NutritionGoal};
+use crate::models::{default_title, Dish, MealPlan, MealType};
+
+/// With nutrition goals, how many of the best-scoring dishes to choose from.
+const GOAL_SHORTLIST: usize = 3;
+
+/// A meal slot to fill and the dishes allowed in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotRule {
+    pub meal_type: MealType,
+    /// Dishes must have one of these tags. When empty, dishes tagged with
+    /// the meal's name (e.g. "breakfast") are used if there are any, and
+    /// otherwise every dish.
+    pub tags: Vec<String>,
+}
+
+impl SlotRule {
+    pub fn new(meal_type: MealType, tags: Vec<String>) -> Self {
+        Self { meal_type, tags }
+    }
+}
+
+/// Constraints for [`generate_week`].
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    /// Slots to fill each day, in order
+    pub slots: Vec<SlotRule>,
+    /// Most minutes of prep and cook time on a weeknight (Monday to
+    /// Thursday). Dishes without times count as zero.
+    pub weeknight_max_minutes: Option<i32>,
+    /// A dish isn't used within this many days of another time it is
+    /// planned or eaten (0 allows repeats)
+    pub no_repeat_days: i64,
+    /// Daily nutrition goals to aim for
+    pub goals: Vec<NutritionGoal>,
+    /// Meals eaten a day, to split daily goals into a share per meal
+    pub meals_per_day: usize,
+}
+
+/// One dish proposed for a slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposedMeal {
+    pub date: NaiveDate,
+    pub meal_type: MealType,
+    pub dish_id: Uuid,
+}
+
+/// The result of [`generate_week`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Proposal {
+    /// Dishes for the slots that could be filled
+    pub meals: Vec<ProposedMeal>,
+    /// Empty slots no dish fits
+    pub unfilled: Vec<(NaiveDate, MealType)>,
+}
+
+impl Proposal {
+    /// Meal plans for the proposed meals.
+    pub fn to_plans(&self, cook: &str, created_by: &str) -> Vec<MealPlan> {
+        self.meals
+            .iter()
+            .map(|meal| {
+                MealPlan::new(
+                    meal.date,
+                    meal.meal_type.clone(),
+                    default_title(&meal.meal_type, meal.date),
+                    created_by,
+                )
+                .with_cook(cook)
+                .with_dish_ids(vec![meal.dish_id])
+            })
+            .collect()
+    }
+}
+
+/// The random number generator for a seed.
+///
+/// ChaCha8 gives the same numbers on every platform and release, unlike
+/// `StdRng`, so a seed shared or noted down keeps producing the same plans.
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Propose dishes for the empty slots of the week starting on `week_start`.
+///
+/// `dishes` should have their components flattened so nutrition and times
+/// are complete. `history` lists when dishes were planned or eaten around
+/// the week; `existing` is the week's plans, whose slots are left alone.
+pub fn generate_week<R: Rng>(
+    week_start: NaiveDate,
+    options: &GeneratorOptions,
+    dishes: &[Dish],
+    history: &[(NaiveDate, Uuid)],
+    existing: &[MealPlan],
+    rng: &mut R,
+) -> Proposal {
+    let mut served: Vec<(NaiveDate, Uuid)> = history.to_vec();
+    served.extend(
+        existing
+            .iter()
+            .flat_map(|p| p.dish_ids.iter().map(move |id| (p.date, *id))),
+    );
+    let mut proposal = Proposal::default();
+
+    for date in week_start.iter_days().take(7) {
+        for rule in &options.slots {
+            let taken = existing
+                .iter()
+                .any(|p| p.date == date && p.meal_type == rule.meal_type);
+            if taken {
+                continue;
+            }
+
+            let minutes_left = options
+                .weeknight_max_minutes
+                .filter(|_| is_weeknight(date))
+                .map(|max| max - minutes_on(date, &served, dishes));
+            let candidates: Vec<&Dish> = allowed_dishes(rule, dishes)
+                .into_iter()
+                .filter(|dish| {
+                    minutes_left.is_none_or(|left| dish.total_time().unwrap_or(0) <= left)
+                })
+                .filter(|dish| {
+                    !served.iter().any(|(day, id)| {
+                        *id == dish.id && (*day - date).num_days().abs() < options.no_repeat_days
+                    })
+                })
+                .collect();
+
+            let Some(dish) = choose(&candidates, options, rng) else {
+                proposal.unfilled.push((date, rule.meal_type.clone()));
+                continue;
+            };
+            served.push((date, dish.id));
+            proposal.meals.push(ProposedMeal {
+                date,
+                meal_type: rule.meal_type.clone(),
+                dish_id: dish.id,
+            });
+        }
+    }
+
+    proposal
+}
+
+fn is_weeknight(date: NaiveDate) -> bool {
+    matches!(
+        date.weekday(),
+        Weekday::Mon | Weekday::Tue | Weekday::Wed | Weekday::Thu
+    )
+}
+
+/// Minutes already committed on `date` by planned and proposed dishes.
+fn minutes_on(date: NaiveDate, served: &[(NaiveDate, Uuid)], dishes: &[Dish]) -> i32 {
+    served
+        .iter()
+        .filter(|(day, _)| *day == date)
+        .filter_map(|(_, id)| dishes.iter().find(|d| d.id == *id))
+        .map(|dish| dish.total_time().unwrap_or(0))
+        .sum()
+}
+
+/// Dishes matching a slot's tags, before the time and repeat checks.
+fn allowed_dishes<'a>(rule: &SlotRule, dishes: &'a [Dish]) -> Vec<&'a Dish> {
+    let tagged = |tags: &[String]| -> Vec<&'a Dish> {
+        dishes
+            .iter()
+            .filter(|dish| {
+                dish.tags
+                    .iter()
+                    .any(|t| tags.iter().any(|tag| t.eq_ignore_ascii_case(tag)))
+            })
+            .collect()
+    };
+
+    if !rule.tags.is_empty() {
+        return tagged(&rule.tags);
+    }
+    let by_meal = tagged(&[rule.meal_type.to_string()]);
+    if by_meal.is_empty() {
+        dishes.iter().collect()
+    } else {
+        by_meal
+    }
+}
+
+/// Pick a candidate: uniformly, or among the closest to the goals.
+fn choose<'a, R: Rng>(
+    candidates: &[&'a Dish],
+    options: &GeneratorOptions,
+    rng: &mut R,
+) -> Option<&'a Dish> {
+    if candidates.is_empty() {
+        return None;
+    }
+    if options.goals.is_empty() {
+        return Some(candidates[rng.random_range(0..candidates.len())]);
+    }
+
+    let meals = options.meals_per_day.max(1) as f64;
+    let mut scored: Vec<(f64, &Dish)> = candidates
+        .iter()
+        .map(|dish| (goal_penalty(dish, &options.goals, meals), *dish))
+        .collect();
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let shortlist = scored.len().min(GOAL_SHORTLIST);
+    Some(scored[rng.random_range(0..shortlist)].1)
+}
+
+/// How far a serving of `dish` is from one meal's share of the goals, as a
+/// sum of fractions of each share. Zero means every goal is met.
+fn goal_penalty(dish: &Dish, goals: &[NutritionGoal], meals: f64) -> f64 {
+    goals
+        .iter()
+        .filter(|goal| goal.amount > 0.0)
+        .map(|goal| {
+            let share = goal.amount / meals;
+            let amount: f64 = dish
+                .nutrients
+                .iter()
+                .flatten()
+                .filter(|n| n.name.eq_ignore_ascii_case(&goal.nutrient))
+                .map(|n| n.amount)
+                .sum();
+            let off = match goal.kind {
+                GoalKind::Target => (amount - share).abs(),
+                GoalKind::Min => (share - amount).max(0.0),
+                GoalKind::Max => (amount - share).max(0.0),
+            };
+            off / share
+        })
+        .sum()
+}
+
+/// Dates and dishes of `plans`, for use as generator history.
+pub fn plan_history(plans: &[MealPlan]) -> Vec<(NaiveDate, Uuid)> {
+    plans
+        .iter()
+        .flat_map(|p| p.dish_ids.iter().map(move |id| (p.date, *id)))
+        .collect()
+}
+
+/// The days either side of a week that can affect repeats within `days`.
+pub fn history_range(week_start: NaiveDate, days: i64) -> (NaiveDate, NaiveDate) {
+    let days = days.max(0);
+    (
+        week_start - Duration::days(days),
+        week_start + Duration::days(6 + days),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Nutrient;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dish(name: &str, tags: &[&str], minutes: i32) -> Dish {
+        let mut dish = Dish::new(name, "alice");
+        dish.tags = tags.iter().map(|t| t.to_string()).collect();
+        dish.cook_time = Some(minutes);
+        dish
+    }
+
+    fn dinners() -> GeneratorOptions {
+        GeneratorOptions {
+            slots: vec![SlotRule::new(MealType::Dinner, Vec::new())],
+            meals_per_day: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_proposal() {
+        let dishes: Vec<Dish> = (0..10).map(|i| dish(&format!("d{}", i), &[], 30)).collect();
+        let monday = date("2026-03-02");
+        let run = |seed| {
+            let mut rng = seeded_rng(seed);
+            generate_week(monday, &dinners(), &dishes, &[], &[], &mut rng)
+        };
+
+        let first = run(7);
+        assert_eq!(first.meals.len(), 7);
+        assert_eq!(first, run(7));
+        assert_ne!(first, run(8));
+    }
+
+    #[test]
+    fn test_seed_output_is_pinned() {
+        // A seed must keep giving the same week across releases
+        let dishes: Vec<Dish> = (0..10).map(|i| dish(&format!("d{}", i), &[], 30)).collect();
+        let mut rng = seeded_rng(42);
+        let proposal = generate_week(date("2026-03-02"), &dinners(), &dishes, &[], &[], &mut rng);
+
+        let picked: Vec<&str> = proposal
+            .meals
+            .iter()
+            .map(|m| {
+                dishes
+                    .iter()
+                    .find(|d| d.id == m.dish_id)
+                    .unwrap()
+                    .name
+                    .as_str()
+            })
+            .collect();
+        assert_eq!(picked, vec!["d2", "d6", "d1", "d9", "d7", "d4", "d3"]);
+    }
+
+    #[test]
+    fn test_constraints() {
+        let monday = date("2026-03-02");
+        let quick = dish("Stir fry", &["dinner", "quick"], 20);
+        let slow = dish("Roast", &["dinner"], 120);
+        let oats = dish("Oats", &["breakfast"], 5);
+        let dishes = vec![quick.clone(), slow.clone(), oats.clone()];
+
+        let mut options = dinners();
+        options.weeknight_max_minutes = Some(60);
+        options.no_repeat_days = 3;
+        // Tuesday already has dinner; the roast was eaten on Saturday
+        let existing = vec![MealPlan::new(
+            date("2026-03-03"),
+            MealType::Dinner,
+            "Out",
+            "alice",
+        )];
+        let history = vec![(date("2026-02-28"), slow.id)];
+
+        let mut rng = seeded_rng(1);
+        let proposal = generate_week(monday, &options, &dishes, &history, &existing, &mut rng);
+
+        let on = |day: &str| proposal.meals.iter().find(|m| m.date == date(day));
+        // Only dinner-tagged dishes; the roast is too slow for weeknights
+        assert!(proposal.meals.iter().all(|m| m.dish_id != oats.id));
+        assert_eq!(on("2026-03-02").unwrap().dish_id, quick.id);
+        assert!(on("2026-03-03").is_none());
+        // Stir fry on Monday blocks Wednesday; the roast is out until Friday
+        assert!(proposal
+            .unfilled
+            .contains(&(date("2026-03-04"), MealType::Dinner)));
+        assert_eq!(on("2026-03-05").unwrap().dish_id, quick.id);
+        assert_eq!(on("2026-03-06").unwrap().dish_id, slow.id);
+    }
+
+    #[test]
+    fn test_nutrition_goals_prefer_closest() {
+        let nutritious = |name: &str, protein: f64| {
+            let mut d = dish(name, &[], 30);
+            d.nutrients = Some(vec![Nutrient::new("protein", protein, "g")]);
+            d
+        };
+        let dishes = vec![
+            nutritious("a", 5.0),
+            nutritious("b", 10.0),
+            nutritious("c", 38.0),
+            nutritious("d", 42.0),
+            nutritious("e", 40.0),
+        ];
+        let mut options = dinners();
+        options.goals = vec![NutritionGoal::new("protein", 120.0, "g")];
+
+        let mut rng = seeded_rng(3);
+        let proposal = generate_week(date("2026-03-02"), &options, &dishes, &[], &[], &mut rng);
+        let picked: Vec<&str> = proposal
+            .meals
+            .iter()
+            .map(|m| {
+                dishes
+                    .iter()
+                    .find(|d| d.id == m.dish_id)
+                    .unwrap()
+                    .name
+                    .as_str()
+            })
+            .collect();
+        assert!(picked.iter().all(|name| ["c", "d", "e"].contains(name)));
+
+        let plans = proposal.to_plans("Bob", "alice");
+        assert_eq!(plans[0].title, "Dinner on 2026-03-02");
+        assert_eq!(plans[0].cook, "Bob");
+    }
+}
@@ -0,0 +1,357 @@
+//! Bulk meal plan operations
+//!
+//! Copy a range of plans to other dates, shift plans by a number of days, or
+//! swap two plans' slots. Each operation works out a [`PlanChanges`] without
+//! touching storage, so it can be previewed and then written as one change.
+//!
+//! A conflict is an incoming plan whose date and meal slot already hold a
+//! plan that isn't itself being moved. [`ConflictStrategy`] decides what
+//! happens to it.
+
+use chrono::{Duration, NaiveDate, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::models::{default_title, MealPlan, MealType};
+
+/// Errors from bulk meal plan operations
+#[derive(Error, Debug, Clone)]
+pub enum PlanOpError {
+    #[error("{} meal plan(s) already at the target", .0.len())]
+    Conflicts(Vec<PlanConflict>),
+
+    #[error("Can't swap a meal plan with itself")]
+    SwapWithSelf,
+}
+
+/// What to do when a plan would land on an occupied slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictStrategy {
+    /// Leave the existing plan alone; the incoming plan is not written
+    #[default]
+    Skip,
+    /// The incoming plan takes the slot
+    Replace,
+    /// Change nothing if any slot is taken
+    Fail,
+}
+
+/// An incoming plan and the plan already in its slot.
+#[derive(Debug, Clone)]
+pub struct PlanConflict {
+    /// The plan as it would be written
+    pub incoming: MealPlan,
+    /// The plan currently in the slot
+    pub existing: MealPlan,
+}
+
+/// Writes and deletes making up one bulk operation.
+#[derive(Debug, Clone, Default)]
+pub struct PlanChanges {
+    /// Plans to create or overwrite
+    pub writes: Vec<MealPlan>,
+    /// Plans to delete, by ID
+    pub deletes: Vec<Uuid>,
+    /// Incoming plans that met an occupied slot, however they were resolved
+    pub conflicts: Vec<PlanConflict>,
+}
+
+impl PlanChanges {
+    /// True when nothing would be written or deleted.
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty() && self.deletes.is_empty()
+    }
+}
+
+/// Copy `plans` forward or back by `days` as new plans.
+///
+/// `existing` is every plan in the target dates. With
+/// [`ConflictStrategy::Replace`] an occupied slot's plan is updated in place
+/// with the copy's title, cook, dishes and servings, keeping its ID.
+/// Leftovers of a copied plan become leftovers of its copy.
+pub fn copy_plans(
+    plans: &[MealPlan],
+    days: i64,
+    existing: &[MealPlan],
+    strategy: ConflictStrategy,
+    created_by: &str,
+) -> Result<PlanChanges, PlanOpError> {
+    let mut changes = PlanChanges::default();
+    let mut copied: Vec<(Uuid, Uuid)> = Vec::new();
+    for plan in plans {
+        let date = plan.date + Duration::days(days);
+        let mut copy = move_plan(plan, date, plan.meal_type.clone());
+        let now = Utc::now();
+        copy.id = Uuid::new_v4();
+        copy.created_by = created_by.to_string();
+        copy.created_at = now;
+
+        match find_slot(existing, date, &copy.meal_type) {
+            Some(current) => {
+                if strategy == ConflictStrategy::Replace {
+                    let mut updated = current.clone();
+                    updated.title = copy.title.clone();
+                    updated.cook = copy.cook.clone();
+                    updated.dish_ids = copy.dish_ids.clone();
+                    updated.servings = copy.servings;
+                    updated.leftovers_of = copy.leftovers_of;
+                    updated.updated_at = now;
+                    copied.push((plan.id, updated.id));
+                    changes.writes.push(updated);
+                }
+                changes.conflicts.push(PlanConflict {
+                    incoming: copy,
+                    existing: current.clone(),
+                });
+            }
+            None => {
+                copied.push((plan.id, copy.id));
+                changes.writes.push(copy);
+            }
+        }
+    }
+    for write in &mut changes.writes {
+        if let Some(&(_, copy_id)) = copied
+            .iter()
+            .find(|(source, _)| Some(*source) == write.leftovers_of)
+        {
+            write.leftovers_of = Some(copy_id);
+        }
+    }
+    finish(changes, strategy)
+}
+
+/// Move `plans` by `days`, keeping their IDs.
+///
+/// `existing` is every plan in the target dates; slots freed by plans that
+/// move are not conflicts. With [`ConflictStrategy::Skip`] a plan that can't
+/// move stays put, which can in turn block the plan moving into its slot.
+/// With [`ConflictStrategy::Replace`] the plans in the way are deleted.
+pub fn shift_plans(
+    plans: &[MealPlan],
+    days: i64,
+    existing: &[MealPlan],
+    strategy: ConflictStrategy,
+) -> Result<PlanChanges, PlanOpError> {
+    let moving: Vec<Uuid> = plans.iter().map(|p| p.id).collect();
+    let mut staying: Vec<&MealPlan> = existing
+        .iter()
+        .filter(|p| !moving.contains(&p.id))
+        .collect();
+    let mut pending: Vec<&MealPlan> = plans.iter().collect();
+    let mut changes = PlanChanges::default();
+
+    // Skipped plans stay in their slot, so repeat until no more are blocked
+    loop {
+        let mut blocked = false;
+        pending.retain(|plan| {
+            let date = plan.date + Duration::days(days);
+            let Some(current) = staying
+                .iter()
+                .find(|p| p.date == date && p.meal_type == plan.meal_type)
+            else {
+                return true;
+            };
+            changes.conflicts.push(PlanConflict {
+                incoming: move_plan(plan, date, plan.meal_type.clone()),
+                existing: (*current).clone(),
+            });
+            match strategy {
+                ConflictStrategy::Replace => {
+                    changes.deletes.push(current.id);
+                    true
+                }
+                ConflictStrategy::Skip | ConflictStrategy::Fail => {
+                    blocked = true;
+                    false
+                }
+            }
+        });
+        if strategy == ConflictStrategy::Replace {
+            staying.retain(|p| !changes.deletes.contains(&p.id));
+        }
+        if !blocked {
+            break;
+        }
+        staying = existing
+            .iter()
+            .filter(|p| !pending.iter().any(|m| m.id == p.id))
+            .collect();
+    }
+
+    changes.writes = pending
+        .into_iter()
+        .map(|plan| {
+            move_plan(
+                plan,
+                plan.date + Duration::days(days),
+                plan.meal_type.clone(),
+            )
+        })
+        .collect();
+    finish(changes, strategy)
+}
+
+/// Exchange the date and meal slot of two plans.
+pub fn swap_plans(a: &MealPlan, b: &MealPlan) -> Result<PlanChanges, PlanOpError> {
+    if a.id == b.id {
+        return Err(PlanOpError::SwapWithSelf);
+    }
+    Ok(PlanChanges {
+        writes: vec![
+            move_plan(a, b.date, b.meal_type.clone()),
+            move_plan(b, a.date, a.meal_type.clone()),
+        ],
+        deletes: Vec::new(),
+        conflicts: Vec::new(),
+    })
+}
+
+/// A copy of `plan` in a new slot, with a default title following it.
+fn move_plan(plan: &MealPlan, date: NaiveDate, meal_type: MealType) -> MealPlan {
+    let mut moved = plan.clone();
+    if plan.title == default_title(&plan.meal_type, plan.date) {
+        moved.title = default_title(&meal_type, date);
+    }
+    moved.date = date;
+    moved.meal_type = meal_type;
+    moved.updated_at = Utc::now();
+    moved
+}
+
+fn find_slot<'a>(
+    plans: &'a [MealPlan],
+    date: NaiveDate,
+    meal_type: &MealType,
+) -> Option<&'a MealPlan> {
+    plans
+        .iter()
+        .find(|p| p.date == date && p.meal_type == *meal_type)
+}
+
+fn finish(changes: PlanChanges, strategy: ConflictStrategy) -> Result<PlanChanges, PlanOpError> {
+    if strategy == ConflictStrategy::Fail && !changes.conflicts.is_empty() {
+        return Err(PlanOpError::Conflicts(changes.conflicts));
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn plan(day: &str, meal_type: MealType) -> MealPlan {
+        let date = date(day);
+        MealPlan::new(
+            date,
+            meal_type.clone(),
+            default_title(&meal_type, date),
+            "alice",
+        )
+    }
+
+    #[test]
+    fn test_copy_plans_conflicts() {
+        let source = vec![
+            plan("2026-01-11", MealType::Dinner).with_cook("Bob"),
+            MealPlan::new(date("2026-01-12"), MealType::Lunch, "Picnic", "alice"),
+        ];
+        let existing = vec![plan("2026-01-19", MealType::Lunch)];
+
+        let skip = copy_plans(&source, 7, &existing, ConflictStrategy::Skip, "carol").unwrap();
+        assert_eq!(skip.writes.len(), 1);
+        let copy = &skip.writes[0];
+        assert_eq!(copy.date, date("2026-01-18"));
+        assert_eq!(copy.title, "Dinner on 2026-01-18");
+        assert_eq!(copy.cook, "Bob");
+        assert_eq!(copy.created_by, "carol");
+        assert_ne!(copy.id, source[0].id);
+        assert_eq!(skip.conflicts.len(), 1);
+
+        let replace =
+            copy_plans(&source, 7, &existing, ConflictStrategy::Replace, "carol").unwrap();
+        assert_eq!(replace.writes.len(), 2);
+        assert_eq!(replace.writes[1].id, existing[0].id);
+        assert_eq!(replace.writes[1].title, "Picnic");
+        assert!(replace.deletes.is_empty());
+
+        let fail = copy_plans(&source, 7, &existing, ConflictStrategy::Fail, "carol");
+        assert!(matches!(fail, Err(PlanOpError::Conflicts(c)) if c.len() == 1));
+    }
+
+    #[test]
+    fn test_copy_plans_relinks_leftovers() {
+        let dinner = plan("2026-01-11", MealType::Dinner);
+        let lunch = plan("2026-01-12", MealType::Lunch).with_leftovers_of(dinner.id);
+        let elsewhere = Uuid::new_v4();
+        let snack = plan("2026-01-12", MealType::Snack).with_leftovers_of(elsewhere);
+
+        let changes = copy_plans(
+            &[dinner, lunch, snack],
+            7,
+            &[],
+            ConflictStrategy::Skip,
+            "carol",
+        )
+        .unwrap();
+        assert_eq!(changes.writes[1].leftovers_of, Some(changes.writes[0].id));
+        assert_eq!(changes.writes[2].leftovers_of, Some(elsewhere));
+    }
+
+    #[test]
+    fn test_shift_plans_frees_own_slots() {
+        // Three dinners in a row move a day later: each lands where the
+        // previous one was, and only the last meets a plan that stays
+        let moving = vec![
+            plan("2026-03-01", MealType::Dinner),
+            plan("2026-03-02", MealType::Dinner),
+            plan("2026-03-03", MealType::Dinner),
+        ];
+        let blocker = plan("2026-03-04", MealType::Dinner);
+        let lunch = plan("2026-03-02", MealType::Lunch);
+        let mut existing = moving.clone();
+        existing.push(blocker.clone());
+        existing.push(lunch);
+
+        let replace = shift_plans(&moving, 1, &existing, ConflictStrategy::Replace).unwrap();
+        assert_eq!(replace.writes.len(), 3);
+        assert_eq!(replace.writes[2].date, date("2026-03-04"));
+        assert_eq!(replace.writes[2].id, moving[2].id);
+        assert_eq!(replace.writes[2].title, "Dinner on 2026-03-04");
+        assert_eq!(replace.deletes, vec![blocker.id]);
+
+        // Skipping the last plan keeps its slot taken, so none can move
+        let skip = shift_plans(&moving, 1, &existing, ConflictStrategy::Skip).unwrap();
+        assert!(skip.is_empty());
+        assert_eq!(skip.conflicts.len(), 3);
+
+        let fail = shift_plans(&moving, 1, &existing, ConflictStrategy::Fail);
+        assert!(matches!(fail, Err(PlanOpError::Conflicts(_))));
+
+        let back = shift_plans(&moving, -3, &existing, ConflictStrategy::Fail).unwrap();
+        assert_eq!(back.writes[0].date, date("2026-02-26"));
+    }
+
+    #[test]
+    fn test_swap_plans() {
+        let a = plan("2026-03-01", MealType::Dinner);
+        let b = MealPlan::new(date("2026-03-05"), MealType::Lunch, "Tacos", "alice");
+
+        let changes = swap_plans(&a, &b).unwrap();
+        let (new_a, new_b) = (&changes.writes[0], &changes.writes[1]);
+        assert_eq!((new_a.id, new_a.date), (a.id, b.date));
+        assert_eq!(new_a.meal_type, MealType::Lunch);
+        assert_eq!(new_a.title, "Lunch on 2026-03-05");
+        assert_eq!(
+            (new_b.date, new_b.meal_type.clone()),
+            (a.date, MealType::Dinner)
+        );
+        assert_eq!(new_b.title, "Tacos");
+
+        assert!(matches!(swap_plans(&a, &a), Err(PlanOpError::SwapWithSelf)));
+    }
+}
@@ -0,0 +1,435 @@
+//! Nutrition trend reports
+//!
+//! Turns personal meal logs into per-day nutrient totals, then summarizes
+//! them over days, ISO weeks or calendar months: average, minimum, maximum
+//! and standard deviation per nutrient, plus streaks of consecutive logged
+//! days. Statistics only count days with at least one logged meal, so a
+//! missed day doesn't drag the averages towards zero.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::MealLog;
+
+/// Nutrients listed first, in this order; any others follow alphabetically.
+const NUTRIENT_ORDER: &[&str] = &["calories", "protein", "carbs", "fat"];
+
+/// How report periods are grouped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    #[default]
+    Day,
+    /// ISO weeks, Monday to Sunday
+    Week,
+    Month,
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::Day => write!(f, "day"),
+            GroupBy::Week => write!(f, "week"),
+            GroupBy::Month => write!(f, "month"),
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" | "daily" => Ok(GroupBy::Day),
+            "week" | "weekly" => Ok(GroupBy::Week),
+            "month" | "monthly" => Ok(GroupBy::Month),
+            _ => Err(format!(
+                "Invalid grouping '{}'. Valid values: day, week, month",
+                s
+            )),
+        }
+    }
+}
+
+impl GroupBy {
+    /// First day of the period containing `date`.
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            GroupBy::Day => date,
+            GroupBy::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            GroupBy::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Last day of the period starting at `start`.
+    fn period_end(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            GroupBy::Day => start,
+            GroupBy::Week => start + Duration::days(6),
+            GroupBy::Month => {
+                let (year, month) = if start.month() == 12 {
+                    (start.year() + 1, 1)
+                } else {
+                    (start.year(), start.month() + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1)
+                    .map(|next| next - Duration::days(1))
+                    .unwrap_or(start)
+            }
+        }
+    }
+
+    /// Label for the period starting at `start`: "2025-01-15", "2025-W03" or "2025-01".
+    pub fn label(&self, start: NaiveDate) -> String {
+        match self {
+            GroupBy::Day => start.to_string(),
+            GroupBy::Week => {
+                let week = start.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            GroupBy::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Nutrients eaten on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyTotals {
+    pub date: NaiveDate,
+    pub meals: usize,
+    pub nutrients: BTreeMap<String, f64>,
+}
+
+/// Summary of one nutrient's daily totals.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NutrientStats {
+    pub nutrient: String,
+    /// Sum over all logged days
+    pub total: f64,
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Population standard deviation of the daily totals
+    pub std_dev: f64,
+}
+
+/// Statistics for one day, week or month.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReportPeriod {
+    pub label: String,
+    /// First day of the period that falls inside the report range
+    pub start: NaiveDate,
+    /// Last day of the period that falls inside the report range
+    pub end: NaiveDate,
+    pub days_logged: usize,
+    pub meals: usize,
+    pub nutrients: Vec<NutrientStats>,
+}
+
+/// Runs of consecutive days with at least one logged meal.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Streaks {
+    /// Length of the run ending on the last day of the range
+    pub current: usize,
+    pub longest: usize,
+    pub longest_start: Option<NaiveDate>,
+    pub longest_end: Option<NaiveDate>,
+}
+
+/// Result of [`nutrition_report`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NutritionReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: GroupBy,
+    /// Days in the range, logged or not
+    pub days: usize,
+    pub days_logged: usize,
+    pub meals: usize,
+    pub periods: Vec<ReportPeriod>,
+    /// Statistics over every logged day in the range
+    pub overall: Vec<NutrientStats>,
+    pub streaks: Streaks,
+    pub daily: Vec<DailyTotals>,
+}
+
+/// Sum meal logs into per-day totals, sorted by date.
+pub fn daily_totals(logs: &[MealLog]) -> Vec<DailyTotals> {
+    let mut days: BTreeMap<NaiveDate, DailyTotals> = BTreeMap::new();
+    for log in logs {
+        let day = days.entry(log.date).or_insert_with(|| DailyTotals {
+            date: log.date,
+            meals: 0,
+            nutrients: BTreeMap::new(),
+        });
+        day.meals += 1;
+        for (name, amount) in log.nutrient_totals() {
+            *day.nutrients.entry(name).or_insert(0.0) += amount;
+        }
+    }
+    days.into_values().collect()
+}
+
+/// Build a nutrition report for `from..=to` from meal logs.
+///
+/// Logs outside the range are ignored.
+pub fn nutrition_report(
+    logs: &[MealLog],
+    from: NaiveDate,
+    to: NaiveDate,
+    group_by: GroupBy,
+) -> NutritionReport {
+    let in_range: Vec<MealLog> = logs
+        .iter()
+        .filter(|l| l.date >= from && l.date <= to)
+        .cloned()
+        .collect();
+    let daily = daily_totals(&in_range);
+
+    let mut periods = Vec::new();
+    let mut start = group_by.period_start(from);
+    while start <= to {
+        let end = group_by.period_end(start);
+        let clamped_start = start.max(from);
+        let clamped_end = end.min(to);
+        let days: Vec<&DailyTotals> = daily
+            .iter()
+            .filter(|d| d.date >= clamped_start && d.date <= clamped_end)
+            .collect();
+
+        periods.push(ReportPeriod {
+            label: group_by.label(start),
+            start: clamped_start,
+            end: clamped_end,
+            days_logged: days.len(),
+            meals: days.iter().map(|d| d.meals).sum(),
+            nutrients: nutrient_stats(&days),
+        });
+        start = end + Duration::days(1);
+    }
+
+    let all_days: Vec<&DailyTotals> = daily.iter().collect();
+    let logged: Vec<NaiveDate> = daily.iter().map(|d| d.date).collect();
+
+    NutritionReport {
+        from,
+        to,
+        group_by,
+        days: ((to - from).num_days() + 1).max(0) as usize,
+        days_logged: daily.len(),
+        meals: in_range.len(),
+        periods,
+        overall: nutrient_stats(&all_days),
+        streaks: streaks(&logged, to),
+        daily,
+    }
+}
+
+/// Statistics per nutrient over the given days.
+///
+/// A nutrient missing from a logged day counts as zero for that day.
+pub fn nutrient_stats(days: &[&DailyTotals]) -> Vec<NutrientStats> {
+    let mut names: Vec<&String> = days.iter().flat_map(|d| d.nutrients.keys()).collect();
+    names.sort_by(|a, b| compare_nutrients(a, b));
+    names.dedup();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let values: Vec<f64> = days
+                .iter()
+                .map(|d| d.nutrients.get(name).copied().unwrap_or(0.0))
+                .collect();
+            let count = values.len() as f64;
+            let total: f64 = values.iter().sum();
+            let average = total / count;
+            let variance = values.iter().map(|v| (v - average).powi(2)).sum::<f64>() / count;
+
+            NutrientStats {
+                nutrient: name.clone(),
+                total,
+                average,
+                min: values.iter().copied().fold(f64::INFINITY, f64::min),
+                max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                std_dev: variance.sqrt(),
+            }
+        })
+        .collect()
+}
+
+/// Current and longest runs of consecutive dates in `logged` (sorted, unique).
+pub fn streaks(logged: &[NaiveDate], last_day: NaiveDate) -> Streaks {
+    let mut result = Streaks::default();
+    let mut run_start: Option<NaiveDate> = None;
+    let mut run_len = 0;
+    let mut prev: Option<NaiveDate> = None;
+
+    for &date in logged {
+        if prev.is_some_and(|p| date - p == Duration::days(1)) {
+            run_len += 1;
+        } else {
+            run_start = Some(date);
+            run_len = 1;
+        }
+        if run_len > result.longest {
+            result.longest = run_len;
+            result.longest_start = run_start;
+            result.longest_end = Some(date);
+        }
+        prev = Some(date);
+    }
+
+    if prev == Some(last_day) {
+        result.current = run_len;
+    }
+    result
+}
+
+/// Order nutrient names: calories, protein, carbs, fat, then alphabetical.
+pub fn compare_nutrients(a: &str, b: &str) -> Ordering {
+    let rank = |name: &str| {
+        NUTRIENT_ORDER
+            .iter()
+            .position(|n| *n == name)
+            .unwrap_or(NUTRIENT_ORDER.len())
+    };
+    rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dish, MealType, Nutrient};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn log(day: &str, calories: f64, protein: f64) -> MealLog {
+        let dish = Dish::new("Meal", "user1").with_nutrients(vec![
+            Nutrient::new("calories", calories, "kcal"),
+            Nutrient::new("protein", protein, "g"),
+        ]);
+        MealLog::new(date(day), MealType::Dinner, "user1").with_dishes(vec![dish])
+    }
+
+    #[test]
+    fn test_daily_totals_sums_meals() {
+        let logs = vec![
+            log("2025-01-02", 500.0, 20.0),
+            log("2025-01-01", 600.0, 30.0),
+            log("2025-01-02", 700.0, 10.0),
+        ];
+
+        let daily = daily_totals(&logs);
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].date, date("2025-01-01"));
+        assert_eq!(daily[1].meals, 2);
+        assert_eq!(daily[1].nutrients["calories"], 1200.0);
+    }
+
+    #[test]
+    fn test_nutrient_stats() {
+        let logs = vec![
+            log("2025-01-01", 2000.0, 100.0),
+            log("2025-01-02", 1800.0, 80.0),
+            log("2025-01-03", 2200.0, 90.0),
+        ];
+        let daily = daily_totals(&logs);
+        let days: Vec<&DailyTotals> = daily.iter().collect();
+
+        let stats = nutrient_stats(&days);
+        assert_eq!(stats[0].nutrient, "calories");
+        assert_eq!(stats[0].total, 6000.0);
+        assert_eq!(stats[0].average, 2000.0);
+        assert_eq!(stats[0].min, 1800.0);
+        assert_eq!(stats[0].max, 2200.0);
+        assert!((stats[0].std_dev - 163.299).abs() < 0.001);
+        assert_eq!(stats[1].nutrient, "protein");
+        assert_eq!(stats[1].average, 90.0);
+    }
+
+    #[test]
+    fn test_streaks() {
+        let logged = vec![
+            date("2025-01-01"),
+            date("2025-01-02"),
+            date("2025-01-03"),
+            date("2025-01-05"),
+            date("2025-01-06"),
+        ];
+
+        let result = streaks(&logged, date("2025-01-06"));
+        assert_eq!(result.current, 2);
+        assert_eq!(result.longest, 3);
+        assert_eq!(result.longest_start, Some(date("2025-01-01")));
+        assert_eq!(result.longest_end, Some(date("2025-01-03")));
+
+        assert_eq!(streaks(&logged, date("2025-01-07")).current, 0);
+        assert_eq!(streaks(&[], date("2025-01-07")), Streaks::default());
+    }
+
+    #[test]
+    fn test_report_grouped_by_week() {
+        // 2025-01-01 is a Wednesday; ISO week 1 runs Dec 30 - Jan 5
+        let logs = vec![
+            log("2025-01-01", 2000.0, 100.0),
+            log("2025-01-04", 1000.0, 50.0),
+            log("2025-01-07", 1500.0, 60.0),
+            log("2025-02-01", 9999.0, 99.0),
+        ];
+
+        let report = nutrition_report(&logs, date("2025-01-01"), date("2025-01-14"), GroupBy::Week);
+
+        assert_eq!(report.days, 14);
+        assert_eq!(report.days_logged, 3);
+        assert_eq!(report.meals, 3);
+        assert_eq!(report.periods.len(), 3);
+
+        let first = &report.periods[0];
+        assert_eq!(first.label, "2025-W01");
+        assert_eq!(first.start, date("2025-01-01"));
+        assert_eq!(first.end, date("2025-01-05"));
+        assert_eq!(first.days_logged, 2);
+        assert_eq!(first.nutrients[0].average, 1500.0);
+
+        assert_eq!(report.periods[1].label, "2025-W02");
+        assert_eq!(report.periods[2].days_logged, 0);
+        assert!(report.periods[2].nutrients.is_empty());
+        assert_eq!(report.overall[0].max, 2000.0);
+    }
+
+    #[test]
+    fn test_report_grouped_by_month() {
+        let logs = vec![
+            log("2024-12-31", 1000.0, 10.0),
+            log("2025-01-15", 2000.0, 20.0),
+        ];
+
+        let report = nutrition_report(
+            &logs,
+            date("2024-12-15"),
+            date("2025-01-20"),
+            GroupBy::Month,
+        );
+
+        assert_eq!(report.periods.len(), 2);
+        assert_eq!(report.periods[0].label, "2024-12");
+        assert_eq!(report.periods[0].start, date("2024-12-15"));
+        assert_eq!(report.periods[0].end, date("2024-12-31"));
+        assert_eq!(report.periods[1].label, "2025-01");
+        assert_eq!(report.periods[1].end, date("2025-01-20"));
+    }
+
+    #[test]
+    fn test_group_by_parse() {
+        assert_eq!("Week".parse::<GroupBy>(), Ok(GroupBy::Week));
+        assert_eq!("monthly".parse::<GroupBy>(), Ok(GroupBy::Month));
+        assert!("year".parse::<GroupBy>().is_err());
+    }
+}
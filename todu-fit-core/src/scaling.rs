@@ -0,0 +1,239 @@
+//! Recipe scaling
+//!
+//! Scales dishes to a target number of servings and rounds the resulting
+//! quantities to amounts that are practical to measure in a kitchen.
+
+use thiserror::Error;
+
+use crate::models::{Dish, Ingredient, Nutrient};
+use crate::units::{Dimension, Unit};
+
+/// Errors that can occur when scaling a dish
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ScaleError {
+    #[error("Dish '{0}' has no servings set, so it can't be scaled by servings")]
+    MissingServings(String),
+
+    #[error("Servings must be greater than zero (got {0})")]
+    InvalidServings(i32),
+}
+
+/// Common fractions used when displaying kitchen quantities.
+const FRACTIONS: &[(f64, &str)] = &[
+    (0.125, "1/8"),
+    (0.25, "1/4"),
+    (1.0 / 3.0, "1/3"),
+    (0.375, "3/8"),
+    (0.5, "1/2"),
+    (0.625, "5/8"),
+    (2.0 / 3.0, "2/3"),
+    (0.75, "3/4"),
+    (0.875, "7/8"),
+];
+
+impl Dish {
+    /// Multiplier needed to turn this dish's servings into `servings`.
+    pub fn scale_factor(&self, servings: i32) -> Result<f64, ScaleError> {
+        if servings <= 0 {
+            return Err(ScaleError::InvalidServings(servings));
+        }
+        match self.servings {
+            Some(base) if base > 0 => Ok(servings as f64 / base as f64),
+            _ => Err(ScaleError::MissingServings(self.name.clone())),
+        }
+    }
+
+    /// Return a copy of this dish scaled to `servings`.
+    ///
+    /// Ingredient quantities are multiplied and rounded to kitchen fractions.
+    /// Nutrients are stored per serving, so they are left unchanged.
+    pub fn scale_to_servings(&self, servings: i32) -> Result<Dish, ScaleError> {
+        let factor = self.scale_factor(servings)?;
+
+        let mut scaled = self.clone();
+        scaled.servings = Some(servings);
+        scaled.ingredients = self
+            .scaled_ingredients(factor)
+            .into_iter()
+            .map(|mut ing| {
+                ing.quantity = round_to_kitchen_fraction(ing.quantity, &ing.unit);
+                ing
+            })
+            .collect();
+
+        Ok(scaled)
+    }
+
+    /// Ingredients multiplied by `factor`, without rounding.
+    pub fn scaled_ingredients(&self, factor: f64) -> Vec<Ingredient> {
+        self.ingredients
+            .iter()
+            .map(|ing| Ingredient::new(&ing.name, ing.quantity * factor, &ing.unit))
+            .collect()
+    }
+
+    /// Nutrients for `servings` servings (the per-serving amounts multiplied).
+    pub fn nutrients_for_servings(&self, servings: f64) -> Vec<Nutrient> {
+        self.nutrients
+            .iter()
+            .flatten()
+            .map(|n| Nutrient::new(&n.name, n.amount * servings, &n.unit))
+            .collect()
+    }
+}
+
+/// Round a quantity to something practical to measure.
+///
+/// Metric mass and volume round to whole grams/milliliters (one decimal below
+/// 10). Everything else rounds to the nearest eighth or third, and to the
+/// nearest half once the quantity reaches 10.
+pub fn round_to_kitchen_fraction(quantity: f64, unit: &str) -> f64 {
+    if quantity <= 0.0 {
+        return 0.0;
+    }
+
+    match Unit::parse(unit) {
+        Some(Unit::Gram) | Some(Unit::Milliliter) | Some(Unit::Milligram) => {
+            if quantity >= 10.0 {
+                quantity.round()
+            } else {
+                (quantity * 10.0).round() / 10.0
+            }
+        }
+        Some(Unit::Kilogram) | Some(Unit::Liter) => (quantity * 100.0).round() / 100.0,
+        Some(u) if u.dimension() == Dimension::Count && quantity >= 10.0 => quantity.round(),
+        _ => {
+            if quantity >= 10.0 {
+                return (quantity * 2.0).round() / 2.0;
+            }
+            let eighths = (quantity * 8.0).round() / 8.0;
+            let thirds = (quantity * 3.0).round() / 3.0;
+            let rounded = if (thirds - quantity).abs() < (eighths - quantity).abs() {
+                thirds
+            } else {
+                eighths
+            };
+            rounded.max(0.125)
+        }
+    }
+}
+
+/// Format a quantity using whole numbers and common fractions ("1 1/2").
+///
+/// Quantities that aren't close to a common fraction are shown with up to
+/// two decimal places.
+pub fn format_kitchen_quantity(quantity: f64) -> String {
+    let whole = quantity.trunc();
+    let frac = quantity - whole;
+
+    if frac < 0.01 {
+        return format!("{}", whole as i64);
+    }
+    if frac > 0.99 {
+        return format!("{}", whole as i64 + 1);
+    }
+
+    if let Some((_, label)) = FRACTIONS.iter().find(|(v, _)| (v - frac).abs() < 0.01) {
+        return if whole == 0.0 {
+            label.to_string()
+        } else {
+            format!("{} {}", whole as i64, label)
+        };
+    }
+
+    let formatted = format!("{:.2}", quantity);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_dish() -> Dish {
+        Dish::new("Pancakes", "user1")
+            .with_servings(4)
+            .with_ingredients(vec![
+                Ingredient::new("flour", 1.5, "cups"),
+                Ingredient::new("eggs", 2.0, ""),
+                Ingredient::new("milk", 300.0, "ml"),
+            ])
+            .with_nutrients(vec![Nutrient::new("calories", 250.0, "kcal")])
+    }
+
+    #[test]
+    fn test_scale_factor() {
+        let dish = sample_dish();
+        assert_eq!(dish.scale_factor(8).unwrap(), 2.0);
+        assert_eq!(dish.scale_factor(2).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_scale_factor_missing_servings() {
+        let dish = Dish::new("Toast", "user1");
+        assert_eq!(
+            dish.scale_factor(2),
+            Err(ScaleError::MissingServings("Toast".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_scale_factor_invalid_servings() {
+        let dish = sample_dish();
+        assert_eq!(dish.scale_factor(0), Err(ScaleError::InvalidServings(0)));
+    }
+
+    #[test]
+    fn test_scale_to_servings() {
+        let scaled = sample_dish().scale_to_servings(6).unwrap();
+        assert_eq!(scaled.servings, Some(6));
+        assert_eq!(scaled.ingredients[0].quantity, 2.25);
+        assert_eq!(scaled.ingredients[1].quantity, 3.0);
+        assert_eq!(scaled.ingredients[2].quantity, 450.0);
+        // Per-serving nutrients are unchanged
+        assert_eq!(scaled.nutrients, sample_dish().nutrients);
+    }
+
+    #[test]
+    fn test_scale_to_servings_rounds_to_fractions() {
+        let scaled = sample_dish().scale_to_servings(1).unwrap();
+        // 1.5 cups / 4 = 0.375 cups
+        assert_eq!(scaled.ingredients[0].quantity, 0.375);
+        // 2 eggs / 4 = 0.5
+        assert_eq!(scaled.ingredients[1].quantity, 0.5);
+        assert_eq!(scaled.ingredients[2].quantity, 75.0);
+    }
+
+    #[test]
+    fn test_nutrients_for_servings() {
+        let totals = sample_dish().nutrients_for_servings(3.0);
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].amount, 750.0);
+        assert_eq!(totals[0].unit, "kcal");
+    }
+
+    #[test]
+    fn test_round_to_kitchen_fraction() {
+        assert_eq!(round_to_kitchen_fraction(0.34, "cup"), 1.0 / 3.0);
+        assert_eq!(round_to_kitchen_fraction(0.7, "cup"), 2.0 / 3.0);
+        assert_eq!(round_to_kitchen_fraction(1.13, "tsp"), 1.125);
+        assert_eq!(round_to_kitchen_fraction(0.01, "tsp"), 0.125);
+        assert_eq!(round_to_kitchen_fraction(12.3, "oz"), 12.5);
+        assert_eq!(round_to_kitchen_fraction(123.4, "g"), 123.0);
+        assert_eq!(round_to_kitchen_fraction(2.345, "g"), 2.3);
+        assert_eq!(round_to_kitchen_fraction(12.4, ""), 12.0);
+    }
+
+    #[test]
+    fn test_format_kitchen_quantity() {
+        assert_eq!(format_kitchen_quantity(2.0), "2");
+        assert_eq!(format_kitchen_quantity(0.5), "1/2");
+        assert_eq!(format_kitchen_quantity(1.5), "1 1/2");
+        assert_eq!(format_kitchen_quantity(2.0 / 3.0), "2/3");
+        assert_eq!(format_kitchen_quantity(1.125), "1 1/8");
+        assert_eq!(format_kitchen_quantity(2.3), "2.3");
+        assert_eq!(format_kitchen_quantity(123.0), "123");
+    }
+}
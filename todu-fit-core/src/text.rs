@@ -0,0 +1,23 @@
+//! Text helpers for display names and headings.
+
+/// Upper-case the first letter ("produce" -> "Produce").
+pub fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capitalize() {
+        assert_eq!(capitalize("produce"), "Produce");
+        assert_eq!(capitalize("second breakfast"), "Second breakfast");
+        assert_eq!(capitalize("éclair"), "Éclair");
+        assert_eq!(capitalize(""), "");
+    }
+}
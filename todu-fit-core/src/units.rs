@@ -0,0 +1,431 @@
+//! Unit handling for ingredient quantities
+//!
+//! Understands common metric and imperial mass, volume and count units,
+//! converts between units of the same dimension, and falls back to
+//! approximate ingredient densities to convert between volume and mass.
+
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::models::Ingredient;
+
+/// Errors that can occur when converting quantities
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum UnitError {
+    #[error("Unknown unit '{0}'")]
+    UnknownUnit(String),
+
+    #[error("Cannot convert {from} to {to}")]
+    Incompatible { from: String, to: String },
+
+    #[error("Cannot convert {from} to {to} for '{ingredient}': density unknown")]
+    UnknownDensity {
+        ingredient: String,
+        from: String,
+        to: String,
+    },
+}
+
+/// The physical dimension a unit measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Mass,
+    Volume,
+    Count,
+}
+
+/// A known measurement unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Milligram,
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+    Milliliter,
+    Liter,
+    Teaspoon,
+    Tablespoon,
+    FluidOunce,
+    Cup,
+    Pint,
+    Quart,
+    Gallon,
+    Each,
+    Dozen,
+}
+
+impl Unit {
+    /// Parse a unit name or abbreviation (case-insensitive).
+    ///
+    /// An empty string is treated as a plain count (`Each`).
+    pub fn parse(s: &str) -> Option<Unit> {
+        let normalized = s.trim().trim_end_matches('.').to_lowercase();
+        let unit = match normalized.as_str() {
+            "mg" | "milligram" | "milligrams" => Unit::Milligram,
+            "g" | "gr" | "gram" | "grams" | "gramme" | "grammes" => Unit::Gram,
+            "kg" | "kgs" | "kilo" | "kilos" | "kilogram" | "kilograms" => Unit::Kilogram,
+            "oz" | "ounce" | "ounces" => Unit::Ounce,
+            "lb" | "lbs" | "pound" | "pounds" => Unit::Pound,
+            "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => Unit::Milliliter,
+            "l" | "liter" | "liters" | "litre" | "litres" => Unit::Liter,
+            "tsp" | "tsps" | "teaspoon" | "teaspoons" => Unit::Teaspoon,
+            "tbsp" | "tbsps" | "tbs" | "tablespoon" | "tablespoons" => Unit::Tablespoon,
+            "fl oz" | "fl. oz" | "floz" | "fluid ounce" | "fluid ounces" => Unit::FluidOunce,
+            "c" | "cup" | "cups" => Unit::Cup,
+            "pt" | "pint" | "pints" => Unit::Pint,
+            "qt" | "quart" | "quarts" => Unit::Quart,
+            "gal" | "gallon" | "gallons" => Unit::Gallon,
+            "" | "each" | "ea" | "whole" | "piece" | "pieces" | "pc" | "pcs" => Unit::Each,
+            "dozen" | "doz" => Unit::Dozen,
+            _ => {
+                // "T" and "t" are the traditional tablespoon/teaspoon shorthands
+                return match s.trim() {
+                    "T" => Some(Unit::Tablespoon),
+                    "t" => Some(Unit::Teaspoon),
+                    _ => None,
+                };
+            }
+        };
+        Some(unit)
+    }
+
+    /// The dimension this unit measures.
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Milligram | Unit::Gram | Unit::Kilogram | Unit::Ounce | Unit::Pound => {
+                Dimension::Mass
+            }
+            Unit::Milliliter
+            | Unit::Liter
+            | Unit::Teaspoon
+            | Unit::Tablespoon
+            | Unit::FluidOunce
+            | Unit::Cup
+            | Unit::Pint
+            | Unit::Quart
+            | Unit::Gallon => Dimension::Volume,
+            Unit::Each | Unit::Dozen => Dimension::Count,
+        }
+    }
+
+    /// How many base units (grams, milliliters or items) one of this unit is.
+    pub fn base_factor(&self) -> f64 {
+        match self {
+            Unit::Milligram => 0.001,
+            Unit::Gram => 1.0,
+            Unit::Kilogram => 1000.0,
+            Unit::Ounce => 28.349523125,
+            Unit::Pound => 453.59237,
+            Unit::Milliliter => 1.0,
+            Unit::Liter => 1000.0,
+            Unit::Teaspoon => 4.92892159375,
+            Unit::Tablespoon => 14.78676478125,
+            Unit::FluidOunce => 29.5735295625,
+            Unit::Cup => 236.5882365,
+            Unit::Pint => 473.176473,
+            Unit::Quart => 946.352946,
+            Unit::Gallon => 3785.411784,
+            Unit::Each => 1.0,
+            Unit::Dozen => 12.0,
+        }
+    }
+
+    /// Canonical short name for the unit.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Milligram => "mg",
+            Unit::Gram => "g",
+            Unit::Kilogram => "kg",
+            Unit::Ounce => "oz",
+            Unit::Pound => "lb",
+            Unit::Milliliter => "ml",
+            Unit::Liter => "l",
+            Unit::Teaspoon => "tsp",
+            Unit::Tablespoon => "tbsp",
+            Unit::FluidOunce => "fl oz",
+            Unit::Cup => "cup",
+            Unit::Pint => "pint",
+            Unit::Quart => "quart",
+            Unit::Gallon => "gallon",
+            Unit::Each => "",
+            Unit::Dozen => "dozen",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unit::parse(s).ok_or_else(|| UnitError::UnknownUnit(s.to_string()))
+    }
+}
+
+/// Approximate densities in grams per milliliter, keyed by ingredient name fragment.
+const DENSITIES: &[(&str, f64)] = &[
+    ("water", 1.0),
+    ("milk", 1.03),
+    ("buttermilk", 1.03),
+    ("cream", 1.0),
+    ("sour cream", 0.97),
+    ("ice cream", 0.56),
+    ("whipped cream", 0.25),
+    ("yogurt", 1.03),
+    ("butter", 0.911),
+    ("oil", 0.92),
+    ("olive oil", 0.91),
+    ("honey", 1.42),
+    ("maple syrup", 1.32),
+    ("molasses", 1.4),
+    ("flour", 0.53),
+    ("bread flour", 0.55),
+    ("whole wheat flour", 0.51),
+    ("sugar", 0.85),
+    ("brown sugar", 0.93),
+    ("powdered sugar", 0.51),
+    ("icing sugar", 0.51),
+    ("salt", 1.22),
+    ("kosher salt", 0.61),
+    ("baking soda", 1.22),
+    ("baking powder", 0.81),
+    ("cocoa", 0.36),
+    ("rice", 0.78),
+    ("oats", 0.38),
+    ("rolled oats", 0.38),
+    ("cornstarch", 0.54),
+    ("peanut butter", 1.08),
+    ("parmesan", 0.42),
+    ("shredded cheese", 0.47),
+    ("chocolate chips", 0.72),
+    ("vinegar", 1.01),
+    ("soy sauce", 1.15),
+    ("broth", 1.0),
+    ("stock", 1.0),
+];
+
+/// Approximate density of an ingredient in grams per milliliter.
+///
+/// Matches whole words at the end of the name (case-insensitive), ignoring
+/// anything after a comma or parenthesis, so "extra virgin olive oil, cold"
+/// is olive oil while "boiled potatoes" and "cream of tartar" match nothing.
+/// The longest match wins, so "brown sugar" is preferred over "sugar" and
+/// "sour cream" over "cream".
+pub fn density(ingredient_name: &str) -> Option<f64> {
+    let name = ingredient_name.to_lowercase();
+    let name = name.split([',', '(']).next().unwrap_or_default();
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let ends_with = |key: &str| {
+        let key: Vec<&str> = key.split(' ').collect();
+        words.ends_with(&key)
+    };
+
+    DENSITIES
+        .iter()
+        .filter(|(key, _)| ends_with(key))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, density)| *density)
+}
+
+/// Convert a quantity between two units of the same dimension.
+pub fn convert(quantity: f64, from: &str, to: &str) -> Result<f64, UnitError> {
+    let from_unit: Unit = from.parse()?;
+    let to_unit: Unit = to.parse()?;
+
+    if from_unit.dimension() != to_unit.dimension() {
+        return Err(UnitError::Incompatible {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+
+    Ok(quantity * from_unit.base_factor() / to_unit.base_factor())
+}
+
+/// Convert a quantity of a named ingredient between two units.
+///
+/// Units of the same dimension convert directly. Volume and mass convert
+/// through the ingredient's approximate density when one is known.
+pub fn convert_ingredient(
+    ingredient_name: &str,
+    quantity: f64,
+    from: &str,
+    to: &str,
+) -> Result<f64, UnitError> {
+    let from_unit: Unit = from.parse()?;
+    let to_unit: Unit = to.parse()?;
+
+    let base = quantity * from_unit.base_factor();
+    let converted = match (from_unit.dimension(), to_unit.dimension()) {
+        (a, b) if a == b => base,
+        (Dimension::Volume, Dimension::Mass) | (Dimension::Mass, Dimension::Volume) => {
+            let density = density(ingredient_name).ok_or_else(|| UnitError::UnknownDensity {
+                ingredient: ingredient_name.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            })?;
+            if from_unit.dimension() == Dimension::Volume {
+                base * density
+            } else {
+                base / density
+            }
+        }
+        _ => {
+            return Err(UnitError::Incompatible {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+        }
+    };
+
+    Ok(converted / to_unit.base_factor())
+}
+
+impl Ingredient {
+    /// Return this ingredient expressed in another unit.
+    pub fn convert_to(&self, unit: &str) -> Result<Ingredient, UnitError> {
+        let quantity = convert_ingredient(&self.name, self.quantity, &self.unit, unit)?;
+        Ok(Ingredient::new(&self.name, quantity, unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        assert_eq!(Unit::parse("g"), Some(Unit::Gram));
+        assert_eq!(Unit::parse("Grams"), Some(Unit::Gram));
+        assert_eq!(Unit::parse("lbs"), Some(Unit::Pound));
+        assert_eq!(Unit::parse("Tbsp."), Some(Unit::Tablespoon));
+        assert_eq!(Unit::parse("T"), Some(Unit::Tablespoon));
+        assert_eq!(Unit::parse("t"), Some(Unit::Teaspoon));
+        assert_eq!(Unit::parse("fl oz"), Some(Unit::FluidOunce));
+        assert_eq!(Unit::parse("litres"), Some(Unit::Liter));
+        assert_eq!(Unit::parse(""), Some(Unit::Each));
+        assert_eq!(Unit::parse("clove"), None);
+    }
+
+    #[test]
+    fn test_from_str_unknown() {
+        let result: Result<Unit, _> = "handful".parse();
+        assert_eq!(result, Err(UnitError::UnknownUnit("handful".to_string())));
+    }
+
+    #[test]
+    fn test_dimensions() {
+        assert_eq!(Unit::Ounce.dimension(), Dimension::Mass);
+        assert_eq!(Unit::FluidOunce.dimension(), Dimension::Volume);
+        assert_eq!(Unit::Dozen.dimension(), Dimension::Count);
+    }
+
+    #[test]
+    fn test_convert_mass() {
+        assert_close(convert(1.0, "lb", "g").unwrap(), 453.59);
+        assert_close(convert(500.0, "g", "lb").unwrap(), 1.10);
+        assert_close(convert(16.0, "oz", "lb").unwrap(), 1.0);
+        assert_close(convert(2.0, "kg", "g").unwrap(), 2000.0);
+    }
+
+    #[test]
+    fn test_convert_volume() {
+        assert_close(convert(3.0, "tsp", "tbsp").unwrap(), 1.0);
+        assert_close(convert(1.0, "cup", "ml").unwrap(), 236.59);
+        assert_close(convert(4.0, "cups", "quart").unwrap(), 1.0);
+        assert_close(convert(1.0, "l", "ml").unwrap(), 1000.0);
+    }
+
+    #[test]
+    fn test_convert_count() {
+        assert_close(convert(1.0, "dozen", "").unwrap(), 12.0);
+        assert_close(convert(6.0, "each", "dozen").unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_convert_incompatible() {
+        let result = convert(1.0, "cup", "g");
+        assert!(matches!(result, Err(UnitError::Incompatible { .. })));
+    }
+
+    #[test]
+    fn test_convert_unknown_unit() {
+        let result = convert(1.0, "pinch", "g");
+        assert_eq!(result, Err(UnitError::UnknownUnit("pinch".to_string())));
+    }
+
+    #[test]
+    fn test_density_longest_match() {
+        assert_eq!(density("Brown Sugar"), Some(0.93));
+        assert_eq!(density("granulated sugar"), Some(0.85));
+        assert_eq!(density("extra virgin olive oil"), Some(0.91));
+        assert_eq!(density("chicken breast"), None);
+        assert_eq!(density("all-purpose flour (sifted)"), Some(0.53));
+        assert_eq!(density("butter, softened"), Some(0.911));
+        assert_eq!(density("heavy cream"), Some(1.0));
+        assert_eq!(density("light sour cream"), Some(0.97));
+        assert_eq!(density("vanilla ice cream"), Some(0.56));
+    }
+
+    #[test]
+    fn test_density_needs_whole_words() {
+        assert_eq!(density("boiled potatoes"), None);
+        assert_eq!(density("cream of tartar"), None);
+        assert_eq!(density("licorice"), None);
+        assert_eq!(density("milk chocolate"), None);
+        assert_eq!(density("rice vinegar"), Some(1.01));
+    }
+
+    #[test]
+    fn test_convert_ingredient_volume_to_mass() {
+        // 1 cup of flour is roughly 125 g
+        let grams = convert_ingredient("all-purpose flour", 1.0, "cup", "g").unwrap();
+        assert_close(grams, 125.39);
+    }
+
+    #[test]
+    fn test_convert_ingredient_mass_to_volume() {
+        let ml = convert_ingredient("water", 250.0, "g", "ml").unwrap();
+        assert_close(ml, 250.0);
+    }
+
+    #[test]
+    fn test_convert_ingredient_unknown_density() {
+        let result = convert_ingredient("chicken", 1.0, "cup", "g");
+        assert!(matches!(result, Err(UnitError::UnknownDensity { .. })));
+    }
+
+    #[test]
+    fn test_convert_ingredient_count_to_mass_fails() {
+        let result = convert_ingredient("flour", 2.0, "", "g");
+        assert!(matches!(result, Err(UnitError::Incompatible { .. })));
+    }
+
+    #[test]
+    fn test_ingredient_convert_to() {
+        let butter = Ingredient::new("butter", 2.0, "tbsp");
+        let converted = butter.convert_to("g").unwrap();
+        assert_eq!(converted.name, "butter");
+        assert_eq!(converted.unit, "g");
+        assert_close(converted.quantity, 26.94);
+    }
+}
@@ -3,7 +3,7 @@ import { useParams, Link } from 'react-router-dom'
 import { useRepoState, RepoLoading } from '../repo'
 import { useMealPlans } from './useMealPlans'
 import { useDishes } from '../dishes/useDishes'
-import { MEAL_TYPES, MEAL_TYPE_COLORS, MEAL_TYPE_LABELS, MealType, MealPlan } from './types'
+import { mealTypeColor, mealTypeLabel, mealTypesIncluding, MealPlan } from './types'
 import { ConfirmDialog } from '../components'
 
 // Date utilities
@@ -26,7 +26,7 @@ function formatDate(date: Date): string {
 }
 
 export function DayView() {
-  const { isReady } = useRepoState()
+  const { isReady, mealTypes } = useRepoState()
 
   if (!isReady) {
     return <RepoLoading />
@@ -58,18 +58,22 @@ function DayViewContent() {
 
   // Group plans by meal type
   const plansByMealType = useMemo(() => {
-    const grouped: Record<MealType, MealPlan[]> = {
-      breakfast: [],
-      lunch: [],
-      dinner: [],
-      snack: [],
-    }
+    const grouped: Record<string, MealPlan[]> = {}
     for (const plan of plans) {
+      if (!grouped[plan.mealType]) {
+        grouped[plan.mealType] = []
+      }
       grouped[plan.mealType].push(plan)
     }
     return grouped
   }, [plans])
 
+  // Sections for the group's meal slots, plus any other slot in use
+  const sectionTypes = useMemo(
+    () => mealTypesIncluding(mealTypes, plans.map((plan) => plan.mealType)),
+    [mealTypes, plans]
+  )
+
   // Format date for display
   const displayDate = currentDate.toLocaleDateString('en-US', {
     weekday: 'long',
@@ -132,8 +136,8 @@ function DayViewContent() {
 
       {/* Meal Sections */}
       <div className="space-y-6">
-        {MEAL_TYPES.map((mealType) => {
-          const typePlans = plansByMealType[mealType]
+        {sectionTypes.map((mealType) => {
+          const typePlans = plansByMealType[mealType] ?? []
 
           return (
             <div
@@ -142,14 +146,14 @@ function DayViewContent() {
             >
               {/* Meal Type Header */}
               <div
-                className={`px-4 py-3 flex justify-between items-center ${MEAL_TYPE_COLORS[mealType]} bg-opacity-20 dark:bg-opacity-30`}
+                className={`px-4 py-3 flex justify-between items-center ${mealTypeColor(mealType)} bg-opacity-20 dark:bg-opacity-30`}
               >
                 <div className="flex items-center gap-2">
                   <span
-                    className={`w-3 h-3 rounded-full ${MEAL_TYPE_COLORS[mealType]}`}
+                    className={`w-3 h-3 rounded-full ${mealTypeColor(mealType)}`}
                   />
                   <h2 className="font-semibold text-gray-900 dark:text-gray-100">
-                    {MEAL_TYPE_LABELS[mealType]}
+                    {mealTypeLabel(mealType)}
                   </h2>
                 </div>
                 <Link
@@ -3,7 +3,7 @@ import { Link, useSearchParams } from 'react-router-dom'
 import { useRepoState, RepoLoading } from '../repo'
 import { useMealPlans } from './useMealPlans'
 import { useDishes } from '../dishes/useDishes'
-import { MEAL_TYPE_COLORS, MEAL_TYPE_LABELS, MealType, MEAL_TYPES, MealPlan } from './types'
+import { mealTypeColor, mealTypeLabel, mealTypesIncluding, MealType, MealPlan } from './types'
 import { ShoppingCart } from './ShoppingCart'
 
 type TabType = 'meals' | 'shopping'
@@ -27,10 +27,12 @@ function addDays(date: Date, days: number): Date {
   return result
 }
 
-function getWeekStart(date: Date): Date {
+// First day of the week containing date, for weeks starting on firstDay
+// (0 = Sunday). Shopping carts are keyed by this date.
+function getWeekStart(date: Date, firstDay: number): Date {
   const result = new Date(date)
-  const day = result.getDay()
-  result.setDate(result.getDate() - day)
+  const daysSinceStart = (result.getDay() - firstDay + 7) % 7
+  result.setDate(result.getDate() - daysSinceStart)
   return result
 }
 
@@ -59,6 +61,7 @@ function MealCalendarContent() {
   const { getPlansForRange, isLoading } = useMealPlans()
   const { getDish } = useDishes()
   const [activeTab, setActiveTab] = useState<TabType>('meals')
+  const { weekStartDay, mealTypes } = useRepoState()
 
   // Get date from URL or default to today
   const dateParam = searchParams.get('date')
@@ -70,7 +73,10 @@ function MealCalendarContent() {
   })
 
   // Calculate week range
-  const weekStart = useMemo(() => getWeekStart(currentDate), [currentDate])
+  const weekStart = useMemo(
+    () => getWeekStart(currentDate, weekStartDay),
+    [currentDate, weekStartDay]
+  )
   const weekEnd = useMemo(() => addDays(weekStart, 6), [weekStart])
 
   // Get plans for the week
@@ -167,7 +173,7 @@ function MealCalendarContent() {
       {/* Calendar Grid - Hidden on mobile, shown on sm+ */}
       <div className="hidden sm:grid grid-cols-7 gap-px bg-gray-200 dark:bg-gray-700 border border-gray-200 dark:border-gray-700 rounded-lg overflow-hidden">
         {/* Day Headers */}
-        {DAY_NAMES.map((name) => (
+        {days.map((day) => DAY_NAMES[day.date.getDay()]).map((name) => (
           <div
             key={name}
             className="bg-gray-100 dark:bg-gray-800 p-3 text-center font-semibold text-gray-600 dark:text-gray-400 text-sm"
@@ -197,8 +203,8 @@ function MealCalendarContent() {
                 {day.plans.slice(0, 4).map((plan, idx) => (
                   <span
                     key={idx}
-                    className={`w-3 h-3 rounded-full ${MEAL_TYPE_COLORS[plan.mealType]}`}
-                    title={MEAL_TYPE_LABELS[plan.mealType]}
+                    className={`w-3 h-3 rounded-full ${mealTypeColor(plan.mealType)}`}
+                    title={mealTypeLabel(plan.mealType)}
                   />
                 ))}
                 {day.plans.length > 4 && (
@@ -241,7 +247,7 @@ function MealCalendarContent() {
                 {day.plans.slice(0, 4).map((plan, idx) => (
                   <span
                     key={idx}
-                    className={`w-3 h-3 rounded-full ${MEAL_TYPE_COLORS[plan.mealType]}`}
+                    className={`w-3 h-3 rounded-full ${mealTypeColor(plan.mealType)}`}
                   />
                 ))}
                 {day.plans.length > 4 && (
@@ -257,10 +263,10 @@ function MealCalendarContent() {
 
       {/* Legend */}
       <div className="flex flex-wrap gap-3 sm:gap-6 mt-4 justify-center text-sm text-gray-600 dark:text-gray-400">
-        {(['breakfast', 'lunch', 'dinner', 'snack'] as MealType[]).map((type) => (
+        {mealTypes.map((type) => (
           <span key={type} className="flex items-center gap-1.5 sm:gap-2">
-            <span className={`w-3 h-3 rounded-full ${MEAL_TYPE_COLORS[type]}`} />
-            <span className="text-xs sm:text-sm">{MEAL_TYPE_LABELS[type]}</span>
+            <span className={`w-3 h-3 rounded-full ${mealTypeColor(type)}`} />
+            <span className="text-xs sm:text-sm">{mealTypeLabel(type)}</span>
           </span>
         ))}
       </div>
@@ -311,6 +317,12 @@ interface WeeklyMealsTabProps {
 }
 
 function WeeklyMealsTab({ weekPlans, getDish }: WeeklyMealsTabProps) {
+  const { mealTypes } = useRepoState()
+  const sectionTypes = useMemo(
+    () => mealTypesIncluding(mealTypes, weekPlans.map((plan) => plan.mealType)),
+    [mealTypes, weekPlans]
+  )
+
   // Group plans by date, then by meal type
   const plansByDate = useMemo(() => {
     const grouped: Record<string, Record<MealType, MealPlan[]>> = {}
@@ -371,16 +383,16 @@ function WeeklyMealsTab({ weekPlans, getDish }: WeeklyMealsTabProps) {
 
           {/* Meals for this day */}
           <div className="divide-y divide-gray-100 dark:divide-gray-700">
-            {MEAL_TYPES.map((mealType) => {
+            {sectionTypes.map((mealType) => {
               const plans = plansByDate[dateStr][mealType]
               if (!plans || plans.length === 0) return null
 
               return (
                 <div key={mealType} className="px-4 py-3">
                   <div className="flex items-center gap-2 mb-2">
-                    <span className={`w-2.5 h-2.5 rounded-full ${MEAL_TYPE_COLORS[mealType]}`} />
+                    <span className={`w-2.5 h-2.5 rounded-full ${mealTypeColor(mealType)}`} />
                     <span className="text-sm font-medium text-gray-600 dark:text-gray-400">
-                      {MEAL_TYPE_LABELS[mealType]}
+                      {mealTypeLabel(mealType)}
                     </span>
                   </div>
                   <div className="ml-4 space-y-1">
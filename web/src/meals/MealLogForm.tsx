@@ -5,7 +5,7 @@ import { useAuth } from '../auth'
 import { useMealLogs } from './useMealLogs'
 import { useMealPlans } from './useMealPlans'
 import { useDishes } from '../dishes/useDishes'
-import { MealLog, MealType, MEAL_TYPES, MEAL_TYPE_LABELS } from './types'
+import { MealLog, MealType, mealTypeLabel, mealTypesIncluding } from './types'
 import { DishSelector } from './DishSelector'
 
 // Get today's date in YYYY-MM-DD format
@@ -18,7 +18,7 @@ function getTodayDate(): string {
 }
 
 export function MealLogForm() {
-  const { isReady } = useRepoState()
+  const { isReady, mealTypes } = useRepoState()
 
   if (!isReady) {
     return <RepoLoading />
@@ -160,9 +160,9 @@ function MealLogFormContent() {
               onChange={(e) => setMealType(e.target.value as MealType)}
               className="w-full px-4 py-3 min-h-[44px] border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent"
             >
-              {MEAL_TYPES.map((type) => (
+              {mealTypesIncluding(mealTypes, [mealType]).map((type) => (
                 <option key={type} value={type}>
-                  {MEAL_TYPE_LABELS[type]}
+                  {mealTypeLabel(type)}
                 </option>
               ))}
             </select>
@@ -174,7 +174,7 @@ function MealLogFormContent() {
           <div className="border border-green-200 dark:border-green-800 bg-green-50 dark:bg-green-900/30 rounded p-4">
             <div className="flex justify-between items-center mb-2">
               <h3 className="font-medium text-green-800 dark:text-green-300">
-                Planned for {MEAL_TYPE_LABELS[mealType]}
+                Planned for {mealTypeLabel(mealType)}
               </h3>
               <button
                 type="button"
@@ -3,7 +3,7 @@ import { useParams, Link } from 'react-router-dom'
 import { useRepoState, RepoLoading } from '../repo'
 import { useMealLogs } from './useMealLogs'
 import { useDishes } from '../dishes/useDishes'
-import { MEAL_TYPES, MEAL_TYPE_COLORS, MEAL_TYPE_LABELS, MealType, MealLog } from './types'
+import { mealTypeColor, mealTypeLabel, mealTypesIncluding, MealLog } from './types'
 import { ConfirmDialog } from '../components'
 
 // Date utilities
@@ -30,7 +30,7 @@ function getTodayDate(): string {
 }
 
 export function MealLogList() {
-  const { isReady } = useRepoState()
+  const { isReady, mealTypes } = useRepoState()
 
   if (!isReady) {
     return <RepoLoading />
@@ -61,18 +61,22 @@ function MealLogListContent() {
 
   // Group logs by meal type
   const logsByMealType = useMemo(() => {
-    const grouped: Record<MealType, MealLog[]> = {
-      breakfast: [],
-      lunch: [],
-      dinner: [],
-      snack: [],
-    }
+    const grouped: Record<string, MealLog[]> = {}
     for (const log of logs) {
+      if (!grouped[log.mealType]) {
+        grouped[log.mealType] = []
+      }
       grouped[log.mealType].push(log)
     }
     return grouped
   }, [logs])
 
+  // Sections for the group's meal slots, plus any other slot in use
+  const sectionTypes = useMemo(
+    () => mealTypesIncluding(mealTypes, logs.map((log) => log.mealType)),
+    [mealTypes, logs]
+  )
+
   // Format date for display
   const displayDate = currentDate.toLocaleDateString('en-US', {
     weekday: 'long',
@@ -158,8 +162,8 @@ function MealLogListContent() {
 
       {/* Meal Sections */}
       <div className="space-y-6">
-        {MEAL_TYPES.map((mealType) => {
-          const typeLogs = logsByMealType[mealType]
+        {sectionTypes.map((mealType) => {
+          const typeLogs = logsByMealType[mealType] ?? []
 
           return (
             <div
@@ -168,14 +172,14 @@ function MealLogListContent() {
             >
               {/* Meal Type Header */}
               <div
-                className={`px-4 py-3 flex justify-between items-center ${MEAL_TYPE_COLORS[mealType]} bg-opacity-20 dark:bg-opacity-30`}
+                className={`px-4 py-3 flex justify-between items-center ${mealTypeColor(mealType)} bg-opacity-20 dark:bg-opacity-30`}
               >
                 <div className="flex items-center gap-2">
                   <span
-                    className={`w-3 h-3 rounded-full ${MEAL_TYPE_COLORS[mealType]}`}
+                    className={`w-3 h-3 rounded-full ${mealTypeColor(mealType)}`}
                   />
                   <h2 className="font-semibold text-gray-900 dark:text-gray-100">
-                    {MEAL_TYPE_LABELS[mealType]}
+                    {mealTypeLabel(mealType)}
                   </h2>
                 </div>
                 <Link
@@ -3,7 +3,7 @@ import { useParams, useNavigate, useSearchParams, Link } from 'react-router-dom'
 import { useRepoState, RepoLoading } from '../repo'
 import { useMealPlans } from './useMealPlans'
 import { useDishes } from '../dishes/useDishes'
-import { MealPlan, MealType, MEAL_TYPES, MEAL_TYPE_LABELS } from './types'
+import { MealPlan, MealType, mealTypeLabel, mealTypesIncluding } from './types'
 import { DishSelector } from './DishSelector'
 
 // Get today's date in YYYY-MM-DD format
@@ -16,7 +16,7 @@ function getTodayDate(): string {
 }
 
 export function MealPlanForm() {
-  const { isReady } = useRepoState()
+  const { isReady, mealTypes } = useRepoState()
 
   if (!isReady) {
     return <RepoLoading />
@@ -70,7 +70,7 @@ function MealPlanFormContent() {
     const now = new Date().toISOString()
 
     // Generate default title if not provided
-    const finalTitle = title.trim() || `${MEAL_TYPE_LABELS[mealType]} on ${date}`
+    const finalTitle = title.trim() || `${mealTypeLabel(mealType)} on ${date}`
 
     if (isEdit && id) {
       updateMealPlan(id, {
@@ -146,9 +146,9 @@ function MealPlanFormContent() {
               onChange={(e) => setMealType(e.target.value as MealType)}
               className="w-full px-4 py-3 min-h-[44px] border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent"
             >
-              {MEAL_TYPES.map((type) => (
+              {mealTypesIncluding(mealTypes, [mealType]).map((type) => (
                 <option key={type} value={type}>
-                  {MEAL_TYPE_LABELS[type]}
+                  {mealTypeLabel(type)}
                 </option>
               ))}
             </select>
@@ -166,7 +166,7 @@ function MealPlanFormContent() {
               id="title"
               value={title}
               onChange={(e) => setTitle(e.target.value)}
-              placeholder={`${MEAL_TYPE_LABELS[mealType]} on ${date}`}
+              placeholder={`${mealTypeLabel(mealType)} on ${date}`}
               className="w-full px-4 py-3 min-h-[44px] border border-gray-300 dark:border-gray-600 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
             />
           </div>
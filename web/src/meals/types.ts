@@ -1,4 +1,5 @@
-export type MealType = 'breakfast' | 'lunch' | 'dinner' | 'snack'
+// Built-in meal types, or any meal slot a group defines
+export type MealType = string
 
 export interface MealPlan {
   id: string
@@ -27,23 +28,40 @@ export interface CliMealPlan {
 // Document as stored by CLI - UUIDs as keys at root level
 export type MealPlansDoc = Record<string, CliMealPlan>
 
-// Meal type display order and colors
+// Built-in meal type display order and colors
 export const MEAL_TYPES: MealType[] = ['breakfast', 'lunch', 'dinner', 'snack']
 
-export const MEAL_TYPE_COLORS: Record<MealType, string> = {
+export const MEAL_TYPE_COLORS: Record<string, string> = {
   breakfast: 'bg-amber-400',
   lunch: 'bg-green-400',
   dinner: 'bg-blue-400',
   snack: 'bg-purple-400',
 }
 
-export const MEAL_TYPE_LABELS: Record<MealType, string> = {
+export const MEAL_TYPE_LABELS: Record<string, string> = {
   breakfast: 'Breakfast',
   lunch: 'Lunch',
   dinner: 'Dinner',
   snack: 'Snack',
 }
 
+// Color for a meal type, with a neutral one for group-defined slots
+export function mealTypeColor(mealType: MealType): string {
+  return MEAL_TYPE_COLORS[mealType] ?? 'bg-gray-400'
+}
+
+// The group's meal types followed by any others in use, such as slots the
+// group has since removed, so no plan or log goes unlisted
+export function mealTypesIncluding(mealTypes: MealType[], used: MealType[]): MealType[] {
+  const extra = used.filter((type, i) => !mealTypes.includes(type) && used.indexOf(type) === i)
+  return [...mealTypes, ...extra]
+}
+
+// Label for a meal type; group-defined slots are capitalized ("Brunch")
+export function mealTypeLabel(mealType: MealType): string {
+  return MEAL_TYPE_LABELS[mealType] ?? mealType.charAt(0).toUpperCase() + mealType.slice(1)
+}
+
 // ============================================================
 // Meal Log Types (what was actually eaten - user-owned/private)
 // ============================================================
@@ -97,5 +115,6 @@ export interface CliShoppingCart {
   manual_items: ManualShoppingItem[]
 }
 
-// Document as stored - week start dates as keys (e.g., "2026-01-11")
+// Document as stored - week start dates as keys (e.g., "2026-01-11"), plus
+// CLI shopping trips keyed by date range (e.g., "2026-01-15..2026-01-18")
 export type ShoppingCartsDoc = Record<string, CliShoppingCart>
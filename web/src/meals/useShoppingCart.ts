@@ -23,11 +23,12 @@ function getString(value: unknown): string {
   return ''
 }
 
-// Get the Sunday of the week containing the given date
-export function getWeekStart(date: Date): string {
+// Get the first day of the week containing the given date, for weeks
+// starting on firstDay (0 = Sunday, matching the group's week_start)
+export function getWeekStart(date: Date, firstDay = 0): string {
   const d = new Date(date)
-  const day = d.getDay()
-  d.setDate(d.getDate() - day)
+  const daysSinceStart = (d.getDay() - firstDay + 7) % 7
+  d.setDate(d.getDate() - daysSinceStart)
   const year = d.getFullYear()
   const month = String(d.getMonth() + 1).padStart(2, '0')
   const dayStr = String(d.getDate()).padStart(2, '0')
@@ -35,9 +36,29 @@ export function getWeekStart(date: Date): string {
 }
 
 // Get week start from a date string (YYYY-MM-DD)
-export function getWeekStartFromDateString(dateStr: string): string {
+export function getWeekStartFromDateString(dateStr: string, firstDay = 0): string {
   const [year, month, day] = dateStr.split('-').map(Number)
-  return getWeekStart(new Date(year, month - 1, day))
+  return getWeekStart(new Date(year, month - 1, day), firstDay)
+}
+
+// Days since the epoch for a date string (YYYY-MM-DD)
+function dayNumber(dateStr: string): number {
+  const [year, month, day] = dateStr.split('-').map(Number)
+  return Date.UTC(year, month - 1, day) / 86400000
+}
+
+// The key of a week's cart: its own key, or else that of the stored week
+// cart overlapping it most, such as a Sunday-keyed cart from before the
+// group changed its week start. Carts keep the key they were created under.
+function findCartKey(doc: ShoppingCartsDoc | undefined, weekStart: string): string {
+  if (!doc || doc[weekStart]) {
+    return weekStart
+  }
+  const start = dayNumber(weekStart)
+  const overlapping = Object.keys(doc).find(
+    (key) => /^\d{4}-\d{2}-\d{2}$/.test(key) && Math.abs(dayNumber(key) - start) <= 3
+  )
+  return overlapping ?? weekStart
 }
 
 export function useShoppingCart(weekStart: string) {
@@ -60,9 +81,11 @@ export function useShoppingCart(weekStart: string) {
     return () => clearTimeout(timer)
   }, [doc])
 
+  const cartKey = useMemo(() => findCartKey(doc, weekStart), [doc, weekStart])
+
   // Get the cart for this week, or empty defaults
   const cart = useMemo((): CliShoppingCart => {
-    const weekCart = doc?.[weekStart]
+    const weekCart = doc?.[cartKey]
     if (!weekCart) {
       return { checked: [], manual_items: [] }
     }
@@ -74,7 +97,7 @@ export function useShoppingCart(weekStart: string) {
         unit: getString(item.unit),
       })),
     }
-  }, [doc, weekStart])
+  }, [doc, cartKey])
 
   // Check if an item is checked (case-insensitive)
   const isChecked = (name: string): boolean => {
@@ -86,14 +109,14 @@ export function useShoppingCart(weekStart: string) {
     const key = name.toLowerCase()
     changeDoc((d) => {
       // Initialize week if needed
-      if (!d[weekStart]) {
-        d[weekStart] = {
+      if (!d[cartKey]) {
+        d[cartKey] = {
           checked: [],
           manual_items: [],
         } as unknown as CliShoppingCart
       }
 
-      const cart = d[weekStart]
+      const cart = d[cartKey]
       const checkedList = cart.checked ?? []
       const index = checkedList.findIndex((item) => getString(item) === key)
 
@@ -111,14 +134,14 @@ export function useShoppingCart(weekStart: string) {
   const addManualItem = (item: ManualShoppingItem) => {
     changeDoc((d) => {
       // Initialize week if needed
-      if (!d[weekStart]) {
-        d[weekStart] = {
+      if (!d[cartKey]) {
+        d[cartKey] = {
           checked: [],
           manual_items: [],
         } as unknown as CliShoppingCart
       }
 
-      const cart = d[weekStart]
+      const cart = d[cartKey]
       if (!cart.manual_items) {
         cart.manual_items = []
       }
@@ -135,9 +158,9 @@ export function useShoppingCart(weekStart: string) {
   const removeManualItem = (name: string) => {
     const key = name.toLowerCase()
     changeDoc((d) => {
-      if (!d[weekStart]) return
+      if (!d[cartKey]) return
 
-      const cart = d[weekStart]
+      const cart = d[cartKey]
       if (!cart.manual_items) return
 
       const index = cart.manual_items.findIndex(
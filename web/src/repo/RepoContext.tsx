@@ -4,6 +4,7 @@ import { IndexedDBStorageAdapter } from '@automerge/automerge-repo-storage-index
 import { BrowserWebSocketClientAdapter } from '@automerge/automerge-repo-network-websocket'
 import { RepoContext as AutomergeRepoContext } from '@automerge/react'
 import { useAuth } from '../auth'
+import { MEAL_TYPES } from '../meals/types'
 
 // ============================================================================
 // Types
@@ -40,6 +41,8 @@ interface GroupDocData {
   dishes_doc_id: string
   mealplans_doc_id: string
   shopping_carts_doc_id?: string  // Optional for backwards compatibility
+  week_start?: string  // First day of the shopping week ("Sun", "Thu", ...)
+  meal_slots?: { meal_type: string; order: number }[]  // Empty for the built-in slots
 }
 
 // GroupDoc is used when creating new documents from the web app
@@ -85,6 +88,21 @@ function parseDocData<T>(doc: RawDoc | null | undefined): T | null {
   return null
 }
 
+// Day names as the CLI stores a group's week_start, indexed like Date.getDay()
+const WEEKDAYS = ['Sun', 'Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat']
+
+// First day of the group's week (0 = Sunday), Sunday if unset
+function parseWeekStart(groupData: GroupDocData): number {
+  const index = WEEKDAYS.indexOf(groupData.week_start ?? '')
+  return index >= 0 ? index : 0
+}
+
+// The group's meal types in display order, or the built-in ones
+function parseMealTypes(groupData: GroupDocData): string[] {
+  const slots = [...(groupData.meal_slots ?? [])].sort((a, b) => a.order - b.order)
+  return slots.length > 0 ? slots.map((slot) => slot.meal_type) : MEAL_TYPES
+}
+
 type RepoStatus = 'idle' | 'loading' | 'ready' | 'error' | 'pending_sync'
 
 interface RepoStateContextType {
@@ -94,6 +112,8 @@ interface RepoStateContextType {
   docUrls: DocUrls | null
   groups: GroupRef[]
   currentGroupName: string | null
+  weekStartDay: number
+  mealTypes: string[]
 }
 
 const RepoStateContext = createContext<RepoStateContextType>({
@@ -103,6 +123,8 @@ const RepoStateContext = createContext<RepoStateContextType>({
   docUrls: null,
   groups: [],
   currentGroupName: null,
+  weekStartDay: 0,
+  mealTypes: MEAL_TYPES,
 })
 
 // Get sync URL from environment or default to localhost
@@ -147,6 +169,8 @@ export function RepoProvider({ children }: { children: ReactNode }) {
   const [docUrls, setDocUrls] = useState<DocUrls | null>(null)
   const [groups, setGroups] = useState<GroupRef[]>([])
   const [currentGroupName, setCurrentGroupName] = useState<string | null>(null)
+  const [weekStartDay, setWeekStartDay] = useState(0)
+  const [mealTypes, setMealTypes] = useState(MEAL_TYPES)
 
   // Initialize repo when authenticated
   useEffect(() => {
@@ -253,6 +277,9 @@ export function RepoProvider({ children }: { children: ReactNode }) {
           return
         }
 
+        setWeekStartDay(parseWeekStart(groupData))
+        setMealTypes(parseMealTypes(groupData))
+
         // Set document URLs using the group's shared docs + our private meallogs
         const dishesUrl = `automerge:${groupData.dishes_doc_id}` as AutomergeUrl
         const mealPlansUrl = `automerge:${groupData.mealplans_doc_id}` as AutomergeUrl
@@ -545,6 +572,9 @@ export function RepoProvider({ children }: { children: ReactNode }) {
         return
       }
 
+      setWeekStartDay(parseWeekStart(groupData))
+      setMealTypes(parseMealTypes(groupData))
+
       // Pre-fetch data documents - need to explicitly request from network
       const dishesUrl = `automerge:${groupData.dishes_doc_id}` as AutomergeUrl
       const mealPlansUrl = `automerge:${groupData.mealplans_doc_id}` as AutomergeUrl
@@ -653,6 +683,8 @@ export function RepoProvider({ children }: { children: ReactNode }) {
         docUrls,
         groups,
         currentGroupName,
+        weekStartDay,
+        mealTypes,
       }}
     >
       {repo ? (